@@ -1,9 +1,20 @@
 #[cfg(unix)]
 extern crate pkg_config;
 
+#[cfg(unix)]
+use std::env;
+
 #[cfg(unix)]
 fn main() {
-    for name in ["openal", "sndfile"].iter() {
+    let mut names = vec!["openal"];
+    if env::var_os("CARGO_FEATURE_PURE_RUST").is_none() {
+        names.push("sndfile");
+    }
+    if env::var_os("CARGO_FEATURE_CHROMAPRINT").is_some() {
+        names.push("libchromaprint");
+    }
+
+    for name in names.iter() {
         let lib = pkg_config::Config::new()
             .print_system_libs(false)
             .find(name)
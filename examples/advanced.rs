@@ -13,12 +13,12 @@ fn main() {
     // stereo ambience
     let mut wind = Sound::new("res/wind.ogg").unwrap();
     wind.set_volume(0.35);
-    wind.connect(&reverb_effect);
+    wind.connect(&reverb_effect).unwrap();
     wind.play();
 
     // mono positioned ambience
     let mut water = Sound::new("res/water.ogg").unwrap();
-    water.connect(&reverb_effect);
+    water.connect(&reverb_effect).unwrap();
     water.set_air_absorption_factor(1.0);
     water.set_position([-1.0, -0.4, 4.0]);
     water.play();
@@ -27,7 +27,7 @@ fn main() {
 
     // mono moving artillery shot
     let mut sound = Sound::new("res/artillery.ogg").unwrap();
-    sound.connect(&reverb_effect);
+    sound.connect(&reverb_effect).unwrap();
     sound.set_air_absorption_factor(1.0);
     sound.set_position([3.0, 3.0, 3.0]);
     sound.play();
@@ -48,7 +48,7 @@ fn main() {
 
     // mono human yelling
     let mut sound = Sound::new("res/yell.ogg").unwrap();
-    sound.connect(&reverb_effect);
+    sound.connect(&reverb_effect).unwrap();
     sound.set_reference_distance(50.0);
     sound.set_air_absorption_factor(5.0);
     sound.set_position([100.0, 0.0, -50.0]);
@@ -58,7 +58,7 @@ fn main() {
 
     // mono distant sniper shot
     let mut sound = Sound::new("res/sniper.ogg").unwrap();
-    sound.connect(&reverb_effect);
+    sound.connect(&reverb_effect).unwrap();
     sound.set_reference_distance(1500.0);
     sound.set_pitch(1.1);
     sound.set_air_absorption_factor(10.0);
@@ -69,7 +69,7 @@ fn main() {
 
     // mono distant gunshots
     let mut sound = Sound::new("res/shots2.ogg").unwrap();
-    sound.connect(&reverb_effect);
+    sound.connect(&reverb_effect).unwrap();
     sound.set_reference_distance(250.0);
     sound.set_pitch(0.8);
     sound.set_air_absorption_factor(5.0);
@@ -80,7 +80,7 @@ fn main() {
 
     // mono nearby gunshots
     let mut sound = Sound::new("res/shots2.ogg").unwrap();
-    sound.connect(&reverb_effect);
+    sound.connect(&reverb_effect).unwrap();
     sound.set_reference_distance(100.0);
     sound.set_air_absorption_factor(5.0);
     sound.set_position([100.0, 0.0, -50.0]);
@@ -90,7 +90,7 @@ fn main() {
 
     // mono distant sniper shot
     let mut sound = Sound::new("res/sniper.ogg").unwrap();
-    sound.connect(&reverb_effect);
+    sound.connect(&reverb_effect).unwrap();
     sound.set_reference_distance(1500.0);
     sound.set_pitch(0.9);
     sound.set_air_absorption_factor(10.0);
@@ -98,11 +98,7 @@ fn main() {
     sound.play();
 
     // fade out
-    for i in 0..3000 {
-        let v = 1.0 - (i as f32 / 3000.0);
-
-        wind.set_volume(v);
-        water.set_volume(v);
-        sleep(Duration::from_millis(1));
-    }
+    wind.fade_out(Duration::from_secs(3));
+    water.fade_out(Duration::from_secs(3));
+    sleep(Duration::from_millis(3100));
 }
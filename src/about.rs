@@ -0,0 +1,139 @@
+//! Versions and capabilities of ears and of the libraries it uses.
+
+use internal::OpenAlData;
+use libc::c_char;
+use openal::ffi;
+use sndfile;
+use std::ffi::CStr;
+
+/// The cargo features of ears enabled at build time.
+const FEATURES: &[&str] = &[
+    #[cfg(feature = "async")]
+    "async",
+    #[cfg(feature = "chromaprint")]
+    "chromaprint",
+    #[cfg(feature = "glam")]
+    "glam",
+    #[cfg(feature = "mint")]
+    "mint",
+    #[cfg(feature = "mp3")]
+    "mp3",
+    #[cfg(feature = "pure-rust")]
+    "pure-rust",
+    #[cfg(feature = "serde")]
+    "serde",
+];
+
+/// Versions and capabilities of ears and of the libraries it uses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct About {
+    /// The version of ears
+    pub version: &'static str,
+    /// The cargo features of ears enabled at build time
+    pub features: Vec<&'static str>,
+    /// The version of libsndfile
+    pub sndfile_version: String,
+    /// The vendor of the OpenAL implementation
+    pub openal_vendor: String,
+    /// The version of the OpenAL implementation
+    pub openal_version: String,
+    /// The renderer of the OpenAL implementation
+    pub openal_renderer: String,
+    /// The name of the playback device
+    pub device: String,
+    /// The AL extensions available
+    pub al_extensions: Vec<String>,
+    /// The ALC extensions of the playback device
+    pub alc_extensions: Vec<String>,
+}
+
+impl About {
+    /**
+     * Check if an AL or ALC extension is available.
+     *
+     * # Argument
+     * * `extension` - The name of the extension, like "AL_SOFT_direct_channels"
+     */
+    pub fn has_extension(&self, extension: &str) -> bool {
+        self.al_extensions.iter().any(|e| e == extension)
+            || self.alc_extensions.iter().any(|e| e == extension)
+    }
+}
+
+fn to_string(string: *const c_char) -> String {
+    match string.is_null() {
+        true => String::new(),
+        false => unsafe { CStr::from_ptr(string) }
+            .to_string_lossy()
+            .into_owned(),
+    }
+}
+
+fn to_list(string: *const c_char) -> Vec<String> {
+    to_string(string)
+        .split_whitespace()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/**
+ * Get the versions and capabilities of ears and of the libraries it uses.
+ *
+ * Include it in bug reports, or use it to check for features at runtime.
+ * The OpenAL information is empty if the OpenAL context can't be created.
+ *
+ * # Example
+ * ```no_run
+ * let about = ears::about();
+ * println!("{:#?}", about);
+ * if about.has_extension("AL_SOFT_direct_channels") {
+ *     println!("Direct channels are supported");
+ * }
+ * ```
+ */
+pub fn about() -> About {
+    let mut about = About {
+        version: env!("CARGO_PKG_VERSION"),
+        features: FEATURES.to_vec(),
+        sndfile_version: sndfile::lib_version(),
+        openal_vendor: String::new(),
+        openal_version: String::new(),
+        openal_renderer: String::new(),
+        device: String::new(),
+        al_extensions: Vec::new(),
+        alc_extensions: Vec::new(),
+    };
+
+    if OpenAlData::check_al_context().is_ok() {
+        unsafe {
+            let device = ffi::alcGetContextsDevice(ffi::alcGetCurrentContext());
+            about.openal_vendor = to_string(ffi::alGetString(ffi::AL_VENDOR));
+            about.openal_version = to_string(ffi::alGetString(ffi::AL_VERSION));
+            about.openal_renderer = to_string(ffi::alGetString(ffi::AL_RENDERER));
+            about.device = to_string(ffi::alcGetString(device, ffi::ALC_DEVICE_SPECIFIER));
+            about.al_extensions = to_list(ffi::alGetString(ffi::AL_EXTENSIONS));
+            about.alc_extensions = to_list(ffi::alcGetString(device, ffi::ALC_EXTENSIONS));
+        }
+    }
+    about
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::to_list;
+    use std::ffi::CString;
+    use std::ptr;
+
+    #[test]
+    fn about_to_list_OK() -> () {
+        let extensions = CString::new("AL_EXT_FLOAT32 AL_SOFT_direct_channels ").unwrap();
+
+        assert_eq!(
+            to_list(extensions.as_ptr()),
+            vec!["AL_EXT_FLOAT32", "AL_SOFT_direct_channels"]
+        );
+        assert!(to_list(ptr::null()).is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+//! Adaptive music, moving between segments on the beat.
+
+use audio_controller::AudioController;
+use error::SoundError;
+use music::Music;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// When a transition leaves the current segment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransitionSync {
+    /// Right away
+    Immediate,
+    /// On the next beat of the current segment
+    NextBeat,
+    /// On the next bar of the current segment
+    NextBar,
+}
+
+/// How an AdaptiveMusic moves from a segment to another.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Transition {
+    /// When the next segment starts
+    pub sync: TransitionSync,
+    /// The duration of the crossfade between the segments, 0 to cut
+    pub crossfade: Duration,
+}
+
+impl Transition {
+    /// Start the next segment right away, crossfading with the current one.
+    pub fn immediate(crossfade: Duration) -> Transition {
+        Transition {
+            sync: TransitionSync::Immediate,
+            crossfade,
+        }
+    }
+
+    /// Start the next segment on the next beat of the current one.
+    pub fn on_beat(crossfade: Duration) -> Transition {
+        Transition {
+            sync: TransitionSync::NextBeat,
+            crossfade,
+        }
+    }
+
+    /// Start the next segment on the next bar of the current one.
+    pub fn on_bar(crossfade: Duration) -> Transition {
+        Transition {
+            sync: TransitionSync::NextBar,
+            crossfade,
+        }
+    }
+}
+
+impl Default for Transition {
+    /// A one second crossfade, starting right away.
+    fn default() -> Transition {
+        Transition::immediate(Duration::from_secs(1))
+    }
+}
+
+struct Segment {
+    music: Music,
+    /// The duration of a beat
+    beat: Duration,
+    beats_per_bar: u32,
+}
+
+/**
+ * Music made of segments, moving from one to another as the game goes.
+ *
+ * Each segment is a looping Music with a tempo. Calling `transition_to`
+ * moves to another segment following the transition set between them:
+ * right away or on the next beat or bar of the current segment, with a
+ * crossfade or a cut.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AdaptiveMusic, SoundError, Transition};
+ * use std::time::Duration;
+ *
+ * fn main() -> Result<(), SoundError> {
+ *     let mut music = AdaptiveMusic::new();
+ *     music.add_segment("explore", "path/to/explore.ogg", 90.0, 4)?;
+ *     music.add_segment("combat", "path/to/combat.ogg", 140.0, 4)?;
+ *     music.set_transition("explore", "combat", Transition::on_bar(Duration::from_secs(0)));
+ *
+ *     music.transition_to("explore")?;
+ *     // An enemy shows up
+ *     music.transition_to("combat")?;
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Default)]
+pub struct AdaptiveMusic {
+    segments: HashMap<String, Segment>,
+    /// The transitions set between two segments, by their names
+    transitions: HashMap<(String, String), Transition>,
+    /// The transition between the segments without one set
+    default_transition: Transition,
+    /// The name of the segment playing
+    current: Option<String>,
+}
+
+impl AdaptiveMusic {
+    /// Create an AdaptiveMusic without segment.
+    pub fn new() -> AdaptiveMusic {
+        AdaptiveMusic::default()
+    }
+
+    /**
+     * Add a segment, looping by default.
+     *
+     * # Arguments
+     * * `name` - The name of the segment, replacing a segment of the same
+     *   name
+     * * `path` - The path of the music file
+     * * `bpm` - The tempo of the segment, in beats per minute
+     * * `beats_per_bar` - The number of beats in a bar of the segment
+     *
+     * # Return
+     * `Ok(())` on success, Err(SoundError) if the file could not be opened.
+     */
+    pub fn add_segment<P: AsRef<Path>>(
+        &mut self,
+        name: &str,
+        path: P,
+        bpm: f32,
+        beats_per_bar: u32,
+    ) -> Result<(), SoundError> {
+        let mut music = Music::new(path)?;
+        music.set_looping(true);
+        let segment = Segment {
+            music,
+            beat: Duration::from_secs_f32(60.0 / bpm.max(f32::MIN_POSITIVE)),
+            beats_per_bar: beats_per_bar.max(1),
+        };
+        if let Some(mut old) = self.segments.insert(name.to_string(), segment) {
+            old.music.stop();
+            if self.current.as_deref() == Some(name) {
+                self.current = None;
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Get the Music playing a segment.
+     *
+     * Useful to change the volume, effects or looping of a segment.
+     *
+     * # Argument
+     * * `name` - The name of the segment
+     */
+    pub fn segment(&mut self, name: &str) -> Option<&mut Music> {
+        self.segments
+            .get_mut(name)
+            .map(|segment| &mut segment.music)
+    }
+
+    /**
+     * Set the transition from a segment to another.
+     *
+     * # Arguments
+     * * `from` - The name of the segment left
+     * * `to` - The name of the segment started
+     * * `transition` - The transition between them
+     */
+    pub fn set_transition(&mut self, from: &str, to: &str, transition: Transition) {
+        self.transitions
+            .insert((from.to_string(), to.to_string()), transition);
+    }
+
+    /**
+     * Set the transition between the segments without one set.
+     *
+     * # Argument
+     * * `transition` - The transition, a one second crossfade by default
+     */
+    pub fn set_default_transition(&mut self, transition: Transition) {
+        self.default_transition = transition;
+    }
+
+    /// Get the name of the segment playing or about to play.
+    pub fn current_segment(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    /**
+     * Move to another segment.
+     *
+     * The first segment starts right away, the next ones follow the
+     * transition from the current segment. Does nothing if the segment is
+     * the current one.
+     *
+     * # Argument
+     * * `name` - The name of the segment
+     *
+     * # Return
+     * `Ok(())` on success, Err(SoundError::UnknownName) if there is no
+     * segment of this name.
+     */
+    pub fn transition_to(&mut self, name: &str) -> Result<(), SoundError> {
+        if !self.segments.contains_key(name) {
+            return Err(SoundError::UnknownName(name.to_string()));
+        }
+        let current = match self.current.take() {
+            Some(current) if current == name => {
+                self.current = Some(current);
+                return Ok(());
+            }
+            Some(current) => current,
+            None => {
+                self.current = Some(name.to_string());
+                self.segments.get_mut(name).unwrap().music.play();
+                return Ok(());
+            }
+        };
+
+        let transition = self
+            .transitions
+            .get(&(current.clone(), name.to_string()))
+            .cloned()
+            .unwrap_or(self.default_transition);
+        let delay = match self.segments.get_mut(&current) {
+            Some(segment) => {
+                let delay = segment.delay(transition.sync);
+                segment.music.fade_out_after(delay, transition.crossfade);
+                delay
+            }
+            None => Duration::from_secs(0),
+        };
+        self.segments
+            .get_mut(name)
+            .unwrap()
+            .music
+            .fade_in_after(delay, transition.crossfade);
+        self.current = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Stop all the segments.
+    pub fn stop(&mut self) {
+        for segment in self.segments.values_mut() {
+            segment.music.stop();
+        }
+        self.current = None;
+    }
+}
+
+impl Segment {
+    /// Get the delay before the segment reaches the point to leave it.
+    fn delay(&self, sync: TransitionSync) -> Duration {
+        let period = match sync {
+            TransitionSync::Immediate => return Duration::from_secs(0),
+            TransitionSync::NextBeat => self.beat,
+            TransitionSync::NextBar => self.beat * self.beats_per_bar,
+        };
+        if !self.music.is_playing() {
+            return Duration::from_secs(0);
+        }
+        let (position, _) = self.music.get_playback_position_precise();
+        time_to_boundary(position, period)
+    }
+}
+
+/**
+ * Get the time from a position to the next multiple of a period.
+ *
+ * # Arguments
+ * * `position` - The position in the music
+ * * `period` - The duration of a beat or a bar
+ */
+fn time_to_boundary(position: Duration, period: Duration) -> Duration {
+    if period.is_zero() {
+        return Duration::from_secs(0);
+    }
+    let period = period.as_secs_f64();
+    let elapsed = position.as_secs_f64() % period;
+    Duration::from_secs_f64(period - elapsed)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::time_to_boundary;
+    use std::time::Duration;
+
+    #[test]
+    fn adaptive_music_time_to_boundary_OK() -> () {
+        let delay = time_to_boundary(Duration::from_millis(2300), Duration::from_millis(500));
+
+        assert!((delay.as_secs_f64() - 0.2).abs() < 1e-9);
+    }
+
+    #[test]
+    fn adaptive_music_time_to_boundary_on_boundary_OK() -> () {
+        let delay = time_to_boundary(Duration::from_secs(2), Duration::from_millis(500));
+
+        assert_eq!(delay, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn adaptive_music_time_to_boundary_no_period_FAIL() -> () {
+        assert_eq!(
+            time_to_boundary(Duration::from_secs(1), Duration::from_secs(0)),
+            Duration::from_secs(0)
+        );
+    }
+}
@@ -0,0 +1,166 @@
+//! Automatic gain control of captured audio.
+
+use std::time::Duration;
+
+/// The duration of the blocks the level is measured on, in seconds
+const BLOCK_DURATION: f32 = 0.01;
+
+/// The settings of the automatic gain control of a Recorder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AgcSettings {
+    /// The RMS level to reach, where 1.0 is a full scale square wave
+    pub target_level: f32,
+    /// How fast the gain goes down when the input gets louder
+    pub attack: Duration,
+    /// How fast the gain goes up when the input gets quieter
+    pub decay: Duration,
+    /// The highest gain applied, which limits how much the background noise
+    /// is amplified during silences
+    pub max_gain: f32,
+}
+
+impl Default for AgcSettings {
+    /// Settings suited to voice: a -20dBFS target, a quick attack and a slow decay.
+    fn default() -> AgcSettings {
+        AgcSettings {
+            target_level: 0.1,
+            attack: Duration::from_millis(20),
+            decay: Duration::from_millis(1000),
+            max_gain: 10.0,
+        }
+    }
+}
+
+/// The state of an automatic gain control, kept between captured chunks.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Agc {
+    settings: AgcSettings,
+    /// The gain to reach at the end of the current block
+    gain: f32,
+    /// The gain applied to the last sample
+    applied_gain: f32,
+    /// How much the applied gain changes at each sample
+    step: f32,
+    /// The samples of the block being measured
+    block: Vec<i16>,
+}
+
+impl Agc {
+    pub(crate) fn new(settings: AgcSettings) -> Agc {
+        Agc {
+            settings,
+            gain: 1.0,
+            applied_gain: 1.0,
+            step: 0.0,
+            block: Vec::new(),
+        }
+    }
+
+    pub(crate) fn settings(&self) -> AgcSettings {
+        self.settings
+    }
+
+    /**
+     * Apply the gain to mono samples, adapting it to their level.
+     *
+     * The gain is adapted once per block of 10 milliseconds, and ramps
+     * linearly over the next block to avoid clicks.
+     */
+    pub(crate) fn process(&mut self, samples: &mut [i16], sample_rate: u32) {
+        let block_size = ((sample_rate as f32 * BLOCK_DURATION) as usize).max(1);
+        let attack = smoothing(self.settings.attack);
+        let decay = smoothing(self.settings.decay);
+
+        for sample in samples.iter_mut() {
+            self.block.push(*sample);
+            self.applied_gain += self.step;
+            *sample = (*sample as f32 * self.applied_gain).clamp(-32768.0, 32767.0) as i16;
+
+            if self.block.len() == block_size {
+                let rms = (self
+                    .block
+                    .iter()
+                    .map(|s| (*s as f32 / 32768.0).powi(2))
+                    .sum::<f32>()
+                    / block_size as f32)
+                    .sqrt();
+                self.block.clear();
+
+                let desired = match rms > 0.0 {
+                    true => (self.settings.target_level / rms).min(self.settings.max_gain),
+                    false => self.settings.max_gain,
+                };
+                let time = match desired < self.gain {
+                    true => attack,
+                    false => decay,
+                };
+                let new_gain =
+                    self.gain + (desired - self.gain) * (1.0 - (-BLOCK_DURATION / time).exp());
+                self.applied_gain = self.gain;
+                self.step = (new_gain - self.gain) / block_size as f32;
+                self.gain = new_gain;
+            }
+        }
+    }
+}
+
+/// The time constant of a smoothing duration, in seconds, never zero.
+fn smoothing(duration: Duration) -> f32 {
+    duration.as_secs_f32().max(1e-4)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{Agc, AgcSettings};
+    use std::f32::consts::PI;
+
+    fn sine(amplitude: f32, len: usize) -> Vec<i16> {
+        (0..len)
+            .map(|i| ((2.0 * PI * 440.0 * i as f32 / 44100.0).sin() * amplitude * 32767.0) as i16)
+            .collect()
+    }
+
+    fn rms(samples: &[i16]) -> f32 {
+        (samples
+            .iter()
+            .map(|s| (*s as f32 / 32768.0).powi(2))
+            .sum::<f32>()
+            / samples.len() as f32)
+            .sqrt()
+    }
+
+    #[test]
+    fn agc_quiet_input_OK() -> () {
+        let mut agc = Agc::new(AgcSettings::default());
+        let mut samples = sine(0.02, 44100 * 5);
+
+        agc.process(&mut samples, 44100);
+        let level = rms(&samples[samples.len() - 4410..]);
+        assert!((level - 0.1).abs() < 0.01, "level {}", level);
+    }
+
+    #[test]
+    fn agc_loud_input_OK() -> () {
+        let mut agc = Agc::new(AgcSettings::default());
+        let mut samples = sine(0.9, 44100);
+
+        agc.process(&mut samples, 44100);
+        let level = rms(&samples[samples.len() - 4410..]);
+        assert!((level - 0.1).abs() < 0.01, "level {}", level);
+    }
+
+    #[test]
+    fn agc_max_gain_OK() -> () {
+        let mut agc = Agc::new(AgcSettings {
+            max_gain: 2.0,
+            ..Default::default()
+        });
+        let mut samples = sine(0.001, 44100 * 5);
+
+        agc.process(&mut samples, 44100);
+        let level = rms(&samples[samples.len() - 4410..]);
+        assert!(level < 0.001 * 2.0, "level {}", level);
+    }
+}
@@ -0,0 +1,270 @@
+//! Custom distance attenuation, computed by ears instead of OpenAL.
+
+use bus;
+use listener;
+use openal::{al, ffi};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+lazy_static! {
+    /// The sources attenuated by a custom curve
+    static ref CURVED_SOURCES: Mutex<HashMap<u32, CurvedSource>> = Mutex::new(HashMap::new());
+}
+
+/// A source attenuated by a custom curve.
+struct CurvedSource {
+    curve: AttenuationCurve,
+    /// The volume set by the user, before the attenuation
+    volume: f32,
+    /// The OpenAL rolloff factor set by the user, restored with the curve removed
+    rolloff: f32,
+}
+
+/**
+ * A custom distance attenuation curve.
+ *
+ * Gives the volume multiplier of an Audio Source for each distance from the
+ * listener, for mixes that don't fit the distance models of OpenAL.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AttenuationCurve, AudioController, Sound};
+ *
+ * let mut sound = Sound::new("res/shot.wav").unwrap();
+ * // Full volume up to 5 units, then fading out until 40 units
+ * sound.set_attenuation_curve(Some(AttenuationCurve::from_points(&[
+ *     (5.0, 1.0),
+ *     (20.0, 0.3),
+ *     (40.0, 0.0),
+ * ])));
+ * // Or any function of the distance
+ * sound.set_attenuation_curve(Some(AttenuationCurve::from_fn(|distance| {
+ *     1.0 / (1.0 + distance * distance * 0.01)
+ * })));
+ * ```
+ */
+#[derive(Clone)]
+pub struct AttenuationCurve {
+    gain: Arc<dyn Fn(f32) -> f32 + Send + Sync>,
+}
+
+impl AttenuationCurve {
+    /**
+     * Create a curve interpolating linearly between points.
+     *
+     * Before the first point and after the last one, the gain of the
+     * closest point is used.
+     *
+     * # Argument
+     * * `points` - The (distance, gain) points of the curve, in any order
+     */
+    pub fn from_points(points: &[(f32, f32)]) -> AttenuationCurve {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        AttenuationCurve::from_fn(move |distance| interpolate(&points, distance))
+    }
+
+    /**
+     * Create a curve from a function.
+     *
+     * # Argument
+     * * `gain` - The function giving the gain for a distance. It is called
+     *   each time the Audio Source or the listener moves, so it should be fast.
+     */
+    pub fn from_fn<F>(gain: F) -> AttenuationCurve
+    where
+        F: Fn(f32) -> f32 + Send + Sync + 'static,
+    {
+        AttenuationCurve {
+            gain: Arc::new(gain),
+        }
+    }
+
+    /**
+     * Evaluate the curve.
+     *
+     * # Argument
+     * * `distance` - The distance between the Audio Source and the listener
+     *
+     * # Return
+     * The volume multiplier at this distance
+     */
+    pub fn gain(&self, distance: f32) -> f32 {
+        (self.gain)(distance)
+    }
+}
+
+impl fmt::Debug for AttenuationCurve {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "AttenuationCurve")
+    }
+}
+
+fn interpolate(points: &[(f32, f32)], distance: f32) -> f32 {
+    match points.iter().position(|p| p.0 > distance) {
+        None => points.last().map_or(1.0, |p| p.1),
+        Some(0) => points[0].1,
+        Some(i) => {
+            let (d0, g0) = points[i - 1];
+            let (d1, g1) = points[i];
+            g0 + (g1 - g0) * (distance - d0) / (d1 - d0)
+        }
+    }
+}
+
+/**
+ * Attenuate a source with a custom curve, or give the attenuation back to
+ * OpenAL.
+ *
+ * While a curve is set, the OpenAL rolloff factor of the source is 0 and its
+ * gain is the volume of the user multiplied by the curve.
+ */
+pub(crate) fn set_source_curve(source: u32, curve: Option<AttenuationCurve>) {
+    let mut sources = CURVED_SOURCES.lock().unwrap();
+    match curve {
+        Some(curve) => {
+            let curved = sources.entry(source).or_insert_with(|| {
+                let volume = bus::source_gain(source);
+                let mut rolloff = 1.0;
+                al::alGetSourcef(source, ffi::AL_ROLLOFF_FACTOR, &mut rolloff);
+                al::alSourcef(source, ffi::AL_ROLLOFF_FACTOR, 0.0);
+                CurvedSource {
+                    curve: curve.clone(),
+                    volume,
+                    rolloff,
+                }
+            });
+            curved.curve = curve;
+            update(source, curved);
+        }
+        None => {
+            if let Some(curved) = sources.remove(&source) {
+                bus::set_source_gain(source, curved.volume);
+                al::alSourcef(source, ffi::AL_ROLLOFF_FACTOR, curved.rolloff);
+            }
+        }
+    }
+}
+
+/// Forget all the curved sources, when the context is cleaned up.
+pub(crate) fn clear() {
+    CURVED_SOURCES.lock().unwrap().clear();
+}
+
+/// Get the custom curve of a source, if any.
+pub(crate) fn source_curve(source: u32) -> Option<AttenuationCurve> {
+    CURVED_SOURCES
+        .lock()
+        .unwrap()
+        .get(&source)
+        .map(|curved| curved.curve.clone())
+}
+
+/**
+ * Set the volume of a source attenuated by a custom curve.
+ *
+ * # Return
+ * false if the source has no custom curve, and the volume wasn't set.
+ */
+pub(crate) fn set_source_volume(source: u32, volume: f32) -> bool {
+    match CURVED_SOURCES.lock().unwrap().get_mut(&source) {
+        Some(curved) => {
+            curved.volume = volume;
+            update(source, curved);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Get the volume of a source attenuated by a custom curve, before the attenuation.
+pub(crate) fn source_volume(source: u32) -> Option<f32> {
+    CURVED_SOURCES
+        .lock()
+        .unwrap()
+        .get(&source)
+        .map(|curved| curved.volume)
+}
+
+/**
+ * Set the rolloff factor of a source attenuated by a custom curve, applied
+ * once the curve is removed.
+ *
+ * # Return
+ * false if the source has no custom curve, and the rolloff factor wasn't set.
+ */
+pub(crate) fn set_source_rolloff(source: u32, rolloff: f32) -> bool {
+    match CURVED_SOURCES.lock().unwrap().get_mut(&source) {
+        Some(curved) => {
+            curved.rolloff = rolloff;
+            true
+        }
+        None => false,
+    }
+}
+
+/// Get the rolloff factor of a source attenuated by a custom curve.
+pub(crate) fn source_rolloff(source: u32) -> Option<f32> {
+    CURVED_SOURCES
+        .lock()
+        .unwrap()
+        .get(&source)
+        .map(|curved| curved.rolloff)
+}
+
+/// Recompute the gain of a source after it moved.
+pub(crate) fn update_source(source: u32) {
+    if let Some(curved) = CURVED_SOURCES.lock().unwrap().get(&source) {
+        update(source, curved);
+    }
+}
+
+/// Recompute the gain of all the sources after the listener moved.
+pub(crate) fn update_all_sources() {
+    for (source, curved) in CURVED_SOURCES.lock().unwrap().iter() {
+        update(*source, curved);
+    }
+}
+
+fn update(source: u32, curved: &CurvedSource) {
+    let mut position = [0.0; 3];
+    let mut relative = 0;
+    al::alGetSourcefv(source, ffi::AL_POSITION, &mut position[0]);
+    al::alGetSourcei(source, ffi::AL_SOURCE_RELATIVE, &mut relative);
+    let origin = match relative as _ {
+        ffi::ALC_TRUE => [0.0; 3],
+        _ => listener::get_position(),
+    };
+    let distance = position
+        .iter()
+        .zip(origin.iter())
+        .map(|(p, o)| (p - o) * (p - o))
+        .sum::<f32>()
+        .sqrt();
+    let gain = curved.volume * curved.curve.gain(distance).max(0.0);
+    bus::set_source_gain(source, gain);
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::AttenuationCurve;
+
+    #[test]
+    fn attenuation_curve_from_points_OK() -> () {
+        let curve = AttenuationCurve::from_points(&[(20.0, 0.0), (10.0, 1.0), (15.0, 0.2)]);
+
+        assert_eq!(curve.gain(0.0), 1.0);
+        assert_eq!(curve.gain(10.0), 1.0);
+        assert!((curve.gain(12.5) - 0.6).abs() < 1e-6);
+        assert!((curve.gain(17.5) - 0.1).abs() < 1e-6);
+        assert_eq!(curve.gain(100.0), 0.0);
+    }
+
+    #[test]
+    fn attenuation_curve_from_points_empty_OK() -> () {
+        assert_eq!(AttenuationCurve::from_points(&[]).gain(5.0), 1.0);
+    }
+}
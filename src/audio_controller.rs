@@ -23,8 +23,21 @@
 
 use std::time::Duration;
 
-use reverb_effect::ReverbEffect;
+use attenuation::AttenuationCurve;
+use bus::Bus;
+use clock;
+use deferred;
+use effect::Effect;
+use error::SoundError;
+use filter::Filter;
+use internal::OpenAlData;
+use openal::al;
+use playback_stats::PlaybackStats;
+use reverb_effect::{ReverbEffect, ReverbEffectError};
+use source_settings::SourceSettings;
+use spatialize::Spatialize;
 use states::State;
+use vector::Vector3;
 
 /// The functionnality that an Audio Source should provide.
 pub trait AudioController {
@@ -37,8 +50,331 @@ pub trait AudioController {
     /// Stop the Audio Source.
     fn stop(&mut self) -> ();
 
-    /// Connect a ReverbEffect to the Source
-    fn connect(&mut self, reverb_effect: &Option<ReverbEffect>);
+    /**
+     * Play or resume the Audio Source, reporting the errors.
+     *
+     * # Return
+     * `Ok(())` on success, Err(SoundError::InvalidOpenALContext) if there is
+     * no OpenAL context or Err(SoundError::InternalOpenALError) if OpenAL
+     * failed.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Sound, SoundError};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut snd = Sound::new("path/to/the/sound.ogg")?;
+     *     snd.try_play()?;
+     *     Ok(())
+     * }
+     * ```
+     */
+    fn try_play(&mut self) -> Result<(), SoundError> {
+        checked(|| self.play())
+    }
+
+    /// Pause the Audio Source, reporting the errors like `try_play`.
+    fn try_pause(&mut self) -> Result<(), SoundError> {
+        checked(|| self.pause())
+    }
+
+    /// Stop the Audio Source, reporting the errors like `try_play`.
+    fn try_stop(&mut self) -> Result<(), SoundError> {
+        checked(|| self.stop())
+    }
+
+    /**
+     * Play or resume the Audio Source at a time on the device clock.
+     *
+     * Several Audio Sources given the same time start together, for
+     * instance on a musical beat. With the AL_SOFT_source_start_delay
+     * extension the device starts them at the exact sample, otherwise a
+     * background thread plays them as close to the time as the system
+     * allows. A time in the past, or a device without the
+     * ALC_SOFT_device_clock extension, plays the Audio Source right away.
+     *
+     * # Argument
+     * * `time` - The time on the device clock, see `ears::device_clock`
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Sound, SoundError};
+     * use std::time::Duration;
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut kick = Sound::new("path/to/kick.wav")?;
+     *     let mut snare = Sound::new("path/to/snare.wav")?;
+     *     if let Some(now) = ears::device_clock() {
+     *         let beat = now + Duration::from_millis(100);
+     *         kick.play_at(beat);
+     *         snare.play_at(beat + Duration::from_millis(500));
+     *     }
+     *     Ok(())
+     * }
+     * ```
+     */
+    fn play_at(&mut self, time: Duration) -> ();
+
+    /**
+     * Play or resume the Audio Source after a delay, like `play_at`.
+     *
+     * # Argument
+     * * `delay` - The delay from now on the device clock
+     */
+    fn play_in(&mut self, delay: Duration) {
+        let now = clock::device_clock().unwrap_or_default();
+        self.play_at(now + delay)
+    }
+
+    /**
+     * Start playing the Audio Source with its volume rising from silence.
+     *
+     * The volume ramps up to the current volume of the Audio Source on a
+     * background thread, so this returns immediately. Calling `set_volume`
+     * or `stop` during the fade interrupts it.
+     *
+     * # Argument
+     * * `duration` - The duration of the fade
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Music, SoundError};
+     * use std::time::Duration;
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     msc.fade_in(Duration::from_secs(3));
+     *     Ok(())
+     * }
+     * ```
+     */
+    fn fade_in(&mut self, duration: Duration) -> ();
+
+    /**
+     * Fade the volume of the Audio Source out, then stop it.
+     *
+     * Once stopped, the volume is set back to its value before the fade, so
+     * the next `play` isn't silent.
+     *
+     * # Argument
+     * * `duration` - The duration of the fade
+     */
+    fn fade_out(&mut self, duration: Duration) -> ();
+
+    /**
+     * Ramp the volume of the Audio Source to a new value.
+     *
+     * # Arguments
+     * * `volume` - The volume at the end of the fade
+     * * `duration` - The duration of the fade
+     */
+    fn fade_to(&mut self, volume: f32, duration: Duration) -> ();
+
+    /**
+     * Connect an effect to the Audio Source.
+     *
+     * Any effect can be connected, e.g. a ReverbEffect or an EchoEffect, and
+     * connecting an `Option` of an effect which is None disconnects it.
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * effects or the connection failed.
+     */
+    fn connect(&mut self, effect: &dyn Effect) -> Result<(), ReverbEffectError> {
+        self.connect_send(0, effect)
+    }
+
+    /**
+     * Disconnect the effect of the Audio Source.
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the disconnection failed.
+     */
+    fn disconnect(&mut self) -> Result<(), ReverbEffectError> {
+        self.disconnect_send(0)
+    }
+
+    /**
+     * Connect an effect to an auxiliary send of the Audio Source.
+     *
+     * Each send can be connected to a different effect, see
+     * `init_with_auxiliary_sends` to get more than the default. The effect
+     * takes precedence over the one connected to the same send by the bus of
+     * the Audio Source, which comes back once the send is disconnected.
+     *
+     * # Arguments
+     * * `send` - The index of the auxiliary send, below `max_auxiliary_sends()`
+     * * `effect` - The effect to connect, or an `Option` of an effect which
+     *   is None to disconnect the send
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * effects, the send doesn't exist or the connection failed.
+     */
+    fn connect_send(&mut self, send: i32, effect: &dyn Effect) -> Result<(), ReverbEffectError>;
+
+    /**
+     * Disconnect the effect of an auxiliary send of the Audio Source.
+     *
+     * # Argument
+     * * `send` - The index of the auxiliary send
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the send doesn't exist or
+     * the disconnection failed.
+     */
+    fn disconnect_send(&mut self, send: i32) -> Result<(), ReverbEffectError> {
+        self.connect_send(send, &None::<ReverbEffect>)
+    }
+
+    /**
+     * Filter the direct path of the Audio Source, or remove the filter with
+     * None.
+     *
+     * The direct path is what the listener hears without any effect, so a
+     * low pass filter muffles the Audio Source, as if behind a wall. The
+     * settings of the filter are copied: apply it again after changing it.
+     *
+     * # Argument
+     * * `filter` - The filter to apply, or None to remove the filter
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * effects or the filter couldn't be applied.
+     */
+    fn set_direct_filter(&mut self, filter: &Option<Filter>) -> Result<(), ReverbEffectError>;
+
+    /**
+     * Set how much the Audio Source is occluded.
+     *
+     * An occluded Audio Source is heard through a wall or a closed door,
+     * from another room: both what the listener hears directly and what
+     * reaches the effects are muffled.
+     *
+     * The occlusion is combined with the filter set with
+     * `set_direct_filter`, and has no effect if the device doesn't support
+     * effects.
+     *
+     * # Argument
+     * * `occlusion` - From 0.0, not occluded (the default), to 1.0, fully occluded
+     */
+    fn set_occlusion(&mut self, occlusion: f32) -> ();
+
+    /**
+     * Get how much the Audio Source is occluded.
+     *
+     * # Return
+     * The occlusion, between 0.0 and 1.0
+     */
+    fn get_occlusion(&self) -> f32;
+
+    /**
+     * Set how much the Audio Source is obstructed.
+     *
+     * An obstructed Audio Source is in the same room as the listener but
+     * hidden behind an obstacle, like a pillar: what the listener hears
+     * directly is muffled, but the effects of the room still get the full
+     * sound.
+     *
+     * The obstruction is combined with the filter set with
+     * `set_direct_filter`, and has no effect if the device doesn't support
+     * effects.
+     *
+     * # Argument
+     * * `obstruction` - From 0.0, not obstructed (the default), to 1.0,
+     *   fully obstructed
+     */
+    fn set_obstruction(&mut self, obstruction: f32) -> ();
+
+    /**
+     * Get how much the Audio Source is obstructed.
+     *
+     * # Return
+     * The obstruction, between 0.0 and 1.0
+     */
+    fn get_obstruction(&self) -> f32;
+
+    /**
+     * Assign the Audio Source to a bus, or remove it from its bus with None.
+     *
+     * The volume and the pitch of the bus multiply the ones of the Audio
+     * Source, and the effects connected to the bus are connected to the
+     * Audio Source. An Audio Source is in a single bus at a time, assigning
+     * it to another bus removes it from the previous one.
+     *
+     * # Argument
+     * * `bus` - The bus, or None to remove the Audio Source from its bus
+     */
+    fn set_bus(&mut self, bus: Option<&Bus>) -> ();
+
+    /**
+     * Check if the Audio Source is assigned to a bus.
+     *
+     * # Argument
+     * * `bus` - The bus to check
+     *
+     * # Return
+     * true if the Audio Source is in the bus, false otherwise.
+     */
+    fn is_in_bus(&self, bus: &Bus) -> bool;
+
+    /**
+     * Set the gain of the direct path of the Audio Source, the dry signal
+     * heard without any effect.
+     *
+     * Together with `set_send_gain`, this balances the mix of the Audio
+     * Source and its effects, e.g. a mostly wet sound far in a cave. The
+     * gain is applied by the same filter as the occlusion, combined with
+     * the filter set with `set_direct_filter`, and has no effect if the
+     * device doesn't support effects.
+     *
+     * # Argument
+     * * `gain` - The gain, between 0.0 and 1.0. Default is 1.0.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, ReverbEffect, ReverbPreset, Sound};
+     * use std::error::Error;
+     *
+     * fn main() -> Result<(), Box<dyn Error>> {
+     *     let cave = ReverbEffect::preset(ReverbPreset::Cave.properties())?;
+     *     let mut snd = Sound::new("path/to/my/sound.ogg")?;
+     *     snd.connect(&cave)?;
+     *     snd.set_dry_gain(0.6);
+     *     snd.set_send_gain(0.4);
+     *     snd.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    fn set_dry_gain(&mut self, gain: f32) -> ();
+
+    /**
+     * Get the gain of the direct path of the Audio Source.
+     *
+     * # Return
+     * The gain, between 0.0 and 1.0
+     */
+    fn get_dry_gain(&self) -> f32;
+
+    /**
+     * Set the gain of the auxiliary sends of the Audio Source, the wet
+     * signal feeding the connected effects.
+     *
+     * Has no effect if the device doesn't support effects.
+     *
+     * # Argument
+     * * `gain` - The gain, between 0.0 and 1.0. Default is 1.0.
+     */
+    fn set_send_gain(&mut self, gain: f32) -> ();
+
+    /**
+     * Get the gain of the auxiliary sends of the Audio Source.
+     *
+     * # Return
+     * The gain, between 0.0 and 1.0
+     */
+    fn get_send_gain(&self) -> f32;
 
     /**
      * Check if the Audio Source is playing or not.
@@ -72,6 +408,27 @@ pub trait AudioController {
      */
     fn get_offset(&self) -> i32;
 
+    /**
+     * Get the precise playback position of the Audio Source.
+     *
+     * Unlike `get_offset`, the position is not rounded to a frame and comes
+     * with the latency of the device, read at the same instant: the audio
+     * heard at this moment is the one at the position minus the latency.
+     * Useful to synchronize visuals with the audio, like in rhythm games.
+     *
+     * The latency is always zero if the AL_SOFT_source_latency extension is
+     * not present.
+     *
+     * # Return
+     * A tuple containing the playback position and the latency of the device.
+     */
+    fn get_playback_position_precise(&self) -> (Duration, Duration);
+
+    /// Set the offset of the Audio Source, reporting the errors like `try_play`.
+    fn try_set_offset(&mut self, offset: i32) -> Result<(), SoundError> {
+        checked(|| self.set_offset(offset))
+    }
+
     /**
      * Set the volume of the Audio Source.
      *
@@ -92,6 +449,11 @@ pub trait AudioController {
      */
     fn get_volume(&self) -> f32;
 
+    /// Set the volume of the Audio Source, reporting the errors like `try_play`.
+    fn try_set_volume(&mut self, volume: f32) -> Result<(), SoundError> {
+        checked(|| self.set_volume(volume))
+    }
+
     /**
      * Set the minimal volume for a Audio Source.
      *
@@ -171,6 +533,11 @@ pub trait AudioController {
      */
     fn get_pitch(&self) -> f32;
 
+    /// Set the pitch of the Audio Source, reporting the errors like `try_play`.
+    fn try_set_pitch(&mut self, pitch: f32) -> Result<(), SoundError> {
+        checked(|| self.set_pitch(pitch))
+    }
+
     /**
      * Set the position of the Audio Source relative to the listener or absolute.
      *
@@ -216,6 +583,25 @@ pub trait AudioController {
      */
     fn get_position(&self) -> [f32; 3];
 
+    /**
+     * Set the position of the Audio Source from any three dimensional vector,
+     * like a `glam::Vec3` with the `glam` feature.
+     *
+     * # Argument
+     * * `position` - The position of the Audio Source, see `Vector3`
+     */
+    fn set_position_from<V: Vector3>(&mut self, position: V)
+    where
+        Self: Sized,
+    {
+        self.set_position(position.to_array())
+    }
+
+    /// Set the position of the Audio Source, reporting the errors like `try_play`.
+    fn try_set_position(&mut self, position: [f32; 3]) -> Result<(), SoundError> {
+        checked(|| self.set_position(position))
+    }
+
     /**
      * Set the direction of the Audio Source.
      *
@@ -236,6 +622,50 @@ pub trait AudioController {
      */
     fn get_direction(&self) -> [f32; 3];
 
+    /**
+     * Set the direction of the Audio Source from any three dimensional
+     * vector, like `set_position_from`.
+     */
+    fn set_direction_from<V: Vector3>(&mut self, direction: V)
+    where
+        Self: Sized,
+    {
+        self.set_direction(direction.to_array())
+    }
+
+    /**
+     * Set the velocity of the Audio Source.
+     *
+     * The velocity doesn't move the Audio Source, it is only used with the
+     * velocity of the listener to compute the Doppler shift, see
+     * `listener::set_doppler_factor`.
+     *
+     * The default velocity is: [0.0, 0.0, 0.0]
+     *
+     * # Argument
+     * `velocity` - The new velocity of the Audio Source [x, y, z].
+     */
+    fn set_velocity(&mut self, velocity: [f32; 3]) -> ();
+
+    /**
+     * Get the velocity of the Audio Source.
+     *
+     * # Return
+     * The current velocity of the Audio Source.
+     */
+    fn get_velocity(&self) -> [f32; 3];
+
+    /**
+     * Set the velocity of the Audio Source from any three dimensional
+     * vector, like `set_position_from`.
+     */
+    fn set_velocity_from<V: Vector3>(&mut self, velocity: V)
+    where
+        Self: Sized,
+    {
+        self.set_velocity(velocity.to_array())
+    }
+
     /**
      * Set the maximum distance of the Audio Source.
      *
@@ -302,6 +732,141 @@ pub trait AudioController {
      */
     fn get_attenuation(&self) -> f32;
 
+    /**
+     * Set the sound cone of the Audio Source.
+     *
+     * Inside the inner angle around the direction of the Audio Source its
+     * volume is unchanged, outside of the outer angle it is multiplied by
+     * `outer_volume`, and interpolated in between.
+     *
+     * The default cone is [360.0, 360.0, 0.0], so the cone has no effect.
+     *
+     * # Arguments
+     * * `inner_angle` - The inner angle in degrees in the range [0.0, 360.0]
+     * * `outer_angle` - The outer angle in degrees in the range [0.0, 360.0]
+     * * `outer_volume` - The volume outside of the outer angle in the range
+     *   [0.0, 1.0]
+     */
+    fn set_cone(&mut self, inner_angle: f32, outer_angle: f32, outer_volume: f32) -> ();
+
+    /**
+     * Get the sound cone of the Audio Source.
+     *
+     * # Return
+     * A tuple containing the inner angle, outer angle and outer volume.
+     */
+    fn get_cone(&self) -> (f32, f32, f32);
+
+    /**
+     * Let OpenAL adjust the auxiliary sends of the Audio Source automatically.
+     *
+     * The value is passed as is to the AL_AUXILIARY_SEND_FILTER_GAIN_AUTO and
+     * AL_AUXILIARY_SEND_FILTER_GAINHF_AUTO properties of the source, ears
+     * computes nothing itself. When true, OpenAL attenuates the signal sent
+     * to the effects from the distance and the cone of the Audio Source,
+     * following the room rolloff and air absorption of the reverb. When
+     * false, only the send filters change it. Nothing is done if the device
+     * doesn't support EFX.
+     *
+     * The default is true.
+     *
+     * # Argument
+     * * `enabled` - false to leave the auxiliary sends unadjusted
+     */
+    fn set_auxiliary_send_auto(&mut self, enabled: bool) -> ();
+
+    /**
+     * Check if OpenAL adjusts the auxiliary sends automatically.
+     *
+     * # Return
+     * True if the auxiliary sends are adjusted automatically, false otherwise
+     */
+    fn get_auxiliary_send_auto(&self) -> bool;
+
+    /**
+     * Enable or disable the Doppler effect for the Audio Source.
+     *
+     * When disabled, the Audio Source moves with the listener as far as the
+     * Doppler effect is concerned, so its pitch doesn't change when the
+     * listener moves fast. Use it for interface sounds and music attached
+     * to the listener.
+     *
+     * The default is true.
+     *
+     * # Argument
+     * * `enabled` - false to exempt the Audio Source from the Doppler effect
+     */
+    fn set_doppler_enabled(&mut self, enabled: bool) -> ();
+
+    /**
+     * Check if the Doppler effect applies to the Audio Source.
+     *
+     * # Return
+     * True if the Doppler effect applies to the Audio Source, false otherwise
+     */
+    fn is_doppler_enabled(&self) -> bool;
+
+    /**
+     * Compute the velocity of the Audio Source from its positions.
+     *
+     * Each `set_position` then sets the velocity to the distance moved since
+     * the previous position divided by the time elapsed, so the Doppler
+     * effect follows the moves without computing the velocity. Positions
+     * given more than half a second apart are a jump, setting the velocity to
+     * zero. The velocity isn't changed when disabled.
+     *
+     * The default is false.
+     *
+     * # Argument
+     * * `enabled` - true to compute the velocity from the positions
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Sound, SoundError};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut car = Sound::new("path/to/engine.ogg")?;
+     *     car.set_auto_velocity(true);
+     *     car.play();
+     *     for frame in 0..600 {
+     *         // Moving at 20 units per second at 60 frames per second
+     *         car.set_position([frame as f32 / 3.0, 0.0, -10.0]);
+     *         std::thread::sleep(std::time::Duration::from_millis(16));
+     *     }
+     *     Ok(())
+     * }
+     * ```
+     */
+    fn set_auto_velocity(&mut self, enabled: bool) -> ();
+
+    /**
+     * Check if the velocity of the Audio Source is computed from its
+     * positions.
+     */
+    fn is_auto_velocity(&self) -> bool;
+
+    /**
+     * Attenuate the Audio Source with a custom curve instead of the OpenAL
+     * distance model.
+     *
+     * The gain of the Audio Source is recomputed each time it or the
+     * listener moves, as its volume multiplied by the curve at their
+     * distance.
+     *
+     * # Argument
+     * * `curve` - The curve to use, or None to use the OpenAL distance model
+     *   again (the default)
+     */
+    fn set_attenuation_curve(&mut self, curve: Option<AttenuationCurve>) -> ();
+
+    /**
+     * Get the custom attenuation curve of the Audio Source.
+     *
+     * # Return
+     * The curve set with `set_attenuation_curve`, if any
+     */
+    fn get_attenuation_curve(&self) -> Option<AttenuationCurve>;
+
     /**
      * Enable or disable direct channel mode for an Audio Source.
      *
@@ -339,8 +904,153 @@ pub trait AudioController {
      */
     fn get_direct_channel(&self) -> bool;
 
+    /**
+     * Choose whether the Audio Source is positioned in 3D.
+     *
+     * By default only mono sources are spatialized. Enabling it downmixes
+     * stereo music to play it from its position, while disabling it plays
+     * mono UI sounds as is, whatever the position of the listener. Applies
+     * only when the AL_SOFT_source_spatialize extension exists.
+     *
+     * [https://openal-soft.org/openal-extensions/SOFT_source_spatialize.txt]()
+     *
+     * # Argument
+     * * `spatialize` - The spatialization mode, `Spatialize::Auto` by default
+     */
+    fn set_spatialized(&mut self, spatialize: Spatialize) -> ();
+
+    /**
+     * Get the spatialization mode of the Audio Source.
+     *
+     * Will always return `Spatialize::Auto` if the AL_SOFT_source_spatialize
+     * extension is not present.
+     *
+     * # Return
+     * The spatialization mode of the Audio Source
+     */
+    fn get_spatialized(&self) -> Spatialize;
+
+    /**
+     * Set the angles of the channels of a stereo Audio Source.
+     *
+     * Narrowing the angles makes distant stereo ambiences sound smaller,
+     * widening them makes the stereo image larger. Applies only when the
+     * AL_EXT_STEREO_ANGLES extension exists and when playing stereo buffers.
+     *
+     * The default angles are [30.0, -30.0].
+     *
+     * # Arguments
+     * * `left` - The angle of the left channel in degrees, counter-clockwise
+     *   from the front
+     * * `right` - The angle of the right channel in degrees, counter-clockwise
+     *   from the front
+     */
+    fn set_stereo_angles(&mut self, left: f32, right: f32) -> ();
+
+    /**
+     * Get the angles of the channels of a stereo Audio Source.
+     *
+     * Will always return [30.0, -30.0] if the AL_EXT_STEREO_ANGLES extension
+     * is not present.
+     *
+     * # Return
+     * A tuple containing the left and right angles in degrees.
+     */
+    fn get_stereo_angles(&self) -> (f32, f32);
+
+    /**
+     * Select the resampler of the Audio Source.
+     *
+     * High quality resamplers like sinc cost more CPU, use them for the
+     * sources where quality matters and keep the default for the others.
+     * Applies only when the AL_SOFT_source_resampler extension exists.
+     *
+     * # Argument
+     * * `index` - The index of the resampler in `ears::resamplers()`
+     */
+    fn set_resampler(&mut self, index: i32) -> ();
+
+    /**
+     * Get the resampler of the Audio Source.
+     *
+     * # Return
+     * The index of the resampler in `ears::resamplers()`
+     */
+    fn get_resampler(&self) -> i32;
+
     /**
      * Returns the duration
      */
     fn get_duration(&self) -> Duration;
+
+    /**
+     * Get the playback statistics of the Audio Source.
+     *
+     * # Return
+     * The totals accumulated since the Audio Source was created.
+     */
+    fn get_stats(&self) -> PlaybackStats;
+
+    /**
+     * Apply all the given settings to the Audio Source.
+     *
+     * The changes are applied at once when the AL_SOFT_deferred_updates
+     * extension is available. The direct filter and the auxiliary sends are
+     * left out if the device doesn't support EFX.
+     *
+     * # Argument
+     * * `settings` - The settings to apply
+     */
+    fn apply(&mut self, settings: &SourceSettings) {
+        deferred::with_deferred_updates(|| {
+            self.set_auto_velocity(settings.auto_velocity);
+            self.set_volume(settings.volume);
+            self.set_min_volume(settings.min_volume);
+            self.set_max_volume(settings.max_volume);
+            self.set_pitch(settings.pitch);
+            self.set_position(settings.position);
+            self.set_direction(settings.direction);
+            self.set_velocity(settings.velocity);
+            self.set_relative(settings.relative);
+            self.set_looping(settings.looping);
+            self.set_max_distance(settings.max_distance);
+            self.set_reference_distance(settings.reference_distance);
+            self.set_attenuation(settings.attenuation);
+            self.set_cone(
+                settings.cone_inner_angle,
+                settings.cone_outer_angle,
+                settings.cone_outer_volume,
+            );
+            if OpenAlData::efx_capable() {
+                self.set_auxiliary_send_auto(settings.auxiliary_send_auto);
+            }
+            self.set_doppler_enabled(settings.doppler_enabled);
+
+            let filter = settings
+                .direct_filter
+                .and_then(|filter_type| Filter::new(filter_type).ok())
+                .map(|mut filter| {
+                    filter.set_gain(settings.direct_filter_gain);
+                    filter.set_gain_lf(settings.direct_filter_gain_lf);
+                    filter.set_gain_hf(settings.direct_filter_gain_hf);
+                    filter
+                });
+            self.set_direct_filter(&filter).ok();
+        })
+    }
+}
+
+/// Run an action of an Audio Source, returning the OpenAL error it raised.
+fn checked<F: FnOnce()>(action: F) -> Result<(), SoundError> {
+    if OpenAlData::check_al_context().is_err() {
+        return Err(SoundError::InvalidOpenALContext);
+    }
+
+    // Clear the errors raised before the action
+    al::openal_has_error();
+    action();
+    match al::openal_has_error() {
+        Some(err) => Err(SoundError::InternalOpenALError(err)),
+        None => Ok(()),
+    }
 }
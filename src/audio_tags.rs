@@ -21,55 +21,73 @@
 
 //! The tags extracted from an audio file.
 
-use sndfile::SndFile;
+use error::SoundError;
+use sndfile::OpenMode::ReadWrite;
 use sndfile::StringSoundType::{
-    Album, Artist, Comment, Copyright, Date, Genre, License, Software, Title, TrackNumber,
+    self, Album, Artist, Comment, Copyright, Date, Genre, License, Software, Title, TrackNumber,
 };
+use sndfile::{Error, SndFile, SndFileError};
+use std::path::Path;
 
 /**
  * Structure containing the tags of a sound.
  *
- * If the tags doesn't exist in the sound file, the string is "".
+ * A tag is None if the sound file doesn't have it.
  */
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct Tags {
-    /// The title of the sound as a String
-    pub title: String,
-    /// The Copyright of the sound as a String
-    pub copyright: String,
-    /// The name of the software used to create the sound as a String
-    pub software: String,
-    /// The name of the artist of the sound as a String
-    pub artist: String,
-    /// A comment as a String
-    pub comment: String,
-    /// The creation date of the sound as a String
-    pub date: String,
-    /// The name of the album where the sound come from as a String
-    pub album: String,
-    /// The license of the sound as a String
-    pub license: String,
-    /// The tracknumber of the sound as a String
-    pub track_number: String,
-    /// The genre of the sound as a String
-    pub genre: String,
+    /// The title of the sound
+    pub title: Option<String>,
+    /// The Copyright of the sound
+    pub copyright: Option<String>,
+    /// The name of the software used to create the sound
+    pub software: Option<String>,
+    /// The name of the artist of the sound
+    pub artist: Option<String>,
+    /// A comment
+    pub comment: Option<String>,
+    /// The creation date of the sound
+    pub date: Option<String>,
+    /// The name of the album where the sound come from
+    pub album: Option<String>,
+    /// The license of the sound
+    pub license: Option<String>,
+    /// The track number of the sound, like "3" or "3/12"
+    pub track_number: Option<String>,
+    /// The genre of the sound
+    pub genre: Option<String>,
 }
 
-impl Default for Tags {
-    fn default() -> Tags {
-        Tags {
-            title: "".to_string(),
-            copyright: "".to_string(),
-            software: "".to_string(),
-            artist: "".to_string(),
-            comment: "".to_string(),
-            date: "".to_string(),
-            album: "".to_string(),
-            license: "".to_string(),
-            track_number: "".to_string(),
-            genre: "".to_string(),
+impl Tags {
+    /**
+     * Get the year of the date tag.
+     *
+     * # Return
+     * The year the date starts with, like 1999 for "1999-03-21", None if
+     * there is no date or it doesn't start with a year.
+     */
+    pub fn year(&self) -> Option<i32> {
+        let date = self.date.as_ref()?.trim();
+        let digits = date
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(date.len());
+        match digits {
+            4 => date[..4].parse().ok(),
+            _ => None,
         }
     }
+
+    /**
+     * Get the number of the track tag.
+     *
+     * # Return
+     * The track number, like 3 for "3/12", None if there is no track
+     * number or it isn't a number.
+     */
+    pub fn track(&self) -> Option<u32> {
+        let track_number = self.track_number.as_ref()?;
+        track_number.split('/').next()?.trim().parse().ok()
+    }
 }
 
 #[deprecated(since = "0.8.0", note = "Please use Default::default() instead")]
@@ -79,17 +97,83 @@ pub fn empty() -> Tags {
 
 pub fn get_sound_tags(file: &SndFile) -> Tags {
     Tags {
-        title: file.get_string(Title).unwrap_or("".to_string()),
-        copyright: file.get_string(Copyright).unwrap_or("".to_string()),
-        software: file.get_string(Software).unwrap_or("".to_string()),
-        artist: file.get_string(Artist).unwrap_or("".to_string()),
-        comment: file.get_string(Comment).unwrap_or("".to_string()),
-        date: file.get_string(Date).unwrap_or("".to_string()),
-        album: file.get_string(Album).unwrap_or("".to_string()),
-        license: file.get_string(License).unwrap_or("".to_string()),
-        track_number: file.get_string(TrackNumber).unwrap_or("".to_string()),
-        genre: file.get_string(Genre).unwrap_or("".to_string()),
+        title: file.get_string(Title),
+        copyright: file.get_string(Copyright),
+        software: file.get_string(Software),
+        artist: file.get_string(Artist),
+        comment: file.get_string(Comment),
+        date: file.get_string(Date),
+        album: file.get_string(Album),
+        license: file.get_string(License),
+        track_number: file.get_string(TrackNumber),
+        genre: file.get_string(Genre),
+    }
+}
+
+/// Set the tags which are Some on a file opened to be written.
+pub(crate) fn set_sound_tags(file: &mut SndFile, tags: &Tags) -> Result<(), SndFileError> {
+    let strings: [(StringSoundType, &Option<String>); 10] = [
+        (Title, &tags.title),
+        (Copyright, &tags.copyright),
+        (Software, &tags.software),
+        (Artist, &tags.artist),
+        (Comment, &tags.comment),
+        (Date, &tags.date),
+        (Album, &tags.album),
+        (License, &tags.license),
+        (TrackNumber, &tags.track_number),
+        (Genre, &tags.genre),
+    ];
+    for (string_type, string) in strings.iter() {
+        let string = match string {
+            Some(string) => string,
+            None => continue,
+        };
+        match file.set_string(*string_type, string.to_string()) {
+            Error::NoError => {}
+            err => {
+                return Err(file.last_error().unwrap_or_else(|| {
+                    SndFileError::UnsupportedEncoding(SndFile::error_number(err))
+                }))
+            }
+        }
     }
+    Ok(())
+}
+
+/**
+ * Write tags in an audio file.
+ *
+ * The tags which are Some replace the ones of the file, the others are
+ * kept. Not all the formats can store all the tags, see the documentation of
+ * libsndfile.
+ *
+ * # Arguments
+ * * `path` - The path of the file
+ * * `tags` - The tags to write
+ *
+ * # Return
+ * `Ok(())` if the tags have been written, Err(SoundError) otherwise.
+ *
+ * # Example
+ * ```no_run
+ * use ears::Tags;
+ *
+ * fn main() -> Result<(), ears::SoundError> {
+ *     let tags = Tags {
+ *         title: Some("Main theme".to_string()),
+ *         artist: Some("The band".to_string()),
+ *         ..Default::default()
+ *     };
+ *     ears::write_tags("res/explosion.wav", &tags)
+ * }
+ * ```
+ */
+pub fn write_tags<P: AsRef<Path>>(path: P, tags: &Tags) -> Result<(), SoundError> {
+    let mut file = SndFile::new(path, ReadWrite).map_err(SoundError::SaveError)?;
+    let written = set_sound_tags(&mut file, tags);
+    file.close();
+    written.map_err(SoundError::SaveError)
 }
 
 /// AudioTags trait implemented by all struct who can provides audio.
@@ -97,3 +181,66 @@ pub trait AudioTags {
     /// Get the tags of the audio source.
     fn get_tags(&self) -> Tags;
 }
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{get_sound_tags, write_tags, Tags};
+    use sndfile::OpenMode::Read;
+    use sndfile::SndFile;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    #[ignore]
+    fn audio_tags_write_OK() -> () {
+        let path = env::temp_dir().join("ears_audio_tags_write.wav");
+        fs::copy("res/explosion.wav", &path).unwrap();
+        let tags = Tags {
+            title: Some("Explosion".to_string()),
+            artist: Some("ears".to_string()),
+            ..Default::default()
+        };
+
+        write_tags(&path, &tags).unwrap();
+        let file = SndFile::new(&path, Read).unwrap();
+        let written = get_sound_tags(&file);
+        file.close();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(written.title, Some("Explosion".to_string()));
+        assert_eq!(written.artist, Some("ears".to_string()));
+        assert_eq!(written.album, None);
+    }
+
+    #[test]
+    fn audio_tags_year_track_OK() -> () {
+        let tags = Tags {
+            date: Some("1999-03-21".to_string()),
+            track_number: Some(" 3/12".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(tags.year(), Some(1999));
+        assert_eq!(tags.track(), Some(3));
+    }
+
+    #[test]
+    fn audio_tags_year_track_FAIL() -> () {
+        let tags = Tags {
+            date: Some("March 1999".to_string()),
+            track_number: Some("A1".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(tags.year(), None);
+        assert_eq!(tags.track(), None);
+        assert_eq!(Tags::default().year(), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn audio_tags_write_FAIL() -> () {
+        assert!(write_tags("res/missing.wav", &Tags::default()).is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! Velocities computed from the successive positions of a source or of the listener.
+
+use std::time::{Duration, Instant};
+
+/// Positions given closer in time are ignored, as their velocity would be noise
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+/// Positions given further apart are a jump, not a motion
+const MAX_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Follows the positions given to an Audio Source or to the listener.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct AutoVelocity {
+    enabled: bool,
+    /// The last position taken into account, and when it was given
+    last: Option<([f32; 3], Instant)>,
+}
+
+impl AutoVelocity {
+    /// Enable or disable the computation, forgetting the last position.
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.last = None;
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /**
+     * Take a new position into account.
+     *
+     * # Return
+     * The velocity to set, None if it shouldn't change.
+     */
+    pub(crate) fn update(&mut self, position: [f32; 3], now: Instant) -> Option<[f32; 3]> {
+        if !self.enabled {
+            return None;
+        }
+
+        let (last_position, last_time) = match self.last {
+            Some(last) => last,
+            None => {
+                self.last = Some((position, now));
+                return None;
+            }
+        };
+        let elapsed = now.saturating_duration_since(last_time);
+        if elapsed < MIN_INTERVAL {
+            return None;
+        }
+
+        self.last = Some((position, now));
+        if elapsed > MAX_INTERVAL {
+            return Some([0.0; 3]);
+        }
+        let seconds = elapsed.as_secs_f32();
+        Some([
+            (position[0] - last_position[0]) / seconds,
+            (position[1] - last_position[1]) / seconds,
+            (position[2] - last_position[2]) / seconds,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::AutoVelocity;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn auto_velocity_update_OK() -> () {
+        let mut auto_velocity = AutoVelocity::default();
+        let start = Instant::now();
+
+        assert_eq!(auto_velocity.update([0.0; 3], start), None);
+        auto_velocity.set_enabled(true);
+        assert_eq!(auto_velocity.update([0.0; 3], start), None);
+
+        let later = start + Duration::from_millis(100);
+        assert_eq!(
+            auto_velocity.update([1.0, 0.0, -2.0], later),
+            Some([10.0, 0.0, -20.0])
+        );
+        // Too close in time, the position is ignored
+        assert_eq!(auto_velocity.update([5.0; 3], later), None);
+    }
+
+    #[test]
+    fn auto_velocity_jump_OK() -> () {
+        let mut auto_velocity = AutoVelocity::default();
+        let start = Instant::now();
+        auto_velocity.set_enabled(true);
+
+        auto_velocity.update([0.0; 3], start);
+        assert_eq!(
+            auto_velocity.update([100.0; 3], start + Duration::from_secs(2)),
+            Some([0.0; 3])
+        );
+    }
+}
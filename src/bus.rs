@@ -0,0 +1,489 @@
+//! Buses grouping Audio Sources, to mix them together.
+
+use effect::{Effect, SlotHandle};
+use efx::max_auxiliary_sends;
+use internal::OpenAlData;
+use occlusion;
+use openal::{al, ffi};
+use reverb_effect::ReverbEffectError;
+use sources;
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The buses and the sources assigned to them
+    static ref MIXER: Mutex<Mixer> = Mutex::new(Mixer::default());
+}
+
+struct Mixer {
+    next_id: u64,
+    buses: HashMap<u64, BusSettings>,
+    sources: HashMap<u32, Member>,
+    /// The pitch of the sources outside of the buses, 1.0 if not set
+    pitches: HashMap<u32, f32>,
+    /// The pitch multiplying the pitch of all the sources
+    global_pitch: f32,
+}
+
+impl Default for Mixer {
+    fn default() -> Mixer {
+        Mixer {
+            next_id: 0,
+            buses: HashMap::new(),
+            sources: HashMap::new(),
+            pitches: HashMap::new(),
+            global_pitch: 1.0,
+        }
+    }
+}
+
+struct BusSettings {
+    volume: f32,
+    pitch: f32,
+    muted: bool,
+    /// The effect slot connected to each auxiliary send of the members
+    sends: HashMap<i32, SlotHandle>,
+}
+
+impl BusSettings {
+    fn gain(&self) -> f32 {
+        match self.muted {
+            true => 0.0,
+            false => self.volume,
+        }
+    }
+}
+
+/// A source assigned to a bus.
+struct Member {
+    bus: u64,
+    /// The volume of the source, before the volume of the bus
+    gain: f32,
+    /// The pitch of the source, before the pitch of the bus
+    pitch: f32,
+}
+
+impl Mixer {
+    /// Apply the settings of a bus to one of its sources.
+    fn update(&self, source: u32) {
+        if let Some(member) = self.sources.get(&source) {
+            let bus = &self.buses[&member.bus];
+            al::alSourcef(source, ffi::AL_GAIN, member.gain * bus.gain());
+            let pitch = member.pitch * bus.pitch * self.global_pitch;
+            al::alSourcef(source, ffi::AL_PITCH, pitch);
+        }
+    }
+
+    /// Apply the global pitch to a source outside of the buses.
+    fn update_pitch(&self, source: u32) {
+        let pitch = self.pitches.get(&source).cloned().unwrap_or(1.0);
+        al::alSourcef(source, ffi::AL_PITCH, pitch * self.global_pitch);
+    }
+
+    /// Apply the settings of a bus to all of its sources.
+    fn update_bus(&self, bus: u64) {
+        for (source, member) in self.sources.iter() {
+            if member.bus == bus {
+                self.update(*source);
+            }
+        }
+    }
+
+    /**
+     * Remove a source from its bus, with its own volume, pitch and auxiliary
+     * sends back.
+     */
+    fn remove(&mut self, source: u32) {
+        if let Some(member) = self.sources.remove(&source) {
+            al::alSourcef(source, ffi::AL_GAIN, member.gain);
+            self.pitches.insert(source, member.pitch);
+            self.update_pitch(source);
+            for send in self.buses[&member.bus].sends.keys() {
+                occlusion::connect_bus_send(source, *send, ffi::AL_EFFECTSLOT_NULL);
+            }
+        }
+    }
+}
+
+/**
+ * A bus, mixing a group of Audio Sources together, e.g. the sound effects,
+ * the music or the voices of a game.
+ *
+ * The volume and the pitch of a bus multiply the ones of its Audio Sources,
+ * and the effects connected to a bus are connected to all of its Audio
+ * Sources. An Audio Source is in a single bus at a time, see
+ * `AudioController::set_bus`.
+ *
+ * The master volume of all the Audio Sources is the volume of the listener,
+ * see `listener::set_volume`.
+ *
+ * Dropping a bus removes its Audio Sources from it.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Bus, Music, Sound};
+ *
+ * let sfx = Bus::new();
+ * let music_bus = Bus::new();
+ *
+ * let mut shot = Sound::new("path/to/shot.ogg").unwrap();
+ * shot.set_bus(Some(&sfx));
+ * let mut music = Music::new("path/to/music.ogg").unwrap();
+ * music.set_bus(Some(&music_bus));
+ *
+ * // Options menu
+ * sfx.set_volume(0.8);
+ * music_bus.set_muted(true);
+ * ears::listener::set_volume(0.5);
+ * ```
+ */
+pub struct Bus {
+    id: u64,
+}
+
+impl Bus {
+    /// Create a bus at full volume and normal pitch, with no Audio Source.
+    pub fn new() -> Bus {
+        let mut mixer = MIXER.lock().unwrap();
+        let id = mixer.next_id;
+        mixer.next_id += 1;
+        mixer.buses.insert(
+            id,
+            BusSettings {
+                volume: 1.0,
+                pitch: 1.0,
+                muted: false,
+                sends: HashMap::new(),
+            },
+        );
+        Bus { id }
+    }
+
+    /**
+     * Set the volume of the bus, multiplying the volume of its Audio
+     * Sources.
+     *
+     * # Argument
+     * * `volume` - The volume of the bus. Default is 1.0.
+     */
+    pub fn set_volume(&self, volume: f32) {
+        check_openal_context!();
+
+        let mut mixer = MIXER.lock().unwrap();
+        mixer.buses.get_mut(&self.id).unwrap().volume = volume.max(0.0);
+        mixer.update_bus(self.id);
+    }
+
+    /// Get the volume of the bus.
+    pub fn get_volume(&self) -> f32 {
+        MIXER.lock().unwrap().buses[&self.id].volume
+    }
+
+    /**
+     * Set the pitch of the bus, multiplying the pitch of its Audio Sources.
+     *
+     * # Argument
+     * * `pitch` - The pitch of the bus. Default is 1.0.
+     */
+    pub fn set_pitch(&self, pitch: f32) {
+        check_openal_context!();
+
+        let mut mixer = MIXER.lock().unwrap();
+        mixer.buses.get_mut(&self.id).unwrap().pitch = pitch.max(0.0);
+        mixer.update_bus(self.id);
+    }
+
+    /// Get the pitch of the bus.
+    pub fn get_pitch(&self) -> f32 {
+        MIXER.lock().unwrap().buses[&self.id].pitch
+    }
+
+    /**
+     * Mute or unmute the bus, keeping its volume.
+     *
+     * # Argument
+     * * `muted` - Whether the Audio Sources of the bus are silenced
+     */
+    pub fn set_muted(&self, muted: bool) {
+        check_openal_context!();
+
+        let mut mixer = MIXER.lock().unwrap();
+        mixer.buses.get_mut(&self.id).unwrap().muted = muted;
+        mixer.update_bus(self.id);
+    }
+
+    /// Whether the bus is muted.
+    pub fn is_muted(&self) -> bool {
+        MIXER.lock().unwrap().buses[&self.id].muted
+    }
+
+    /**
+     * Connect an effect to an auxiliary send of all the Audio Sources of
+     * the bus, including the ones assigned later.
+     *
+     * The bus holds the effect, which keeps working after being dropped
+     * until it is disconnected from the bus. An Audio Source connecting its
+     * own effect to the same send, see `AudioController::connect_send`,
+     * takes precedence over the one of the bus.
+     *
+     * # Arguments
+     * * `send` - The index of the auxiliary send, below `max_auxiliary_sends()`
+     * * `effect` - The effect to connect, or an `Option` of an effect which
+     *   is None to disconnect the send
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * effects or the send doesn't exist.
+     */
+    pub fn connect_send(&self, send: i32, effect: &dyn Effect) -> Result<(), ReverbEffectError> {
+        check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
+
+        let slot = effect.slot() as i32;
+        if !OpenAlData::efx_capable() {
+            return match slot {
+                ffi::AL_EFFECTSLOT_NULL => Ok(()),
+                _ => Err(ReverbEffectError::EfxUnsupported),
+            };
+        }
+        if send < 0 || send >= max_auxiliary_sends() {
+            return Err(ReverbEffectError::InvalidSend(send));
+        }
+
+        let mut mixer = MIXER.lock().unwrap();
+        let sends = &mut mixer.buses.get_mut(&self.id).unwrap().sends;
+        // The previous slot is released once the sources left it
+        let previous = match SlotHandle::new(effect) {
+            Some(handle) => sends.insert(send, handle),
+            None => sends.remove(&send),
+        };
+        for (source, member) in mixer.sources.iter() {
+            if member.bus == self.id {
+                occlusion::connect_bus_send(*source, send, slot);
+            }
+        }
+        drop(previous);
+        Ok(())
+    }
+
+    /**
+     * Connect an effect to the first auxiliary send of all the Audio Sources
+     * of the bus.
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * effects.
+     */
+    pub fn connect(&self, effect: &dyn Effect) -> Result<(), ReverbEffectError> {
+        self.connect_send(0, effect)
+    }
+}
+
+impl Default for Bus {
+    fn default() -> Bus {
+        Bus::new()
+    }
+}
+
+impl Drop for Bus {
+    fn drop(&mut self) {
+        // The Audio Sources are left in the bus only while the context exists
+        let mut mixer = MIXER.lock().unwrap();
+        let members: Vec<u32> = mixer
+            .sources
+            .iter()
+            .filter(|(_, member)| member.bus == self.id)
+            .map(|(source, _)| *source)
+            .collect();
+        for source in members {
+            mixer.remove(source);
+        }
+        mixer.buses.remove(&self.id);
+    }
+}
+
+/**
+ * Assign a source to a bus, or remove it from its bus with None.
+ *
+ * The source keeps its own volume and pitch, multiplied by the ones of the
+ * bus.
+ */
+pub(crate) fn set_source_bus(source: u32, bus: Option<&Bus>) {
+    let mut mixer = MIXER.lock().unwrap();
+    let mut gain = 1.0;
+    match mixer.sources.get(&source) {
+        Some(member) => gain = member.gain,
+        None => al::alGetSourcef(source, ffi::AL_GAIN, &mut gain),
+    }
+    mixer.remove(source);
+
+    if let Some(bus) = bus {
+        let pitch = mixer.pitches.remove(&source).unwrap_or(1.0);
+        mixer.sources.insert(
+            source,
+            Member {
+                bus: bus.id,
+                gain,
+                pitch,
+            },
+        );
+        for (send, handle) in mixer.buses[&bus.id].sends.iter() {
+            occlusion::connect_bus_send(source, *send, handle.slot() as i32);
+        }
+        mixer.update(source);
+    }
+}
+
+/// Whether a source is assigned to a bus.
+pub(crate) fn source_in_bus(source: u32, bus: &Bus) -> bool {
+    MIXER
+        .lock()
+        .unwrap()
+        .sources
+        .get(&source)
+        .is_some_and(|member| member.bus == bus.id)
+}
+
+/// Set the gain of a source, multiplied by the volume of its bus.
+pub(crate) fn set_source_gain(source: u32, gain: f32) {
+    let mut mixer = MIXER.lock().unwrap();
+    match mixer.sources.get_mut(&source) {
+        Some(member) => member.gain = gain,
+        None => return al::alSourcef(source, ffi::AL_GAIN, gain),
+    }
+    mixer.update(source);
+}
+
+/// Get the gain of a source, without the volume of its bus.
+pub(crate) fn source_gain(source: u32) -> f32 {
+    if let Some(member) = MIXER.lock().unwrap().sources.get(&source) {
+        return member.gain;
+    }
+    let mut gain = 0.0;
+    al::alGetSourcef(source, ffi::AL_GAIN, &mut gain);
+    gain
+}
+
+/// Set the pitch of a source, multiplied by the pitch of its bus.
+pub(crate) fn set_source_pitch(source: u32, pitch: f32) {
+    // OpenAL rejects negative pitches, so the previous pitch is kept
+    if pitch < 0.0 {
+        return;
+    }
+    let mut mixer = MIXER.lock().unwrap();
+    match mixer.sources.get_mut(&source) {
+        Some(member) => member.pitch = pitch,
+        None => {
+            mixer.pitches.insert(source, pitch);
+            return mixer.update_pitch(source);
+        }
+    }
+    mixer.update(source);
+}
+
+/// Get the pitch of a source, without the pitch of its bus and the global pitch.
+pub(crate) fn source_pitch(source: u32) -> f32 {
+    let mixer = MIXER.lock().unwrap();
+    match mixer.sources.get(&source) {
+        Some(member) => member.pitch,
+        None => mixer.pitches.get(&source).cloned().unwrap_or(1.0),
+    }
+}
+
+/// Apply the global pitch to a new source.
+pub(crate) fn add_source(source: u32) {
+    let mixer = MIXER.lock().unwrap();
+    if !mixer.sources.contains_key(&source) {
+        mixer.update_pitch(source);
+    }
+}
+
+/// Forget a source being deleted.
+pub(crate) fn remove_source(source: u32) {
+    let mut mixer = MIXER.lock().unwrap();
+    mixer.sources.remove(&source);
+    mixer.pitches.remove(&source);
+}
+
+/// Forget all the sources and effect slots, when the context is cleaned up.
+pub(crate) fn clear() {
+    let mut mixer = MIXER.lock().unwrap();
+    mixer.sources.clear();
+    mixer.pitches.clear();
+    let sends: Vec<_> = mixer
+        .buses
+        .values_mut()
+        .map(|bus| mem::take(&mut bus.sends))
+        .collect();
+    drop(sends);
+}
+
+/**
+ * Set the pitch of all the Audio Sources, e.g. for a slow motion effect.
+ *
+ * The global pitch multiplies the pitch of each Audio Source and of its bus,
+ * which are kept: setting it back to 1.0 restores them.
+ *
+ * # Argument
+ * * `pitch` - The global pitch. Default is 1.0.
+ *
+ * # Example
+ * ```no_run
+ * // Bullet time
+ * ears::set_global_pitch(0.5);
+ * // Back to normal
+ * ears::set_global_pitch(1.0);
+ * ```
+ */
+pub fn set_global_pitch(pitch: f32) {
+    check_openal_context!();
+
+    let sources = sources::all();
+    let mut mixer = MIXER.lock().unwrap();
+    mixer.global_pitch = pitch.max(0.0);
+    for source in sources {
+        match mixer.sources.contains_key(&source) {
+            true => mixer.update(source),
+            false => mixer.update_pitch(source),
+        }
+    }
+}
+
+/// Get the pitch of all the Audio Sources.
+pub fn get_global_pitch() -> f32 {
+    MIXER.lock().unwrap().global_pitch
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{get_global_pitch, set_global_pitch, Bus};
+
+    #[test]
+    #[ignore]
+    fn bus_set_volume_OK() -> () {
+        let bus = Bus::new();
+
+        bus.set_volume(0.5);
+        assert_eq!(bus.get_volume(), 0.5);
+    }
+
+    #[test]
+    #[ignore]
+    fn bus_set_muted_OK() -> () {
+        let bus = Bus::new();
+
+        bus.set_muted(true);
+        assert!(bus.is_muted());
+        assert_eq!(bus.get_volume(), 1.0);
+    }
+
+    #[test]
+    #[ignore]
+    fn bus_set_global_pitch_OK() -> () {
+        set_global_pitch(0.5);
+        assert_eq!(get_global_pitch(), 0.5);
+        set_global_pitch(1.0);
+    }
+}
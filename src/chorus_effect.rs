@@ -0,0 +1,364 @@
+//! The chorus and flanger effects.
+
+use effect::{Effect, EffectSlot};
+use openal::ffi;
+use reverb_effect::ReverbEffectError;
+
+/// The shape of the oscillator of a chorus or a flanger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Waveform {
+    Sinusoid,
+    Triangle,
+}
+
+impl Waveform {
+    /// The OpenAL identifier of the waveform, the same for both effects.
+    fn al_waveform(self) -> i32 {
+        match self {
+            Waveform::Sinusoid => ffi::AL_CHORUS_WAVEFORM_SINUSOID,
+            Waveform::Triangle => ffi::AL_CHORUS_WAVEFORM_TRIANGLE,
+        }
+    }
+}
+
+/**
+ * A chorus effect, thickening the Audio Sources connected to it.
+ *
+ * The chorus mixes the sound with copies of itself, delayed by a slowly
+ * varying amount, as if played by several instruments at once.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, ChorusEffect, Music};
+ * use std::error::Error;
+ *
+ * fn main() -> Result<(), Box<dyn Error>> {
+ *     let mut chorus = ChorusEffect::new()?;
+ *     chorus.set_depth(0.3);
+ *
+ *     let mut music = Music::new("path/to/my/music.ogg")?;
+ *     music.connect(&chorus)?;
+ *     music.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct ChorusEffect {
+    effect: EffectSlot,
+    waveform: Waveform,
+    phase: i32,
+    rate: f32,
+    depth: f32,
+    feedback: f32,
+    delay: f32,
+}
+
+impl ChorusEffect {
+    /**
+     * Create a chorus effect with the default settings.
+     *
+     * # Return
+     * A `Result` containing Ok(ChorusEffect) on success,
+     * Err(ReverbEffectError) if the device doesn't support effects or there
+     * has been an error.
+     */
+    pub fn new() -> Result<ChorusEffect, ReverbEffectError> {
+        Ok(ChorusEffect {
+            effect: EffectSlot::new(ffi::AL_EFFECT_CHORUS)?,
+            waveform: Waveform::Triangle,
+            phase: 90,
+            rate: 1.1,
+            depth: 0.1,
+            feedback: 0.25,
+            delay: 0.016,
+        })
+    }
+
+    /**
+     * Set the shape of the oscillator modulating the delay.
+     *
+     * # Argument
+     * * `waveform` - The waveform. Default is `Waveform::Triangle`.
+     */
+    pub fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
+        self.effect
+            .set_i(ffi::AL_CHORUS_WAVEFORM, waveform.al_waveform());
+    }
+
+    /// Get the shape of the oscillator modulating the delay.
+    pub fn get_waveform(&self) -> Waveform {
+        self.waveform
+    }
+
+    /**
+     * Set the phase difference between the oscillators of the left and the
+     * right channels.
+     *
+     * # Argument
+     * * `phase` - The phase in degrees, between -180 and 180. Default is 90.
+     */
+    pub fn set_phase(&mut self, phase: i32) {
+        self.phase = phase.clamp(-180, 180);
+        self.effect.set_i(ffi::AL_CHORUS_PHASE, self.phase);
+    }
+
+    /// Get the phase difference between the oscillators, in degrees.
+    pub fn get_phase(&self) -> i32 {
+        self.phase
+    }
+
+    /**
+     * Set the frequency of the oscillator.
+     *
+     * # Argument
+     * * `rate` - The rate in Hz, between 0.0 and 10.0. Default is 1.1.
+     */
+    pub fn set_rate(&mut self, rate: f32) {
+        self.rate = rate.clamp(0.0, 10.0);
+        self.effect.set_f(ffi::AL_CHORUS_RATE, self.rate);
+    }
+
+    /// Get the frequency of the oscillator, in Hz.
+    pub fn get_rate(&self) -> f32 {
+        self.rate
+    }
+
+    /**
+     * Set how much the oscillator modulates the delay.
+     *
+     * # Argument
+     * * `depth` - The depth, between 0.0 and 1.0. Default is 0.1.
+     */
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth.clamp(0.0, 1.0);
+        self.effect.set_f(ffi::AL_CHORUS_DEPTH, self.depth);
+    }
+
+    /// Get how much the oscillator modulates the delay.
+    pub fn get_depth(&self) -> f32 {
+        self.depth
+    }
+
+    /**
+     * Set how much of the output is fed back into the effect.
+     *
+     * # Argument
+     * * `feedback` - The feedback, between -1.0 and 1.0, negative to invert
+     *   the phase. Default is 0.25.
+     */
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(-1.0, 1.0);
+        self.effect.set_f(ffi::AL_CHORUS_FEEDBACK, self.feedback);
+    }
+
+    /// Get how much of the output is fed back into the effect.
+    pub fn get_feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /**
+     * Set the average delay between the sound and its copy.
+     *
+     * # Argument
+     * * `delay` - The delay in seconds, between 0.0 and 0.016. Default is 0.016.
+     */
+    pub fn set_delay(&mut self, delay: f32) {
+        self.delay = delay.clamp(0.0, 0.016);
+        self.effect.set_f(ffi::AL_CHORUS_DELAY, self.delay);
+    }
+
+    /// Get the average delay between the sound and its copy, in seconds.
+    pub fn get_delay(&self) -> f32 {
+        self.delay
+    }
+}
+
+impl Effect for ChorusEffect {
+    fn slot(&self) -> u32 {
+        self.effect.slot()
+    }
+}
+
+/**
+ * A flanger effect, sweeping through the Audio Sources connected to it.
+ *
+ * The flanger mixes the sound with a copy of itself delayed by a very short
+ * and varying amount, giving a swooshing sound.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, FlangerEffect, Sound};
+ * use std::error::Error;
+ *
+ * fn main() -> Result<(), Box<dyn Error>> {
+ *     let flanger = FlangerEffect::new()?;
+ *
+ *     let mut snd = Sound::new("path/to/my/sound.ogg")?;
+ *     snd.connect(&flanger)?;
+ *     snd.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct FlangerEffect {
+    effect: EffectSlot,
+    waveform: Waveform,
+    phase: i32,
+    rate: f32,
+    depth: f32,
+    feedback: f32,
+    delay: f32,
+}
+
+impl FlangerEffect {
+    /**
+     * Create a flanger effect with the default settings.
+     *
+     * # Return
+     * A `Result` containing Ok(FlangerEffect) on success,
+     * Err(ReverbEffectError) if the device doesn't support effects or there
+     * has been an error.
+     */
+    pub fn new() -> Result<FlangerEffect, ReverbEffectError> {
+        Ok(FlangerEffect {
+            effect: EffectSlot::new(ffi::AL_EFFECT_FLANGER)?,
+            waveform: Waveform::Triangle,
+            phase: 0,
+            rate: 0.27,
+            depth: 1.0,
+            feedback: -0.5,
+            delay: 0.002,
+        })
+    }
+
+    /**
+     * Set the shape of the oscillator modulating the delay.
+     *
+     * # Argument
+     * * `waveform` - The waveform. Default is `Waveform::Triangle`.
+     */
+    pub fn set_waveform(&mut self, waveform: Waveform) {
+        self.waveform = waveform;
+        self.effect
+            .set_i(ffi::AL_FLANGER_WAVEFORM, waveform.al_waveform());
+    }
+
+    /// Get the shape of the oscillator modulating the delay.
+    pub fn get_waveform(&self) -> Waveform {
+        self.waveform
+    }
+
+    /**
+     * Set the phase difference between the oscillators of the left and the
+     * right channels.
+     *
+     * # Argument
+     * * `phase` - The phase in degrees, between -180 and 180. Default is 0.
+     */
+    pub fn set_phase(&mut self, phase: i32) {
+        self.phase = phase.clamp(-180, 180);
+        self.effect.set_i(ffi::AL_FLANGER_PHASE, self.phase);
+    }
+
+    /// Get the phase difference between the oscillators, in degrees.
+    pub fn get_phase(&self) -> i32 {
+        self.phase
+    }
+
+    /**
+     * Set the frequency of the oscillator.
+     *
+     * # Argument
+     * * `rate` - The rate in Hz, between 0.0 and 10.0. Default is 0.27.
+     */
+    pub fn set_rate(&mut self, rate: f32) {
+        self.rate = rate.clamp(0.0, 10.0);
+        self.effect.set_f(ffi::AL_FLANGER_RATE, self.rate);
+    }
+
+    /// Get the frequency of the oscillator, in Hz.
+    pub fn get_rate(&self) -> f32 {
+        self.rate
+    }
+
+    /**
+     * Set how much the oscillator modulates the delay.
+     *
+     * # Argument
+     * * `depth` - The depth, between 0.0 and 1.0. Default is 1.0.
+     */
+    pub fn set_depth(&mut self, depth: f32) {
+        self.depth = depth.clamp(0.0, 1.0);
+        self.effect.set_f(ffi::AL_FLANGER_DEPTH, self.depth);
+    }
+
+    /// Get how much the oscillator modulates the delay.
+    pub fn get_depth(&self) -> f32 {
+        self.depth
+    }
+
+    /**
+     * Set how much of the output is fed back into the effect.
+     *
+     * # Argument
+     * * `feedback` - The feedback, between -1.0 and 1.0, negative to invert
+     *   the phase. Default is -0.5.
+     */
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(-1.0, 1.0);
+        self.effect.set_f(ffi::AL_FLANGER_FEEDBACK, self.feedback);
+    }
+
+    /// Get how much of the output is fed back into the effect.
+    pub fn get_feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /**
+     * Set the average delay between the sound and its copy.
+     *
+     * # Argument
+     * * `delay` - The delay in seconds, between 0.0 and 0.004. Default is 0.002.
+     */
+    pub fn set_delay(&mut self, delay: f32) {
+        self.delay = delay.clamp(0.0, 0.004);
+        self.effect.set_f(ffi::AL_FLANGER_DELAY, self.delay);
+    }
+
+    /// Get the average delay between the sound and its copy, in seconds.
+    pub fn get_delay(&self) -> f32 {
+        self.delay
+    }
+}
+
+impl Effect for FlangerEffect {
+    fn slot(&self) -> u32 {
+        self.effect.slot()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use chorus_effect::{ChorusEffect, FlangerEffect, Waveform};
+
+    #[test]
+    #[ignore]
+    fn chorus_effect_set_waveform_OK() -> () {
+        let mut chorus = ChorusEffect::new().expect("Cannot create the effect");
+
+        chorus.set_waveform(Waveform::Sinusoid);
+        assert_eq!(chorus.get_waveform(), Waveform::Sinusoid);
+    }
+
+    #[test]
+    #[ignore]
+    fn flanger_effect_set_delay_FAIL() -> () {
+        let mut flanger = FlangerEffect::new().expect("Cannot create the effect");
+
+        flanger.set_delay(0.1);
+        assert_eq!(flanger.get_delay(), 0.004);
+    }
+}
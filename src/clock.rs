@@ -0,0 +1,139 @@
+//! Device clocks, to align audio with other events.
+
+use internal::OpenAlData;
+use libc::c_void;
+use openal::ffi;
+use std::ffi::CString;
+use std::mem;
+use std::time::Duration;
+
+/// The clock of an OpenAL device, from the ALC_SOFT_device_clock extension.
+#[derive(Clone, Copy)]
+pub(crate) struct DeviceClock {
+    device: ffi::ALCdevicePtr,
+    get_integer64v: ffi::GetInteger64vFn,
+}
+
+impl DeviceClock {
+    /// Get the clock of a device, if the device supports the extension.
+    pub(crate) fn new(device: ffi::ALCdevicePtr) -> Option<DeviceClock> {
+        if device == 0 {
+            return None;
+        }
+        let extension = CString::new("ALC_SOFT_device_clock").unwrap();
+        let function = CString::new("alcGetInteger64vSOFT").unwrap();
+        let get_integer64v = unsafe {
+            if ffi::alcIsExtensionPresent(device, extension.as_ptr()) == ffi::ALC_FALSE {
+                return None;
+            }
+            ffi::alcGetProcAddress(device, function.as_ptr())
+        };
+        if get_integer64v.is_null() {
+            return None;
+        }
+        Some(DeviceClock {
+            device,
+            get_integer64v: unsafe {
+                mem::transmute::<*mut c_void, ffi::GetInteger64vFn>(get_integer64v)
+            },
+        })
+    }
+
+    /**
+     * Read the clock and the latency of the device.
+     *
+     * # Return
+     * The time elapsed on the device clock and the latency of the device,
+     * both in nanoseconds, read at the same instant.
+     */
+    pub(crate) fn clock_latency(&self) -> (i64, i64) {
+        let mut values = [0i64; 2];
+        unsafe {
+            (self.get_integer64v)(
+                self.device,
+                ffi::ALC_DEVICE_CLOCK_LATENCY_SOFT,
+                2,
+                values.as_mut_ptr(),
+            );
+        }
+        (values[0], values[1])
+    }
+
+    /// Get the clock of the playback device, if it supports the extension.
+    pub(crate) fn playback() -> Option<DeviceClock> {
+        DeviceClock::new(unsafe { ffi::alcGetContextsDevice(ffi::alcGetCurrentContext()) })
+    }
+}
+
+/**
+ * Compute when the first sample of captured audio entered the capture device.
+ *
+ * The capture latency is subtracted from the playback clock read at the same
+ * time, so the result is on the playback clock.
+ *
+ * # Arguments
+ * * `clock` - The playback clock when the samples were read, in nanoseconds
+ * * `latency` - The capture latency when the samples were read, in nanoseconds
+ * * `samples` - The number of samples read
+ * * `sample_rate` - The sample rate of the capture
+ *
+ * # Return
+ * The time of the first sample on the playback device clock.
+ */
+pub(crate) fn capture_start_time(
+    clock: i64,
+    latency: i64,
+    samples: i64,
+    sample_rate: i64,
+) -> Duration {
+    let duration = samples * 1_000_000_000 / sample_rate;
+    Duration::from_nanos((clock - latency - duration).max(0) as u64)
+}
+
+/**
+ * Get the current time on the clock of the playback device.
+ *
+ * The device clock advances with the audio actually processed by the
+ * device, so it's the one to use to align played audio with captured
+ * audio and with game events, for instance in a replay system.
+ *
+ * # Return
+ * The time elapsed on the device clock, or `None` if the device doesn't
+ * support the ALC_SOFT_device_clock extension.
+ *
+ * # Example
+ * ```no_run
+ * if let Some(clock) = ears::device_clock() {
+ *     println!("The device clock is at {:?}", clock);
+ * }
+ * ```
+ */
+pub fn device_clock() -> Option<Duration> {
+    check_openal_context!(None);
+
+    DeviceClock::playback().map(|clock| Duration::from_nanos(clock.clock_latency().0.max(0) as u64))
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::capture_start_time;
+    use std::time::Duration;
+
+    #[test]
+    fn clock_capture_start_time_OK() -> () {
+        // 4410 samples are 100ms at 44100Hz
+        let start = capture_start_time(1_000_000_000, 20_000_000, 4410, 44100);
+
+        assert_eq!(start, Duration::from_millis(880));
+    }
+
+    #[test]
+    fn clock_capture_start_time_before_start_FAIL() -> () {
+        assert_eq!(
+            capture_start_time(0, 20_000_000, 4410, 44100),
+            Duration::from_secs(0)
+        );
+    }
+}
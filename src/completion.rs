@@ -0,0 +1,89 @@
+//! Callbacks called when a Sound finishes playing.
+
+use openal::{al, ffi};
+use std::collections::HashMap;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// A function called once an Audio Source played to its end
+pub(crate) type CompleteCallback = Box<dyn FnMut() + Send>;
+
+/// The callback of an Audio Source, shared with the Audio Source
+pub(crate) type SharedCompleteCallback = Arc<Mutex<Option<CompleteCallback>>>;
+
+/// How often the watched sources are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+lazy_static! {
+    /// The playing sources with a completion callback, and whether the watcher thread runs
+    static ref WATCHED_SOURCES: Mutex<(HashMap<u32, SharedCompleteCallback>, bool)> =
+        Mutex::new((HashMap::new(), false));
+}
+
+/**
+ * Call the callback of a source once it stops playing by itself.
+ *
+ * A single thread watches all the sources, and only runs while some are
+ * watched.
+ */
+pub(crate) fn watch(source: u32, callback: SharedCompleteCallback) {
+    let mut watched = WATCHED_SOURCES.lock().unwrap();
+    watched.0.insert(source, callback);
+    if !watched.1 {
+        watched.1 = true;
+        thread::Builder::new()
+            .name(String::from("ears-completion"))
+            .spawn(watch_sources)
+            .unwrap();
+    }
+}
+
+/// Stop watching a source, which was stopped on purpose or deleted.
+pub(crate) fn unwatch(source: u32) {
+    WATCHED_SOURCES.lock().unwrap().0.remove(&source);
+}
+
+/// Forget all the watched sources, when the context is cleaned up.
+pub(crate) fn clear() {
+    let watched = mem::take(&mut WATCHED_SOURCES.lock().unwrap().0);
+    drop(watched);
+}
+
+fn watch_sources() {
+    loop {
+        sleep(POLL_INTERVAL);
+
+        let completed: Vec<SharedCompleteCallback> = {
+            let mut watched = WATCHED_SOURCES.lock().unwrap();
+            let stopped: Vec<u32> = watched
+                .0
+                .keys()
+                .cloned()
+                .filter(|source| al::alGetState(*source) == ffi::AL_STOPPED)
+                .collect();
+            let completed = stopped
+                .iter()
+                .filter_map(|source| watched.0.remove(source))
+                .collect();
+            if watched.0.is_empty() {
+                watched.1 = false;
+                return call_all(completed);
+            }
+            completed
+        };
+
+        // The callbacks are called without the lock, so they can play sounds
+        call_all(completed);
+    }
+}
+
+fn call_all(completed: Vec<SharedCompleteCallback>) {
+    for callback in completed {
+        if let Some(ref mut callback) = *callback.lock().unwrap() {
+            callback();
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! Upload compressed audio data without expanding it to PCM16.
+//!
+//! Some OpenAL implementations can store IMA4, MSADPCM and mu-law buffers
+//! directly. When a WAV file already uses one of those encodings its data
+//! chunk is uploaded as is, which uses about a quarter of the memory.
+
+use std::fs;
+use std::path::Path;
+
+use internal::OpenAlData;
+use limits;
+use openal::ffi;
+use sndfile::{FormatType, SndInfo};
+
+/// Compressed samples ready to be given to alBufferData.
+pub struct CompressedData {
+    /// The OpenAL format of the data
+    pub format: i32,
+    /// The number of sample frames per block, for block based formats
+    pub block_alignment: Option<i32>,
+    /// The encoded samples
+    pub data: Vec<u8>,
+}
+
+/// The parts of a WAV file needed to upload its data chunk.
+#[derive(Debug, PartialEq)]
+struct WavChunks<'a> {
+    format_tag: u16,
+    channels: u16,
+    block_align: u16,
+    data: &'a [u8],
+}
+
+const WAVE_FORMAT_MS_ADPCM: u16 = 0x0002;
+const WAVE_FORMAT_MULAW: u16 = 0x0007;
+const WAVE_FORMAT_IMA_ADPCM: u16 = 0x0011;
+
+// Block alignments OpenAL uses when AL_SOFT_block_alignment isn't available
+const DEFAULT_IMA4_ALIGNMENT: i32 = 65;
+const DEFAULT_MSADPCM_ALIGNMENT: i32 = 64;
+
+fn read_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let b = bytes.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let b = bytes.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+// Walk the RIFF chunks to find the fmt and data chunks.
+fn parse_wav(bytes: &[u8]) -> Option<WavChunks<'_>> {
+    if bytes.get(0..4)? != b"RIFF" || bytes.get(8..12)? != b"WAVE" {
+        return None;
+    }
+
+    let mut fmt = None;
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        let id = &bytes[offset..offset + 4];
+        let size = read_u32(bytes, offset + 4)? as usize;
+        let body = offset + 8;
+        match id {
+            b"fmt " => {
+                fmt = Some((
+                    read_u16(bytes, body)?,
+                    read_u16(bytes, body + 2)?,
+                    read_u16(bytes, body + 12)?,
+                ));
+            }
+            b"data" => {
+                let (format_tag, channels, block_align) = fmt?;
+                let end = bytes.len().min(body.checked_add(size)?);
+                return Some(WavChunks {
+                    format_tag,
+                    channels,
+                    block_align,
+                    data: &bytes[body..end],
+                });
+            }
+            _ => {}
+        }
+        // Chunks are padded to an even size
+        offset = body.checked_add(size)?.checked_add(size & 1)?;
+    }
+    None
+}
+
+// Pick the OpenAL format and block alignment for the chunks, if supported.
+fn al_format(wav: &WavChunks, block_alignment_capable: bool) -> Option<(i32, Option<i32>)> {
+    let channels = wav.channels as i32;
+    let stereo = match channels {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+
+    let (format, alignment, default_alignment) = match wav.format_tag {
+        WAVE_FORMAT_MULAW => {
+            if !OpenAlData::extension_present("AL_EXT_MULAW") {
+                return None;
+            }
+            let format = match stereo {
+                true => ffi::AL_FORMAT_STEREO_MULAW_EXT,
+                false => ffi::AL_FORMAT_MONO_MULAW_EXT,
+            };
+            return Some((format, None));
+        }
+        WAVE_FORMAT_IMA_ADPCM => {
+            if !OpenAlData::extension_present("AL_EXT_IMA4") {
+                return None;
+            }
+            // A 4 byte header per channel holds the first sample
+            let header = 4 * channels;
+            let alignment = (wav.block_align as i32 - header) * 2 / channels + 1;
+            let format = match stereo {
+                true => ffi::AL_FORMAT_STEREO_IMA4,
+                false => ffi::AL_FORMAT_MONO_IMA4,
+            };
+            (format, alignment, DEFAULT_IMA4_ALIGNMENT)
+        }
+        WAVE_FORMAT_MS_ADPCM => {
+            if !OpenAlData::extension_present("AL_SOFT_MSADPCM") {
+                return None;
+            }
+            // A 7 byte header per channel holds the first two samples
+            let header = 7 * channels;
+            let alignment = (wav.block_align as i32 - header) * 2 / channels + 2;
+            let format = match stereo {
+                true => ffi::AL_FORMAT_STEREO_MSADPCM_SOFT,
+                false => ffi::AL_FORMAT_MONO_MSADPCM_SOFT,
+            };
+            (format, alignment, DEFAULT_MSADPCM_ALIGNMENT)
+        }
+        _ => return None,
+    };
+
+    if alignment <= 0 {
+        None
+    } else if block_alignment_capable {
+        Some((format, Some(alignment)))
+    } else if alignment == default_alignment {
+        Some((format, None))
+    } else {
+        None
+    }
+}
+
+/**
+ * Load the data chunk of a compressed WAV file.
+ *
+ * # Return
+ * `Some(CompressedData)` if the file is IMA ADPCM, MS ADPCM or mu-law
+ * encoded and the OpenAL implementation can store it, `None` if the
+ * samples have to be decoded to PCM16.
+ */
+pub fn load(path: &Path, info: &SndInfo) -> Option<CompressedData> {
+    let major = info.format & FormatType::FormatTypeMask as i32;
+    let sub = info.format & FormatType::FormatSubMask as i32;
+    if major != FormatType::FormatWav as i32 {
+        return None;
+    }
+    if sub != FormatType::FormatUlaw as i32
+        && sub != FormatType::FormatImaAdpcm as i32
+        && sub != FormatType::FormatApcm as i32
+    {
+        return None;
+    }
+
+    if !limits::check_file_size(fs::metadata(path).ok()?.len()) {
+        return None;
+    }
+    let bytes = fs::read(path).ok()?;
+    let wav = parse_wav(&bytes)?;
+    let block_alignment_capable = OpenAlData::extension_present("AL_SOFT_block_alignment");
+    let (format, block_alignment) = al_format(&wav, block_alignment_capable)?;
+
+    Some(CompressedData {
+        format,
+        block_alignment,
+        data: wav.data.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{parse_wav, WavChunks, WAVE_FORMAT_IMA_ADPCM};
+
+    fn chunk(id: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(body);
+        if body.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    fn wav(chunks: &[Vec<u8>]) -> Vec<u8> {
+        let mut body = b"WAVE".to_vec();
+        for c in chunks {
+            body.extend_from_slice(c);
+        }
+        chunk(b"RIFF", &body)
+    }
+
+    fn fmt(format_tag: u16, channels: u16, block_align: u16) -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(&format_tag.to_le_bytes());
+        body.extend_from_slice(&channels.to_le_bytes());
+        body.extend_from_slice(&22050u32.to_le_bytes());
+        body.extend_from_slice(&11100u32.to_le_bytes());
+        body.extend_from_slice(&block_align.to_le_bytes());
+        body.extend_from_slice(&4u16.to_le_bytes());
+        chunk(b"fmt ", &body)
+    }
+
+    #[test]
+    fn parse_wav_OK() -> () {
+        let bytes = wav(&[
+            fmt(WAVE_FORMAT_IMA_ADPCM, 1, 36),
+            chunk(b"fact", &[1, 2, 3]),
+            chunk(b"data", &[7; 36]),
+        ]);
+
+        assert_eq!(
+            parse_wav(&bytes),
+            Some(WavChunks {
+                format_tag: WAVE_FORMAT_IMA_ADPCM,
+                channels: 1,
+                block_align: 36,
+                data: &[7; 36],
+            })
+        );
+    }
+
+    #[test]
+    fn parse_wav_no_fmt_FAIL() -> () {
+        let bytes = wav(&[chunk(b"data", &[7; 36])]);
+
+        assert_eq!(parse_wav(&bytes), None);
+    }
+
+    #[test]
+    fn parse_wav_not_riff_FAIL() -> () {
+        assert_eq!(parse_wav(b"OggS0000WAVE"), None);
+    }
+}
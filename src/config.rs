@@ -0,0 +1,90 @@
+//! Device selection from the environment.
+//!
+//! The playback device is read from the `EARS_DEVICE` environment variable
+//! and the capture device from `EARS_CAPTURE_DEVICE`. When a variable is not
+//! set, the `device` and `capture-device` keys of the file pointed to by
+//! `EARS_CONFIG` are used instead, one `key = value` per line, with `#`
+//! starting a comment.
+
+use std::env;
+use std::fs;
+
+use logging::{self, LogLevel};
+
+const DEVICE_VAR: &str = "EARS_DEVICE";
+const CAPTURE_DEVICE_VAR: &str = "EARS_CAPTURE_DEVICE";
+const CONFIG_VAR: &str = "EARS_CONFIG";
+
+/// Get the name of the playback device to open, if any was configured.
+pub fn device() -> Option<String> {
+    setting(DEVICE_VAR, "device")
+}
+
+/// Get the name of the capture device to open, if any was configured.
+pub fn capture_device() -> Option<String> {
+    setting(CAPTURE_DEVICE_VAR, "capture-device")
+}
+
+fn setting(var: &str, key: &str) -> Option<String> {
+    match env::var(var) {
+        Ok(ref value) if !value.is_empty() => Some(value.clone()),
+        _ => {
+            let path = env::var_os(CONFIG_VAR)?;
+            match fs::read_to_string(&path) {
+                Ok(contents) => parse_value(&contents, key),
+                Err(err) => {
+                    let message = format!("cannot read {}: {}", path.to_string_lossy(), err);
+                    logging::log(LogLevel::Warning, &message);
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// Find the value of a key in a config file, the last occurrence wins.
+fn parse_value(contents: &str, key: &str) -> Option<String> {
+    contents
+        .lines()
+        .rev()
+        .map(|line| line.split('#').next().unwrap_or(""))
+        .filter_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(k), Some(v)) if k.trim() == key => Some(v.trim().to_string()),
+                _ => None,
+            }
+        })
+        .find(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::parse_value;
+
+    #[test]
+    fn config_parse_value_OK() -> () {
+        let contents = "# audio setup\n\
+                        device = OpenAL Soft on Headphones\n\
+                        capture-device=USB Microphone # the good one\n";
+
+        assert_eq!(
+            parse_value(contents, "device"),
+            Some("OpenAL Soft on Headphones".to_string())
+        );
+        assert_eq!(
+            parse_value(contents, "capture-device"),
+            Some("USB Microphone".to_string())
+        );
+    }
+
+    #[test]
+    fn config_parse_value_missing_FAIL() -> () {
+        let contents = "# device = Speakers\ndevice =\nother = value\n";
+
+        assert_eq!(parse_value(contents, "device"), None);
+        assert_eq!(parse_value(contents, "capture-device"), None);
+    }
+}
@@ -0,0 +1,105 @@
+//! A handle owning the OpenAL context, to shut the audio down explicitly.
+
+use internal::{cleanup, OpenAlContextError, OpenAlData};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether a Context owns the OpenAL context
+static ALIVE: AtomicBool = AtomicBool::new(false);
+
+/**
+ * A handle owning the OpenAL device and context, closing them when dropped.
+ *
+ * By default the context is created on the first use of __ears__ and only
+ * closed when the program exits. Applications which need to shut the audio
+ * down and restart it, like plugins loaded and unloaded by a host, can
+ * instead hold the Context returned by `init()` for as long as they use
+ * __ears__.
+ *
+ * Every Sound, SoundData, Music, Recorder and effect should be dropped
+ * before the Context, as their OpenAL objects belong to it: the ones dropped
+ * later have nothing left to delete. The preloaded files and the Sounds
+ * played with `play_sound` are dropped with it, and the device callback and
+ * the automatic reconnection are disabled. Once the Context is dropped, the
+ * next use of __ears__ creates a new context.
+ *
+ * To create the context with custom settings, call `init_with_config` first.
+ *
+ * Only one Context exists at a time.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Sound};
+ *
+ * fn main() -> Result<(), Box<dyn std::error::Error>> {
+ *     let context = ears::init()?;
+ *     {
+ *         let mut snd = Sound::new("path/to/my/sound.ogg")?;
+ *         snd.play();
+ *         while snd.is_playing() {}
+ *     }
+ *     // The device is closed here
+ *     drop(context);
+ *     Ok(())
+ * }
+ * ```
+ */
+#[must_use = "the context is closed when the Context is dropped"]
+pub struct Context {
+    _private: (),
+}
+
+impl Context {
+    /**
+     * Create the OpenAL context, or take ownership of the existing one.
+     *
+     * # Return
+     * The Context, or an OpenAlContextError if the context can't be created,
+     * OpenAlContextError::ContextInUse if another Context owns it.
+     */
+    pub(crate) fn new() -> Result<Context, OpenAlContextError> {
+        if ALIVE.swap(true, Ordering::SeqCst) {
+            return Err(OpenAlContextError::ContextInUse);
+        }
+        if let Err(err) = OpenAlData::check_al_context() {
+            ALIVE.store(false, Ordering::SeqCst);
+            return Err(err);
+        }
+        Ok(Context { _private: () })
+    }
+}
+
+impl Drop for Context {
+    /// Close the OpenAL context and devices.
+    fn drop(&mut self) {
+        cleanup();
+        ALIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use init;
+    use internal::OpenAlData;
+
+    #[test]
+    #[ignore]
+    fn context_restart_OK() -> () {
+        let context = init().expect("Cannot create the context");
+        drop(context);
+
+        let context = init().expect("Cannot create the context again");
+        assert!(OpenAlData::check_al_context().is_ok());
+        drop(context);
+    }
+
+    #[test]
+    #[ignore]
+    fn context_new_twice_FAIL() -> () {
+        let context = init().expect("Cannot create the context");
+
+        assert!(init().is_err());
+        drop(context);
+    }
+}
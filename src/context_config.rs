@@ -0,0 +1,269 @@
+//! Settings of the OpenAL context, applied when it is created.
+
+use hrtf::HrtfMode;
+use openal::ffi;
+
+/**
+ * The settings of the OpenAL context, given to `init_with_config`.
+ *
+ * They are requests: the implementation may not support some of them, or
+ * grant other values. The default value of each field lets the
+ * implementation choose.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{ContextConfig, ContextSettings, HrtfMode};
+ *
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     ears::init_with_config(ContextConfig {
+ *         hrtf: HrtfMode::Enabled,
+ *         settings: ContextSettings::new()
+ *             .frequency(48000)
+ *             .mono_sources(128)
+ *             .refresh(100),
+ *         ..Default::default()
+ *     })?;
+ *     println!("HRTF enabled: {}", ears::is_hrtf_enabled());
+ *     println!("Mixing at {} Hz", ears::output_frequency());
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ContextConfig {
+    /// The playback device to open, see `devices()`. None for the one
+    /// configured with `EARS_DEVICE`, or the default one.
+    pub device: Option<String>,
+    /// The number of auxiliary sends per source, see
+    /// `init_with_auxiliary_sends`. 0 for the default.
+    pub auxiliary_sends: i32,
+    /// Whether to render the 3D sounds with HRTF, for headphones
+    pub hrtf: HrtfMode,
+    /// The name of the HRTF profile to use, see `hrtf_profiles()`. None
+    /// for the default one.
+    pub hrtf_profile: Option<String>,
+    /// The output frequency, source counts and refresh rate
+    pub settings: ContextSettings,
+}
+
+/**
+ * The output frequency, source counts and refresh rate of the context.
+ *
+ * Each setting left unset lets the implementation choose.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{ContextConfig, ContextSettings};
+ *
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     // A lower latency, for a rhythm game
+ *     ears::init_with_config(ContextConfig {
+ *         settings: ContextSettings::new().frequency(48000).refresh(200),
+ *         ..Default::default()
+ *     })?;
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct ContextSettings {
+    frequency: i32,
+    mono_sources: i32,
+    stereo_sources: i32,
+    refresh: i32,
+}
+
+impl ContextSettings {
+    /// Create ContextSettings letting the implementation choose everything.
+    pub fn new() -> ContextSettings {
+        ContextSettings::default()
+    }
+
+    /**
+     * Set the mixing frequency of the output.
+     *
+     * # Argument
+     * * `frequency` - The frequency in Hz
+     */
+    pub fn frequency(self, frequency: i32) -> ContextSettings {
+        ContextSettings { frequency, ..self }
+    }
+
+    /**
+     * Set the number of mono sources, used by the Sounds and Musics of one
+     * channel.
+     *
+     * # Argument
+     * * `mono_sources` - The number of mono sources
+     */
+    pub fn mono_sources(self, mono_sources: i32) -> ContextSettings {
+        ContextSettings {
+            mono_sources,
+            ..self
+        }
+    }
+
+    /**
+     * Set the number of stereo sources, used by the Sounds and Musics of
+     * several channels.
+     *
+     * # Argument
+     * * `stereo_sources` - The number of stereo sources
+     */
+    pub fn stereo_sources(self, stereo_sources: i32) -> ContextSettings {
+        ContextSettings {
+            stereo_sources,
+            ..self
+        }
+    }
+
+    /**
+     * Set how many times per second the output is updated.
+     *
+     * Higher values lower the latency, at the cost of more processing.
+     *
+     * # Argument
+     * * `refresh` - The number of updates per second
+     */
+    pub fn refresh(self, refresh: i32) -> ContextSettings {
+        ContextSettings { refresh, ..self }
+    }
+}
+
+impl ContextConfig {
+    /**
+     * Get the ALC attributes of the context.
+     *
+     * # Argument
+     * * `hrtf_profiles` - The HRTF profiles of the device
+     *
+     * # Return
+     * The attributes, as key and value pairs ending with a 0.
+     */
+    pub(crate) fn attributes(&self, hrtf_profiles: &[String]) -> Vec<i32> {
+        let mut attributes = vec![];
+        let values = [
+            (ffi::ALC_FREQUENCY, self.settings.frequency),
+            (ffi::ALC_MONO_SOURCES, self.settings.mono_sources),
+            (ffi::ALC_STEREO_SOURCES, self.settings.stereo_sources),
+            (ffi::ALC_REFRESH, self.settings.refresh),
+        ];
+        for (attribute, value) in values.iter() {
+            if *value > 0 {
+                attributes.extend_from_slice(&[*attribute, *value]);
+            }
+        }
+        if self.auxiliary_sends > 0 {
+            attributes.extend_from_slice(&[ffi::ALC_MAX_AUXILIARY_SENDS, self.auxiliary_sends]);
+        }
+        if self.hrtf != HrtfMode::Default {
+            attributes.extend_from_slice(&[ffi::ALC_HRTF_SOFT, self.hrtf.value()]);
+        }
+        let profile = self
+            .hrtf_profile
+            .as_ref()
+            .and_then(|name| hrtf_profiles.iter().position(|p| p == name));
+        if let Some(index) = profile {
+            attributes.extend_from_slice(&[ffi::ALC_HRTF_ID_SOFT, index as i32]);
+        }
+        attributes.push(0);
+        attributes
+    }
+}
+
+fn get_integer(param: i32) -> i32 {
+    let mut value = 0;
+    unsafe {
+        let device = ffi::alcGetContextsDevice(ffi::alcGetCurrentContext());
+        ffi::alcGetIntegerv(device, param, 1, &mut value);
+    }
+    value
+}
+
+/**
+ * Get the mixing frequency of the output, granted by the implementation.
+ *
+ * # Return
+ * The frequency in Hz, 0 if the context can't be created.
+ */
+pub fn output_frequency() -> i32 {
+    check_openal_context!(0);
+
+    get_integer(ffi::ALC_FREQUENCY)
+}
+
+/**
+ * Get the number of mono sources granted by the implementation.
+ *
+ * # Return
+ * The maximum number of Sounds and Musics of one channel alive at once.
+ */
+pub fn max_mono_sources() -> i32 {
+    check_openal_context!(0);
+
+    get_integer(ffi::ALC_MONO_SOURCES)
+}
+
+/**
+ * Get the number of stereo sources granted by the implementation.
+ *
+ * # Return
+ * The maximum number of Sounds and Musics of several channels alive at
+ * once.
+ */
+pub fn max_stereo_sources() -> i32 {
+    check_openal_context!(0);
+
+    get_integer(ffi::ALC_STEREO_SOURCES)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{ContextConfig, ContextSettings};
+    use hrtf::HrtfMode;
+    use openal::ffi;
+
+    #[test]
+    fn context_config_attributes_default_OK() -> () {
+        assert_eq!(ContextConfig::default().attributes(&[]), vec![0]);
+    }
+
+    #[test]
+    fn context_config_attributes_OK() -> () {
+        let config = ContextConfig {
+            settings: ContextSettings::new().frequency(48000).stereo_sources(4),
+            auxiliary_sends: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            config.attributes(&[]),
+            vec![
+                ffi::ALC_FREQUENCY,
+                48000,
+                ffi::ALC_STEREO_SOURCES,
+                4,
+                ffi::ALC_MAX_AUXILIARY_SENDS,
+                2,
+                0
+            ]
+        );
+    }
+
+    #[test]
+    fn context_config_attributes_hrtf_OK() -> () {
+        let config = ContextConfig {
+            hrtf: HrtfMode::Enabled,
+            hrtf_profile: Some("Kemar 48kHz".to_string()),
+            ..Default::default()
+        };
+        let profiles = ["Built-In HRTF".to_string(), "Kemar 48kHz".to_string()];
+
+        assert_eq!(
+            config.attributes(&profiles),
+            vec![ffi::ALC_HRTF_SOFT, 1, ffi::ALC_HRTF_ID_SOFT, 1, 0]
+        );
+    }
+}
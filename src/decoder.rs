@@ -0,0 +1,276 @@
+//! Decoding of audio files, to use their samples without playing them.
+
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use audio_tags::{get_sound_tags, AudioTags, Tags};
+use error::SoundError;
+use sndfile::OpenMode::Read as ReadMode;
+use sndfile::SeekMode::SeekSet;
+use sndfile::{SndFile, SndFileError, SndInfo};
+use stream_info::{self, StreamInfo};
+
+/**
+ * A decoder reading the samples of an audio file.
+ *
+ * It reads all the files a Sound or a Music can play, frame by frame, so
+ * applications can draw waveforms or process the samples themselves. No
+ * OpenAL context is needed.
+ *
+ * # Example
+ * ```no_run
+ * use ears::decoder::Decoder;
+ *
+ * fn main() -> Result<(), ears::SoundError> {
+ *     let mut decoder = Decoder::open("res/music.ogg")?;
+ *     let channels = decoder.channels();
+ *     let mut samples = vec![0.; 4096 * channels];
+ *     let mut peak = 0f32;
+ *     loop {
+ *         let frames = decoder.read_f32(&mut samples)?;
+ *         if frames == 0 {
+ *             break;
+ *         }
+ *         for sample in &samples[..frames * channels] {
+ *             peak = peak.max(sample.abs());
+ *         }
+ *     }
+ *     println!("{} peaks at {}", decoder.stream_info(), peak);
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct Decoder {
+    file: SndFile,
+    infos: SndInfo,
+    /// The size of the file in bytes, if known
+    size: Option<u64>,
+    /// The frame read next
+    position: u64,
+}
+
+impl Decoder {
+    /**
+     * Open an audio file.
+     *
+     * # Argument
+     * * `path` - The path of the file
+     *
+     * # Return
+     * A `Result` containing Ok(Decoder) on success, Err(SoundError)
+     * if the file can't be read.
+     */
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Decoder, SoundError> {
+        let size = fs::metadata(path.as_ref()).ok().map(|m| m.len());
+        let file = SndFile::new(path, ReadMode).map_err(SoundError::LoadError)?;
+        Ok(Decoder::new(file, size))
+    }
+
+    /**
+     * Decode an audio file from a reader, like a file in an archive.
+     *
+     * # Argument
+     * * `reader` - The reader of the encoded file
+     *
+     * # Return
+     * A `Result` containing Ok(Decoder) on success, Err(SoundError)
+     * if the file can't be read, for instance Err(SoundError::SeekError) if
+     * the reader can't seek.
+     */
+    pub fn from_reader<R: Read + Seek + Send + 'static>(
+        mut reader: R,
+    ) -> Result<Decoder, SoundError> {
+        let size = reader_size(&mut reader)?;
+        let file = SndFile::new_with_reader(reader).map_err(SoundError::LoadError)?;
+        Ok(Decoder::new(file, Some(size)))
+    }
+
+    fn new(file: SndFile, size: Option<u64>) -> Decoder {
+        Decoder {
+            infos: file.get_sndinfo(),
+            file,
+            size,
+            position: 0,
+        }
+    }
+
+    /**
+     * Get the format of the file.
+     *
+     * # Return
+     * The container, codec, sample width, sample rate, channels, estimated
+     * bitrate and seekability of the file
+     */
+    pub fn stream_info(&self) -> StreamInfo {
+        stream_info::stream_info(&self.infos, self.size)
+    }
+
+    /// Get the number of frames of the file.
+    pub fn frames(&self) -> u64 {
+        self.infos.frames.max(0) as u64
+    }
+
+    /// Get the number of channels of the file.
+    pub fn channels(&self) -> usize {
+        self.infos.channels as usize
+    }
+
+    /// Get the sample rate of the file, in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.infos.samplerate as u32
+    }
+
+    /// Get the duration of the file.
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames() as f64 / self.sample_rate() as f64)
+    }
+
+    /// Get the frame read next.
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /**
+     * Read the next frames as 16 bits samples.
+     *
+     * # Argument
+     * * `samples` - The interleaved samples to fill, by whole frames
+     *
+     * # Return
+     * A `Result` containing Ok(frames) with the number of frames read,
+     * 0 at the end of the file, or Err(SoundError) if the file can't be
+     * decoded.
+     */
+    pub fn read_i16(&mut self, samples: &mut [i16]) -> Result<usize, SoundError> {
+        let frames = (samples.len() / self.channels()) as i64;
+        let read = self.file.readf_i16(samples, frames);
+        self.after_read(read)
+    }
+
+    /**
+     * Read the next frames as float samples, between -1.0 and 1.0.
+     *
+     * # Argument
+     * * `samples` - The interleaved samples to fill, by whole frames
+     *
+     * # Return
+     * A `Result` containing Ok(frames) with the number of frames read,
+     * 0 at the end of the file, or Err(SoundError) if the file can't be
+     * decoded.
+     */
+    pub fn read_f32(&mut self, samples: &mut [f32]) -> Result<usize, SoundError> {
+        let frames = (samples.len() / self.channels()) as i64;
+        let read = self.file.readf_f32(samples, frames);
+        self.after_read(read)
+    }
+
+    fn after_read(&mut self, read: i64) -> Result<usize, SoundError> {
+        match self.file.last_error() {
+            Some(err) if read <= 0 => Err(SoundError::LoadError(err)),
+            _ => {
+                self.position += read.max(0) as u64;
+                Ok(read.max(0) as usize)
+            }
+        }
+    }
+
+    /**
+     * Move to a frame, read next.
+     *
+     * # Argument
+     * * `frame` - The frame, from the start of the file
+     *
+     * # Return
+     * A `Result` containing Ok(()) on success, Err(SoundError) if the
+     * file can't be seeked to this frame.
+     */
+    pub fn seek(&mut self, frame: u64) -> Result<(), SoundError> {
+        if self.file.seek(frame as i64, SeekSet) < 0 {
+            let err = self.file.last_error().unwrap_or_else(|| {
+                SndFileError::new(format!("the file can't be seeked to frame {}", frame))
+            });
+            return Err(SoundError::SeekError(err));
+        }
+        self.position = frame;
+        Ok(())
+    }
+
+    /**
+     * Move to a time, read next.
+     *
+     * # Argument
+     * * `offset` - The time, from the start of the file
+     */
+    pub fn seek_to(&mut self, offset: Duration) -> Result<(), SoundError> {
+        let frame = (offset.as_secs_f64() * self.sample_rate() as f64) as u64;
+        self.seek(frame)
+    }
+}
+
+impl AudioTags for Decoder {
+    /**
+     * Get the tags of the file.
+     *
+     * # Return
+     * The tags of the file, None for the ones it doesn't have
+     */
+    fn get_tags(&self) -> Tags {
+        get_sound_tags(&self.file)
+    }
+}
+
+/// Get the size of a reader, leaving it at its start.
+pub(crate) fn reader_size<R: Seek>(reader: &mut R) -> Result<u64, SoundError> {
+    let seek_error =
+        |err: io::Error| SoundError::SeekError(SndFileError::SystemError(err.to_string()));
+    let size = reader.seek(SeekFrom::End(0)).map_err(seek_error)?;
+    reader.rewind().map_err(seek_error)?;
+    Ok(size)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::Decoder;
+    use std::io::Cursor;
+
+    #[test]
+    #[ignore]
+    fn decoder_read_seek_OK() -> () {
+        let mut decoder = Decoder::open("res/explosion.wav").unwrap();
+        let mut start = vec![0i16; 256 * decoder.channels()];
+        let mut again = start.clone();
+
+        assert!(decoder.frames() > 256);
+        assert_eq!(decoder.read_i16(&mut start).unwrap(), 256);
+        assert_eq!(decoder.position(), 256);
+        decoder.seek(0).unwrap();
+        assert_eq!(decoder.read_i16(&mut again).unwrap(), 256);
+        assert_eq!(start, again);
+    }
+
+    #[test]
+    #[ignore]
+    fn decoder_read_to_end_OK() -> () {
+        let mut decoder = Decoder::open("res/shots.ogg").unwrap();
+        let mut samples = vec![0.; 4096 * decoder.channels()];
+        let mut frames = 0;
+
+        loop {
+            match decoder.read_f32(&mut samples).unwrap() {
+                0 => break,
+                read => frames += read as u64,
+            }
+        }
+        assert_eq!(frames, decoder.frames());
+    }
+
+    #[test]
+    #[ignore]
+    fn decoder_from_reader_FAIL() -> () {
+        assert!(Decoder::from_reader(Cursor::new(vec![0u8; 64])).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! Batches of property changes applied at once, from the
+//! AL_SOFT_deferred_updates extension.
+
+use internal::OpenAlData;
+use openal::al;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// How many batches are running on all the threads, and whether the
+    /// updates are deferred until the last one ends
+    static ref BATCHES: Mutex<(usize, bool)> = Mutex::new((0, false));
+}
+
+/// Ends a batch, even if its function panicked.
+struct Batch;
+
+impl Drop for Batch {
+    fn drop(&mut self) {
+        let mut batches = BATCHES.lock().unwrap();
+        batches.0 -= 1;
+        if batches.0 == 0 && batches.1 {
+            batches.1 = false;
+            al::alProcessUpdatesSOFT();
+        }
+    }
+}
+
+/**
+ * Apply all the changes made by a function at once.
+ *
+ * Changing the positions and volumes of many Audio Sources, and of the
+ * listener, one after the other lets the mixer hear some changes before
+ * the others, which can be heard as zipper noise. The changes made inside
+ * the function are instead all applied together when it returns.
+ *
+ * Batches can be nested, even across threads: the changes are applied
+ * when the last running batch returns. Without the AL_SOFT_deferred_updates extension the changes are
+ * applied one by one, as usual.
+ *
+ * # Argument
+ * * `f` - The function changing the properties
+ *
+ * # Return
+ * The result of the function
+ *
+ * # Example
+ * ```no_run
+ * use ears::{listener, AudioController, Sound, SoundError};
+ *
+ * fn main() -> Result<(), SoundError> {
+ *     let mut car = Sound::new("path/to/engine.ogg")?;
+ *     let mut horn = Sound::new("path/to/horn.ogg")?;
+ *
+ *     ears::with_deferred_updates(|| {
+ *         listener::set_position([1., 0., 0.]);
+ *         car.set_position([4., 0., -2.]);
+ *         horn.set_volume(0.5);
+ *     });
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn with_deferred_updates<F: FnOnce() -> R, R>(f: F) -> R {
+    {
+        let capable =
+            OpenAlData::check_al_context().is_ok() && OpenAlData::deferred_updates_capable();
+        let mut batches = BATCHES.lock().unwrap();
+        if batches.0 == 0 && capable {
+            al::alDeferUpdatesSOFT();
+            batches.1 = true;
+        }
+        batches.0 += 1;
+    }
+    let _batch = Batch;
+    f()
+}
@@ -0,0 +1,140 @@
+//! Enumeration of the playback and capture devices.
+
+use libc::c_char;
+use openal::ffi;
+use std::ffi::{CStr, CString};
+
+/// A playback or capture device, as listed by `devices()` and `capture_devices()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// The name of the device, to give to `init_with_device` or `init_in_with`
+    pub name: String,
+    /// Whether the device is the one opened by default
+    pub is_default: bool,
+}
+
+/// Check if an ALC extension is present, without any device opened.
+fn enumeration_present(extension: &str) -> bool {
+    let c_str = CString::new(extension).unwrap();
+    unsafe { ffi::alcIsExtensionPresent(0, c_str.as_ptr()) == ffi::ALC_TRUE }
+}
+
+/// Read a list of strings separated by null characters, and ending with an empty one.
+fn to_list(list: *const c_char) -> Vec<String> {
+    let mut names = Vec::new();
+    if list.is_null() {
+        return names;
+    }
+    let mut next = list;
+    loop {
+        let name = unsafe { CStr::from_ptr(next) };
+        let len = name.to_bytes().len();
+        if len == 0 {
+            return names;
+        }
+        names.push(name.to_string_lossy().into_owned());
+        next = unsafe { next.add(len + 1) };
+    }
+}
+
+/**
+ * List the playback devices of the system.
+ *
+ * All the devices are listed with the ALC_ENUMERATE_ALL_EXT extension,
+ * otherwise only the ones of ALC_ENUMERATION_EXT are. The list can be
+ * called before initializing __ears__, as it doesn't need a context.
+ *
+ * # Return
+ * The playback devices, empty if the OpenAL implementation can't list them.
+ *
+ * # Example
+ * ```no_run
+ * for device in ears::devices() {
+ *     println!("{}{}", device.name, if device.is_default { " (default)" } else { "" });
+ * }
+ * ```
+ */
+pub fn devices() -> Vec<DeviceInfo> {
+    let (devices, default) = if enumeration_present("ALC_ENUMERATE_ALL_EXT") {
+        (
+            ffi::ALC_ALL_DEVICES_SPECIFIER,
+            ffi::ALC_DEFAULT_ALL_DEVICES_SPECIFIER,
+        )
+    } else if enumeration_present("ALC_ENUMERATION_EXT") {
+        (ffi::ALC_DEVICE_SPECIFIER, ffi::ALC_DEFAULT_DEVICE_SPECIFIER)
+    } else {
+        return Vec::new();
+    };
+
+    device_list(devices, default)
+}
+
+/**
+ * List the capture devices of the system, like microphones.
+ *
+ * The list can be called before initializing __ears__, as it doesn't need a
+ * context.
+ *
+ * # Return
+ * The capture devices, empty if the OpenAL implementation can't list them.
+ *
+ * # Example
+ * ```no_run
+ * use ears::Recorder;
+ *
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     let devices = ears::capture_devices();
+ *     let usb = devices.iter().find(|d| d.name.contains("USB"));
+ *     let context = ears::init_in_with(usb.map(|d| d.name.as_str()), 48000, 1)?;
+ *     let mut recorder = Recorder::new(context);
+ *     recorder.start();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn capture_devices() -> Vec<DeviceInfo> {
+    if !enumeration_present("ALC_ENUMERATION_EXT") {
+        return Vec::new();
+    }
+    device_list(
+        ffi::ALC_CAPTURE_DEVICE_SPECIFIER,
+        ffi::ALC_CAPTURE_DEFAULT_DEVICE_SPECIFIER,
+    )
+}
+
+/// List the devices of a specifier, marking the one of the default specifier.
+fn device_list(devices: i32, default: i32) -> Vec<DeviceInfo> {
+    let names = to_list(unsafe { ffi::alcGetString(0, devices) });
+    let default = to_list(unsafe { ffi::alcGetString(0, default) });
+    names
+        .into_iter()
+        .map(|name| DeviceInfo {
+            is_default: default.first() == Some(&name),
+            name,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::to_list;
+    use std::ptr;
+
+    #[test]
+    fn devices_to_list_OK() -> () {
+        let list = b"OpenAL Soft on Speakers\0OpenAL Soft on Headphones\0\0";
+
+        assert_eq!(
+            to_list(list.as_ptr() as *const _),
+            vec!["OpenAL Soft on Speakers", "OpenAL Soft on Headphones"]
+        );
+    }
+
+    #[test]
+    fn devices_to_list_empty_OK() -> () {
+        assert!(to_list(b"\0\0".as_ptr() as *const _).is_empty());
+        assert!(to_list(ptr::null()).is_empty());
+    }
+}
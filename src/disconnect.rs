@@ -0,0 +1,239 @@
+//! Detection of the loss of the playback device.
+
+use internal::{reopen_device, OpenAlData};
+use openal::ffi;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How often the connection of the device is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A change of the connection of the playback device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// The playback device disappeared, e.g. headphones were unplugged
+    Disconnected,
+    /// The playback has been moved to a device, after a disconnection
+    Reconnected,
+}
+
+/// A function called when the connection of the playback device changes
+type DeviceCallback = Box<dyn FnMut(DeviceEvent) + Send>;
+
+#[derive(Default)]
+struct Watcher {
+    callback: Option<DeviceCallback>,
+    /// Increased each time the callback is set or removed
+    generation: u64,
+    auto_reconnect: bool,
+    /// Stops the watcher thread once dropped
+    stop_sender: Option<Sender<()>>,
+    /// The watcher thread, None when it doesn't run
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watcher {
+    fn is_needed(&self) -> bool {
+        self.callback.is_some() || self.auto_reconnect
+    }
+}
+
+lazy_static! {
+    static ref WATCHER: Mutex<Watcher> = Mutex::new(Watcher::default());
+}
+
+/**
+ * Check if the playback device is still connected.
+ *
+ * The context is not created by the check.
+ *
+ * # Return
+ * false if the device has been lost, always true if the OpenAL
+ * implementation doesn't support the ALC_EXT_disconnect extension or if
+ * there is no context.
+ */
+pub fn is_device_connected() -> bool {
+    if !OpenAlData::is_created() {
+        return true;
+    }
+    if !OpenAlData::alc_extension_present("ALC_EXT_disconnect") {
+        return true;
+    }
+    let mut connected = ffi::ALC_TRUE as i32;
+    unsafe {
+        let device = ffi::alcGetContextsDevice(ffi::alcGetCurrentContext());
+        ffi::alcGetIntegerv(device, ffi::ALC_CONNECTED, 1, &mut connected);
+    }
+    connected != 0
+}
+
+/**
+ * Set a callback called when the playback device is lost, and when the
+ * playback is moved to another device.
+ *
+ * The device is checked regularly from a thread, which calls the callback.
+ * Nothing is ever reported if the OpenAL implementation doesn't support the
+ * ALC_EXT_disconnect extension.
+ *
+ * # Argument
+ * * `callback` - The function called with each change of the connection
+ *
+ * # Example
+ * ```no_run
+ * use ears::DeviceEvent;
+ *
+ * ears::set_auto_reconnect(true);
+ * ears::set_device_callback(|event| match event {
+ *     DeviceEvent::Disconnected => println!("The audio device has been lost"),
+ *     DeviceEvent::Reconnected => println!("Playing on the new default device"),
+ * });
+ * ```
+ */
+pub fn set_device_callback<F>(callback: F)
+where
+    F: FnMut(DeviceEvent) + Send + 'static,
+{
+    let mut watcher = WATCHER.lock().unwrap();
+    watcher.callback = Some(Box::new(callback));
+    watcher.generation += 1;
+    start(&mut watcher);
+}
+
+/**
+ * Remove the callback set with `set_device_callback`.
+ */
+pub fn clear_device_callback() {
+    let mut watcher = WATCHER.lock().unwrap();
+    watcher.callback = None;
+    watcher.generation += 1;
+}
+
+/**
+ * Move the playback to another device automatically once the playback
+ * device is lost.
+ *
+ * The device is reopened in place: the configured device if it is
+ * available, the new default device otherwise. This needs the
+ * ALC_SOFT_reopen_device extension. The Audio Sources keep their settings,
+ * but the ones stopped by the loss of the device, including the Musics,
+ * have to be played again.
+ *
+ * # Argument
+ * * `enabled` - Whether to reconnect automatically. Default is false.
+ */
+pub fn set_auto_reconnect(enabled: bool) {
+    let mut watcher = WATCHER.lock().unwrap();
+    watcher.auto_reconnect = enabled;
+    start(&mut watcher);
+}
+
+/**
+ * Check if the playback is moved automatically once the playback device
+ * is lost.
+ */
+pub fn is_auto_reconnect() -> bool {
+    WATCHER.lock().unwrap().auto_reconnect
+}
+
+fn start(watcher: &mut Watcher) {
+    if watcher.is_needed() && watcher.thread.is_none() {
+        let (stop_sender, stop_receiver) = channel();
+        watcher.stop_sender = Some(stop_sender);
+        watcher.thread = Some(
+            thread::Builder::new()
+                .name(String::from("ears-device"))
+                .spawn(move || watch_device(stop_receiver))
+                .unwrap(),
+        );
+    }
+}
+
+fn watch_device(stop_receiver: Receiver<()>) {
+    let mut connected = true;
+    loop {
+        match stop_receiver.recv_timeout(POLL_INTERVAL) {
+            Err(RecvTimeoutError::Timeout) => {}
+            _ => return,
+        }
+
+        let auto_reconnect = {
+            let mut watcher = WATCHER.lock().unwrap();
+            if !watcher.is_needed() {
+                watcher.stop_sender = None;
+                watcher.thread = None;
+                return;
+            }
+            watcher.auto_reconnect
+        };
+        // Nothing to watch once the context is cleaned up
+        if !OpenAlData::is_created() {
+            continue;
+        }
+
+        if connected && !is_device_connected() {
+            connected = false;
+            notify(DeviceEvent::Disconnected);
+        }
+        if !connected && auto_reconnect && reopen_device() {
+            connected = true;
+            notify(DeviceEvent::Reconnected);
+        } else if !connected && is_device_connected() {
+            // Reconnected by the application
+            connected = true;
+            notify(DeviceEvent::Reconnected);
+        }
+    }
+}
+
+/**
+ * Remove the callback and the automatic reconnection, and stop the watcher
+ * thread, when the context is cleaned up.
+ */
+pub(crate) fn stop() {
+    let thread = {
+        let mut watcher = WATCHER.lock().unwrap();
+        watcher.callback = None;
+        watcher.generation += 1;
+        watcher.auto_reconnect = false;
+        watcher.stop_sender = None;
+        watcher.thread.take()
+    };
+    if let Some(thread) = thread {
+        // The callback itself may clean up the context
+        if thread.thread().id() != thread::current().id() {
+            thread.join().ok();
+        }
+    }
+}
+
+/// Call the callback without the lock, so it can change the settings.
+fn notify(event: DeviceEvent) {
+    let (callback, generation) = {
+        let mut watcher = WATCHER.lock().unwrap();
+        (watcher.callback.take(), watcher.generation)
+    };
+    if let Some(mut callback) = callback {
+        callback(event);
+        let mut watcher = WATCHER.lock().unwrap();
+        // Keep the callback set or removed by the callback itself
+        if watcher.generation == generation {
+            watcher.callback = Some(callback);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::is_device_connected;
+
+    #[test]
+    #[ignore]
+    fn disconnect_is_device_connected_OK() -> () {
+        assert!(is_device_connected());
+    }
+}
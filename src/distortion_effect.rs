@@ -0,0 +1,163 @@
+//! The distortion effect.
+
+use effect::{Effect, EffectSlot};
+use openal::ffi;
+use reverb_effect::ReverbEffectError;
+
+/**
+ * A distortion effect, clipping the Audio Sources connected to it.
+ *
+ * The sound is filtered by a low pass filter, distorted, then shaped by a
+ * band of an equalizer, e.g. for the sound of a broken speaker or an
+ * overdriven guitar.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, DistortionEffect, Sound};
+ * use std::error::Error;
+ *
+ * fn main() -> Result<(), Box<dyn Error>> {
+ *     let mut distortion = DistortionEffect::new()?;
+ *     distortion.set_edge(0.8);
+ *
+ *     let mut snd = Sound::new("path/to/my/sound.ogg")?;
+ *     snd.connect(&distortion)?;
+ *     snd.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct DistortionEffect {
+    effect: EffectSlot,
+    edge: f32,
+    gain: f32,
+    lowpass_cutoff: f32,
+    eq_center: f32,
+    eq_bandwidth: f32,
+}
+
+impl DistortionEffect {
+    /**
+     * Create a distortion effect with the default settings.
+     *
+     * # Return
+     * A `Result` containing Ok(DistortionEffect) on success,
+     * Err(ReverbEffectError) if the device doesn't support effects or there
+     * has been an error.
+     */
+    pub fn new() -> Result<DistortionEffect, ReverbEffectError> {
+        Ok(DistortionEffect {
+            effect: EffectSlot::new(ffi::AL_EFFECT_DISTORTION)?,
+            edge: 0.2,
+            gain: 0.05,
+            lowpass_cutoff: 8000.0,
+            eq_center: 3600.0,
+            eq_bandwidth: 3600.0,
+        })
+    }
+
+    /**
+     * Set how hard the sound is clipped.
+     *
+     * # Argument
+     * * `edge` - The edge, between 0.0 and 1.0. Default is 0.2.
+     */
+    pub fn set_edge(&mut self, edge: f32) {
+        self.edge = edge.clamp(0.0, 1.0);
+        self.effect.set_f(ffi::AL_DISTORTION_EDGE, self.edge);
+    }
+
+    /// Get how hard the sound is clipped.
+    pub fn get_edge(&self) -> f32 {
+        self.edge
+    }
+
+    /**
+     * Set the volume of the distorted sound.
+     *
+     * # Argument
+     * * `gain` - The gain, between 0.01 and 1.0. Default is 0.05.
+     */
+    pub fn set_gain(&mut self, gain: f32) {
+        self.gain = gain.clamp(0.01, 1.0);
+        self.effect.set_f(ffi::AL_DISTORTION_GAIN, self.gain);
+    }
+
+    /// Get the volume of the distorted sound.
+    pub fn get_gain(&self) -> f32 {
+        self.gain
+    }
+
+    /**
+     * Set the cutoff frequency of the low pass filter before the distortion.
+     *
+     * # Argument
+     * * `lowpass_cutoff` - The frequency in Hz, between 80.0 and 24000.0. Default is 8000.0.
+     */
+    pub fn set_lowpass_cutoff(&mut self, lowpass_cutoff: f32) {
+        self.lowpass_cutoff = lowpass_cutoff.clamp(80.0, 24000.0);
+        self.effect
+            .set_f(ffi::AL_DISTORTION_LOWPASS_CUTOFF, self.lowpass_cutoff);
+    }
+
+    /// Get the cutoff frequency of the low pass filter, in Hz.
+    pub fn get_lowpass_cutoff(&self) -> f32 {
+        self.lowpass_cutoff
+    }
+
+    /**
+     * Set the center frequency of the band shaped after the distortion.
+     *
+     * # Argument
+     * * `eq_center` - The frequency in Hz, between 80.0 and 24000.0. Default is 3600.0.
+     */
+    pub fn set_eq_center(&mut self, eq_center: f32) {
+        self.eq_center = eq_center.clamp(80.0, 24000.0);
+        self.effect
+            .set_f(ffi::AL_DISTORTION_EQCENTER, self.eq_center);
+    }
+
+    /// Get the center frequency of the shaped band, in Hz.
+    pub fn get_eq_center(&self) -> f32 {
+        self.eq_center
+    }
+
+    /**
+     * Set the width of the band shaped after the distortion.
+     *
+     * # Argument
+     * * `eq_bandwidth` - The width in Hz, between 80.0 and 24000.0. Default is 3600.0.
+     */
+    pub fn set_eq_bandwidth(&mut self, eq_bandwidth: f32) {
+        self.eq_bandwidth = eq_bandwidth.clamp(80.0, 24000.0);
+        self.effect
+            .set_f(ffi::AL_DISTORTION_EQBANDWIDTH, self.eq_bandwidth);
+    }
+
+    /// Get the width of the shaped band, in Hz.
+    pub fn get_eq_bandwidth(&self) -> f32 {
+        self.eq_bandwidth
+    }
+}
+
+impl Effect for DistortionEffect {
+    fn slot(&self) -> u32 {
+        self.effect.slot()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use distortion_effect::DistortionEffect;
+
+    #[test]
+    #[ignore]
+    fn distortion_effect_set_gain_FAIL() -> () {
+        let mut distortion = DistortionEffect::new().expect("Cannot create the effect");
+
+        distortion.set_gain(0.0);
+        assert_eq!(distortion.get_gain(), 0.01);
+    }
+}
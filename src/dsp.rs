@@ -0,0 +1,307 @@
+//! Processing applied to the samples of a Music as they are streamed.
+
+use sample::Sample;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the crossfeed is applied to all the Musics
+static CROSSFEED: AtomicBool = AtomicBool::new(false);
+
+/// The number of frames analysed by `Music::spectrum`
+pub(crate) const SPECTRUM_FRAMES: usize = 2048;
+
+/// The longest crossfeed delay, in frames
+const MAX_DELAY: usize = 32;
+/// The delay of the crossfed signal, in seconds
+const CROSSFEED_DELAY: f32 = 0.0003;
+/// The cutoff frequency of the crossfed signal, in Hz
+const CROSSFEED_CUTOFF: f32 = 700.0;
+/// The gain of the crossfed signal
+const CROSSFEED_GAIN: f32 = 0.35;
+
+/// A function processing the interleaved samples of each buffer of a Music,
+/// with the number of channels and the sample rate
+pub(crate) type BufferCallback = Box<dyn FnMut(&mut [i16], usize, i32) + Send>;
+
+/// The processing options of a Music.
+#[derive(Default)]
+pub struct StreamDsp {
+    /// How much of the center of stereo tracks to remove, in the range [0.0, 1.0]
+    pub center_removal: f32,
+    /// The state of the crossfeed, kept between buffers
+    crossfeed: Option<Crossfeed>,
+    /// The function given the samples once processed
+    pub(crate) callback: Option<BufferCallback>,
+    /// The mono samples of the last buffers streamed, the most recent last
+    history: VecDeque<Vec<i16>>,
+    /// How many buffers to keep in the history, for the analysis
+    history_buffers: usize,
+}
+
+impl StreamDsp {
+    /// Create a StreamDsp keeping the mono mix of the last buffers processed.
+    pub(crate) fn with_history(buffers: usize) -> StreamDsp {
+        StreamDsp {
+            history_buffers: buffers,
+            ..Default::default()
+        }
+    }
+
+    /// Apply the enabled processing to interleaved samples.
+    pub(crate) fn process<S: Sample>(
+        &mut self,
+        samples: &mut [S],
+        channels: usize,
+        sample_rate: i32,
+    ) {
+        if self.center_removal > 0.0 && channels == 2 {
+            remove_center(samples, self.center_removal);
+        }
+        match (is_crossfeed_enabled() && channels == 2, self.crossfeed) {
+            (true, None) => self.crossfeed = Some(Crossfeed::new(sample_rate)),
+            (false, Some(_)) => self.crossfeed = None,
+            _ => {}
+        }
+        if let Some(ref mut crossfeed) = self.crossfeed {
+            crossfeed.process(samples);
+        }
+        if let Some(ref mut callback) = self.callback {
+            S::with_i16(samples, |samples| callback(samples, channels, sample_rate));
+        }
+        if self.history_buffers > 0 {
+            self.history.push_back(to_mono(samples, channels));
+            while self.history.len() > self.history_buffers {
+                self.history.pop_front();
+            }
+        }
+    }
+
+    /**
+     * Get the mono samples played before a position in the queued buffers.
+     *
+     * # Arguments
+     * * `queued` - The number of buffers queued on the source, the most
+     *   recent ones of the history
+     * * `offset` - The frame played in the queued buffers
+     * * `frames` - The number of frames to get
+     *
+     * # Return
+     * At most `frames` samples, the last one just before the position.
+     */
+    pub(crate) fn recent_samples(&self, queued: usize, offset: usize, frames: usize) -> Vec<i16> {
+        let total: usize = self.history.iter().map(|buffer| buffer.len()).sum();
+        let queued_frames: usize = self
+            .history
+            .iter()
+            .rev()
+            .take(queued)
+            .map(|buffer| buffer.len())
+            .sum();
+        let position = (total - queued_frames + offset).min(total);
+        let start = position.saturating_sub(frames);
+        self.history
+            .iter()
+            .flatten()
+            .skip(start)
+            .take(position - start)
+            .cloned()
+            .collect()
+    }
+}
+
+/**
+ * Enable or disable the headphone crossfeed.
+ *
+ * When listening with headphones, each ear only hears its own channel,
+ * which makes hard panned material tiring to listen to. The crossfeed
+ * blends a delayed and low-passed portion of each channel into the other,
+ * like what happens with speakers.
+ *
+ * It applies to all the stereo Musics, mono and multi-channel files and
+ * Sounds are not affected. The change applies to the samples streamed
+ * after the call, so it can take up to a couple of seconds to be heard.
+ *
+ * # Argument
+ * * `enabled` - `true` to enable the crossfeed, `false` to disable it (the
+ *   default)
+ */
+pub fn set_crossfeed(enabled: bool) {
+    CROSSFEED.store(enabled, Ordering::Relaxed);
+}
+
+/**
+ * Check if the headphone crossfeed is enabled.
+ *
+ * # Return
+ * `true` if the crossfeed is enabled, `false` otherwise
+ */
+pub fn is_crossfeed_enabled() -> bool {
+    CROSSFEED.load(Ordering::Relaxed)
+}
+
+/// The state of the crossfeed of a stereo stream.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Crossfeed {
+    /// The delay of the crossfed signal, in frames
+    delay: usize,
+    /// The coefficient of the low-pass filter
+    alpha: f32,
+    /// The last input frames, for the delay
+    history: [[f32; 2]; MAX_DELAY],
+    /// The position of the next input frame in the history
+    position: usize,
+    /// The low-passed delayed signal of each channel
+    filtered: [f32; 2],
+}
+
+impl Crossfeed {
+    fn new(sample_rate: i32) -> Crossfeed {
+        let sample_rate = sample_rate.max(1) as f32;
+        Crossfeed {
+            delay: ((CROSSFEED_DELAY * sample_rate).round() as usize).min(MAX_DELAY - 1),
+            alpha: 1.0 - (-2.0 * PI * CROSSFEED_CUTOFF / sample_rate).exp(),
+            history: [[0.0; 2]; MAX_DELAY],
+            position: 0,
+            filtered: [0.0; 2],
+        }
+    }
+
+    // Blend each channel of interleaved stereo samples into the other.
+    fn process<S: Sample>(&mut self, samples: &mut [S]) {
+        for frame in samples.chunks_mut(2) {
+            if frame.len() < 2 {
+                break;
+            }
+            let input = [frame[0].to_f32(), frame[1].to_f32()];
+            self.history[self.position] = input;
+            let delayed = self.history[(self.position + MAX_DELAY - self.delay) % MAX_DELAY];
+            self.position = (self.position + 1) % MAX_DELAY;
+
+            for (filtered, delayed) in self.filtered.iter_mut().zip(delayed.iter()) {
+                *filtered += self.alpha * (delayed - *filtered);
+            }
+            frame[0] = S::from_f32(
+                (input[0] + CROSSFEED_GAIN * self.filtered[1]) / (1.0 + CROSSFEED_GAIN),
+            );
+            frame[1] = S::from_f32(
+                (input[1] + CROSSFEED_GAIN * self.filtered[0]) / (1.0 + CROSSFEED_GAIN),
+            );
+        }
+    }
+}
+
+// Average the channels of interleaved samples into 16 bits mono samples.
+fn to_mono<S: Sample>(samples: &[S], channels: usize) -> Vec<i16> {
+    samples
+        .chunks(channels.max(1))
+        .map(|frame| {
+            let sum: f32 = frame.iter().map(|sample| sample.to_f32()).sum();
+            (sum / frame.len() as f32 / S::FULL_SCALE * 32768.0) as i16
+        })
+        .collect()
+}
+
+// Subtract the content common to both channels of interleaved stereo
+// samples, where vocals are usually mixed.
+fn remove_center<S: Sample>(samples: &mut [S], strength: f32) {
+    for frame in samples.chunks_mut(2) {
+        if frame.len() < 2 {
+            break;
+        }
+        let left = frame[0].to_f32();
+        let right = frame[1].to_f32();
+        let center = (left + right) / 2.0 * strength;
+        frame[0] = S::from_f32(left - center);
+        frame[1] = S::from_f32(right - center);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use dsp::{Crossfeed, StreamDsp, CROSSFEED_GAIN};
+
+    #[test]
+    fn dsp_center_removal_OK() -> () {
+        let mut dsp = StreamDsp {
+            center_removal: 1.0,
+            ..Default::default()
+        };
+        let mut samples = [1000, 1000, 1000, -1000, 600, 200];
+
+        dsp.process(&mut samples, 2, 44100);
+        assert_eq!(samples, [0, 0, 1000, -1000, 200, -200]);
+    }
+
+    #[test]
+    fn dsp_center_removal_half_OK() -> () {
+        let mut dsp = StreamDsp {
+            center_removal: 0.5,
+            ..Default::default()
+        };
+        let mut samples = [1000, 1000];
+
+        dsp.process(&mut samples, 2, 44100);
+        assert_eq!(samples, [500, 500]);
+    }
+
+    #[test]
+    fn dsp_center_removal_mono_unchanged() -> () {
+        let mut dsp = StreamDsp {
+            center_removal: 1.0,
+            ..Default::default()
+        };
+        let mut samples = [1000, 1000];
+
+        dsp.process(&mut samples, 1, 44100);
+        assert_eq!(samples, [1000, 1000]);
+    }
+
+    #[test]
+    fn dsp_callback_OK() -> () {
+        let mut dsp = StreamDsp {
+            callback: Some(Box::new(|samples: &mut [i16], channels: usize, _: i32| {
+                assert_eq!(channels, 2);
+                for sample in samples.iter_mut() {
+                    *sample /= 2;
+                }
+            })),
+            ..Default::default()
+        };
+        let mut samples = [0.5f32, -1.0];
+
+        dsp.process(&mut samples, 2, 44100);
+        assert!((samples[0] - 0.25).abs() < 1e-4);
+        assert!((samples[1] + 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn dsp_recent_samples_OK() -> () {
+        let mut dsp = StreamDsp::with_history(3);
+        for buffer in 0..4 {
+            let mut samples = [buffer * 10, buffer * 10, buffer * 10 + 2, buffer * 10 + 2];
+            dsp.process(&mut samples, 2, 44100);
+        }
+
+        // The last 2 buffers are queued, the first of them is playing its second frame
+        assert_eq!(dsp.recent_samples(2, 1, 3), vec![10, 12, 20]);
+        assert_eq!(dsp.recent_samples(2, 0, 10), vec![10, 12]);
+    }
+
+    #[test]
+    fn dsp_crossfeed_OK() -> () {
+        let mut crossfeed = Crossfeed::new(44100);
+        let mut samples = [10000, 0].repeat(4410);
+
+        crossfeed.process(&mut samples);
+        let last = &samples[samples.len() - 2..];
+        let expected_left = (10000.0 / (1.0 + CROSSFEED_GAIN)) as i16;
+        let expected_right = (10000.0 * CROSSFEED_GAIN / (1.0 + CROSSFEED_GAIN)) as i16;
+        assert!((last[0] - expected_left).abs() <= 1);
+        assert!((last[1] - expected_right).abs() <= 1);
+        // The crossfed signal is delayed
+        assert_eq!(samples[1], 0);
+    }
+}
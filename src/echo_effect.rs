@@ -0,0 +1,171 @@
+//! The echo effect.
+
+use effect::{Effect, EffectSlot};
+use openal::ffi;
+use reverb_effect::ReverbEffectError;
+
+/**
+ * An echo effect, repeating the Audio Sources connected to it.
+ *
+ * The echo alternates between a left and a right tap, each repeated with
+ * less high frequencies and less volume.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, EchoEffect, Sound};
+ * use std::error::Error;
+ *
+ * fn main() -> Result<(), Box<dyn Error>> {
+ *     let mut echo = EchoEffect::new()?;
+ *     echo.set_delay(0.2);
+ *     echo.set_feedback(0.7);
+ *
+ *     let mut snd = Sound::new("path/to/my/sound.ogg")?;
+ *     snd.connect(&echo)?;
+ *     snd.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct EchoEffect {
+    effect: EffectSlot,
+    delay: f32,
+    lr_delay: f32,
+    damping: f32,
+    feedback: f32,
+    spread: f32,
+}
+
+impl EchoEffect {
+    /**
+     * Create an echo effect with the default settings.
+     *
+     * # Return
+     * A `Result` containing Ok(EchoEffect) on success,
+     * Err(ReverbEffectError) if the device doesn't support effects or there
+     * has been an error.
+     */
+    pub fn new() -> Result<EchoEffect, ReverbEffectError> {
+        Ok(EchoEffect {
+            effect: EffectSlot::new(ffi::AL_EFFECT_ECHO)?,
+            delay: 0.1,
+            lr_delay: 0.1,
+            damping: 0.5,
+            feedback: 0.5,
+            spread: -1.0,
+        })
+    }
+
+    /**
+     * Set the delay between the sound and the first tap.
+     *
+     * # Argument
+     * * `delay` - The delay in seconds, between 0.0 and 0.207. Default is 0.1.
+     */
+    pub fn set_delay(&mut self, delay: f32) {
+        self.delay = delay.clamp(0.0, 0.207);
+        self.effect.set_f(ffi::AL_ECHO_DELAY, self.delay);
+    }
+
+    /// Get the delay between the sound and the first tap, in seconds.
+    pub fn get_delay(&self) -> f32 {
+        self.delay
+    }
+
+    /**
+     * Set the delay between the left and the right taps.
+     *
+     * # Argument
+     * * `lr_delay` - The delay in seconds, between 0.0 and 0.404. Default
+     *   is 0.1.
+     */
+    pub fn set_lr_delay(&mut self, lr_delay: f32) {
+        self.lr_delay = lr_delay.clamp(0.0, 0.404);
+        self.effect.set_f(ffi::AL_ECHO_LRDELAY, self.lr_delay);
+    }
+
+    /// Get the delay between the left and the right taps, in seconds.
+    pub fn get_lr_delay(&self) -> f32 {
+        self.lr_delay
+    }
+
+    /**
+     * Set how much the high frequencies are attenuated at each repetition.
+     *
+     * # Argument
+     * * `damping` - The damping, between 0.0 and 0.99. Default is 0.5.
+     */
+    pub fn set_damping(&mut self, damping: f32) {
+        self.damping = damping.clamp(0.0, 0.99);
+        self.effect.set_f(ffi::AL_ECHO_DAMPING, self.damping);
+    }
+
+    /// Get how much the high frequencies are attenuated at each repetition.
+    pub fn get_damping(&self) -> f32 {
+        self.damping
+    }
+
+    /**
+     * Set how much of the sound is repeated at each tap.
+     *
+     * # Argument
+     * * `feedback` - The feedback, between 0.0 and 1.0. Default is 0.5.
+     */
+    pub fn set_feedback(&mut self, feedback: f32) {
+        self.feedback = feedback.clamp(0.0, 1.0);
+        self.effect.set_f(ffi::AL_ECHO_FEEDBACK, self.feedback);
+    }
+
+    /// Get how much of the sound is repeated at each tap.
+    pub fn get_feedback(&self) -> f32 {
+        self.feedback
+    }
+
+    /**
+     * Set how far apart the left and the right taps are panned.
+     *
+     * # Argument
+     * * `spread` - The spread, between -1.0 and 1.0, 0.0 to keep both taps
+     *   centered. Default is -1.0.
+     */
+    pub fn set_spread(&mut self, spread: f32) {
+        self.spread = spread.clamp(-1.0, 1.0);
+        self.effect.set_f(ffi::AL_ECHO_SPREAD, self.spread);
+    }
+
+    /// Get how far apart the left and the right taps are panned.
+    pub fn get_spread(&self) -> f32 {
+        self.spread
+    }
+}
+
+impl Effect for EchoEffect {
+    fn slot(&self) -> u32 {
+        self.effect.slot()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use echo_effect::EchoEffect;
+
+    #[test]
+    #[ignore]
+    fn echo_effect_set_delay_OK() -> () {
+        let mut echo = EchoEffect::new().expect("Cannot create the effect");
+
+        echo.set_delay(0.15);
+        assert_eq!(echo.get_delay(), 0.15);
+    }
+
+    #[test]
+    #[ignore]
+    fn echo_effect_set_spread_FAIL() -> () {
+        let mut echo = EchoEffect::new().expect("Cannot create the effect");
+
+        echo.set_spread(3.0);
+        assert_eq!(echo.get_spread(), 1.0);
+    }
+}
@@ -0,0 +1,309 @@
+//! The common interface of the EFX effects.
+
+use internal::OpenAlData;
+use logging::{self, LogLevel};
+use openal::{al, ffi};
+use reverb_effect::{ReverbEffect, ReverbEffectError};
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The effect loaded in each Auxiliary Effect Slot, and how many hold it
+    static ref SLOTS: Mutex<HashMap<u32, SharedSlot>> = Mutex::new(HashMap::new());
+}
+
+struct SharedSlot {
+    effect_id: u32,
+    /// The effect itself and its `SlotHandle`s
+    holders: usize,
+}
+
+/**
+ * An effect which can be connected to an Audio Source.
+ *
+ * Each effect is loaded in its own Auxiliary Effect Slot, and the Audio
+ * Sources connected to the slot feed the effect through their auxiliary
+ * sends, see `AudioController::connect`.
+ *
+ * `Option<E>` is an effect too, where None is no effect at all: connecting
+ * it disconnects the send.
+ */
+pub trait Effect {
+    /// The Auxiliary Effect Slot of the effect, 0 for no effect.
+    fn slot(&self) -> u32;
+
+    /**
+     * Chain the effect to another one: the output of this effect goes
+     * through the target effect instead of going straight to the speakers.
+     *
+     * Needs the AL_SOFT_effect_target extension.
+     *
+     * # Argument
+     * * `target` - The effect to feed, or an `Option` of an effect which is
+     *   None to unchain the effect
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * chaining effects or the chaining failed, e.g. in a loop.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, EchoEffect, Effect, ReverbEffect, ReverbPreset, Sound};
+     * use std::error::Error;
+     *
+     * fn main() -> Result<(), Box<dyn Error>> {
+     *     // The echoes reverberate in the cave
+     *     let echo = EchoEffect::new()?;
+     *     let cave = ReverbEffect::preset(ReverbPreset::Cave.properties())?;
+     *     echo.chain(&cave)?;
+     *
+     *     let mut snd = Sound::new("path/to/my/sound.ogg")?;
+     *     snd.connect(&echo)?;
+     *     snd.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    fn chain(&self, target: &dyn Effect) -> Result<(), ReverbEffectError> {
+        check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
+
+        if self.slot() == ffi::AL_EFFECTSLOT_NULL as u32 {
+            return Ok(());
+        }
+        if !OpenAlData::extension_present("AL_SOFT_effect_target") {
+            return Err(ReverbEffectError::ChainingUnsupported);
+        }
+
+        al::alAuxiliaryEffectSloti(self.slot(), ffi::AL_EFFECTSLOT_TARGET_SOFT, target.slot());
+
+        // Check if there is OpenAL internal error
+        match al::openal_has_error() {
+            Some(err) => Err(ReverbEffectError::InternalOpenALError(err)),
+            None => Ok(()),
+        }
+    }
+
+    /**
+     * Unchain the effect, its output goes straight to the speakers again.
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * chaining effects or the unchaining failed.
+     */
+    fn unchain(&self) -> Result<(), ReverbEffectError> {
+        self.chain(&None::<ReverbEffect>)
+    }
+}
+
+impl Effect for ReverbEffect {
+    fn slot(&self) -> u32 {
+        ReverbEffect::slot(self)
+    }
+}
+
+impl<E: Effect> Effect for Option<E> {
+    fn slot(&self) -> u32 {
+        self.as_ref()
+            .map_or(ffi::AL_EFFECTSLOT_NULL as u32, |effect| effect.slot())
+    }
+}
+
+/// An Effect Object loaded in its own Auxiliary Effect Slot Object.
+pub(crate) struct EffectSlot {
+    effect_id: u32,
+    effect_slot_id: u32,
+}
+
+impl EffectSlot {
+    /**
+     * Create an effect and its slot.
+     *
+     * # Argument
+     * * `effect_type` - The OpenAL type of the effect, e.g. AL_EFFECT_ECHO
+     */
+    pub(crate) fn new(effect_type: i32) -> Result<EffectSlot, ReverbEffectError> {
+        check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
+
+        if !OpenAlData::efx_capable() {
+            return Err(ReverbEffectError::EfxUnsupported);
+        }
+
+        let mut effect_slot_id = 0;
+        al::alGenAuxiliaryEffectSlots(1, &mut effect_slot_id);
+        let mut effect_id = 0;
+        al::alGenEffects(1, &mut effect_id);
+        al::alEffecti(effect_id, ffi::AL_EFFECT_TYPE, effect_type);
+
+        register_slot(effect_slot_id, effect_id);
+        let effect = EffectSlot {
+            effect_id,
+            effect_slot_id,
+        };
+
+        // Check if there is OpenAL internal error
+        if let Some(err) = al::openal_has_error() {
+            return Err(ReverbEffectError::InternalOpenALError(err));
+        };
+
+        effect.update_slot();
+        Ok(effect)
+    }
+
+    pub(crate) fn slot(&self) -> u32 {
+        self.effect_slot_id
+    }
+
+    /// Set a float parameter of the effect.
+    pub(crate) fn set_f(&self, param: i32, value: f32) {
+        check_openal_context!();
+        al::alEffectf(self.effect_id, param, value);
+        self.update_slot();
+    }
+
+    /// Set an integer parameter of the effect.
+    pub(crate) fn set_i(&self, param: i32, value: i32) {
+        check_openal_context!();
+        al::alEffecti(self.effect_id, param, value);
+        self.update_slot();
+    }
+
+    // The slot keeps a copy of the effect, load it again after a change
+    fn update_slot(&self) {
+        al::alAuxiliaryEffectSloti(
+            self.effect_slot_id,
+            ffi::AL_EFFECTSLOT_EFFECT,
+            self.effect_id,
+        );
+    }
+}
+
+impl Drop for EffectSlot {
+    // Delete the Effect Object and Auxiliary Effect Slot Object
+    fn drop(&mut self) {
+        // The objects of the last holder were deleted with the context
+        if release_slot(self.effect_slot_id) && OpenAlData::is_created() {
+            delete_effect_slot(self.effect_id, self.effect_slot_id);
+        }
+    }
+}
+
+/**
+ * A hold on the Auxiliary Effect Slot of an effect, e.g. by a bus, keeping
+ * the slot and its effect alive after the effect is dropped.
+ */
+pub(crate) struct SlotHandle {
+    effect_id: u32,
+    effect_slot_id: u32,
+}
+
+impl SlotHandle {
+    /// Hold the slot of an effect, None for no effect.
+    pub(crate) fn new(effect: &dyn Effect) -> Option<SlotHandle> {
+        let effect_slot_id = effect.slot();
+        let mut slots = SLOTS.lock().unwrap();
+        let shared = slots.get_mut(&effect_slot_id)?;
+        shared.holders += 1;
+        Some(SlotHandle {
+            effect_id: shared.effect_id,
+            effect_slot_id,
+        })
+    }
+
+    pub(crate) fn slot(&self) -> u32 {
+        self.effect_slot_id
+    }
+}
+
+impl Drop for SlotHandle {
+    fn drop(&mut self) {
+        // The objects of the last holder were deleted with the context
+        if release_slot(self.effect_slot_id) && OpenAlData::is_created() {
+            delete_effect_slot(self.effect_id, self.effect_slot_id);
+        }
+    }
+}
+
+/// Record a new slot and its effect, held by the effect.
+pub(crate) fn register_slot(effect_slot_id: u32, effect_id: u32) {
+    let shared = SharedSlot {
+        effect_id,
+        holders: 1,
+    };
+    SLOTS.lock().unwrap().insert(effect_slot_id, shared);
+}
+
+/**
+ * Release a hold on a slot.
+ *
+ * # Return
+ * true if nothing holds the slot anymore, and it must be deleted.
+ */
+pub(crate) fn release_slot(effect_slot_id: u32) -> bool {
+    let mut slots = SLOTS.lock().unwrap();
+    match slots.get_mut(&effect_slot_id) {
+        Some(shared) if shared.holders > 1 => {
+            shared.holders -= 1;
+            false
+        }
+        _ => {
+            slots.remove(&effect_slot_id);
+            true
+        }
+    }
+}
+
+// Delete the Effect Object and Auxiliary Effect Slot Object
+fn delete_effect_slot(mut effect_id: u32, mut effect_slot_id: u32) {
+    al::alAuxiliaryEffectSloti(
+        effect_slot_id,
+        ffi::AL_EFFECTSLOT_EFFECT,
+        ffi::AL_EFFECT_NULL as u32,
+    );
+
+    unsafe {
+        ffi::alDeleteEffects(1, &mut effect_id);
+        ffi::alDeleteAuxiliaryEffectSlots(1, &mut effect_slot_id);
+    }
+
+    // Check if there is OpenAL internal error
+    if let Some(err) = al::openal_has_error() {
+        let message = format!(
+            "Ears failed to drop an effect completely, one or more source is probably still referencing it: {}",
+            err
+        );
+        logging::log(LogLevel::Warning, &message);
+    };
+}
+
+/// Forget all the slots, when the context is cleaned up.
+pub(crate) fn clear() {
+    let slots = mem::take(&mut *SLOTS.lock().unwrap());
+    drop(slots);
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use echo_effect::EchoEffect;
+    use effect::Effect;
+    use reverb_effect::ReverbEffect;
+
+    #[test]
+    fn effect_none_slot_OK() -> () {
+        let effect: Option<ReverbEffect> = None;
+
+        assert_eq!(effect.slot(), 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn effect_chain_OK() -> () {
+        let echo = EchoEffect::new().expect("Cannot create the effect");
+        let reverb = ReverbEffect::new().expect("Cannot create the effect");
+
+        assert!(echo.chain(&reverb).is_ok());
+        assert!(echo.unchain().is_ok());
+    }
+}
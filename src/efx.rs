@@ -0,0 +1,186 @@
+use internal::OpenAlData;
+use openal::{al, ffi};
+
+/// The types of effect an OpenAL implementation can provide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EffectType {
+    Reverb,
+    EaxReverb,
+    Chorus,
+    Distortion,
+    Echo,
+    Flanger,
+    FrequencyShifter,
+    VocalMorpher,
+    PitchShifter,
+    RingModulator,
+    Autowah,
+    Compressor,
+    Equalizer,
+}
+
+impl EffectType {
+    /// All the effect types, in the order of their OpenAL identifiers.
+    pub fn all() -> [EffectType; 13] {
+        [
+            EffectType::Reverb,
+            EffectType::Chorus,
+            EffectType::Distortion,
+            EffectType::Echo,
+            EffectType::Flanger,
+            EffectType::FrequencyShifter,
+            EffectType::VocalMorpher,
+            EffectType::PitchShifter,
+            EffectType::RingModulator,
+            EffectType::Autowah,
+            EffectType::Compressor,
+            EffectType::Equalizer,
+            EffectType::EaxReverb,
+        ]
+    }
+
+    /// The OpenAL identifier of the effect type.
+    pub(crate) fn al_type(self) -> i32 {
+        match self {
+            EffectType::Reverb => ffi::AL_EFFECT_REVERB,
+            EffectType::EaxReverb => ffi::AL_EFFECT_EAXREVERB,
+            EffectType::Chorus => ffi::AL_EFFECT_CHORUS,
+            EffectType::Distortion => ffi::AL_EFFECT_DISTORTION,
+            EffectType::Echo => ffi::AL_EFFECT_ECHO,
+            EffectType::Flanger => ffi::AL_EFFECT_FLANGER,
+            EffectType::FrequencyShifter => ffi::AL_EFFECT_FREQUENCY_SHIFTER,
+            EffectType::VocalMorpher => ffi::AL_EFFECT_VOCAL_MORPHER,
+            EffectType::PitchShifter => ffi::AL_EFFECT_PITCH_SHIFTER,
+            EffectType::RingModulator => ffi::AL_EFFECT_RING_MODULATOR,
+            EffectType::Autowah => ffi::AL_EFFECT_AUTOWAH,
+            EffectType::Compressor => ffi::AL_EFFECT_COMPRESSOR,
+            EffectType::Equalizer => ffi::AL_EFFECT_EQUALIZER,
+        }
+    }
+}
+
+/// What the EFX extension of the current device supports.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EfxSupport {
+    /// How many effects a single source can be connected to at once
+    pub max_auxiliary_sends: i32,
+    /// The effect types that can be created
+    pub effect_types: Vec<EffectType>,
+}
+
+impl EfxSupport {
+    /**
+     * Check if an effect type can be created.
+     *
+     * # Argument
+     * * `effect_type` - The effect type to check
+     */
+    pub fn supports(&self, effect_type: EffectType) -> bool {
+        self.effect_types.contains(&effect_type)
+    }
+}
+
+/**
+ * Get the number of auxiliary sends granted to each source.
+ *
+ * This is how many effects a single source can be connected to at once,
+ * see `init_with_auxiliary_sends` to request more.
+ *
+ * # Return
+ * The number of auxiliary sends, 0 if the device doesn't support effects.
+ */
+pub fn max_auxiliary_sends() -> i32 {
+    check_openal_context!(0);
+
+    if !OpenAlData::efx_capable() {
+        return 0;
+    }
+
+    let mut max_auxiliary_sends = 0;
+    unsafe {
+        let device = ffi::alcGetContextsDevice(ffi::alcGetCurrentContext());
+        ffi::alcGetIntegerv(
+            device,
+            ffi::ALC_MAX_AUXILIARY_SENDS,
+            1,
+            &mut max_auxiliary_sends,
+        );
+    }
+    max_auxiliary_sends
+}
+
+/**
+ * Probe the EFX extension of the current device.
+ *
+ * Effects are optional in OpenAL: use this to check what is available
+ * before creating a ReverbEffect or connecting sources to effects.
+ *
+ * # Return
+ * `Some(EfxSupport)` describing the extension, or `None` if the device
+ * doesn't support effects at all.
+ *
+ * # Example
+ * ```no_run
+ * match ears::efx_support() {
+ *     Some(efx) => println!("{} effects per source", efx.max_auxiliary_sends),
+ *     None => println!("no effects on this device"),
+ * }
+ * ```
+ */
+pub fn efx_support() -> Option<EfxSupport> {
+    check_openal_context!(None);
+
+    if !OpenAlData::efx_capable() {
+        return None;
+    }
+
+    let max_auxiliary_sends = max_auxiliary_sends();
+
+    // Clear any previous error so it isn't attributed to the probing
+    al::openal_has_error();
+
+    let mut effect_id = 0;
+    al::alGenEffects(1, &mut effect_id);
+    let effect_types = EffectType::all()
+        .iter()
+        .cloned()
+        .filter(|effect_type| {
+            al::alEffecti(effect_id, ffi::AL_EFFECT_TYPE, effect_type.al_type());
+            al::openal_has_error().is_none()
+        })
+        .collect();
+    unsafe {
+        ffi::alDeleteEffects(1, &mut effect_id);
+    }
+
+    Some(EfxSupport {
+        max_auxiliary_sends,
+        effect_types,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::*;
+
+    #[test]
+    fn effect_type_all_OK() -> () {
+        let all = EffectType::all();
+
+        for (i, effect_type) in all.iter().enumerate() {
+            assert!(!all[i + 1..].contains(effect_type));
+        }
+        assert_eq!(all[0].al_type(), ffi::AL_EFFECT_REVERB);
+    }
+
+    #[test]
+    #[ignore]
+    fn efx_support_OK() -> () {
+        let efx = efx_support().expect("EFX is not supported");
+
+        assert!(efx.max_auxiliary_sends >= 1);
+        assert!(efx.supports(EffectType::Reverb));
+    }
+}
@@ -0,0 +1,274 @@
+//! The equalizer effect.
+
+use effect::{Effect, EffectSlot};
+use openal::ffi;
+use reverb_effect::ReverbEffectError;
+
+/**
+ * A four bands equalizer effect, shaping the tone of the Audio Sources
+ * connected to it.
+ *
+ * The low and high bands are shelves below and above their cutoff
+ * frequencies, the two mid bands are peaks around their center
+ * frequencies. A gain below 1.0 attenuates a band, above 1.0 amplifies it.
+ *
+ * Connecting several Audio Sources to the same equalizer shapes them all as
+ * a group.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, EqualizerEffect, Music};
+ * use std::error::Error;
+ *
+ * fn main() -> Result<(), Box<dyn Error>> {
+ *     // Boost the bass and cut the treble
+ *     let mut eq = EqualizerEffect::new()?;
+ *     eq.set_low_gain(2.0);
+ *     eq.set_high_gain(0.5);
+ *
+ *     let mut music = Music::new("path/to/my/music.ogg")?;
+ *     music.connect(&eq)?;
+ *     music.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct EqualizerEffect {
+    effect: EffectSlot,
+    low_gain: f32,
+    low_cutoff: f32,
+    mid1_gain: f32,
+    mid1_center: f32,
+    mid1_width: f32,
+    mid2_gain: f32,
+    mid2_center: f32,
+    mid2_width: f32,
+    high_gain: f32,
+    high_cutoff: f32,
+}
+
+impl EqualizerEffect {
+    /**
+     * Create an equalizer effect which doesn't change anything yet.
+     *
+     * # Return
+     * A `Result` containing Ok(EqualizerEffect) on success,
+     * Err(ReverbEffectError) if the device doesn't support effects or there
+     * has been an error.
+     */
+    pub fn new() -> Result<EqualizerEffect, ReverbEffectError> {
+        Ok(EqualizerEffect {
+            effect: EffectSlot::new(ffi::AL_EFFECT_EQUALIZER)?,
+            low_gain: 1.0,
+            low_cutoff: 200.0,
+            mid1_gain: 1.0,
+            mid1_center: 500.0,
+            mid1_width: 1.0,
+            mid2_gain: 1.0,
+            mid2_center: 3000.0,
+            mid2_width: 1.0,
+            high_gain: 1.0,
+            high_cutoff: 6000.0,
+        })
+    }
+
+    /**
+     * Set the gain of the low band.
+     *
+     * # Argument
+     * * `low_gain` - The gain, between 0.126 and 7.943. Default is 1.0.
+     */
+    pub fn set_low_gain(&mut self, low_gain: f32) {
+        self.low_gain = low_gain.clamp(0.126, 7.943);
+        self.effect.set_f(ffi::AL_EQUALIZER_LOW_GAIN, self.low_gain);
+    }
+
+    /// Get the gain of the low band.
+    pub fn get_low_gain(&self) -> f32 {
+        self.low_gain
+    }
+
+    /**
+     * Set the cutoff frequency of the low band.
+     *
+     * # Argument
+     * * `low_cutoff` - The frequency in Hz, between 50.0 and 800.0. Default is 200.0.
+     */
+    pub fn set_low_cutoff(&mut self, low_cutoff: f32) {
+        self.low_cutoff = low_cutoff.clamp(50.0, 800.0);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_LOW_CUTOFF, self.low_cutoff);
+    }
+
+    /// Get the cutoff frequency of the low band, in Hz.
+    pub fn get_low_cutoff(&self) -> f32 {
+        self.low_cutoff
+    }
+
+    /**
+     * Set the gain of the first mid band.
+     *
+     * # Argument
+     * * `mid1_gain` - The gain, between 0.126 and 7.943. Default is 1.0.
+     */
+    pub fn set_mid1_gain(&mut self, mid1_gain: f32) {
+        self.mid1_gain = mid1_gain.clamp(0.126, 7.943);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_MID1_GAIN, self.mid1_gain);
+    }
+
+    /// Get the gain of the first mid band.
+    pub fn get_mid1_gain(&self) -> f32 {
+        self.mid1_gain
+    }
+
+    /**
+     * Set the center frequency of the first mid band.
+     *
+     * # Argument
+     * * `mid1_center` - The frequency in Hz, between 200.0 and 3000.0. Default is 500.0.
+     */
+    pub fn set_mid1_center(&mut self, mid1_center: f32) {
+        self.mid1_center = mid1_center.clamp(200.0, 3000.0);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_MID1_CENTER, self.mid1_center);
+    }
+
+    /// Get the center frequency of the first mid band, in Hz.
+    pub fn get_mid1_center(&self) -> f32 {
+        self.mid1_center
+    }
+
+    /**
+     * Set the width of the first mid band.
+     *
+     * # Argument
+     * * `mid1_width` - The width in octaves, between 0.01 and 1.0. Default is 1.0.
+     */
+    pub fn set_mid1_width(&mut self, mid1_width: f32) {
+        self.mid1_width = mid1_width.clamp(0.01, 1.0);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_MID1_WIDTH, self.mid1_width);
+    }
+
+    /// Get the width of the first mid band, in octaves.
+    pub fn get_mid1_width(&self) -> f32 {
+        self.mid1_width
+    }
+
+    /**
+     * Set the gain of the second mid band.
+     *
+     * # Argument
+     * * `mid2_gain` - The gain, between 0.126 and 7.943. Default is 1.0.
+     */
+    pub fn set_mid2_gain(&mut self, mid2_gain: f32) {
+        self.mid2_gain = mid2_gain.clamp(0.126, 7.943);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_MID2_GAIN, self.mid2_gain);
+    }
+
+    /// Get the gain of the second mid band.
+    pub fn get_mid2_gain(&self) -> f32 {
+        self.mid2_gain
+    }
+
+    /**
+     * Set the center frequency of the second mid band.
+     *
+     * # Argument
+     * * `mid2_center` - The frequency in Hz, between 1000.0 and 8000.0. Default is 3000.0.
+     */
+    pub fn set_mid2_center(&mut self, mid2_center: f32) {
+        self.mid2_center = mid2_center.clamp(1000.0, 8000.0);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_MID2_CENTER, self.mid2_center);
+    }
+
+    /// Get the center frequency of the second mid band, in Hz.
+    pub fn get_mid2_center(&self) -> f32 {
+        self.mid2_center
+    }
+
+    /**
+     * Set the width of the second mid band.
+     *
+     * # Argument
+     * * `mid2_width` - The width in octaves, between 0.01 and 1.0. Default is 1.0.
+     */
+    pub fn set_mid2_width(&mut self, mid2_width: f32) {
+        self.mid2_width = mid2_width.clamp(0.01, 1.0);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_MID2_WIDTH, self.mid2_width);
+    }
+
+    /// Get the width of the second mid band, in octaves.
+    pub fn get_mid2_width(&self) -> f32 {
+        self.mid2_width
+    }
+
+    /**
+     * Set the gain of the high band.
+     *
+     * # Argument
+     * * `high_gain` - The gain, between 0.126 and 7.943. Default is 1.0.
+     */
+    pub fn set_high_gain(&mut self, high_gain: f32) {
+        self.high_gain = high_gain.clamp(0.126, 7.943);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_HIGH_GAIN, self.high_gain);
+    }
+
+    /// Get the gain of the high band.
+    pub fn get_high_gain(&self) -> f32 {
+        self.high_gain
+    }
+
+    /**
+     * Set the cutoff frequency of the high band.
+     *
+     * # Argument
+     * * `high_cutoff` - The frequency in Hz, between 4000.0 and 16000.0. Default is 6000.0.
+     */
+    pub fn set_high_cutoff(&mut self, high_cutoff: f32) {
+        self.high_cutoff = high_cutoff.clamp(4000.0, 16000.0);
+        self.effect
+            .set_f(ffi::AL_EQUALIZER_HIGH_CUTOFF, self.high_cutoff);
+    }
+
+    /// Get the cutoff frequency of the high band, in Hz.
+    pub fn get_high_cutoff(&self) -> f32 {
+        self.high_cutoff
+    }
+}
+
+impl Effect for EqualizerEffect {
+    fn slot(&self) -> u32 {
+        self.effect.slot()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use equalizer_effect::EqualizerEffect;
+
+    #[test]
+    #[ignore]
+    fn equalizer_effect_set_low_gain_OK() -> () {
+        let mut eq = EqualizerEffect::new().expect("Cannot create the effect");
+
+        eq.set_low_gain(2.0);
+        assert_eq!(eq.get_low_gain(), 2.0);
+    }
+
+    #[test]
+    #[ignore]
+    fn equalizer_effect_set_high_cutoff_FAIL() -> () {
+        let mut eq = EqualizerEffect::new().expect("Cannot create the effect");
+
+        eq.set_high_cutoff(100.0);
+        assert_eq!(eq.get_high_cutoff(), 4000.0);
+    }
+}
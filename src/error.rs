@@ -1,4 +1,5 @@
-use openal::al;
+use openal::al::{self, AlErrorCode};
+use reverb_effect::ReverbEffectError;
 use sndfile::SndFileError;
 use std::error::Error;
 use std::fmt;
@@ -16,6 +17,50 @@ pub enum SoundError {
 
     /// Internal OpenAL error.
     InternalOpenALError(al::AlError),
+
+    /// The header of the file describes audio that can't be played.
+    InvalidHeader,
+
+    /// The decoded samples would exceed the maximum decoded size.
+    TooLarge,
+
+    /// The loading didn't end in time.
+    Timeout,
+
+    /// Error while saving a file.
+    SaveError(SndFileError),
+
+    /// No sound of this name in the SoundBank, or no segment of this name in
+    /// the AdaptiveMusic.
+    UnknownName(String),
+
+    /// The playback device has been lost while playing.
+    DeviceLost,
+
+    /// The file has a number of channels which can't be played.
+    UnsupportedChannelCount(i32),
+
+    /// The file can't be seeked to the requested position.
+    SeekError(SndFileError),
+
+    /// The device doesn't support the effect, or has no such auxiliary send.
+    EffectUnsupported,
+}
+
+impl SoundError {
+    /**
+     * Get the code of the OpenAL error.
+     *
+     * # Return
+     * The code of the OpenAL error, None if the error doesn't come from
+     * OpenAL.
+     */
+    pub fn al_error_code(&self) -> Option<AlErrorCode> {
+        match self {
+            SoundError::InternalOpenALError(err) => Some(err.code()),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for SoundError {
@@ -28,6 +73,17 @@ impl fmt::Display for SoundError {
                 SoundError::LoadError(err) => format!("error while loading music file: {}", err),
                 SoundError::InvalidFormat => "unrecognized music format".to_string(),
                 SoundError::InternalOpenALError(err) => format!("internal OpenAL error: {}", err),
+                SoundError::InvalidHeader => "invalid file header".to_string(),
+                SoundError::TooLarge => "the decoded file exceeds the maximum size".to_string(),
+                SoundError::Timeout => "the loading timed out".to_string(),
+                SoundError::SaveError(err) => format!("error while saving the file: {}", err),
+                SoundError::UnknownName(name) => format!("no sound or segment named {}", name),
+                SoundError::DeviceLost => "the playback device has been lost".to_string(),
+                SoundError::UnsupportedChannelCount(channels) =>
+                    format!("{} channels can't be played", channels),
+                SoundError::SeekError(err) => format!("error while seeking the file: {}", err),
+                SoundError::EffectUnsupported =>
+                    "the device doesn't support the effect".to_string(),
             }
         )
     }
@@ -46,6 +102,53 @@ impl Error for SoundError {
             SoundError::LoadError(err) => Some(err),
             SoundError::InvalidFormat => None,
             SoundError::InternalOpenALError(err) => Some(err),
+            SoundError::InvalidHeader => None,
+            SoundError::TooLarge => None,
+            SoundError::Timeout => None,
+            SoundError::SaveError(err) => Some(err),
+            SoundError::UnknownName(_) => None,
+            SoundError::DeviceLost => None,
+            SoundError::UnsupportedChannelCount(_) => None,
+            SoundError::SeekError(err) => Some(err),
+            SoundError::EffectUnsupported => None,
+        }
+    }
+}
+
+impl From<ReverbEffectError> for SoundError {
+    fn from(err: ReverbEffectError) -> SoundError {
+        match err {
+            ReverbEffectError::InvalidOpenALContext => SoundError::InvalidOpenALContext,
+            ReverbEffectError::EfxUnsupported
+            | ReverbEffectError::InvalidSend(_)
+            | ReverbEffectError::ChainingUnsupported => SoundError::EffectUnsupported,
+            ReverbEffectError::InternalOpenALError(err) => SoundError::InternalOpenALError(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::SoundError;
+    use openal::al::{AlError, AlErrorCode};
+    use openal::ffi;
+    use reverb_effect::ReverbEffectError;
+
+    #[test]
+    fn sound_error_al_error_code_OK() -> () {
+        let err = SoundError::InternalOpenALError(AlError::new(ffi::AL_INVALID_VALUE));
+
+        assert_eq!(err.al_error_code(), Some(AlErrorCode::InvalidValue));
+        assert_eq!(SoundError::DeviceLost.al_error_code(), None);
+    }
+
+    #[test]
+    fn sound_error_from_reverb_effect_error_OK() -> () {
+        match SoundError::from(ReverbEffectError::InvalidSend(3)) {
+            SoundError::EffectUnsupported => {}
+            err => panic!("unexpected error {}", err),
         }
     }
 }
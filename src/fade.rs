@@ -0,0 +1,208 @@
+//! Volume fades of Sounds and Musics, ramped by a background thread.
+
+use attenuation;
+use bus;
+use openal::{al, ffi};
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// A function stopping an Audio Source at the end of a fade out
+pub(crate) type FadeEnd = Box<dyn FnOnce() + Send>;
+
+/// How often the volume of the fading sources is updated
+const UPDATE_INTERVAL: Duration = Duration::from_millis(10);
+
+struct Fade {
+    from: f32,
+    to: f32,
+    start: Instant,
+    duration: Duration,
+    /// For a fade out, the volume to restore once stopped and how to stop
+    end: Option<(f32, FadeEnd)>,
+}
+
+lazy_static! {
+    /// The fading sources, and whether the fading thread runs
+    static ref FADES: Mutex<(HashMap<u32, Fade>, bool)> = Mutex::new((HashMap::new(), false));
+}
+
+/**
+ * Ramp the volume of a source, replacing its current fade.
+ *
+ * # Arguments
+ * * `source` - The source to fade
+ * * `from` - The volume at the start of the fade
+ * * `to` - The volume at the end of the fade
+ * * `duration` - The duration of the fade
+ * * `end` - For a fade out, the volume to restore and the function stopping
+ *   the source, both called at the end of the fade
+ */
+pub(crate) fn start(
+    source: u32,
+    from: f32,
+    to: f32,
+    duration: Duration,
+    end: Option<(f32, FadeEnd)>,
+) {
+    start_after(source, from, to, Duration::from_secs(0), duration, end)
+}
+
+/**
+ * Ramp the volume of a source after a delay, like `start`.
+ *
+ * The volume stays at `from` until the fade starts.
+ */
+pub(crate) fn start_after(
+    source: u32,
+    from: f32,
+    to: f32,
+    delay: Duration,
+    duration: Duration,
+    end: Option<(f32, FadeEnd)>,
+) {
+    let fade = Fade {
+        from,
+        to,
+        start: Instant::now() + delay,
+        duration,
+        end,
+    };
+    let mut fades = FADES.lock().unwrap();
+    fades.0.insert(source, fade);
+    if !fades.1 {
+        fades.1 = true;
+        thread::Builder::new()
+            .name(String::from("ears-fade"))
+            .spawn(update_fades)
+            .unwrap();
+    }
+}
+
+/**
+ * Stop the fade of a source, where it is.
+ *
+ * The volume of a fade out is restored, as if it ended.
+ */
+pub(crate) fn cancel(source: u32) {
+    let fade = FADES.lock().unwrap().0.remove(&source);
+    if let Some((volume, _)) = fade.and_then(|fade| fade.end) {
+        set_volume(source, volume);
+    }
+}
+
+/// Forget all the fades, when the context is cleaned up.
+pub(crate) fn clear() {
+    let fades = mem::take(&mut FADES.lock().unwrap().0);
+    drop(fades);
+}
+
+/**
+ * Get the volume a source fades to, or is restored to after a fade out.
+ *
+ * # Return
+ * None if the source isn't fading.
+ */
+pub(crate) fn base_volume(source: u32) -> Option<f32> {
+    FADES
+        .lock()
+        .unwrap()
+        .0
+        .get(&source)
+        .map(|fade| match fade.end {
+            Some((volume, _)) => volume,
+            None => fade.to,
+        })
+}
+
+/// Get the volume of a fade after some time, ramped linearly.
+fn ramp(from: f32, to: f32, elapsed: Duration, duration: Duration) -> f32 {
+    if elapsed >= duration {
+        to
+    } else {
+        from + (to - from) * (elapsed.as_secs_f32() / duration.as_secs_f32())
+    }
+}
+
+fn set_volume(source: u32, volume: f32) {
+    if !attenuation::set_source_volume(source, volume) {
+        bus::set_source_gain(source, volume);
+    }
+}
+
+fn update_fades() {
+    loop {
+        sleep(UPDATE_INTERVAL);
+
+        let ended: Vec<(u32, Fade)> = {
+            let mut fades = FADES.lock().unwrap();
+            let now = Instant::now();
+            for (source, fade) in fades.0.iter() {
+                let volume = match now.checked_duration_since(fade.start) {
+                    Some(elapsed) => ramp(fade.from, fade.to, elapsed, fade.duration),
+                    // The fade didn't start yet
+                    None => fade.from,
+                };
+                set_volume(*source, volume);
+            }
+            let ended_sources: Vec<u32> = fades
+                .0
+                .iter()
+                .filter(|(_, fade)| now >= fade.start + fade.duration)
+                .map(|(source, _)| *source)
+                .collect();
+            let ended = ended_sources
+                .into_iter()
+                .filter_map(|source| fades.0.remove(&source).map(|fade| (source, fade)))
+                .collect();
+            if fades.0.is_empty() {
+                fades.1 = false;
+                drop(fades);
+                return end_all(ended);
+            }
+            ended
+        };
+
+        // The sources are stopped without the lock, as stopping a Music
+        // waits for its streaming thread
+        end_all(ended);
+    }
+}
+
+fn end_all(ended: Vec<(u32, Fade)>) {
+    for (source, fade) in ended {
+        if let Some((volume, stop)) = fade.end {
+            stop();
+            set_volume(source, volume);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::ramp;
+    use std::time::Duration;
+
+    #[test]
+    fn fade_ramp_OK() -> () {
+        let duration = Duration::from_secs(2);
+
+        assert_eq!(ramp(1.0, 0.0, Duration::from_secs(0), duration), 1.0);
+        assert_eq!(ramp(1.0, 0.0, Duration::from_millis(500), duration), 0.75);
+        assert!((ramp(0.2, 0.6, Duration::from_secs(1), duration) - 0.4).abs() < 1e-6);
+        assert_eq!(ramp(1.0, 0.0, Duration::from_secs(3), duration), 0.0);
+    }
+
+    #[test]
+    fn fade_ramp_zero_duration_OK() -> () {
+        assert_eq!(
+            ramp(1.0, 0.5, Duration::from_secs(0), Duration::from_secs(0)),
+            0.5
+        );
+    }
+}
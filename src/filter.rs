@@ -0,0 +1,313 @@
+//! Frequency filters applied to the direct path of an Audio Source.
+
+use internal::OpenAlData;
+use openal::{al, ffi};
+use std::error::Error;
+use std::fmt;
+
+/// All possible errors when creating a Filter.
+pub enum FilterError {
+    /// Happens when OpenAL failed to load for some reason.
+    InvalidOpenALContext,
+
+    /// The device doesn't support the EFX extension.
+    EfxUnsupported,
+
+    /// Internal OpenAL error.
+    InternalOpenALError(al::AlError),
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                FilterError::InvalidOpenALContext => "invalid OpenAL context".to_string(),
+                FilterError::EfxUnsupported => "the device doesn't support filters".to_string(),
+                FilterError::InternalOpenALError(err) => format!("internal OpenAL error: {}", err),
+            }
+        )
+    }
+}
+
+impl fmt::Debug for FilterError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+impl Error for FilterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FilterError::InvalidOpenALContext => None,
+            FilterError::EfxUnsupported => None,
+            FilterError::InternalOpenALError(err) => Some(err),
+        }
+    }
+}
+
+/// The kinds of frequency filter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterType {
+    /// Attenuates the high frequencies, to muffle a sound
+    LowPass,
+    /// Attenuates the low frequencies, for a thin sound like a phone call
+    HighPass,
+    /// Attenuates both the low and the high frequencies
+    BandPass,
+}
+
+impl FilterType {
+    /// The OpenAL identifier of the filter type.
+    fn al_type(self) -> i32 {
+        match self {
+            FilterType::LowPass => ffi::AL_FILTER_LOWPASS,
+            FilterType::HighPass => ffi::AL_FILTER_HIGHPASS,
+            FilterType::BandPass => ffi::AL_FILTER_BANDPASS,
+        }
+    }
+}
+
+/**
+ * A frequency filter, wrapping an OpenAL EFX filter object.
+ *
+ * A filter is applied to an Audio Source with
+ * `AudioController::set_direct_filter`, which copies its settings: changing
+ * a filter afterwards only affects the Audio Sources it is applied to
+ * again.
+ *
+ * All the gains are between 0.0 and 1.0. The low pass filter attenuates the
+ * frequencies above 5kHz and the high pass filter the frequencies below
+ * 250Hz, the band pass filter does both.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Filter, Sound};
+ * use std::error::Error;
+ *
+ * fn main() -> Result<(), Box<dyn Error>> {
+ *     let mut snd = Sound::new("path/to/my/sound.ogg")?;
+ *     // Muffle the sound, as if heard underwater
+ *     let underwater = Filter::low_pass(0.8, 0.1)?;
+ *     snd.set_direct_filter(&Some(underwater))?;
+ *     snd.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct Filter {
+    filter_id: u32,
+    filter_type: FilterType,
+    gain: f32,
+    gain_lf: f32,
+    gain_hf: f32,
+}
+
+impl Filter {
+    /**
+     * Create a filter which doesn't attenuate anything yet.
+     *
+     * # Argument
+     * * `filter_type` - The kind of filter
+     *
+     * # Return
+     * A `Result` containing Ok(Filter) on success, Err(FilterError)
+     * if the device doesn't support filters or there has been an error.
+     */
+    pub fn new(filter_type: FilterType) -> Result<Filter, FilterError> {
+        check_openal_context!(Err(FilterError::InvalidOpenALContext));
+
+        if !OpenAlData::efx_capable() {
+            return Err(FilterError::EfxUnsupported);
+        }
+
+        let mut filter_id = 0;
+        al::alGenFilters(1, &mut filter_id);
+        al::alFilteri(filter_id, ffi::AL_FILTER_TYPE, filter_type.al_type());
+
+        // Check if there is OpenAL internal error
+        if let Some(err) = al::openal_has_error() {
+            unsafe {
+                ffi::alDeleteFilters(1, &mut filter_id);
+            }
+            return Err(FilterError::InternalOpenALError(err));
+        };
+
+        Ok(Filter {
+            filter_id,
+            filter_type,
+            gain: 1.0,
+            gain_lf: 1.0,
+            gain_hf: 1.0,
+        })
+    }
+
+    /**
+     * Create a low pass filter.
+     *
+     * # Arguments
+     * * `gain` - The gain of all the frequencies
+     * * `gain_hf` - The additional gain of the high frequencies
+     */
+    pub fn low_pass(gain: f32, gain_hf: f32) -> Result<Filter, FilterError> {
+        let mut filter = Filter::new(FilterType::LowPass)?;
+        filter.set_gain(gain);
+        filter.set_gain_hf(gain_hf);
+        Ok(filter)
+    }
+
+    /**
+     * Create a high pass filter.
+     *
+     * # Arguments
+     * * `gain` - The gain of all the frequencies
+     * * `gain_lf` - The additional gain of the low frequencies
+     */
+    pub fn high_pass(gain: f32, gain_lf: f32) -> Result<Filter, FilterError> {
+        let mut filter = Filter::new(FilterType::HighPass)?;
+        filter.set_gain(gain);
+        filter.set_gain_lf(gain_lf);
+        Ok(filter)
+    }
+
+    /**
+     * Create a band pass filter.
+     *
+     * # Arguments
+     * * `gain` - The gain of all the frequencies
+     * * `gain_lf` - The additional gain of the low frequencies
+     * * `gain_hf` - The additional gain of the high frequencies
+     */
+    pub fn band_pass(gain: f32, gain_lf: f32, gain_hf: f32) -> Result<Filter, FilterError> {
+        let mut filter = Filter::new(FilterType::BandPass)?;
+        filter.set_gain(gain);
+        filter.set_gain_lf(gain_lf);
+        filter.set_gain_hf(gain_hf);
+        Ok(filter)
+    }
+
+    /// Get the kind of the filter.
+    pub fn filter_type(&self) -> FilterType {
+        self.filter_type
+    }
+
+    /**
+     * Set the gain of all the frequencies.
+     *
+     * # Argument
+     * * `gain` - The new gain, between 0.0 and 1.0. Default is 1.0.
+     */
+    pub fn set_gain(&mut self, gain: f32) {
+        check_openal_context!();
+
+        self.gain = gain.clamp(0.0, 1.0);
+        let param = match self.filter_type {
+            FilterType::LowPass => ffi::AL_LOWPASS_GAIN,
+            FilterType::HighPass => ffi::AL_HIGHPASS_GAIN,
+            FilterType::BandPass => ffi::AL_BANDPASS_GAIN,
+        };
+        al::alFilterf(self.filter_id, param, self.gain);
+    }
+
+    /// Get the gain of all the frequencies.
+    pub fn get_gain(&self) -> f32 {
+        self.gain
+    }
+
+    /**
+     * Set the additional gain of the low frequencies.
+     *
+     * Has no effect on a low pass filter.
+     *
+     * # Argument
+     * * `gain_lf` - The new gain, between 0.0 and 1.0. Default is 1.0.
+     */
+    pub fn set_gain_lf(&mut self, gain_lf: f32) {
+        check_openal_context!();
+
+        let param = match self.filter_type {
+            FilterType::LowPass => return,
+            FilterType::HighPass => ffi::AL_HIGHPASS_GAINLF,
+            FilterType::BandPass => ffi::AL_BANDPASS_GAINLF,
+        };
+        self.gain_lf = gain_lf.clamp(0.0, 1.0);
+        al::alFilterf(self.filter_id, param, self.gain_lf);
+    }
+
+    /// Get the additional gain of the low frequencies.
+    pub fn get_gain_lf(&self) -> f32 {
+        self.gain_lf
+    }
+
+    /**
+     * Set the additional gain of the high frequencies.
+     *
+     * Has no effect on a high pass filter.
+     *
+     * # Argument
+     * * `gain_hf` - The new gain, between 0.0 and 1.0. Default is 1.0.
+     */
+    pub fn set_gain_hf(&mut self, gain_hf: f32) {
+        check_openal_context!();
+
+        let param = match self.filter_type {
+            FilterType::LowPass => ffi::AL_LOWPASS_GAINHF,
+            FilterType::HighPass => return,
+            FilterType::BandPass => ffi::AL_BANDPASS_GAINHF,
+        };
+        self.gain_hf = gain_hf.clamp(0.0, 1.0);
+        al::alFilterf(self.filter_id, param, self.gain_hf);
+    }
+
+    /// Get the additional gain of the high frequencies.
+    pub fn get_gain_hf(&self) -> f32 {
+        self.gain_hf
+    }
+
+    pub(crate) fn id(&self) -> u32 {
+        self.filter_id
+    }
+}
+
+impl Drop for Filter {
+    // Delete the Filter Object, the Audio Sources keep a copy of its settings
+    fn drop(&mut self) {
+        // The objects were deleted with the context
+        if !OpenAlData::is_created() {
+            return;
+        }
+
+        unsafe {
+            ffi::alDeleteFilters(1, &mut self.filter_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{Filter, FilterType};
+
+    #[test]
+    #[ignore]
+    fn filter_low_pass_OK() -> () {
+        let filter = Filter::low_pass(0.5, 2.0).expect("Cannot create the filter");
+
+        assert_eq!(filter.filter_type(), FilterType::LowPass);
+        assert_eq!(filter.get_gain(), 0.5);
+        assert_eq!(filter.get_gain_hf(), 1.0);
+        assert_eq!(filter.get_gain_lf(), 1.0);
+    }
+
+    #[test]
+    #[ignore]
+    fn filter_high_pass_gain_hf_FAIL() -> () {
+        let mut filter = Filter::high_pass(1.0, 0.2).expect("Cannot create the filter");
+
+        filter.set_gain_hf(0.3);
+        assert_eq!(filter.get_gain_hf(), 1.0);
+        assert_eq!(filter.get_gain_lf(), 0.2);
+    }
+}
@@ -0,0 +1,277 @@
+//! Audio fingerprints computed with chromaprint.
+
+use libc::{c_char, c_int, c_void};
+use sndfile::OpenMode::Read;
+use sndfile::{SndFile, SndFileError};
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::path::Path;
+use std::ptr;
+use std::slice;
+
+mod ffi {
+    use libc::{c_char, c_int, c_void};
+
+    pub type ChromaprintContext = *mut c_void;
+
+    pub const CHROMAPRINT_ALGORITHM_DEFAULT: c_int = 1;
+
+    #[link(name = "chromaprint")]
+    extern "C" {
+        pub fn chromaprint_new(algorithm: c_int) -> ChromaprintContext;
+        pub fn chromaprint_free(ctx: ChromaprintContext);
+        pub fn chromaprint_start(
+            ctx: ChromaprintContext,
+            sample_rate: c_int,
+            num_channels: c_int,
+        ) -> c_int;
+        pub fn chromaprint_feed(ctx: ChromaprintContext, data: *const i16, size: c_int) -> c_int;
+        pub fn chromaprint_finish(ctx: ChromaprintContext) -> c_int;
+        pub fn chromaprint_get_fingerprint(
+            ctx: ChromaprintContext,
+            fingerprint: *mut *mut c_char,
+        ) -> c_int;
+        pub fn chromaprint_get_raw_fingerprint(
+            ctx: ChromaprintContext,
+            fingerprint: *mut *mut u32,
+            size: *mut c_int,
+        ) -> c_int;
+        pub fn chromaprint_dealloc(ptr: *mut c_void);
+    }
+}
+
+/// The maximum duration of audio used for a fingerprint, in seconds
+const MAX_DURATION: i64 = 120;
+
+/// The number of frames read from the file at once
+const READ_FRAMES: i64 = 4096;
+
+/// Error types that can occur while computing a fingerprint.
+pub enum FingerprintError {
+    /// The SoundData wasn't loaded from a file.
+    NoSourceFile,
+
+    /// Error while reading the file.
+    LoadError(SndFileError),
+
+    /// Chromaprint failed to process the audio.
+    ChromaprintError,
+}
+
+impl fmt::Display for FingerprintError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                FingerprintError::NoSourceFile => "the sound wasn't loaded from a file".to_string(),
+                FingerprintError::LoadError(err) => format!("error while loading file: {}", err),
+                FingerprintError::ChromaprintError => "chromaprint failed".to_string(),
+            }
+        )
+    }
+}
+
+impl fmt::Debug for FingerprintError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+impl Error for FingerprintError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FingerprintError::NoSourceFile => None,
+            FingerprintError::LoadError(err) => Some(err),
+            FingerprintError::ChromaprintError => None,
+        }
+    }
+}
+
+/// The chromaprint fingerprint of a piece of audio.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fingerprint {
+    /// The compressed and base64 encoded fingerprint, as used by AcoustID
+    pub encoded: String,
+    /// The raw fingerprint, one item per frame of about 0.12 seconds
+    pub raw: Vec<u32>,
+}
+
+impl Fingerprint {
+    /**
+     * Compare two fingerprints.
+     *
+     * # Argument
+     * * `other` - The fingerprint to compare with
+     *
+     * # Return
+     * The fraction of the bits shared by the two raw fingerprints, over
+     * their common length: 1.0 for the same audio, around 0.5 for
+     * unrelated audio.
+     */
+    pub fn similarity(&self, other: &Fingerprint) -> f32 {
+        let len = self.raw.len().min(other.raw.len());
+        if len == 0 {
+            return 0.0;
+        }
+        let different_bits: u32 = self
+            .raw
+            .iter()
+            .zip(other.raw.iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum();
+        1.0 - different_bits as f32 / (len * 32) as f32
+    }
+}
+
+struct Context(ffi::ChromaprintContext);
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe { ffi::chromaprint_free(self.0) };
+    }
+}
+
+/**
+ * Compute the fingerprint of interleaved 16 bits samples.
+ *
+ * # Arguments
+ * * `samples` - The interleaved samples
+ * * `channels` - The number of channels of the samples
+ * * `sample_rate` - The sample rate of the samples
+ *
+ * # Return
+ * A `Result` containing Ok(Fingerprint) on success, Err(FingerprintError)
+ * if there has been an error.
+ */
+pub fn fingerprint_samples(
+    samples: &[i16],
+    channels: i32,
+    sample_rate: i32,
+) -> Result<Fingerprint, FingerprintError> {
+    let context = start(channels, sample_rate)?;
+    feed(&context, samples)?;
+    finish(&context)
+}
+
+/**
+ * Compute the fingerprint of a sound file.
+ *
+ * Only the first two minutes of the file are used, which is enough to tell
+ * songs apart. Fingerprints of the same audio in different formats or at
+ * different bitrates are very similar, so they can be compared with
+ * `Fingerprint::similarity` to find duplicate assets.
+ *
+ * # Argument
+ * * `path` - The path of the file
+ *
+ * # Return
+ * A `Result` containing Ok(Fingerprint) on success, Err(FingerprintError)
+ * if there has been an error.
+ *
+ * # Example
+ * ```no_run
+ * let a = ears::fingerprint_file("res/music.ogg").unwrap();
+ * let b = ears::fingerprint_file("res/music.flac").unwrap();
+ * if a.similarity(&b) > 0.9 {
+ *     println!("Duplicate assets");
+ * }
+ * ```
+ */
+pub fn fingerprint_file<P: AsRef<Path>>(path: P) -> Result<Fingerprint, FingerprintError> {
+    let mut file = SndFile::new(path, Read).map_err(FingerprintError::LoadError)?;
+    let infos = file.get_sndinfo();
+    let context = start(infos.channels, infos.samplerate)?;
+
+    let mut remaining = MAX_DURATION * infos.samplerate as i64;
+    let mut samples = vec![0i16; (READ_FRAMES * infos.channels as i64) as usize];
+    while remaining > 0 {
+        let frames = READ_FRAMES.min(remaining);
+        let len = samples.len() as i64;
+        let read = file.read_i16(&mut samples[..], len.min(frames * infos.channels as i64));
+        if read <= 0 {
+            break;
+        }
+        feed(&context, &samples[..read as usize])?;
+        remaining -= read / infos.channels as i64;
+    }
+    file.close();
+
+    finish(&context)
+}
+
+fn start(channels: i32, sample_rate: i32) -> Result<Context, FingerprintError> {
+    let context = unsafe { ffi::chromaprint_new(ffi::CHROMAPRINT_ALGORITHM_DEFAULT) };
+    if context.is_null() {
+        return Err(FingerprintError::ChromaprintError);
+    }
+    let context = Context(context);
+    match unsafe { ffi::chromaprint_start(context.0, sample_rate, channels) } {
+        1 => Ok(context),
+        _ => Err(FingerprintError::ChromaprintError),
+    }
+}
+
+fn feed(context: &Context, samples: &[i16]) -> Result<(), FingerprintError> {
+    match unsafe { ffi::chromaprint_feed(context.0, samples.as_ptr(), samples.len() as c_int) } {
+        1 => Ok(()),
+        _ => Err(FingerprintError::ChromaprintError),
+    }
+}
+
+fn finish(context: &Context) -> Result<Fingerprint, FingerprintError> {
+    unsafe {
+        if ffi::chromaprint_finish(context.0) != 1 {
+            return Err(FingerprintError::ChromaprintError);
+        }
+
+        let mut encoded: *mut c_char = ptr::null_mut();
+        if ffi::chromaprint_get_fingerprint(context.0, &mut encoded) != 1 {
+            return Err(FingerprintError::ChromaprintError);
+        }
+        let encoded_string = CStr::from_ptr(encoded).to_string_lossy().into_owned();
+        ffi::chromaprint_dealloc(encoded as *mut c_void);
+
+        let mut raw: *mut u32 = ptr::null_mut();
+        let mut size = 0;
+        if ffi::chromaprint_get_raw_fingerprint(context.0, &mut raw, &mut size) != 1 {
+            return Err(FingerprintError::ChromaprintError);
+        }
+        let raw_vec = slice::from_raw_parts(raw, size as usize).to_vec();
+        ffi::chromaprint_dealloc(raw as *mut c_void);
+
+        Ok(Fingerprint {
+            encoded: encoded_string,
+            raw: raw_vec,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::Fingerprint;
+
+    fn fingerprint(raw: Vec<u32>) -> Fingerprint {
+        Fingerprint {
+            encoded: String::new(),
+            raw,
+        }
+    }
+
+    #[test]
+    fn fingerprint_similarity_OK() -> () {
+        let a = fingerprint(vec![0xFFFF_0000, 0x1234_5678]);
+        let b = fingerprint(vec![0xFFFF_00FF, 0x1234_5678, 0xDEAD_BEEF]);
+
+        assert_eq!(a.similarity(&a), 1.0);
+        assert_eq!(a.similarity(&b), 1.0 - 8.0 / 64.0);
+    }
+
+    #[test]
+    fn fingerprint_similarity_empty_FAIL() -> () {
+        assert_eq!(fingerprint(vec![]).similarity(&fingerprint(vec![1])), 0.0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Noise gate of captured audio, dropping the silences.
+
+use std::ops::Range;
+use std::time::Duration;
+
+/// The duration of the blocks the level is measured on, in seconds
+const BLOCK_DURATION: f32 = 0.01;
+
+/// The settings of the noise gate of a Recorder.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GateSettings {
+    /// The RMS level the input must reach to be kept, where 1.0 is a full
+    /// scale square wave
+    pub threshold: f32,
+    /// How long the input is still kept once it gets below the threshold,
+    /// so the ends of words and the short pauses aren't cut
+    pub hold: Duration,
+}
+
+impl Default for GateSettings {
+    /// Settings suited to voice: a -34dBFS threshold and a 300ms hold.
+    fn default() -> GateSettings {
+        GateSettings {
+            threshold: 0.02,
+            hold: Duration::from_millis(300),
+        }
+    }
+}
+
+/// The state of a noise gate, kept between captured chunks.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Gate {
+    settings: GateSettings,
+    /// The samples of the block being measured
+    block: Vec<i16>,
+    /// How many samples are still kept before the gate closes
+    hold_left: usize,
+}
+
+impl Gate {
+    pub(crate) fn new(settings: GateSettings) -> Gate {
+        Gate {
+            settings,
+            block: Vec::new(),
+            hold_left: 0,
+        }
+    }
+
+    pub(crate) fn settings(&self) -> GateSettings {
+        self.settings
+    }
+
+    /**
+     * Keep the blocks of interleaved samples above the threshold, or in the
+     * hold time following them.
+     *
+     * The samples are measured and kept by blocks of 10 milliseconds, so the
+     * last partial block is only returned by a later call.
+     */
+    pub(crate) fn process(
+        &mut self,
+        samples: &[i16],
+        channels: usize,
+        sample_rate: u32,
+    ) -> Vec<i16> {
+        let block_size = block_size(channels, sample_rate);
+        let hold = (self.settings.hold.as_secs_f32() * sample_rate as f32) as usize * channels;
+        let mut kept = Vec::new();
+
+        for sample in samples {
+            self.block.push(*sample);
+            if self.block.len() == block_size {
+                if rms(&self.block) >= self.settings.threshold {
+                    self.hold_left = hold;
+                    kept.extend_from_slice(&self.block);
+                } else if self.hold_left > 0 {
+                    self.hold_left = self.hold_left.saturating_sub(block_size);
+                    kept.extend_from_slice(&self.block);
+                }
+                self.block.clear();
+            }
+        }
+        kept
+    }
+}
+
+/**
+ * Find the interleaved samples between the leading and trailing silences.
+ *
+ * # Return
+ * The range of samples from the first to the last block of 10 milliseconds
+ * above the threshold, empty if there isn't any.
+ */
+pub(crate) fn sound_bounds(
+    samples: &[i16],
+    channels: usize,
+    sample_rate: u32,
+    threshold: f32,
+) -> Range<usize> {
+    let block_size = block_size(channels, sample_rate);
+    let mut loud = samples
+        .chunks(block_size)
+        .enumerate()
+        .filter(|(_, block)| rms(block) >= threshold)
+        .map(|(i, _)| i);
+
+    match loud.next() {
+        Some(first) => {
+            let last = loud.next_back().unwrap_or(first);
+            first * block_size..((last + 1) * block_size).min(samples.len())
+        }
+        None => 0..0,
+    }
+}
+
+/// The number of interleaved samples of a block, a whole number of frames.
+fn block_size(channels: usize, sample_rate: u32) -> usize {
+    ((sample_rate as f32 * BLOCK_DURATION) as usize).max(1) * channels
+}
+
+fn rms(samples: &[i16]) -> f32 {
+    (samples
+        .iter()
+        .map(|s| (*s as f32 / 32768.0).powi(2))
+        .sum::<f32>()
+        / samples.len() as f32)
+        .sqrt()
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{sound_bounds, Gate, GateSettings};
+    use std::time::Duration;
+
+    #[test]
+    fn gate_drops_silence_OK() -> () {
+        let mut gate = Gate::new(GateSettings {
+            hold: Duration::from_millis(20),
+            ..Default::default()
+        });
+        let mut samples = vec![0i16; 4410];
+        samples.extend(vec![8000i16; 441]);
+        samples.extend(vec![0i16; 4410]);
+
+        let kept = gate.process(&samples, 1, 44100);
+        // The loud block, then the two blocks of the hold time
+        assert_eq!(kept.len(), 441 * 3);
+        assert!(kept[..441].iter().all(|s| *s == 8000));
+    }
+
+    #[test]
+    fn gate_partial_block_OK() -> () {
+        let mut gate = Gate::new(GateSettings::default());
+
+        assert!(gate.process(&[8000i16; 400], 1, 44100).is_empty());
+        assert_eq!(gate.process(&[8000i16; 41], 1, 44100).len(), 441);
+    }
+
+    #[test]
+    fn gate_sound_bounds_OK() -> () {
+        let mut samples = vec![0i16; 882 * 3];
+        samples.extend(vec![8000i16; 882 * 2]);
+        samples.extend(vec![0i16; 882 * 5]);
+
+        assert_eq!(sound_bounds(&samples, 2, 44100, 0.02), 882 * 3..882 * 5);
+        assert_eq!(sound_bounds(&[0i16; 882], 2, 44100, 0.02), 0..0);
+    }
+}
@@ -0,0 +1,191 @@
+//! Head-related transfer functions, rendering 3D sounds for headphones.
+
+use internal::OpenAlData;
+use libc::c_char;
+use openal::ffi;
+use std::ffi::{CStr, CString};
+use std::mem;
+
+/// Whether to render the 3D sounds with HRTF, see `ContextConfig`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum HrtfMode {
+    /// Let the implementation choose, usually enabled for headphones only
+    #[default]
+    Default,
+    /// Use HRTF, if the output allows it
+    Enabled,
+    /// Don't use HRTF
+    Disabled,
+}
+
+impl HrtfMode {
+    /// The value of the ALC_HRTF_SOFT attribute.
+    pub(crate) fn value(self) -> i32 {
+        match self {
+            HrtfMode::Default => ffi::ALC_DONT_CARE_SOFT,
+            HrtfMode::Enabled => ffi::ALC_TRUE as i32,
+            HrtfMode::Disabled => ffi::ALC_FALSE as i32,
+        }
+    }
+}
+
+/// Why HRTF is used or not by the current device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HrtfStatus {
+    /// HRTF is not used
+    Disabled,
+    /// HRTF is used
+    Enabled,
+    /// HRTF is not used as the implementation denied it
+    Denied,
+    /// HRTF is used as the implementation requires it
+    Required,
+    /// HRTF is used as headphones have been detected
+    HeadphonesDetected,
+    /// HRTF is not used as the output format doesn't allow it
+    UnsupportedFormat,
+    /// The implementation doesn't support the ALC_SOFT_HRTF extension
+    Unsupported,
+}
+
+fn hrtf_capable() -> bool {
+    OpenAlData::alc_extension_present("ALC_SOFT_HRTF")
+}
+
+fn get_integer(param: i32) -> i32 {
+    let mut value = 0;
+    unsafe {
+        let device = ffi::alcGetContextsDevice(ffi::alcGetCurrentContext());
+        ffi::alcGetIntegerv(device, param, 1, &mut value);
+    }
+    value
+}
+
+fn to_string(string: *const c_char) -> String {
+    match string.is_null() {
+        true => String::new(),
+        false => unsafe { CStr::from_ptr(string).to_string_lossy().into_owned() },
+    }
+}
+
+/**
+ * List the HRTF profiles of a device.
+ *
+ * Used when creating the context, before it is current.
+ */
+pub(crate) fn device_hrtf_profiles(device: ffi::ALCdevicePtr) -> Vec<String> {
+    let extension = CString::new("ALC_SOFT_HRTF").unwrap();
+    let function = CString::new("alcGetStringiSOFT").unwrap();
+    let get_stringi = unsafe {
+        if ffi::alcIsExtensionPresent(device, extension.as_ptr()) == ffi::ALC_FALSE {
+            return Vec::new();
+        }
+        ffi::alcGetProcAddress(device, function.as_ptr())
+    };
+    if get_stringi.is_null() {
+        return Vec::new();
+    }
+    let get_stringi: ffi::GetDeviceStringiFn = unsafe { mem::transmute(get_stringi) };
+
+    let mut count = 0;
+    unsafe {
+        ffi::alcGetIntegerv(device, ffi::ALC_NUM_HRTF_SPECIFIERS_SOFT, 1, &mut count);
+    }
+    (0..count)
+        .map(|i| to_string(unsafe { get_stringi(device, ffi::ALC_HRTF_SPECIFIER_SOFT, i) }))
+        .collect()
+}
+
+/**
+ * List the HRTF profiles of the current device.
+ *
+ * # Return
+ * The names of the profiles, to use in `ContextConfig`. Empty if the
+ * device doesn't support HRTF.
+ */
+pub fn hrtf_profiles() -> Vec<String> {
+    check_openal_context!(Vec::new());
+
+    device_hrtf_profiles(unsafe { ffi::alcGetContextsDevice(ffi::alcGetCurrentContext()) })
+}
+
+/**
+ * Check if the 3D sounds are rendered with HRTF.
+ */
+pub fn is_hrtf_enabled() -> bool {
+    check_openal_context!(false);
+
+    hrtf_capable() && get_integer(ffi::ALC_HRTF_SOFT) == ffi::ALC_TRUE as i32
+}
+
+/**
+ * Get why HRTF is used or not.
+ *
+ * # Example
+ * ```no_run
+ * use ears::HrtfStatus;
+ *
+ * match ears::hrtf_status() {
+ *     HrtfStatus::UnsupportedFormat => println!("Switch the output to stereo to use HRTF"),
+ *     status => println!("HRTF: {:?}", status),
+ * }
+ * ```
+ */
+pub fn hrtf_status() -> HrtfStatus {
+    check_openal_context!(HrtfStatus::Unsupported);
+
+    if !hrtf_capable() {
+        return HrtfStatus::Unsupported;
+    }
+    match get_integer(ffi::ALC_HRTF_STATUS_SOFT) {
+        ffi::ALC_HRTF_ENABLED_SOFT => HrtfStatus::Enabled,
+        ffi::ALC_HRTF_DENIED_SOFT => HrtfStatus::Denied,
+        ffi::ALC_HRTF_REQUIRED_SOFT => HrtfStatus::Required,
+        ffi::ALC_HRTF_HEADPHONES_DETECTED_SOFT => HrtfStatus::HeadphonesDetected,
+        ffi::ALC_HRTF_UNSUPPORTED_FORMAT_SOFT => HrtfStatus::UnsupportedFormat,
+        _ => HrtfStatus::Disabled,
+    }
+}
+
+/**
+ * Get the HRTF profile in use.
+ *
+ * # Return
+ * The name of the profile, None if HRTF is not used.
+ */
+pub fn hrtf_profile() -> Option<String> {
+    check_openal_context!(None);
+
+    if !is_hrtf_enabled() {
+        return None;
+    }
+    let profile = to_string(unsafe {
+        let device = ffi::alcGetContextsDevice(ffi::alcGetCurrentContext());
+        ffi::alcGetString(device, ffi::ALC_HRTF_SPECIFIER_SOFT)
+    });
+    match profile.is_empty() {
+        true => None,
+        false => Some(profile),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{hrtf_status, HrtfMode, HrtfStatus};
+    use openal::ffi;
+
+    #[test]
+    fn hrtf_mode_value_OK() -> () {
+        assert_eq!(HrtfMode::Default.value(), ffi::ALC_DONT_CARE_SOFT);
+        assert_eq!(HrtfMode::Enabled.value(), 1);
+        assert_eq!(HrtfMode::Disabled.value(), 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn hrtf_status_OK() -> () {
+        assert_ne!(hrtf_status(), HrtfStatus::Unsupported);
+    }
+}
@@ -28,25 +28,137 @@
  * and destroyed in a another task.
  */
 
-use internal::{OpenAlContextError, OpenAlData};
+use context::Context;
+use context_config::ContextConfig;
+use internal::{set_output_device, OpenAlContextError, OpenAlData};
 use record_context::RecordContext;
 
 /**
  * Initialize the internal context
  *
+ * The returned Context owns the OpenAL context, which is closed when it is
+ * dropped. The context created by another `init_*` function or by the first
+ * use of __ears__ is taken over.
+ *
+ * # Return
+ * `Ok(Context)` if initialization is successful, `Err(OpenAlContextError)`
+ * otherwise, `OpenAlContextError::ContextInUse` if another Context owns the
+ * OpenAL context
+ *
+ * # Example
+ * ```no_run
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     let context = ears::init()?;
+ *     // Play some sounds, then close the device
+ *     drop(context);
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn init() -> Result<Context, OpenAlContextError> {
+    Context::new()
+}
+
+/**
+ * Initialize the internal context, requesting a number of auxiliary sends
+ *
+ * Auxiliary sends are the number of effects a single source can be connected
+ * to at once. Some implementations default to only one or two, so request
+ * more if you use several sends. The implementation may grant fewer, check
+ * the granted value with `max_auxiliary_sends()`.
+ *
+ * This has to be called before any other function of __ears__, as the
+ * request is ignored once the context is created.
+ *
+ * # Argument
+ * * `sends` - The number of auxiliary sends to request per source
+ *
+ * # Return
+ * `Ok(())` if initialization is successful, `Err(OpenAlContextError)` otherwise
+ *
+ * # Example
+ * ```no_run
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     ears::init_with_auxiliary_sends(4)?;
+ *     println!("{} sends granted", ears::max_auxiliary_sends());
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn init_with_auxiliary_sends(sends: i32) -> Result<(), OpenAlContextError> {
+    OpenAlData::request_auxiliary_sends(sends);
+    OpenAlData::check_al_context()
+}
+
+/**
+ * Initialize the internal context on a playback device
+ *
+ * The names of the devices are listed by `devices()`. The device overrides
+ * the one configured with `EARS_DEVICE`. If the context already exists on
+ * another device, the playback is moved to this one like with
+ * `set_output_device`, which needs the ALC_SOFT_reopen_device extension.
+ *
+ * # Argument
+ * * `name` - The name of the playback device to open
+ *
+ * # Return
+ * `Ok(())` if initialization is successful, `Err(OpenAlContextError)` otherwise,
+ * for instance if the device cannot be opened, or
+ * `OpenAlContextError::ReopenUnsupported` if the context already exists on
+ * another device and cannot be moved
+ *
+ * # Example
+ * ```no_run
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     let device = ears::devices().into_iter().find(|d| d.name.contains("Headphones"));
+ *     if let Some(device) = device {
+ *         ears::init_with_device(&device.name)?;
+ *     }
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn init_with_device(name: &str) -> Result<(), OpenAlContextError> {
+    if !OpenAlData::is_created() {
+        OpenAlData::request_device(name);
+        return OpenAlData::check_al_context();
+    }
+    match OpenAlData::requested_device() {
+        Some(ref device) if device == name => Ok(()),
+        _ => set_output_device(name),
+    }
+}
+
+/**
+ * Initialize the internal context with custom settings
+ *
+ * This has to be called before any other function of __ears__, as the
+ * settings are ignored once the context is created.
+ *
+ * # Argument
+ * * `context_config` - The settings of the context
+ *
  * # Return
  * `Ok(())` if initialization is successful, `Err(OpenAlContextError)` otherwise
  *
  * # Example
  * ```no_run
+ * use ears::{ContextConfig, HrtfMode};
+ *
  * fn main() -> Result<(), ears::OpenAlContextError> {
- *     ears::init()?;
+ *     ears::init_with_config(ContextConfig {
+ *         hrtf: HrtfMode::Enabled,
+ *         auxiliary_sends: 4,
+ *         ..Default::default()
+ *     })?;
+ *     println!("Using the HRTF profile {:?}", ears::hrtf_profile());
  *     Ok(())
  * }
  * ```
  */
-pub fn init() -> Result<(), OpenAlContextError> {
-    return OpenAlData::check_al_context();
+pub fn init_with_config(context_config: ContextConfig) -> Result<(), OpenAlContextError> {
+    OpenAlData::request_config(context_config);
+    OpenAlData::check_al_context()
 }
 
 /**
@@ -67,6 +179,80 @@ pub fn init_in() -> Result<RecordContext, OpenAlContextError> {
     return OpenAlData::check_al_input_context();
 }
 
+/**
+ * Initialize the input device context, capturing several channels
+ *
+ * Audio interfaces with several inputs expose them as the channels of the
+ * capture device. Choose which of them a Recorder records with
+ * `Recorder::set_channel_map`.
+ *
+ * This has to be called before `init_in`, as the input device is opened only
+ * once: once it is open, asking for another device or format returns
+ * `OpenAlContextError::CaptureInUse`.
+ *
+ * # Argument
+ * * `channels` - The number of channels to capture: 1, 2, 4, 6, 7 or 8
+ *
+ * # Return
+ * `Ok(RecordContext)` if initialization is successful, `Err(OpenAlContextError)`
+ * otherwise, for instance if the device cannot capture that many channels, or
+ * `OpenAlContextError::CaptureInUse` if the capture device is already open
+ * with another number of channels
+ *
+ * # Example
+ * ```no_run
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     let context = ears::init_in_with_channels(4)?;
+ *     println!("Capturing {} channels", context.channels());
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn init_in_with_channels(channels: i32) -> Result<RecordContext, OpenAlContextError> {
+    OpenAlData::request_capture_channels(channels)?;
+    OpenAlData::check_al_input_context()
+}
+
+/**
+ * Initialize the input device context on a capture device, with a format
+ *
+ * This has to be called before `init_in`, as the input device is opened only
+ * once: once it is open, asking for another device or format returns
+ * `OpenAlContextError::CaptureInUse`. The samples are always captured as
+ * 16 bits integers, see `Recorder::set_format` for the format of the saved
+ * files.
+ *
+ * # Arguments
+ * * `device` - The name of the capture device, as listed by
+ *   `capture_devices()`, or None for the one configured with
+ *   `EARS_CAPTURE_DEVICE` or the default one
+ * * `sample_rate` - The sample rate to capture at, in Hz
+ * * `channels` - The number of channels to capture: 1, 2, 4, 6, 7 or 8
+ *
+ * # Return
+ * `Ok(RecordContext)` if initialization is successful, `Err(OpenAlContextError)`
+ * otherwise, for instance if the device cannot be opened with that format, or
+ * `OpenAlContextError::CaptureInUse` if the capture device is already open
+ * with other settings
+ *
+ * # Example
+ * ```no_run
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     let context = ears::init_in_with(Some("USB Microphone"), 48000, 2)?;
+ *     println!("Capturing at {} Hz", context.sample_rate());
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn init_in_with(
+    device: Option<&str>,
+    sample_rate: i32,
+    channels: i32,
+) -> Result<RecordContext, OpenAlContextError> {
+    OpenAlData::request_capture(device, sample_rate, channels)?;
+    OpenAlData::check_al_input_context()
+}
+
 #[cfg(test)]
 mod test {
     #![allow(non_snake_case)]
@@ -84,7 +270,7 @@ mod test {
     #[test]
     #[ignore]
     fn test_init_in_with_normal_init_OK() -> () {
-        init();
+        let _context = init();
         assert!(init_in().is_ok())
     }
 
@@ -97,7 +283,7 @@ mod test {
     #[test]
     #[ignore]
     fn test_init_in_in_another_task_OK() -> () {
-        init();
+        let _context = init();
         thread::spawn(move || assert!(init_in().is_err()));
     }
 }
@@ -26,16 +26,33 @@
 
 #![macro_use]
 
+use attenuation;
+use bus;
+use completion;
+use config;
+use context_config::ContextConfig;
+use disconnect;
+use effect;
+use fade;
+use hrtf;
 use libc;
-use openal::ffi;
+use listener;
+use logging::{self, LogLevel};
+use occlusion;
+use openal::{al, ffi};
+use preload;
 use record_context;
 use record_context::RecordContext;
+use schedule;
+use sound_handle;
+use sources;
 use std::cell::RefCell;
 use std::error::Error;
 use std::ffi::CString;
 use std::fmt;
+use std::mem;
 use std::ptr;
-use std::sync::Mutex;
+use std::sync::{Mutex, Once};
 
 #[derive(Clone)]
 pub enum OpenAlContextError {
@@ -46,6 +63,10 @@ pub enum OpenAlContextError {
     DefaultCaptureDeviceError,
     WrongThread,
     LockError(String),
+    ReopenUnsupported,
+    DeviceError(String),
+    ContextInUse,
+    CaptureInUse,
 }
 
 impl fmt::Display for OpenAlContextError {
@@ -68,6 +89,13 @@ impl fmt::Display for OpenAlContextError {
                         .to_string(),
                 OpenAlContextError::LockError(err) =>
                     format!("Cannot lock OpenAL context mutex: {}", err),
+                OpenAlContextError::ReopenUnsupported =>
+                    "the OpenAL implementation cannot reopen devices".to_string(),
+                OpenAlContextError::DeviceError(name) => format!("cannot open the device {}", name),
+                OpenAlContextError::ContextInUse =>
+                    "a Context already owns the OpenAL context".to_string(),
+                OpenAlContextError::CaptureInUse =>
+                    "the capture device is already open with other settings".to_string(),
             }
         )
     }
@@ -82,8 +110,35 @@ impl fmt::Debug for OpenAlContextError {
 impl Error for OpenAlContextError {}
 
 lazy_static! {
-    static ref AL_CONTEXT: Mutex<Result<OpenAlData, OpenAlContextError>> =
-        Mutex::new(OpenAlData::new());
+    /// The context, None until it is created or once it is cleaned up
+    static ref AL_CONTEXT: Mutex<Option<Result<OpenAlData, OpenAlContextError>>> =
+        Mutex::new(None);
+}
+
+/// Registers the cleanup of the context at exit, once
+static REGISTER_CLEANUP: Once = Once::new();
+
+lazy_static! {
+    /// The settings to request when creating the context
+    static ref REQUESTED_CONFIG: Mutex<ContextConfig> = Mutex::new(ContextConfig::default());
+}
+
+/// The capture device to open, and its format.
+#[derive(Clone, PartialEq)]
+struct CaptureRequest {
+    /// The name of the device, None for the configured one
+    device: Option<String>,
+    sample_rate: i32,
+    channels: i32,
+}
+
+lazy_static! {
+    /// The capture device to open when the input context is requested
+    static ref REQUESTED_CAPTURE: Mutex<CaptureRequest> = Mutex::new(CaptureRequest {
+        device: None,
+        sample_rate: 44100,
+        channels: 1,
+    });
 }
 
 #[derive(Clone)]
@@ -91,36 +146,138 @@ pub struct OpenAlData {
     pub al_context: ffi::ALCcontextPtr,
     pub al_device: ffi::ALCdevicePtr,
     pub al_capt_device: ffi::ALCdevicePtr,
+    pub al_capt_channels: i32,
+    pub al_capt_rate: i32,
 }
 
 impl OpenAlData {
     /// Create a new OpenAlData struct
     ///
+    /// Opens the playback device of the settings, or the configured one with
+    /// a fallback to the default one if None.
+    ///
     /// Private method.
-    fn new() -> Result<OpenAlData, OpenAlContextError> {
-        let device = unsafe { ffi::alcOpenDevice(ptr::null_mut()) };
+    fn new(context_config: ContextConfig) -> Result<OpenAlData, OpenAlContextError> {
+        let device = match context_config.device {
+            Some(ref name) => match CString::new(name.clone()) {
+                Ok(c_name) => unsafe { ffi::alcOpenDevice(c_name.as_ptr() as *mut _) },
+                Err(_) => 0,
+            },
+            None => open_device(config::device(), |name| unsafe { ffi::alcOpenDevice(name) }),
+        };
         if device == 0 {
-            return Err(OpenAlContextError::DefaultDeviceError);
+            return Err(match context_config.device {
+                Some(name) => OpenAlContextError::DeviceError(name),
+                None => OpenAlContextError::DefaultDeviceError,
+            });
         }
-        let context = unsafe { ffi::alcCreateContext(device, ptr::null_mut()) };
+        let mut attributes = context_config.attributes(&hrtf::device_hrtf_profiles(device));
+        let context = unsafe { ffi::alcCreateContext(device, attributes.as_mut_ptr()) };
         if context == 0 {
+            unsafe { ffi::alcCloseDevice(device) };
             return Err(OpenAlContextError::CreationError);
         }
         if unsafe { ffi::alcMakeContextCurrent(context) } == ffi::ALC_FALSE {
+            unsafe {
+                ffi::alcDestroyContext(context);
+                ffi::alcCloseDevice(device);
+            }
             return Err(OpenAlContextError::MakeCurrentError);
         }
 
-        unsafe {
+        REGISTER_CLEANUP.call_once(|| unsafe {
             libc::atexit(cleanup_openal_context);
-        }
+        });
 
         Ok(OpenAlData {
             al_context: context,
             al_device: device,
             al_capt_device: 0,
+            al_capt_channels: 0,
+            al_capt_rate: 0,
         })
     }
 
+    /// Set the number of auxiliary sends to request when the context is created.
+    ///
+    /// Has no effect if the context already exists.
+    pub fn request_auxiliary_sends(sends: i32) {
+        REQUESTED_CONFIG.lock().unwrap().auxiliary_sends = sends;
+    }
+
+    /// Set the playback device to open when the context is created.
+    ///
+    /// Has no effect if the context already exists.
+    pub fn request_device(name: &str) {
+        REQUESTED_CONFIG.lock().unwrap().device = Some(name.to_string());
+    }
+
+    /// Set all the settings to request when the context is created.
+    ///
+    /// Has no effect if the context already exists.
+    pub fn request_config(context_config: ContextConfig) {
+        *REQUESTED_CONFIG.lock().unwrap() = context_config;
+    }
+
+    /// Set the number of channels to request when the capture device is opened.
+    ///
+    /// # Return
+    /// OpenAlContextError::CaptureInUse if the capture device is already open
+    /// with another number of channels.
+    pub fn request_capture_channels(channels: i32) -> Result<(), OpenAlContextError> {
+        let request = REQUESTED_CAPTURE.lock().unwrap().clone();
+        OpenAlData::request_capture(request.device.as_deref(), request.sample_rate, channels)
+    }
+
+    /// Set the capture device to open, and its format.
+    ///
+    /// # Return
+    /// OpenAlContextError::CaptureInUse if the capture device is already open
+    /// with other settings, in which case the request is left unchanged.
+    pub fn request_capture(
+        device: Option<&str>,
+        sample_rate: i32,
+        channels: i32,
+    ) -> Result<(), OpenAlContextError> {
+        let request = CaptureRequest {
+            device: device.map(|name| name.to_string()),
+            sample_rate,
+            channels,
+        };
+        let capture_open = match AL_CONTEXT.lock() {
+            Ok(guard) => match *guard {
+                Some(Ok(ref context)) => context.al_capt_device != 0,
+                _ => false,
+            },
+            Err(poison_error) => {
+                return Err(OpenAlContextError::LockError(poison_error.to_string()))
+            }
+        };
+        let mut requested = REQUESTED_CAPTURE.lock().unwrap();
+        if capture_open {
+            if *requested != request {
+                return Err(OpenAlContextError::CaptureInUse);
+            }
+        } else {
+            *requested = request;
+        }
+        Ok(())
+    }
+
+    /// Check if the context is already created.
+    pub fn is_created() -> bool {
+        match AL_CONTEXT.lock() {
+            Ok(guard) => matches!(*guard, Some(Ok(_))),
+            Err(_) => false,
+        }
+    }
+
+    /// Get the name of the playback device requested, None for the
+    /// configured one.
+    pub fn requested_device() -> Option<String> {
+        REQUESTED_CONFIG.lock().unwrap().device.clone()
+    }
+
     /// Check if the context is created.
     ///
     /// This function check is the OpenAl context is already created.
@@ -135,7 +292,7 @@ impl OpenAlData {
             return Ok(());
         }
         match AL_CONTEXT.lock() {
-            Ok(guard) => match *guard {
+            Ok(mut guard) => match *get_or_create(&mut guard) {
                 Ok(_) => Ok(()),
                 Err(ref err) => Err(err.clone()),
             },
@@ -146,9 +303,13 @@ impl OpenAlData {
     fn is_input_context_init() -> Result<RecordContext, OpenAlContextError> {
         match AL_CONTEXT.lock() {
             Ok(mut guard) => {
-                if let Ok(ref mut new_context) = *guard {
+                if let Ok(ref mut new_context) = *get_or_create(&mut guard) {
                     if new_context.al_capt_device != 0 {
-                        Ok(record_context::new(new_context.al_capt_device))
+                        Ok(record_context::new(
+                            new_context.al_capt_device,
+                            new_context.al_capt_channels,
+                            new_context.al_capt_rate,
+                        ))
                     } else {
                         let c_str = CString::new("ALC_EXT_CAPTURE").unwrap();
                         if unsafe {
@@ -157,19 +318,32 @@ impl OpenAlData {
                         {
                             return Err(OpenAlContextError::NoInputDevice);
                         } else {
-                            new_context.al_capt_device = unsafe {
-                                ffi::alcCaptureOpenDevice(
-                                    ptr::null_mut(),
-                                    44100,
-                                    ffi::AL_FORMAT_MONO16,
-                                    44100,
-                                )
+                            let request = REQUESTED_CAPTURE.lock().unwrap().clone();
+                            let (rate, channels) = (request.sample_rate, request.channels);
+                            let format = match al::get_channels_format(channels) {
+                                Some(format) if rate > 0 => format,
+                                _ => return Err(OpenAlContextError::DefaultCaptureDeviceError),
+                            };
+                            let open = |name| unsafe {
+                                ffi::alcCaptureOpenDevice(name, rate, format, rate)
+                            };
+                            new_context.al_capt_device = match request.device {
+                                Some(ref name) => match CString::new(name.clone()) {
+                                    Ok(c_name) => open(c_name.as_ptr() as *mut _),
+                                    Err(_) => 0,
+                                },
+                                None => open_device(config::capture_device(), open),
                             };
                             if new_context.al_capt_device == 0 {
-                                return Err(OpenAlContextError::DefaultCaptureDeviceError);
+                                return Err(match request.device {
+                                    Some(name) => OpenAlContextError::DeviceError(name),
+                                    None => OpenAlContextError::DefaultCaptureDeviceError,
+                                });
                             } else {
+                                new_context.al_capt_channels = channels;
+                                new_context.al_capt_rate = rate;
                                 let cap_device = new_context.al_capt_device;
-                                return Ok(record_context::new(cap_device));
+                                return Ok(record_context::new(cap_device, channels, rate));
                             }
                         }
                     }
@@ -181,13 +355,89 @@ impl OpenAlData {
         }
     }
 
+    /// Check if an AL extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn extension_present(extension: &str) -> bool {
+        let c_str = CString::new(extension).unwrap();
+        unsafe { ffi::alIsExtensionPresent(c_str.as_ptr()) == ffi::AL_TRUE }
+    }
+
+    /// Check if an ALC extension is present on the current device
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn alc_extension_present(extension: &str) -> bool {
+        let c_str = CString::new(extension).unwrap();
+        unsafe {
+            let device = ffi::alcGetContextsDevice(ffi::alcGetCurrentContext());
+            device != 0 && ffi::alcIsExtensionPresent(device, c_str.as_ptr()) == ffi::ALC_TRUE
+        }
+    }
+
+    /// Check if ALC_EXT_EFX extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn efx_capable() -> bool {
+        OpenAlData::alc_extension_present("ALC_EXT_EFX")
+    }
+
     /// Check if AL_SOFT_direct_channels extension is present
     ///
     /// # Return
     /// true if the extension is present, otherwise false.
     pub fn direct_channel_capable() -> bool {
-        let c_str = CString::new("AL_SOFT_direct_channels").unwrap();
-        unsafe { ffi::alIsExtensionPresent(c_str.as_ptr()) == ffi::AL_TRUE }
+        OpenAlData::extension_present("AL_SOFT_direct_channels")
+    }
+
+    /// Check if AL_EXT_STEREO_ANGLES extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn stereo_angles_capable() -> bool {
+        OpenAlData::extension_present("AL_EXT_STEREO_ANGLES")
+    }
+
+    /// Check if AL_SOFT_source_spatialize extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn source_spatialize_capable() -> bool {
+        OpenAlData::extension_present("AL_SOFT_source_spatialize")
+    }
+
+    /// Check if AL_SOFT_source_resampler extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn source_resampler_capable() -> bool {
+        OpenAlData::extension_present("AL_SOFT_source_resampler")
+    }
+
+    /// Check if AL_SOFT_source_latency extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn source_latency_capable() -> bool {
+        OpenAlData::extension_present("AL_SOFT_source_latency")
+    }
+
+    /// Check if AL_SOFT_source_start_delay extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn source_start_delay_capable() -> bool {
+        OpenAlData::extension_present("AL_SOFT_source_start_delay")
+    }
+
+    /// Check if AL_SOFT_deferred_updates extension is present
+    ///
+    /// # Return
+    /// true if the extension is present, otherwise false.
+    pub fn deferred_updates_capable() -> bool {
+        OpenAlData::extension_present("AL_SOFT_deferred_updates")
     }
 
     /// Check if the input context is created.
@@ -211,11 +461,162 @@ impl OpenAlData {
     }
 }
 
+/// Get the settings requested for the context.
+fn requested_config() -> ContextConfig {
+    REQUESTED_CONFIG.lock().unwrap().clone()
+}
+
+/// Get the playback device requested with `init_with_device`, if any.
+fn requested_device() -> Option<String> {
+    requested_config().device
+}
+
+/// Open the configured device, falling back to the default one if it fails.
+fn open_device<F>(name: Option<String>, open: F) -> ffi::ALCdevicePtr
+where
+    F: Fn(*mut libc::c_char) -> ffi::ALCdevicePtr,
+{
+    if let Some(name) = name {
+        if let Ok(c_name) = CString::new(name.clone()) {
+            let device = open(c_name.as_ptr() as *mut _);
+            if device != 0 {
+                return device;
+            }
+        }
+        logging::log(
+            LogLevel::Warning,
+            &format!("cannot open the device {}, using the default one", name),
+        );
+    }
+    open(ptr::null_mut())
+}
+
+/**
+ * Reopen the playback device in place after its loss, with the
+ * ALC_SOFT_reopen_device extension.
+ *
+ * The requested device is used, else the one configured with `EARS_DEVICE`,
+ * or the default one if it cannot be opened.
+ *
+ * # Return
+ * true if the device has been reopened.
+ */
+pub(crate) fn reopen_device() -> bool {
+    let (device, reopen) = match reopen_function() {
+        Ok(function) => function,
+        Err(_) => return false,
+    };
+
+    let reopened = open_device(requested_device().or_else(config::device), |name| unsafe {
+        match reopen(device, name, ptr::null()) {
+            ffi::ALC_FALSE => 0,
+            _ => device,
+        }
+    });
+    reopened != 0
+}
+
+/**
+ * Switch the playback to another device, e.g. when headphones are plugged
+ * in.
+ *
+ * The device is reopened in place with the ALC_SOFT_reopen_device
+ * extension, so every Sound, SoundData, Music and ReverbEffect moves to the
+ * new device with its settings. The device is then also used by the
+ * automatic reconnection, see `set_auto_reconnect`.
+ *
+ * # Argument
+ * * `name` - The name of the playback device, as listed by `devices()`
+ *
+ * # Return
+ * `Ok(())` if the playback switched to the device, an OpenAlContextError
+ * otherwise, in which case the playback stays on the current device:
+ * Err(OpenAlContextError::ReopenUnsupported) without the extension.
+ *
+ * # Example
+ * ```no_run
+ * fn main() -> Result<(), ears::OpenAlContextError> {
+ *     if let Some(headphones) = ears::devices().iter().find(|d| d.name.contains("Headphones")) {
+ *         ears::set_output_device(&headphones.name)?;
+ *     }
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn set_output_device(name: &str) -> Result<(), OpenAlContextError> {
+    OpenAlData::check_al_context()?;
+    let (device, reopen) = reopen_function()?;
+
+    let c_name = match CString::new(name) {
+        Ok(c_name) => c_name,
+        Err(_) => return Err(OpenAlContextError::DeviceError(name.to_string())),
+    };
+    if unsafe { reopen(device, c_name.as_ptr(), ptr::null()) } == ffi::ALC_FALSE {
+        return Err(OpenAlContextError::DeviceError(name.to_string()));
+    }
+    OpenAlData::request_device(name);
+    Ok(())
+}
+
+/// Get the playback device and its alcReopenDeviceSOFT function, without
+/// creating the context.
+fn reopen_function() -> Result<(ffi::ALCdevicePtr, ffi::ReopenDeviceFn), OpenAlContextError> {
+    let guard = match AL_CONTEXT.lock() {
+        Ok(guard) => guard,
+        Err(poison_error) => return Err(OpenAlContextError::LockError(poison_error.to_string())),
+    };
+    let device = match *guard {
+        Some(Ok(ref context)) => context.al_device,
+        Some(Err(ref err)) => return Err(err.clone()),
+        None => return Err(OpenAlContextError::CreationError),
+    };
+
+    let extension = CString::new("ALC_SOFT_reopen_device").unwrap();
+    let function = CString::new("alcReopenDeviceSOFT").unwrap();
+    let reopen = unsafe {
+        if ffi::alcIsExtensionPresent(device, extension.as_ptr()) == ffi::ALC_FALSE {
+            return Err(OpenAlContextError::ReopenUnsupported);
+        }
+        ffi::alcGetProcAddress(device, function.as_ptr())
+    };
+    if reopen.is_null() {
+        return Err(OpenAlContextError::ReopenUnsupported);
+    }
+    let reopen: ffi::ReopenDeviceFn = unsafe { mem::transmute(reopen) };
+    Ok((device, reopen))
+}
+
+/// Get the context, creating it with the requested settings if needed.
+fn get_or_create(
+    context: &mut Option<Result<OpenAlData, OpenAlContextError>>,
+) -> &mut Result<OpenAlData, OpenAlContextError> {
+    context.get_or_insert_with(|| OpenAlData::new(requested_config()))
+}
+
 /// Does early cleanup of the library. This is automatically called when the program exits.
+///
+/// The context is created again by the next use of __ears__.
 pub fn cleanup() {
+    // The watcher would otherwise check the device of the next context
+    disconnect::stop();
+    // The Sounds owned by ears delete their objects while the context exists
+    sound_handle::clear();
+    preload::clear_preload_cache();
+    // The ids of the sources and effect slots are reused by the next context
+    attenuation::clear();
+    bus::clear();
+    completion::clear();
+    effect::clear();
+    fade::clear();
+    listener::clear_doppler_exempt();
+    occlusion::clear();
+    schedule::clear();
+    sources::clear();
+
     if let Ok(mut guard) = AL_CONTEXT.lock() {
-        if let Ok(ref mut context) = *guard {
+        if let Some(Ok(context)) = guard.take() {
             unsafe {
+                ffi::alcMakeContextCurrent(0);
                 ffi::alcDestroyContext(context.al_context);
                 if context.al_capt_device != 0 {
                     ffi::alcCaptureCloseDevice(context.al_capt_device);
@@ -229,12 +630,17 @@ extern "C" fn cleanup_openal_context() {
     cleanup()
 }
 
-
 macro_rules! check_openal_context(
+    () => (
+            check_openal_context!(())
+        );
     ($def_ret:expr) => (
-            match OpenAlData::check_al_context() {
+            match ::internal::OpenAlData::check_al_context() {
                 Ok(_)    => {},
-                Err(err) => { println!("{}", err); return $def_ret; }
+                Err(err) => {
+                    ::logging::log(::logging::LogLevel::Error, &err.to_string());
+                    return $def_ret;
+                }
             }
         );
 );
@@ -0,0 +1,213 @@
+//! Music made of several stems played in sync.
+
+use audio_controller::AudioController;
+use error::SoundError;
+use music::Music;
+use source_group::SourceGroup;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// The largest gap between the layers before `resync` aligns them again
+const MAX_DRIFT: Duration = Duration::from_millis(10);
+
+/**
+ * Play several music files, the stems of a piece, in sync.
+ *
+ * Each layer is streamed on its own Music, and all the layers start on the
+ * same sample so they stay aligned. Changing the volume of the layers
+ * while they play makes adaptive music: the drums can come in when a fight
+ * starts, and fade out once it's over.
+ *
+ * The stems should have the same duration and sample rate.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{LayeredMusic, SoundError};
+ * use std::time::Duration;
+ *
+ * fn main() -> Result<(), SoundError> {
+ *     let mut music = LayeredMusic::new(&["path/to/pads.ogg", "path/to/drums.ogg"])?;
+ *     music.set_layer_volume(1, 0.0);
+ *     music.set_looping(true);
+ *     music.play();
+ *
+ *     // The fight starts
+ *     music.fade_layer(1, 1.0, Duration::from_secs(2));
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct LayeredMusic {
+    layers: Vec<Music>,
+    /// The last underrun of the layers `resync` has seen
+    last_underrun: Option<Instant>,
+}
+
+impl LayeredMusic {
+    /**
+     * Create a LayeredMusic from the files of its layers.
+     *
+     * # Argument
+     * * `paths` - The paths of the music files, in the order of the layers
+     *
+     * # Return
+     * A `Result` containing Ok(LayeredMusic) on success, Err(SoundError) if
+     * one of the files could not be opened.
+     */
+    pub fn new<P: AsRef<Path>>(paths: &[P]) -> Result<LayeredMusic, SoundError> {
+        let layers = paths
+            .iter()
+            .map(Music::new)
+            .collect::<Result<Vec<Music>, SoundError>>()?;
+        Ok(LayeredMusic {
+            layers,
+            last_underrun: None,
+        })
+    }
+
+    /// Get the number of layers.
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /**
+     * Get the Music playing a layer.
+     *
+     * Useful to give a layer its own effects, filter or bus. Playing,
+     * pausing or stopping the layer on its own makes it leave the others.
+     *
+     * # Argument
+     * * `index` - The index of the layer
+     */
+    pub fn layer(&mut self, index: usize) -> Option<&mut Music> {
+        self.layers.get_mut(index)
+    }
+
+    /**
+     * Set the volume of a layer.
+     *
+     * # Arguments
+     * * `index` - The index of the layer, ignored if out of range
+     * * `volume` - The volume of the layer, should be between 0.0 and 1.0
+     */
+    pub fn set_layer_volume(&mut self, index: usize, volume: f32) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.set_volume(volume);
+        }
+    }
+
+    /**
+     * Get the volume of a layer.
+     *
+     * # Argument
+     * * `index` - The index of the layer
+     *
+     * # Return
+     * The volume of the layer, or None if the index is out of range
+     */
+    pub fn get_layer_volume(&self, index: usize) -> Option<f32> {
+        self.layers.get(index).map(|layer| layer.get_volume())
+    }
+
+    /**
+     * Ramp the volume of a layer, to bring it in or out of the mix.
+     *
+     * # Arguments
+     * * `index` - The index of the layer, ignored if out of range
+     * * `volume` - The volume of the layer at the end of the fade
+     * * `duration` - The duration of the fade
+     */
+    pub fn fade_layer(&mut self, index: usize, volume: f32, duration: Duration) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.fade_to(volume, duration);
+        }
+    }
+
+    /// Play or resume all the layers, starting them on the same sample.
+    pub fn play(&mut self) {
+        self.group().play_all();
+    }
+
+    /// Pause all the layers on the same sample.
+    pub fn pause(&mut self) {
+        self.group().pause_all();
+    }
+
+    /// Stop all the layers, going back to the start.
+    pub fn stop(&mut self) {
+        self.group().stop_all();
+    }
+
+    /**
+     * Align the layers again if one of them fell out of sync.
+     *
+     * A layer drifts when its stream underruns, as it pauses while the
+     * others keep playing. The layers are then moved back to the position
+     * of the first one and restarted together. Call it regularly, for
+     * instance once per frame, to keep them aligned.
+     *
+     * # Return
+     * true if the layers had to be aligned again, false otherwise
+     */
+    pub fn resync(&mut self) -> bool {
+        if !self.is_playing() {
+            return false;
+        }
+        let underrun = self
+            .layers
+            .iter()
+            .filter_map(|layer| layer.get_stats().last_underrun)
+            .max();
+        let reference = self.layers[0].get_playback_position_precise().0;
+        let drifted = self.layers.iter().any(|layer| {
+            let position = layer.get_playback_position_precise().0;
+            let drift = position.max(reference) - position.min(reference);
+            drift > MAX_DRIFT
+        });
+        let underrun_seen = underrun.is_some() && underrun != self.last_underrun;
+        self.last_underrun = underrun;
+        if !drifted && !underrun_seen {
+            return false;
+        }
+
+        let offset = self.layers[0].get_offset();
+        self.group().pause_all();
+        for layer in self.layers.iter_mut() {
+            layer.stop_keep_position();
+            layer.set_offset(offset);
+        }
+        self.play();
+        true
+    }
+
+    /// Get a SourceGroup of all the layers.
+    fn group(&mut self) -> SourceGroup<'_> {
+        let mut group = SourceGroup::new();
+        for layer in self.layers.iter_mut() {
+            group.add(layer);
+        }
+        group
+    }
+
+    /// Check if the layers are playing.
+    pub fn is_playing(&self) -> bool {
+        self.layers.iter().any(|layer| layer.is_playing())
+    }
+
+    /**
+     * Set whether all the layers loop.
+     *
+     * # Argument
+     * * `looping` - true to loop, false otherwise
+     */
+    pub fn set_looping(&mut self, looping: bool) {
+        for layer in self.layers.iter_mut() {
+            layer.set_looping(looping);
+        }
+    }
+
+    /// Check if the layers are looping.
+    pub fn is_looping(&self) -> bool {
+        self.layers.iter().all(|layer| layer.is_looping())
+    }
+}
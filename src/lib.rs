@@ -65,43 +65,179 @@ fn main() -> Result<(), SoundError> {
 #![allow(unused_must_use)]
 //#![allow(improper_ctypes)]
 
+#[cfg(feature = "glam")]
+extern crate glam;
+#[cfg(feature = "pure-rust")]
+extern crate hound;
 extern crate libc;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "mint")]
+extern crate mint;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+#[cfg(any(feature = "mp3", feature = "pure-rust"))]
+extern crate symphonia;
 
 // Reexport public API
+pub use about::{about, About};
+pub use adaptive_music::{AdaptiveMusic, Transition, TransitionSync};
+pub use agc::AgcSettings;
+pub use attenuation::AttenuationCurve;
 pub use audio_controller::AudioController;
-pub use audio_tags::{AudioTags, Tags};
-pub use einit::{init, init_in};
+pub use audio_tags::{write_tags, AudioTags, Tags};
+pub use bus::{get_global_pitch, set_global_pitch, Bus};
+pub use chorus_effect::{ChorusEffect, FlangerEffect, Waveform};
+pub use clock::device_clock;
+pub use context::Context;
+pub use context_config::{
+    max_mono_sources, max_stereo_sources, output_frequency, ContextConfig, ContextSettings,
+};
+pub use deferred::with_deferred_updates;
+pub use devices::{capture_devices, devices, DeviceInfo};
+pub use disconnect::{
+    clear_device_callback, is_auto_reconnect, is_device_connected, set_auto_reconnect,
+    set_device_callback, DeviceEvent,
+};
+pub use distortion_effect::DistortionEffect;
+pub use dsp::{is_crossfeed_enabled, set_crossfeed};
+pub use echo_effect::EchoEffect;
+pub use effect::Effect;
+pub use efx::{efx_support, max_auxiliary_sends, EffectType, EfxSupport};
+pub use einit::{
+    init, init_in, init_in_with, init_in_with_channels, init_with_auxiliary_sends,
+    init_with_config, init_with_device,
+};
+pub use equalizer_effect::EqualizerEffect;
 pub use error::SoundError;
-pub use internal::{cleanup, OpenAlContextError};
+pub use filter::{Filter, FilterError, FilterType};
+#[cfg(feature = "chromaprint")]
+pub use fingerprint::{fingerprint_file, fingerprint_samples, Fingerprint, FingerprintError};
+pub use gate::GateSettings;
+pub use hrtf::{hrtf_profile, hrtf_profiles, hrtf_status, is_hrtf_enabled, HrtfMode, HrtfStatus};
+pub use internal::{cleanup, set_output_device, OpenAlContextError};
+pub use layered_music::LayeredMusic;
+pub use limits::{max_decoded_size, set_max_decoded_size};
+pub use load::{load_music, load_sound, LoadHandle};
+pub use logging::{clear_log_callback, set_log_callback, LogLevel};
+pub use lyrics::{LyricLine, Lyrics};
+pub use monitor::Monitor;
 pub use music::Music;
-pub use presets::ReverbPreset;
+pub use openal::al::{AlError, AlErrorCode};
+#[cfg(feature = "async")]
+pub use playback_future::PlaybackFuture;
+pub use playback_stats::PlaybackStats;
+pub use playlist::Playlist;
+pub use preload::{clear_preload_cache, preload, PreloadHandle, PreloadProgress};
+#[cfg(feature = "serde")]
+pub use presets::ReverbPresetError;
+pub use presets::{ReverbPreset, ReverbProperties};
 pub use record_context::RecordContext;
-pub use recorder::Recorder;
-pub use reverb_effect::ReverbEffect;
+pub use recorder::{AudioFormat, CaptureChunk, RecordFormat, Recorder};
+pub use resampler::{default_resampler, resamplers};
+pub use reverb_effect::{ReverbEffect, ReverbEffectError};
+pub use ring_modulator_effect::{RingModulatorEffect, RingModulatorWaveform};
+pub use sndfile::SndFileError;
 pub use sound::Sound;
+pub use sound_bank::SoundBank;
 pub use sound_data::SoundData;
+pub use sound_handle::{play_sound, play_sound_data, SoundHandle};
+pub use sound_pool::{SoundPool, VoiceStealing};
+pub use source_group::SourceGroup;
+pub use source_settings::SourceSettings;
+pub use sources::{pause_all, resume_all, stop_all};
+pub use spatialize::Spatialize;
+pub use speaker_test::{speaker_test, Speaker};
 pub use states::State;
+pub use stream_config::StreamConfig;
+pub use stream_info::StreamInfo;
+pub use vector::Vector3;
 
 // Hidden internal bindings
+mod compressed_buffer;
+mod config;
+mod dsp;
 mod internal;
+#[cfg(all(feature = "mp3", not(feature = "pure-rust")))]
+mod mp3;
 mod openal;
+mod sample;
+mod schedule;
+mod scrub;
 mod sndfile;
+mod source_latency;
+mod spectrum;
+#[cfg(any(feature = "mp3", feature = "pure-rust"))]
+mod symphonia_io;
 
 // The public ears API
 
+mod about;
+mod adaptive_music;
+mod agc;
+mod attenuation;
 mod audio_controller;
 mod audio_tags;
+mod auto_velocity;
+mod bus;
+mod chorus_effect;
+mod clock;
+mod completion;
+mod context;
+mod context_config;
+pub mod decoder;
+mod deferred;
+mod devices;
+mod disconnect;
+mod distortion_effect;
+mod echo_effect;
+mod effect;
+mod efx;
 #[path = "init.rs"]
 mod einit;
+mod equalizer_effect;
 mod error;
+mod fade;
+mod filter;
+#[cfg(feature = "chromaprint")]
+mod fingerprint;
+mod gate;
+mod hrtf;
+mod layered_music;
+mod limits;
 pub mod listener;
+mod load;
+mod logging;
+mod lyrics;
+mod monitor;
 mod music;
+mod occlusion;
+#[cfg(feature = "async")]
+mod playback_future;
+mod playback_stats;
+mod playlist;
+mod preload;
 mod presets;
 mod record_context;
 mod recorder;
+mod resampler;
 mod reverb_effect;
+mod ring_modulator_effect;
 mod sound;
+mod sound_bank;
 mod sound_data;
+mod sound_handle;
+mod sound_pool;
+mod source_group;
+mod source_settings;
+mod sources;
+mod spatialize;
+mod speaker_test;
 mod states;
+mod stream_config;
+mod stream_info;
+mod stream_service;
+mod vector;
@@ -0,0 +1,149 @@
+//! Limits applied when decoding files, so untrusted files can't exhaust the memory.
+
+use error::SoundError;
+use sndfile::SndInfo;
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The most channels a file can declare, far beyond any real layout
+const MAX_CHANNELS: i32 = 64;
+/// The highest sample rate a file can declare
+const MAX_SAMPLE_RATE: i32 = 768_000;
+/// The default maximum size of the decoded samples of a SoundData, 1GiB
+const DEFAULT_MAX_DECODED_SIZE: usize = 1 << 30;
+
+/// The maximum size of the decoded samples of a SoundData, in bytes, 0 for no limit
+static MAX_DECODED_SIZE: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_DECODED_SIZE);
+
+/**
+ * Set the maximum size of the samples of a SoundData.
+ *
+ * SoundDatas, and so Sounds, decode whole files in memory. A file declaring
+ * more samples than this limit fails to load with `SoundError::TooLarge`
+ * instead of allocating the memory, which matters when loading files from
+ * users or mods. Musics are streamed and not affected.
+ *
+ * # Argument
+ * * `bytes` - The maximum size of the decoded samples, in bytes, or None for
+ *   no limit. The default is 1GiB, about 3 hours of stereo audio at 44100Hz.
+ *
+ * # Example
+ * ```no_run
+ * // Sound effects of mods are at most 64MiB
+ * ears::set_max_decoded_size(Some(64 << 20));
+ * ```
+ */
+pub fn set_max_decoded_size(bytes: Option<usize>) {
+    MAX_DECODED_SIZE.store(bytes.unwrap_or(0), Ordering::Relaxed);
+}
+
+/**
+ * Get the maximum size of the samples of a SoundData.
+ *
+ * # Return
+ * The maximum size of the decoded samples in bytes, None if there is no limit
+ */
+pub fn max_decoded_size() -> Option<usize> {
+    match MAX_DECODED_SIZE.load(Ordering::Relaxed) {
+        0 => None,
+        bytes => Some(bytes),
+    }
+}
+
+/// Check that the header of a file describes audio that can be played.
+pub(crate) fn check_header(infos: &SndInfo) -> Result<(), SoundError> {
+    if infos.channels < 1
+        || infos.channels > MAX_CHANNELS
+        || infos.samplerate < 1
+        || infos.samplerate > MAX_SAMPLE_RATE
+        || infos.frames < 0
+    {
+        Err(SoundError::InvalidHeader)
+    } else {
+        Ok(())
+    }
+}
+
+/**
+ * Check the header of a file to be decoded in memory.
+ *
+ * # Return
+ * The number of 16 bits samples to decode, or an error if the header is
+ * invalid or the samples exceed the maximum decoded size.
+ */
+pub(crate) fn decoded_samples(infos: &SndInfo) -> Result<usize, SoundError> {
+    check_header(infos)?;
+    let samples = (infos.frames as u64)
+        .checked_mul(infos.channels as u64)
+        .ok_or(SoundError::TooLarge)?;
+    let bytes = samples.checked_mul(2).ok_or(SoundError::TooLarge)?;
+    match max_decoded_size() {
+        Some(max) if bytes > max as u64 => Err(SoundError::TooLarge),
+        _ => usize::try_from(samples).map_err(|_| SoundError::TooLarge),
+    }
+}
+
+/// Check that a file can be read whole in memory.
+pub(crate) fn check_file_size(bytes: u64) -> bool {
+    match max_decoded_size() {
+        Some(max) => bytes <= max as u64,
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{check_header, decoded_samples};
+    use error::SoundError;
+    use sndfile::SndInfo;
+
+    fn infos(frames: i64, samplerate: i32, channels: i32) -> SndInfo {
+        SndInfo {
+            frames,
+            samplerate,
+            channels,
+            format: 0,
+            sections: 1,
+            seekable: 1,
+        }
+    }
+
+    #[test]
+    fn limits_decoded_samples_OK() -> () {
+        assert_eq!(decoded_samples(&infos(44100, 44100, 2)).ok(), Some(88200));
+        assert_eq!(decoded_samples(&infos(0, 8000, 1)).ok(), Some(0));
+    }
+
+    #[test]
+    fn limits_check_header_FAIL() -> () {
+        for bad in [
+            infos(100, 44100, 0),
+            infos(100, 44100, -2),
+            infos(100, 44100, 1000),
+            infos(100, 0, 2),
+            infos(100, 100_000_000, 2),
+            infos(-1, 44100, 2),
+        ]
+        .iter()
+        {
+            match check_header(bad) {
+                Err(SoundError::InvalidHeader) => {}
+                other => panic!("{:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn limits_decoded_samples_FAIL() -> () {
+        match decoded_samples(&infos(i64::MAX, 44100, 64)) {
+            Err(SoundError::TooLarge) => {}
+            other => panic!("{:?}", other),
+        }
+        match decoded_samples(&infos(1 << 30, 44100, 2)) {
+            Err(SoundError::TooLarge) => {}
+            other => panic!("{:?}", other),
+        }
+    }
+}
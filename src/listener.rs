@@ -21,8 +21,46 @@
 
 //! Module for manage the listener in the scene.
 
+use attenuation;
+use auto_velocity::AutoVelocity;
+use deferred;
 use internal::OpenAlData;
 use openal::{al, ffi};
+use std::sync::Mutex;
+use std::time::Instant;
+use vector::Vector3;
+
+/**
+ * The location and orientation of the listener.
+ *
+ * The default transform is at the origin, facing -Z with Y up.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+    /// The position of the listener [x, y, z]
+    pub position: [f32; 3],
+    /// The direction the listener faces [x, y, z]
+    pub forward: [f32; 3],
+    /// The top of the listener [x, y, z]
+    pub up: [f32; 3],
+}
+
+impl Default for Transform {
+    fn default() -> Transform {
+        Transform {
+            position: [0.0, 0.0, 0.0],
+            forward: [0.0, 0.0, -1.0],
+            up: [0.0, 1.0, 0.0],
+        }
+    }
+}
+
+lazy_static! {
+    /// The sources following the listener velocity to cancel the Doppler effect
+    static ref DOPPLER_EXEMPT_SOURCES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+    /// Computes the listener velocity from its positions, when enabled
+    static ref AUTO_VELOCITY: Mutex<AutoVelocity> = Mutex::new(AutoVelocity::default());
+}
 
 /**
  * Set the global volume of the scene.
@@ -79,16 +117,26 @@ pub fn get_volume() -> f32 {
  *
  * # Argument
  * * `position` - A three dimensional vector of f32 containing the position
- * of the listener [x, y, z].
+ *   of the listener [x, y, z], see `Vector3`.
  *
  * # Example
  * ```
  * # use ears::listener;
  * listener::set_position([45., 90., 35.]);
  */
-pub fn set_position(position: [f32; 3]) -> () {
+pub fn set_position<V: Vector3>(position: V) -> () {
     check_openal_context!(());
+    let position = position.to_array();
     al::alListenerfv(ffi::AL_POSITION, &position[0]);
+    attenuation::update_all_sources();
+
+    let velocity = AUTO_VELOCITY
+        .lock()
+        .unwrap()
+        .update(position, Instant::now());
+    if let Some(velocity) = velocity {
+        set_velocity(velocity);
+    }
 }
 
 /**
@@ -119,7 +167,7 @@ pub fn get_position() -> [f32; 3] {
  * Default orientation is : at[0.0, 0.0, -1.0] - up[0.0, 1.0, 0.0]
  *
  * # Arguments
- * * `orientation_at` - The front as a three dimensional vector [x, y, z].
+ * * `orientation_at` - The front as a three dimensional vector [x, y, z], see `Vector3`.
  * * `orientation_up` - The top as a three dimensional vector [x, y, z].
  *
  * # Example
@@ -128,8 +176,9 @@ pub fn get_position() -> [f32; 3] {
  * listener::set_orientation([0.3f32, -0.4f32, 0.9f32], [0.7f32, 0.3f32, 0.8f32]);
  * ```
  */
-pub fn set_orientation(orientation_at: [f32; 3], orientation_up: [f32; 3]) {
+pub fn set_orientation<A: Vector3, U: Vector3>(orientation_at: A, orientation_up: U) {
     check_openal_context!(());
+    let (orientation_at, orientation_up) = (orientation_at.to_array(), orientation_up.to_array());
     let orientation: [f32; 6] = [
         orientation_at[0],
         orientation_at[1],
@@ -165,6 +214,57 @@ pub fn get_orientation() -> ([f32; 3], [f32; 3]) {
     )
 }
 
+/**
+ * Set the position and the orientation of the listener at once.
+ *
+ * The changes are applied together when the AL_SOFT_deferred_updates
+ * extension is available, so a camera can be followed with one call per
+ * frame.
+ *
+ * # Arguments
+ * * `position` - The position of the listener [x, y, z]
+ * * `forward` - The direction the listener faces [x, y, z]
+ * * `up` - The top of the listener [x, y, z]
+ *
+ * # Example
+ * ```
+ * # use ears::listener;
+ * listener::set_transform([10., 1.8, -4.], [0., 0., -1.], [0., 1., 0.]);
+ * ```
+ */
+pub fn set_transform<P: Vector3, F: Vector3, U: Vector3>(position: P, forward: F, up: U) {
+    check_openal_context!();
+
+    deferred::with_deferred_updates(|| {
+        set_position(position);
+        set_orientation(forward, up);
+    });
+}
+
+/**
+ * Get the position and the orientation of the listener.
+ *
+ * # Return
+ * The Transform of the listener
+ *
+ * # Example
+ * ```
+ * # use ears::listener;
+ * let transform = listener::get_transform();
+ * println!("Listener at {:?} facing {:?}", transform.position, transform.forward);
+ * ```
+ */
+pub fn get_transform() -> Transform {
+    check_openal_context!(Transform::default());
+
+    let (forward, up) = get_orientation();
+    Transform {
+        position: get_position(),
+        forward,
+        up,
+    }
+}
+
 /**
  * Set the velocity of the listener.
  *
@@ -172,12 +272,14 @@ pub fn get_orientation() -> ([f32; 3], [f32; 3]) {
  *
  * # Argument
  * * `velocity` - A three dimensional vector of f32 containing the velocity
- * of the sound [x, y, z].
+ *   of the sound [x, y, z], see `Vector3`.
  */
-pub fn set_velocity(velocity: [f32; 3]) -> () {
+pub fn set_velocity<V: Vector3>(velocity: V) -> () {
     check_openal_context!(());
+    let velocity = velocity.to_array();
 
     al::alListenerfv(ffi::AL_VELOCITY, &velocity[0]);
+    update_doppler_exempt_sources();
 }
 
 /**
@@ -195,10 +297,146 @@ pub fn get_velocity() -> [f32; 3] {
     velocity
 }
 
+/**
+ * Compute the velocity of the listener from its positions.
+ *
+ * Each `set_position` or `set_transform` then sets the velocity to the
+ * distance moved since the previous position divided by the time elapsed,
+ * like `AudioController::set_auto_velocity` does for the sources.
+ *
+ * The default is false.
+ *
+ * # Argument
+ * * `enabled` - true to compute the velocity from the positions
+ *
+ * # Example
+ * ```
+ * # use ears::listener;
+ * listener::set_auto_velocity(true);
+ * ```
+ */
+pub fn set_auto_velocity(enabled: bool) {
+    AUTO_VELOCITY.lock().unwrap().set_enabled(enabled);
+}
+
+/// Check if the velocity of the listener is computed from its positions.
+pub fn is_auto_velocity() -> bool {
+    AUTO_VELOCITY.lock().unwrap().is_enabled()
+}
+
+/**
+ * Set the strength of the Doppler effect.
+ *
+ * Scales the pitch shift caused by the velocities of the sources and of the
+ * listener. Default Doppler factor is 1.0, 0.0 disables the Doppler effect.
+ *
+ * # Argument
+ * * `factor` - The new Doppler factor, greater or equal to 0.0
+ *
+ * # Example
+ * ```
+ * # use ears::listener;
+ * listener::set_doppler_factor(0.5f32);
+ * ```
+ */
+pub fn set_doppler_factor(factor: f32) {
+    check_openal_context!();
+    al::alDopplerFactor(factor);
+}
+
+/**
+ * Get the strength of the Doppler effect.
+ *
+ * # Return
+ * The Doppler factor
+ */
+pub fn get_doppler_factor() -> f32 {
+    check_openal_context!(1.0);
+    al::alGetFloat(ffi::AL_DOPPLER_FACTOR)
+}
+
+/**
+ * Set the speed of sound used for the Doppler effect.
+ *
+ * The speed is in the units of the velocities: with positions in meters and
+ * velocities in meters per second, the default of 343.3 is the speed of
+ * sound in air.
+ *
+ * # Argument
+ * * `speed` - The new speed of sound, greater than 0.0
+ *
+ * # Example
+ * ```
+ * # use ears::listener;
+ * // Positions and velocities in feet
+ * listener::set_speed_of_sound(1126.0f32);
+ * ```
+ */
+pub fn set_speed_of_sound(speed: f32) {
+    check_openal_context!();
+    al::alSpeedOfSound(speed);
+}
+
+/**
+ * Get the speed of sound used for the Doppler effect.
+ *
+ * # Return
+ * The speed of sound
+ */
+pub fn get_speed_of_sound() -> f32 {
+    check_openal_context!(343.3);
+    al::alGetFloat(ffi::AL_SPEED_OF_SOUND)
+}
+
+/**
+ * Exempt a source from the Doppler effect, or stop exempting it.
+ *
+ * The Doppler shift depends on the velocity of the source relative to the
+ * listener, so an exempt source gets the velocity of the listener, updated
+ * each time the listener velocity changes. A relative source already moves
+ * with the listener, so it gets no velocity.
+ */
+pub(crate) fn set_doppler_exempt(source: u32, exempt: bool) {
+    let mut sources = DOPPLER_EXEMPT_SOURCES.lock().unwrap();
+    sources.retain(|s| *s != source);
+    if exempt {
+        sources.push(source);
+        update_source_velocity(source, get_velocity());
+    }
+}
+
+/// Forget all the exempt sources, when the context is cleaned up.
+pub(crate) fn clear_doppler_exempt() {
+    DOPPLER_EXEMPT_SOURCES.lock().unwrap().clear();
+}
+
+/// Give all the exempt sources the velocity of the listener.
+fn update_doppler_exempt_sources() {
+    let velocity = get_velocity();
+    for source in DOPPLER_EXEMPT_SOURCES.lock().unwrap().iter() {
+        update_source_velocity(*source, velocity);
+    }
+}
+
+/// Give a source the velocity of the listener, or none if it is relative.
+pub(crate) fn update_source_velocity(source: u32, velocity: [f32; 3]) {
+    let mut relative = 0;
+    al::alGetSourcei(source, ffi::AL_SOURCE_RELATIVE, &mut relative);
+    let velocity = match relative as _ {
+        ffi::ALC_TRUE => [0.0; 3],
+        _ => velocity,
+    };
+    al::alSourcefv(source, ffi::AL_VELOCITY, &velocity[0]);
+}
+
 #[cfg(test)]
 mod test {
+    #![allow(non_snake_case)]
+
     use listener::{
-        get_orientation, get_position, get_volume, set_orientation, set_position, set_volume,
+        get_doppler_factor, get_orientation, get_position, get_speed_of_sound, get_transform,
+        get_volume, set_doppler_factor, set_orientation, set_position, set_speed_of_sound,
+        set_transform, set_volume, Transform,
     };
 
     #[test]
@@ -226,4 +464,27 @@ mod test {
         assert_eq!(s1, [50f32, 150f32, 234f32]);
         assert_eq!(s2, [277f32, 125f32, 71f32])
     }
+
+    #[test]
+    #[ignore]
+    fn listener_set_transform_OK() -> () {
+        set_transform([1., 2., 3.], [1., 0., 0.], [0., 0., 1.]);
+        assert_eq!(
+            get_transform(),
+            Transform {
+                position: [1., 2., 3.],
+                forward: [1., 0., 0.],
+                up: [0., 0., 1.],
+            }
+        );
+    }
+
+    #[test]
+    #[ignore]
+    pub fn listener_set_doppler_factor_OK() -> () {
+        set_doppler_factor(0.5);
+        assert_eq!(get_doppler_factor(), 0.5);
+        set_speed_of_sound(1126.);
+        assert_eq!(get_speed_of_sound(), 1126.);
+    }
 }
@@ -0,0 +1,287 @@
+//! Loading of Sounds and Musics on a background thread.
+
+use error::SoundError;
+use music::Music;
+use sound::Sound;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::task::Waker;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+/**
+ * Handle on a Sound or a Music loading on a background thread.
+ *
+ * Reading a file on a network share or through a slow reader can block for
+ * a long time. The handle lets the calling thread poll the loading, wait
+ * for it with a timeout, or give up on it.
+ *
+ * The underlying I/O can't be interrupted: a cancelled or timed out loading
+ * goes on in the background, and its result is freed as soon as it ends.
+ */
+pub struct LoadHandle<T> {
+    receiver: Receiver<Result<T, SoundError>>,
+    cancelled: Arc<AtomicBool>,
+    result: Option<Result<T, SoundError>>,
+    done: bool,
+    /// The task awaiting the handle, woken once the loading ends
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<T: Send + 'static> LoadHandle<T> {
+    /// Run a loading function on a background thread.
+    pub(crate) fn spawn<F>(load: F) -> LoadHandle<T>
+    where
+        F: FnOnce() -> Result<T, SoundError> + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let thread_cancelled = cancelled.clone();
+        let waker = Arc::new(Mutex::new(None::<Waker>));
+        let thread_waker = waker.clone();
+
+        thread::Builder::new()
+            .name(String::from("ears-load"))
+            .spawn(move || {
+                if !thread_cancelled.load(Ordering::Relaxed) {
+                    sender.send(load()).ok();
+                }
+                if let Some(waker) = thread_waker.lock().unwrap().take() {
+                    waker.wake();
+                }
+            })
+            .unwrap();
+
+        LoadHandle {
+            receiver,
+            cancelled,
+            result: None,
+            done: false,
+            waker,
+        }
+    }
+
+    /// Check if the loading is done, successfully or not.
+    pub fn is_done(&mut self) -> bool {
+        if !self.done {
+            match self.receiver.try_recv() {
+                Ok(result) => {
+                    self.result = Some(result);
+                    self.done = true;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => panic!("the ears-load thread panicked"),
+            }
+        }
+        self.done
+    }
+
+    /**
+     * Take the result of the loading if it is done.
+     *
+     * # Return
+     * Some(Result) once the loading is done, None while it is going on.
+     * The result can only be taken once.
+     */
+    pub fn try_take(&mut self) -> Option<Result<T, SoundError>> {
+        self.is_done();
+        self.result.take()
+    }
+
+    /**
+     * Block until the loading is done.
+     *
+     * # Return
+     * A `Result` containing Ok(T) on success, Err(SoundError) if there has
+     * been an error.
+     */
+    pub fn wait(mut self) -> Result<T, SoundError> {
+        self.check_not_taken();
+        match self.result.take() {
+            Some(result) => result,
+            None => self.receiver.recv().expect("the ears-load thread panicked"),
+        }
+    }
+
+    /**
+     * Block until the loading is done, or until a timeout.
+     *
+     * # Argument
+     * * `timeout` - The longest time to wait for
+     *
+     * # Return
+     * A `Result` containing Ok(T) on success, Err(SoundError::Timeout) if the
+     * loading didn't end in time, or the error of the loading.
+     */
+    pub fn wait_timeout(mut self, timeout: Duration) -> Result<T, SoundError> {
+        self.check_not_taken();
+        if let Some(result) = self.result.take() {
+            return result;
+        }
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => {
+                self.cancel();
+                Err(SoundError::Timeout)
+            }
+            Err(RecvTimeoutError::Disconnected) => panic!("the ears-load thread panicked"),
+        }
+    }
+
+    /**
+     * Give up on the loading.
+     *
+     * If the loading didn't start yet, it never does. Otherwise its result is
+     * freed once it ends.
+     */
+    pub fn cancel(self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn check_not_taken(&self) {
+        if self.done && self.result.is_none() {
+            panic!("the result of the loading was already taken");
+        }
+    }
+}
+
+/**
+ * Await the loading, with the `async` feature.
+ *
+ * The loading goes on in the background whether the handle is polled or
+ * not.
+ */
+#[cfg(feature = "async")]
+impl<T: Send + Unpin + 'static> Future for LoadHandle<T> {
+    type Output = Result<T, SoundError>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        *self.waker.lock().unwrap() = Some(context.waker().clone());
+        // Checked after setting the waker, so the end of the loading can't be missed
+        self.check_not_taken();
+        match self.try_take() {
+            Some(result) => Poll::Ready(result),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/**
+ * Load a Sound on a background thread.
+ *
+ * # Argument
+ * * `path` - The path of the sound file
+ *
+ * # Return
+ * A LoadHandle to get the Sound once loaded.
+ *
+ * # Example
+ * ```no_run
+ * use ears::AudioController;
+ *
+ * let mut loading = ears::load_sound("//server/share/shot.wav");
+ * loop {
+ *     // Render a frame of the loading screen here
+ *     if let Some(sound) = loading.try_take() {
+ *         sound.unwrap().play();
+ *         break;
+ *     }
+ * }
+ * ```
+ */
+pub fn load_sound<P: AsRef<Path>>(path: P) -> LoadHandle<Sound> {
+    let path = path.as_ref().to_path_buf();
+    LoadHandle::spawn(move || Sound::new(path))
+}
+
+/**
+ * Open a Music on a background thread.
+ *
+ * # Argument
+ * * `path` - The path of the music file
+ *
+ * # Return
+ * A LoadHandle to get the Music once opened.
+ */
+pub fn load_music<P: AsRef<Path>>(path: P) -> LoadHandle<Music> {
+    let path = path.as_ref().to_path_buf();
+    LoadHandle::spawn(move || Music::new(path))
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::LoadHandle;
+    use error::SoundError;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    #[test]
+    fn load_handle_wait_OK() -> () {
+        let handle = LoadHandle::spawn(|| Ok(42));
+
+        assert_eq!(handle.wait().ok(), Some(42));
+    }
+
+    #[test]
+    fn load_handle_try_take_OK() -> () {
+        let mut handle = LoadHandle::spawn(|| Ok(42));
+
+        while !handle.is_done() {}
+        assert_eq!(handle.try_take().and_then(|r| r.ok()), Some(42));
+        assert!(handle.try_take().is_none());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn load_handle_await_OK() -> () {
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake};
+        use std::thread;
+
+        struct Unpark(thread::Thread);
+        impl Wake for Unpark {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let mut handle = LoadHandle::spawn(|| {
+            sleep(Duration::from_millis(10));
+            Ok(42)
+        });
+        let waker = Arc::new(Unpark(thread::current())).into();
+        let mut context = Context::from_waker(&waker);
+        loop {
+            match Pin::new(&mut handle).poll(&mut context) {
+                Poll::Ready(result) => return assert_eq!(result.ok(), Some(42)),
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn load_handle_wait_timeout_FAIL() -> () {
+        let handle = LoadHandle::spawn(|| {
+            sleep(Duration::from_secs(1));
+            Ok(42)
+        });
+
+        match handle.wait_timeout(Duration::from_millis(10)) {
+            Err(SoundError::Timeout) => {}
+            other => panic!("{:?}", other.ok()),
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! The diagnostics of __ears__, printed or given to a callback.
+
+use std::sync::RwLock;
+
+/// The severity of a diagnostic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    /// Something went wrong, but __ears__ went on, e.g. with a default device
+    Warning,
+    /// An operation failed, e.g. because there is no OpenAL context
+    Error,
+}
+
+/// A function receiving the diagnostics
+type LogCallback = Box<dyn Fn(LogLevel, &str) + Send + Sync>;
+
+lazy_static! {
+    static ref CALLBACK: RwLock<Option<LogCallback>> = RwLock::new(None);
+}
+
+/**
+ * Set a callback receiving the diagnostics of __ears__.
+ *
+ * Without a callback, the diagnostics are printed to the standard error.
+ * The callback can be called from any thread, including the streaming and
+ * recording ones, so it should return quickly.
+ *
+ * # Argument
+ * * `callback` - The function called with the level and the message of each
+ *   diagnostic, replacing any previous one
+ *
+ * # Example
+ * ```no_run
+ * use ears::LogLevel;
+ *
+ * ears::set_log_callback(|level, message| {
+ *     if level == LogLevel::Error {
+ *         eprintln!("audio: {}", message);
+ *     }
+ * });
+ * ```
+ */
+pub fn set_log_callback<F>(callback: F)
+where
+    F: Fn(LogLevel, &str) + Send + Sync + 'static,
+{
+    *CALLBACK.write().unwrap() = Some(Box::new(callback));
+}
+
+/**
+ * Remove the log callback, printing the diagnostics to the standard error
+ * again.
+ */
+pub fn clear_log_callback() {
+    *CALLBACK.write().unwrap() = None;
+}
+
+/// Give a diagnostic to the callback, or print it.
+pub(crate) fn log(level: LogLevel, message: &str) {
+    match *CALLBACK.read().unwrap() {
+        Some(ref callback) => callback(level, message),
+        None => eprintln!("{}", message),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{clear_log_callback, log, set_log_callback, LogLevel};
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn logging_callback_OK() -> () {
+        let (sender, receiver) = channel();
+        set_log_callback(move |level, message| {
+            sender.send((level, message.to_string())).unwrap();
+        });
+
+        log(LogLevel::Warning, "cannot open the device");
+        clear_log_callback();
+        log(LogLevel::Error, "printed");
+
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![(LogLevel::Warning, "cannot open the device".to_string())]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Timed lyrics or subtitles to display along with a Music.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// A line of lyrics and the time at which it starts.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LyricLine {
+    /// The time from the start of the Music at which the line starts
+    pub time: Duration,
+    /// The text of the line
+    pub text: String,
+}
+
+/**
+ * Lines of lyrics or subtitles sorted by time.
+ *
+ * Once given to a Music, each line is emitted when the playback reaches it.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Lyrics, Music};
+ *
+ * fn main() -> Result<(), Box<dyn std::error::Error>> {
+ *     let lyrics = Lyrics::from_lrc_file("path/to/music.lrc")?;
+ *
+ *     let mut msc = Music::new("path/to/music.ogg")?;
+ *     msc.set_lyrics(lyrics, |line| println!("{}", line.text));
+ *     msc.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Lyrics {
+    lines: Vec<LyricLine>,
+}
+
+// Parse a [mm:ss.xx] timestamp, without the brackets.
+fn parse_timestamp(tag: &str) -> Option<Duration> {
+    let mut parts = tag.splitn(2, ':');
+    let minutes: u64 = parts.next()?.trim().parse().ok()?;
+    let seconds: f64 = parts.next()?.trim().parse().ok()?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    let millis = minutes * 60_000 + (seconds * 1000.0).round() as u64;
+    Some(Duration::from_millis(millis))
+}
+
+impl Lyrics {
+    /**
+     * Create new Lyrics from a list of lines.
+     *
+     * # Argument
+     * * `lines` - The lines, in any order
+     */
+    pub fn new(mut lines: Vec<LyricLine>) -> Lyrics {
+        lines.sort_by_key(|line| line.time);
+        Lyrics { lines }
+    }
+
+    /**
+     * Parse lyrics in the LRC format.
+     *
+     * Each line starts with one or more `[mm:ss.xx]` timestamps followed by
+     * its text. The `[offset:ms]` tag is honored, other tags and invalid
+     * lines are ignored.
+     *
+     * # Argument
+     * * `lrc` - The content of the LRC file
+     */
+    pub fn parse_lrc(lrc: &str) -> Lyrics {
+        let mut lines = Vec::new();
+        let mut offset_ms: i64 = 0;
+
+        for line in lrc.lines() {
+            let mut rest = line.trim();
+            let mut times = Vec::new();
+
+            while rest.starts_with('[') {
+                let end = match rest.find(']') {
+                    Some(end) => end,
+                    None => break,
+                };
+                let tag = &rest[1..end];
+                rest = &rest[end + 1..];
+
+                if let Some(time) = parse_timestamp(tag) {
+                    times.push(time);
+                } else if let Some(offset) = tag.strip_prefix("offset:") {
+                    offset_ms = offset.trim().parse().unwrap_or(0);
+                }
+            }
+
+            for time in times {
+                lines.push(LyricLine {
+                    time,
+                    text: rest.trim().to_string(),
+                });
+            }
+        }
+
+        // A positive offset shows the lyrics sooner
+        if offset_ms != 0 {
+            for line in lines.iter_mut() {
+                let millis = line.time.as_millis() as i64 - offset_ms;
+                line.time = Duration::from_millis(millis.max(0) as u64);
+            }
+        }
+
+        Lyrics::new(lines)
+    }
+
+    /**
+     * Read and parse a LRC file.
+     *
+     * # Argument
+     * * `path` - The path of the LRC file
+     */
+    pub fn from_lrc_file<P: AsRef<Path>>(path: P) -> io::Result<Lyrics> {
+        Ok(Lyrics::parse_lrc(&fs::read_to_string(path)?))
+    }
+
+    /// Get all the lines, sorted by time.
+    pub fn lines(&self) -> &[LyricLine] {
+        &self.lines
+    }
+
+    /**
+     * Get the lines starting in a time range.
+     *
+     * # Arguments
+     * * `from` - The start of the range, included
+     * * `to` - The end of the range, excluded
+     */
+    pub fn lines_between(&self, from: Duration, to: Duration) -> &[LyricLine] {
+        let start = self.lines.partition_point(|line| line.time < from);
+        let end = self.lines.partition_point(|line| line.time < to);
+        &self.lines[start..end.max(start)]
+    }
+
+    /**
+     * Get the line being sung at the given time.
+     *
+     * # Argument
+     * * `time` - The time from the start of the Music
+     */
+    pub fn line_at(&self, time: Duration) -> Option<&LyricLine> {
+        let end = self.lines.partition_point(|line| line.time <= time);
+        self.lines[..end].last()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use lyrics::{LyricLine, Lyrics};
+    use std::time::Duration;
+
+    fn line(millis: u64, text: &str) -> LyricLine {
+        LyricLine {
+            time: Duration::from_millis(millis),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn lyrics_parse_lrc_OK() -> () {
+        let lyrics = Lyrics::parse_lrc(
+            "[ar:Someone]\n[00:12.50]Second\n[00:01.00][01:00.00] First and last \nno time",
+        );
+
+        assert_eq!(
+            lyrics.lines(),
+            &[
+                line(1000, "First and last"),
+                line(12500, "Second"),
+                line(60000, "First and last"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lyrics_parse_lrc_offset_OK() -> () {
+        let lyrics = Lyrics::parse_lrc("[offset:500]\n[00:02.00]Hello\n[00:00.20]Hi");
+
+        assert_eq!(lyrics.lines(), &[line(0, "Hi"), line(1500, "Hello")]);
+    }
+
+    #[test]
+    fn lyrics_lines_between_OK() -> () {
+        let lyrics = Lyrics::new(vec![line(3000, "c"), line(1000, "a"), line(2000, "b")]);
+
+        let lines = lyrics.lines_between(Duration::from_millis(1000), Duration::from_millis(3000));
+        assert_eq!(lines, &[line(1000, "a"), line(2000, "b")]);
+        assert!(lyrics
+            .lines_between(Duration::from_millis(3500), Duration::from_millis(3000))
+            .is_empty());
+    }
+
+    #[test]
+    fn lyrics_line_at_OK() -> () {
+        let lyrics = Lyrics::new(vec![line(1000, "a"), line(2000, "b")]);
+
+        assert_eq!(lyrics.line_at(Duration::from_millis(500)), None);
+        assert_eq!(
+            lyrics.line_at(Duration::from_millis(2000)),
+            Some(&line(2000, "b"))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Playback of the captured audio, to hear the microphone.
+
+use libc::c_void;
+use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use error::SoundError;
+use internal::OpenAlData;
+use openal::{al, ffi};
+use recorder::Recorder;
+
+/// The number of buffers queued on the source of a Monitor
+const BUFFER_COUNT: usize = 4;
+
+/// The smallest number of frames of a buffer
+const MIN_BUFFER_FRAMES: usize = 64;
+
+/// How long the thread waits for captured audio before checking the buffers
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/**
+ * Play the audio captured by a Recorder, so users can hear their
+ * microphone.
+ *
+ * The captured samples are queued on a streaming source as soon as the
+ * Recorder gets them, with a latency that can be adjusted: lower values
+ * make the delay less noticeable, but may cause crackles on a busy system.
+ * When the capture gets ahead of the playback, the oldest audio is dropped
+ * to keep the latency. The monitoring stops when the Monitor is dropped.
+ *
+ * The channels of the Recorder, set with `Recorder::set_channel_map`, must
+ * not change while it is monitored.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{Monitor, Recorder};
+ * use std::time::Duration;
+ *
+ * fn main() -> Result<(), Box<dyn std::error::Error>> {
+ *     let mut recorder = Recorder::new(ears::init_in()?);
+ *     let mut monitor = Monitor::new(&mut recorder)?;
+ *     monitor.set_latency(Duration::from_millis(30));
+ *     monitor.set_gain(0.8);
+ *     recorder.start();
+ *
+ *     std::thread::sleep(Duration::from_secs(10));
+ *     recorder.stop();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct Monitor {
+    al_source: u32,
+    al_buffers: [u32; BUFFER_COUNT],
+    latency: Arc<Mutex<Duration>>,
+    stop: Arc<AtomicBool>,
+    thread_handle: Option<JoinHandle<()>>,
+}
+
+impl Monitor {
+    /**
+     * Start monitoring a Recorder.
+     *
+     * The audio captured from now on is played, after the automatic gain
+     * control and before the noise gate of the Recorder. Only the last
+     * Monitor of a Recorder gets the audio.
+     *
+     * # Argument
+     * * `recorder` - The Recorder to monitor
+     *
+     * # Return
+     * A `Result` containing Ok(Monitor) on success, Err(SoundError)
+     * if there has been an error, for instance
+     * Err(SoundError::UnsupportedChannelCount) if the Recorder records more
+     * than 2 channels.
+     */
+    pub fn new(recorder: &mut Recorder) -> Result<Monitor, SoundError> {
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
+
+        let channels = recorder.get_channel_map().len();
+        let format = match channels {
+            1 => ffi::AL_FORMAT_MONO16,
+            2 => ffi::AL_FORMAT_STEREO16,
+            _ => return Err(SoundError::UnsupportedChannelCount(channels as i32)),
+        };
+
+        let mut al_source = 0;
+        let mut al_buffers = [0; BUFFER_COUNT];
+        al::alGenSources(1, &mut al_source);
+        al::alGenBuffers(BUFFER_COUNT as i32, &mut al_buffers[0]);
+        if let Some(err) = al::openal_has_error() {
+            return Err(SoundError::InternalOpenALError(err));
+        };
+        // Play the input as is, wherever the listener is
+        al::alSourcei(al_source, ffi::AL_SOURCE_RELATIVE, ffi::AL_TRUE as i32);
+
+        let (sender, receiver) = channel();
+        recorder.set_monitor(sender);
+
+        let latency = Arc::new(Mutex::new(Duration::from_millis(50)));
+        let stop = Arc::new(AtomicBool::new(false));
+        let stream = Stream {
+            al_source,
+            al_buffers,
+            format,
+            channels,
+            sample_rate: recorder.sample_rate(),
+            latency: latency.clone(),
+            stop: stop.clone(),
+        };
+        let thread_handle = thread::Builder::new()
+            .name(String::from("ears-monitor"))
+            .spawn(move || stream.run(receiver))
+            .unwrap();
+
+        Ok(Monitor {
+            al_source,
+            al_buffers,
+            latency,
+            stop,
+            thread_handle: Some(thread_handle),
+        })
+    }
+
+    /**
+     * Set the delay between the capture and the playback.
+     *
+     * # Argument
+     * * `latency` - The audio kept queued on the source. Default is 50ms.
+     */
+    pub fn set_latency(&mut self, latency: Duration) {
+        *self.latency.lock().unwrap() = latency;
+    }
+
+    /**
+     * Get the delay between the capture and the playback.
+     */
+    pub fn get_latency(&self) -> Duration {
+        *self.latency.lock().unwrap()
+    }
+
+    /**
+     * Set the gain of the monitored audio.
+     *
+     * # Argument
+     * * `gain` - The gain, 1.0 to play the input unchanged. Default is 1.0.
+     */
+    pub fn set_gain(&mut self, gain: f32) {
+        check_openal_context!();
+
+        al::alSourcef(self.al_source, ffi::AL_GAIN, gain);
+    }
+
+    /**
+     * Get the gain of the monitored audio.
+     */
+    pub fn get_gain(&self) -> f32 {
+        check_openal_context!(0.);
+
+        let mut gain = 0.;
+        al::alGetSourcef(self.al_source, ffi::AL_GAIN, &mut gain);
+        gain
+    }
+}
+
+impl Drop for Monitor {
+    /// Stop the monitoring and destroy the source.
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.thread_handle.take() {
+            handle.join();
+        }
+        // The objects were deleted with the context
+        if !OpenAlData::is_created() {
+            return;
+        }
+        unsafe {
+            ffi::alSourceStop(self.al_source);
+            al::alSourcei(self.al_source, ffi::AL_BUFFER, 0);
+            ffi::alDeleteBuffers(BUFFER_COUNT as i32, &mut self.al_buffers[0]);
+            ffi::alDeleteSources(1, &mut self.al_source);
+        }
+    }
+}
+
+/// The state of the thread queuing the captured audio.
+struct Stream {
+    al_source: u32,
+    al_buffers: [u32; BUFFER_COUNT],
+    format: i32,
+    channels: usize,
+    sample_rate: i32,
+    latency: Arc<Mutex<Duration>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Stream {
+    fn run(self, receiver: Receiver<Vec<i16>>) {
+        let mut free = self.al_buffers.to_vec();
+        let mut pending: Vec<i16> = Vec::new();
+
+        while !self.stop.load(Ordering::SeqCst) {
+            match receiver.recv_timeout(POLL_INTERVAL) {
+                Ok(samples) => pending.extend(samples),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            let mut processed = 0;
+            al::alGetSourcei(self.al_source, ffi::AL_BUFFERS_PROCESSED, &mut processed);
+            for _ in 0..processed {
+                let mut buffer = 0;
+                al::alSourceUnqueueBuffers(self.al_source, 1, &mut buffer);
+                free.push(buffer);
+            }
+
+            let latency = *self.latency.lock().unwrap();
+            let block = buffer_size(latency, self.sample_rate, self.channels);
+            drop_excess(&mut pending, block * BUFFER_COUNT);
+
+            while pending.len() >= block {
+                let buffer = match free.pop() {
+                    Some(buffer) => buffer,
+                    None => break,
+                };
+                al::alBufferData(
+                    buffer,
+                    self.format,
+                    pending.as_ptr() as *mut c_void,
+                    (mem::size_of::<i16>() * block) as i32,
+                    self.sample_rate,
+                );
+                al::alSourceQueueBuffers(self.al_source, 1, &buffer);
+                pending.drain(..block);
+            }
+
+            // Start, or restart after running out of audio
+            let mut state = 0;
+            al::alGetSourcei(self.al_source, ffi::AL_SOURCE_STATE, &mut state);
+            if state != ffi::AL_PLAYING && BUFFER_COUNT - free.len() >= 2 {
+                al::alSourcePlay(self.al_source);
+            }
+        }
+    }
+}
+
+/// The number of interleaved samples of each buffer, for a latency.
+fn buffer_size(latency: Duration, sample_rate: i32, channels: usize) -> usize {
+    let frames = latency.as_secs_f32() * sample_rate as f32 / BUFFER_COUNT as f32;
+    (frames as usize).max(MIN_BUFFER_FRAMES) * channels
+}
+
+/// Drop the oldest samples over a maximum, a multiple of the channels.
+fn drop_excess(pending: &mut Vec<i16>, max: usize) {
+    let excess = pending.len().saturating_sub(max);
+    pending.drain(..excess);
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{buffer_size, drop_excess, Monitor};
+    use init_in;
+    use recorder::Recorder;
+    use std::time::Duration;
+
+    #[test]
+    fn monitor_buffer_size_OK() -> () {
+        assert_eq!(buffer_size(Duration::from_millis(40), 44100, 2), 441 * 2);
+        assert_eq!(buffer_size(Duration::from_millis(0), 44100, 1), 64);
+    }
+
+    #[test]
+    fn monitor_drop_excess_OK() -> () {
+        let mut pending = vec![1, 2, 3, 4, 5, 6];
+
+        drop_excess(&mut pending, 4);
+        assert_eq!(pending, vec![3, 4, 5, 6]);
+        drop_excess(&mut pending, 8);
+        assert_eq!(pending, vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    #[ignore]
+    fn monitor_create_OK() -> () {
+        let mut recorder = Recorder::new(init_in().unwrap());
+
+        assert!(Monitor::new(&mut recorder).is_ok());
+    }
+}
@@ -0,0 +1,391 @@
+//! MP3 decoding with symphonia, read by libsndfile as a WAV file.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::iter;
+use std::path::Path;
+use std::sync::Mutex;
+
+use sndfile::{SndFileError, SndReader};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+use symphonia_io::{count_frames, decode_error, is_end_of_stream, ReaderSource};
+
+/// The bytes of each sample of the WAV file
+const SAMPLE_BYTES: u64 = 2;
+
+/// The RIFF INFO chunks of the tags libsndfile reads
+const INFO_CHUNKS: [(StandardTagKey, &[u8; 4]); 9] = [
+    (StandardTagKey::TrackTitle, b"INAM"),
+    (StandardTagKey::Artist, b"IART"),
+    (StandardTagKey::Album, b"IPRD"),
+    (StandardTagKey::Date, b"ICRD"),
+    (StandardTagKey::Comment, b"ICMT"),
+    (StandardTagKey::Genre, b"IGNR"),
+    (StandardTagKey::TrackNumber, b"ITRK"),
+    (StandardTagKey::Copyright, b"ICOP"),
+    (StandardTagKey::Encoder, b"ISFT"),
+];
+
+/**
+ * An MP3 file decoded on demand, read as a 16 bits PCM WAV file.
+ *
+ * libsndfile opens it like any other virtual file, so Sounds and Musics play
+ * MP3 files the same way as the other formats, seeking included. The tags
+ * of the MP3 file are given in the INFO chunk of the WAV file.
+ */
+pub(crate) struct Mp3Reader {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    /// The bytes of a frame
+    block_align: u64,
+    /// The WAV header, up to the start of the samples
+    header: Vec<u8>,
+    /// The length of the samples, in bytes
+    data_len: u64,
+    /// The position of the next read, in the WAV file
+    position: u64,
+    /// The last decoded samples, in bytes
+    decoded: Vec<u8>,
+    /// The position of the decoded samples, in bytes from the start of the samples
+    decoded_start: u64,
+    /// Whether the decoder has read all the packets
+    ended: bool,
+}
+
+/**
+ * Check if a file is an MP3 file, by its extension or its first bytes.
+ */
+pub(crate) fn is_mp3_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("mp3"));
+    extension || File::open(path).is_ok_and(|mut file| is_mp3(&mut file))
+}
+
+/**
+ * Check if a reader starts with an ID3 tag or an MPEG layer III frame,
+ * leaving it at its start.
+ */
+pub(crate) fn is_mp3<R: Read + Seek>(reader: &mut R) -> bool {
+    let mut magic = [0u8; 3];
+    let read = reader.read_exact(&mut magic).is_ok();
+    reader.seek(SeekFrom::Start(0)).is_ok() && read && has_mp3_magic(&magic)
+}
+
+fn has_mp3_magic(magic: &[u8; 3]) -> bool {
+    // The frame sync, then the layer bits of layer III
+    &magic[..] == b"ID3" || (magic[0] == 0xFF && magic[1] & 0xE6 == 0xE2)
+}
+
+impl Mp3Reader {
+    /// Open an MP3 file.
+    pub(crate) fn open(path: &Path) -> Result<Mp3Reader, SndFileError> {
+        let file = File::open(path).map_err(|err| SndFileError::SystemError(err.to_string()))?;
+        Mp3Reader::new_with_source(Box::new(file))
+    }
+
+    /// Read an MP3 file from a reader.
+    pub(crate) fn new<R: SndReader + 'static>(reader: R) -> Result<Mp3Reader, SndFileError> {
+        Mp3Reader::new_with_source(Box::new(ReaderSource(Mutex::new(reader))))
+    }
+
+    fn new_with_source(source: Box<dyn MediaSource>) -> Result<Mp3Reader, SndFileError> {
+        let stream = MediaSourceStream::new(source, Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension("mp3");
+        let mut probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(decode_error)?;
+
+        let mut tags: Vec<Tag> = probed
+            .metadata
+            .get()
+            .and_then(|metadata| metadata.current().map(|revision| revision.tags().to_vec()))
+            .unwrap_or_default();
+        if let Some(revision) = probed.format.metadata().current() {
+            tags.extend_from_slice(revision.tags());
+        }
+
+        let mut format = probed.format;
+        let track = format
+            .default_track()
+            .ok_or_else(|| SndFileError::UnrecognizedFormat("no MP3 stream".to_string()))?;
+        let track_id = track.id;
+        let params = track.codec_params.clone();
+        let decoder = symphonia::default::get_codecs()
+            .make(&params, &DecoderOptions::default())
+            .map_err(decode_error)?;
+
+        let channels = params.channels.map_or(0, |channels| channels.count()) as u64;
+        let sample_rate = params.sample_rate.unwrap_or(0);
+        if channels == 0 || sample_rate == 0 {
+            return Err(SndFileError::MalformedFile(
+                "the MP3 stream has no format".to_string(),
+            ));
+        }
+        let frames = match params.n_frames {
+            Some(frames) => frames,
+            None => count_frames(&mut *format, track_id)?,
+        };
+
+        let block_align = channels * SAMPLE_BYTES;
+        let data_len = frames * block_align;
+        Ok(Mp3Reader {
+            format,
+            decoder,
+            track_id,
+            block_align,
+            header: wav_header(channels as u16, sample_rate, data_len, &tags),
+            data_len,
+            position: 0,
+            decoded: Vec::new(),
+            decoded_start: 0,
+            ended: false,
+        })
+    }
+
+    /// Decode the samples at a position, in bytes from the start of the samples.
+    fn decode_at(&mut self, position: u64) -> io::Result<()> {
+        let decoded_end = self.decoded_start + self.decoded.len() as u64;
+        if position < self.decoded_start || position > decoded_end {
+            self.seek_frame(position / self.block_align)?;
+        }
+        while position >= self.decoded_start + self.decoded.len() as u64 {
+            if !self.decode_next()? {
+                // Silence up to the length announced by the header
+                self.decoded_start = position;
+                self.decoded = vec![0; 4096.min(self.data_len - position) as usize];
+            }
+        }
+        // The corrupt packets skipped before the decoded one are silent
+        if self.decoded_start > position {
+            let gap = (self.decoded_start - position) as usize;
+            self.decoded.splice(0..0, iter::repeat(0).take(gap));
+            self.decoded_start = position;
+        }
+        Ok(())
+    }
+
+    /// Decode the next packet, returning false at the end of the stream.
+    fn decode_next(&mut self) -> io::Result<bool> {
+        while !self.ended {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(ref err) if is_end_of_stream(err) => {
+                    self.ended = true;
+                    break;
+                }
+                Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // A corrupt frame is skipped
+                Err(Error::DecodeError(_)) => continue,
+                Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+            };
+            let mut samples = SampleBuffer::<i16>::new(decoded.capacity() as u64, *decoded.spec());
+            samples.copy_interleaved_ref(decoded);
+
+            self.decoded_start = packet.ts() * self.block_align;
+            self.decoded = samples
+                .samples()
+                .iter()
+                .flat_map(|sample| sample.to_le_bytes())
+                .collect();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Move the decoder before a frame.
+    fn seek_frame(&mut self, frame: u64) -> io::Result<()> {
+        let seeked = self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::TimeStamp {
+                ts: frame,
+                track_id: self.track_id,
+            },
+        );
+        match seeked {
+            Ok(seeked) => self.decoded_start = seeked.actual_ts * self.block_align,
+            // Seeking past the end of the stream
+            Err(Error::SeekError(_)) => self.decoded_start = frame * self.block_align,
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        }
+        self.decoder.reset();
+        self.decoded.clear();
+        self.ended = false;
+        Ok(())
+    }
+}
+
+impl Read for Mp3Reader {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let header_len = self.header.len() as u64;
+        if self.position < header_len {
+            let header = &self.header[self.position as usize..];
+            let read = header.len().min(buffer.len());
+            buffer[..read].copy_from_slice(&header[..read]);
+            self.position += read as u64;
+            return Ok(read);
+        }
+
+        let position = self.position - header_len;
+        if position >= self.data_len || buffer.is_empty() {
+            return Ok(0);
+        }
+        self.decode_at(position)?;
+        let available = &self.decoded[(position - self.decoded_start) as usize..];
+        let read = available
+            .len()
+            .min(buffer.len())
+            .min((self.data_len - position) as usize);
+        buffer[..read].copy_from_slice(&available[..read]);
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for Mp3Reader {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let length = self.header.len() as u64 + self.data_len;
+        let position = match position {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+            SeekFrom::End(offset) => length as i64 + offset,
+        };
+        if position < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the file",
+            ));
+        }
+        self.position = position as u64;
+        Ok(self.position)
+    }
+}
+
+/**
+ * Write the header of a 16 bits PCM WAV file, with the tags in an INFO
+ * chunk.
+ *
+ * # Arguments
+ * * `channels` - The number of channels
+ * * `sample_rate` - The sample rate
+ * * `data_len` - The length of the samples, in bytes
+ * * `tags` - The tags of the file
+ */
+fn wav_header(channels: u16, sample_rate: u32, data_len: u64, tags: &[Tag]) -> Vec<u8> {
+    let block_align = channels * SAMPLE_BYTES as u16;
+
+    let mut info = Vec::new();
+    for (key, id) in INFO_CHUNKS.iter() {
+        let tag = tags.iter().find(|tag| tag.std_key == Some(*key));
+        if let Some(tag) = tag {
+            let mut text = tag.value.to_string().into_bytes();
+            text.push(0);
+            info.extend_from_slice(*id);
+            info.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            info.extend_from_slice(&text);
+            if text.len() % 2 == 1 {
+                info.push(0);
+            }
+        }
+    }
+
+    let mut header = Vec::new();
+    header.extend_from_slice(b"RIFF");
+    header.extend_from_slice(&[0; 4]);
+    header.extend_from_slice(b"WAVE");
+    header.extend_from_slice(b"fmt ");
+    header.extend_from_slice(&16u32.to_le_bytes());
+    header.extend_from_slice(&1u16.to_le_bytes());
+    header.extend_from_slice(&channels.to_le_bytes());
+    header.extend_from_slice(&sample_rate.to_le_bytes());
+    header.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    header.extend_from_slice(&block_align.to_le_bytes());
+    header.extend_from_slice(&16u16.to_le_bytes());
+    if !info.is_empty() {
+        header.extend_from_slice(b"LIST");
+        header.extend_from_slice(&(info.len() as u32 + 4).to_le_bytes());
+        header.extend_from_slice(b"INFO");
+        header.extend_from_slice(&info);
+    }
+    header.extend_from_slice(b"data");
+    header.extend_from_slice(&(data_len.min(u32::MAX as u64) as u32).to_le_bytes());
+
+    let riff_len = (header.len() as u64 - 8 + data_len).min(u32::MAX as u64) as u32;
+    header[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    header
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{has_mp3_magic, wav_header};
+    use symphonia::core::meta::{StandardTagKey, Tag, Value};
+
+    #[test]
+    fn mp3_magic_OK() -> () {
+        assert!(has_mp3_magic(b"ID3"));
+        assert!(has_mp3_magic(&[0xFF, 0xFB, 0x90]));
+    }
+
+    #[test]
+    fn mp3_magic_FAIL() -> () {
+        assert!(!has_mp3_magic(b"RIF"));
+        // AAC uses the same frame sync, without a layer
+        assert!(!has_mp3_magic(&[0xFF, 0xF1, 0x50]));
+    }
+
+    #[test]
+    fn mp3_wav_header_OK() -> () {
+        let header = wav_header(2, 44100, 4000, &[]);
+
+        assert_eq!(header.len(), 44);
+        assert_eq!(&header[0..4], b"RIFF");
+        assert_eq!(
+            u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+            4036
+        );
+        assert_eq!(&header[36..40], b"data");
+        assert_eq!(
+            u32::from_le_bytes([header[40], header[41], header[42], header[43]]),
+            4000
+        );
+    }
+
+    #[test]
+    fn mp3_wav_header_tags_OK() -> () {
+        let tags = [Tag::new(
+            Some(StandardTagKey::TrackTitle),
+            "TIT2",
+            Value::String("Song".to_string()),
+        )];
+        let header = wav_header(1, 22050, 0, &tags);
+
+        assert_eq!(&header[36..40], b"LIST");
+        assert_eq!(&header[44..48], b"INFO");
+        assert_eq!(&header[48..52], b"INAM");
+        assert_eq!(&header[56..61], b"Song\0");
+        // Padded to an even length
+        assert_eq!(&header[62..66], b"data");
+    }
+}
@@ -22,41 +22,82 @@
 //! Play Music easily.
 
 use libc::c_void;
+use std::collections::HashMap;
 use std::convert::TryInto;
+use std::fs;
+use std::io::{self, Seek, SeekFrom};
 use std::mem;
-use std::sync::atomic::{AtomicI64, Ordering};
-use std::sync::mpsc::{channel, Receiver, Sender};
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, AtomicU32, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::sleep;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use std::vec::Vec;
 
+use attenuation::{self, AttenuationCurve};
 use audio_controller::AudioController;
 use audio_tags::{get_sound_tags, AudioTags, Tags};
+use auto_velocity::AutoVelocity;
+use bus::{self, Bus};
+use completion::SharedCompleteCallback;
+use decoder;
+use disconnect;
+use dsp::{StreamDsp, SPECTRUM_FRAMES};
+use effect::Effect;
+use efx::max_auxiliary_sends;
 use error::SoundError;
+use fade::{self, FadeEnd};
+use filter::Filter;
 use internal::OpenAlData;
+use limits;
+use listener;
+use load;
+#[cfg(feature = "async")]
+use load::LoadHandle;
+use lyrics::{LyricLine, Lyrics};
+use occlusion;
 use openal::{al, ffi};
-use reverb_effect::ReverbEffect;
+#[cfg(feature = "async")]
+use playback_future::PlaybackFuture;
+use playback_stats::PlaybackStats;
+use reverb_effect::ReverbEffectError;
+use sample::{self, Sample};
+use schedule;
+use scrub::Scrub;
 use sndfile::OpenMode::Read;
 use sndfile::SeekMode::SeekSet;
-use sndfile::{SndFile, SndInfo};
+use sndfile::{SndFile, SndFileError, SndInfo};
+use source_latency;
+use sources;
+use spatialize::Spatialize;
+use spectrum;
 use states::State;
 use states::State::{Initial, Paused, Playing, Stopped};
+use stream_config::StreamConfig;
+use stream_info::{self, StreamInfo};
+use stream_service::{self, StreamHandle};
 
-const BUFFER_COUNT: i32 = 2;
+type LoopCallback = Box<dyn FnMut(u32) + Send>;
+type LyricsCallback = Box<dyn FnMut(&LyricLine) + Send>;
+type ErrorCallback = Box<dyn FnMut(&SoundError) + Send>;
 
 /**
  * Play Music easily.
  *
  * Simple class to play music easily in 2 lines.
  *
- * Music is played in their own task and the samples are loaded progressively
- * using circular buffers.
+ * Musics are streamed by a single thread shared by all of them, and the
+ * samples are loaded progressively using circular buffers.
  *
  * Music maintains it's own cursor into the underlying file and will use that
  * cursor to continuously refill each buffer as it's processed by the source.
  *
+ * Files of more than 16 bits per sample are streamed as 32 bits floats when
+ * the OpenAL implementation supports the AL_EXT_FLOAT32 extension, and as
+ * 16 bits samples otherwise.
+ *
  * They aren't associated to a SoundData like Sounds.
  *
  * # Examples
@@ -75,31 +116,149 @@ pub struct Music {
     /// The internal OpenAL source identifier
     al_source: u32,
     /// The internal OpenAL buffers
-    al_buffers: [u32; 2],
-    /// The file open with libmscfile
-    file: Option<Box<SndFile>>,
-    /// Information of the file
-    file_infos: SndInfo,
+    al_buffers: Vec<u32>,
+    /// The number and size of the buffers
+    stream_config: StreamConfig,
+    /// The file being played, replaced by the streaming thread when the
+    /// queued file starts
+    track: Arc<Mutex<Arc<Track>>>,
+    /// The file to play once the current one ended
+    queued: Arc<Mutex<Option<Track>>>,
     /// Quantity of sample to read each time
     sample_to_read: i64, // TODO: usize?
     /// Format of the sample
     sample_format: i32,
-    /// Audio tags
-    sound_tags: Tags,
+    /// Float format the samples are streamed with, for files of more than 16 bits
+    float_format: Option<i32>,
     /// Current cursor into the music file
     cursor: Arc<AtomicI64>,
+    /// The frame played when the music was last stopped
+    last_position: i64,
+    /// The time on the device clock at which the next `play` starts, if any
+    start_time: Option<Duration>,
     /// State
     state: State,
     /// Whether this music is looping or not
     is_looping: bool,
     /// Channel to tell the thread, if is_looping changed
     looping_sender: Option<Sender<bool>>,
+    /// The section looped over, the whole file if None, cleared by the
+    /// streaming thread when the queued file starts
+    loop_points: Arc<Mutex<Option<(i64, i64)>>>,
+    /// How many times the cursor wrapped in the queued buffers
+    queued_loops: Arc<AtomicU32>,
 
     /// Channel to tell the thread to set offset
     offset_sender: Option<Sender<i32>>,
 
-    /// Thread which streams the music file
-    thread_handle: Option<thread::JoinHandle<()>>,
+    /// Channel to tell the thread the music was stopped on purpose
+    stop_sender: Option<Sender<()>>,
+
+    /// Playback statistics, updated by the streaming thread
+    stats: Arc<Mutex<PlaybackStats>>,
+
+    /// Called by the streaming thread each time the music loops
+    loop_callback: Arc<Mutex<Option<LoopCallback>>>,
+
+    /// Called by the streaming thread once the music played to its end
+    complete_callback: SharedCompleteCallback,
+
+    /// The last error of the streaming thread, until taken
+    error: Arc<Mutex<Option<SoundError>>>,
+    /// Called by the streaming thread when the streaming fails
+    error_callback: Arc<Mutex<Option<ErrorCallback>>>,
+
+    /// Whether the reverb send level is computed from the distance
+    auxiliary_send_auto: bool,
+
+    /// Whether the Doppler effect applies to the Music
+    doppler_enabled: bool,
+    /// The velocity of the Music, kept while the Doppler effect is disabled
+    velocity: [f32; 3],
+    /// Computes the velocity from the positions, when enabled
+    auto_velocity: AutoVelocity,
+
+    /// Processing applied to the samples as they are streamed
+    dsp: Arc<Mutex<StreamDsp>>,
+
+    /// Lyrics emitted by the streaming thread as the music plays
+    lyrics: Arc<Mutex<Option<(Lyrics, LyricsCallback)>>>,
+
+    /// The stream of the music file, updated by the service thread
+    stream: Option<StreamHandle>,
+
+    /// Thread which plays the grains while scrubbing
+    scrub: Option<Scrub>,
+}
+
+/// A file played by a Music, shared with its stream while it is read.
+struct Track {
+    /// The file open with libsndfile, closed with the track
+    file: Mutex<SndFile>,
+    /// Information of the file
+    infos: SndInfo,
+    /// The size of the file in bytes, if known
+    size: Option<u64>,
+    /// Audio tags
+    tags: Tags,
+}
+
+impl Track {
+    fn new(file: Box<SndFile>, size: Option<u64>) -> Result<Track, SoundError> {
+        let infos = file.get_sndinfo();
+        limits::check_header(&infos)?;
+        Ok(Track {
+            tags: get_sound_tags(&file),
+            file: Mutex::new(*file),
+            infos,
+            size,
+        })
+    }
+}
+
+impl Drop for Track {
+    fn drop(&mut self) {
+        self.file.lock().unwrap().close();
+    }
+}
+
+// Fill a buffer like `fill_buffer`, going on with the queued track once the
+// current one was read to its end.
+//
+// Returns how many times the cursor wrapped around the end of the file, and
+// the track started while filling the buffer, if any.
+fn fill_buffer_queued<S: Sample>(
+    samples: &mut Vec<S>,
+    reading: &mut Arc<Track>,
+    cursor: Arc<AtomicI64>,
+    is_looping: bool,
+    loop_points: &Mutex<Option<(i64, i64)>>,
+    queued: &Mutex<Option<Track>>,
+) -> Result<(u32, Option<Arc<Track>>), SndFileError> {
+    let mut started = None;
+    let has_queued = queued.lock().unwrap().is_some();
+    let mut loops = fill_buffer(
+        samples,
+        &mut reading.file.lock().unwrap(),
+        cursor.clone(),
+        is_looping && !has_queued,
+        *loop_points.lock().unwrap(),
+    )?;
+    while samples.len() < samples.capacity() {
+        let next = match queued.lock().unwrap().take() {
+            Some(next) => Arc::new(next),
+            None => break,
+        };
+        // The previous track is closed once the Music moved to this one too
+        *reading = next.clone();
+        cursor.store(0, Ordering::Relaxed);
+        started = Some(next);
+        // The loop points were set for the previous track
+        *loop_points.lock().unwrap() = None;
+        let mut file = reading.file.lock().unwrap();
+        loops += fill_buffer(samples, &mut file, cursor.clone(), is_looping, None)?;
+    }
+    Ok((loops, started))
 }
 
 // Recursively fill a buffer with data, returning the frame offset into
@@ -117,72 +276,135 @@ pub struct Music {
 // N*C. The buffer pointed to by "ptr" should be the same number of bytes
 // in each case.
 //
+// When looping with loop points, the cursor wraps from the end frame back to
+// the start frame instead of from the end of the file back to its start.
+//
+// Returns how many times the cursor wrapped around the end of the file, or
+// the error of the file if it can't be read up to its last frame.
+//
 // ref: http://www.mega-nerd.com/libsndfile/api.html#read
-fn fill_buffer(
-    samples: &mut Vec<i16>,
+fn fill_buffer<S: Sample>(
+    samples: &mut Vec<S>,
     sndfile: &mut SndFile,
     cursor: Arc<AtomicI64>,
     is_looping: bool,
-) {
+    loop_points: Option<(i64, i64)>,
+) -> Result<u32, SndFileError> {
     // First, find where the buffer is currently filled to
     let buffer_position = samples.len();
     let cursor_position = cursor.load(Ordering::Relaxed);
 
+    let channels = sndfile.get_sndinfo().channels as i64;
+    let frames = sndfile.get_sndinfo().frames;
+
+    // The section the cursor loops over, the whole file by default
+    let (loop_start, loop_end) = loop_bounds(frames, loop_points);
+
     // Move the sound file to where we want to read from
     sndfile.seek(cursor_position, SeekSet);
 
-    // Read data from sound file into the buffer, from the current buffer position onwards
-    let read_amount = (samples.capacity() - samples.len()) as i64;
-    let read_length = sndfile.read_i16(&mut samples[buffer_position..], read_amount) as usize;
+    // Read data from sound file into the buffer, from the current buffer position onwards,
+    // stopping at the end of the loop
+    let mut read_amount = (samples.capacity() - samples.len()) as i64;
+    if is_looping && cursor_position < loop_end {
+        read_amount = read_amount.min((loop_end - cursor_position) * channels);
+    }
+    let read_length = S::read(sndfile, &mut samples[buffer_position..], read_amount) as usize;
 
     // Update the vector length manually
     unsafe {
         samples.set_len(buffer_position + read_length);
     }
 
-    let channels = sndfile.get_sndinfo().channels as i64;
-    let frames = sndfile.get_sndinfo().frames;
+    // Nothing left to read before the end of the file, e.g. a truncated file
+    if read_length == 0 && read_amount > 0 && cursor_position < frames {
+        return Err(sndfile.last_error().unwrap_or_else(|| {
+            SndFileError::MalformedFile(String::from("the file ended before its last frame"))
+        }));
+    }
 
     // Calculate where the next cursor is at, based on how many 'items' were read
     // divided by the channels in the source sound file.
     let mut new_cursor_position = cursor_position + read_length as i64 / channels;
 
-    // Modulo on new cursor position to wrap around if we're looping
-    if is_looping {
-        new_cursor_position = new_cursor_position % frames;
+    // Wrap the new cursor position around if we're looping
+    let mut loops = 0;
+    if is_looping && frames > 0 && new_cursor_position >= loop_end {
+        loops += 1;
+        new_cursor_position = loop_start;
     }
 
     cursor.store(new_cursor_position, Ordering::Relaxed);
 
     // If we haven't reached capacity yet, keep recursing
     if samples.len() != samples.capacity() && read_length > 0 {
-        fill_buffer(samples, sndfile, cursor, is_looping)
+        loops += fill_buffer(samples, sndfile, cursor, is_looping, loop_points)?;
+    }
+
+    Ok(loops)
+}
+
+// Report an error of the streaming thread to the error callback, and keep it
+// for `take_error`.
+fn report_error(
+    err: SoundError,
+    error: &Mutex<Option<SoundError>>,
+    error_callback: &Mutex<Option<ErrorCallback>>,
+) {
+    if let Some(ref mut callback) = *error_callback.lock().unwrap() {
+        callback(&err);
+    }
+    *error.lock().unwrap() = Some(err);
+}
+
+// Get the section a cursor loops over in a file of `frames` frames, the whole
+// file if the loop points don't fit in it.
+fn loop_bounds(frames: i64, loop_points: Option<(i64, i64)>) -> (i64, i64) {
+    match loop_points {
+        Some((start, end)) if start < frames => (start, end.min(frames)),
+        _ => (0, frames),
     }
 }
 
 // Becaused the Music source is playing buffered audio, we need to be
 // able to calculate the offset into the full file ourselves
+//
+// `wrapped` tells if the cursor wrapped around the loop in the queued buffers.
 fn calculate_true_offset(
     info: &SndInfo,
     cursor: i64,
-    buffer_size: i64,
+    buffer_frames: i64,
     buffers_queued: i32,
     source_offset: i32,
+    loop_points: Option<(i64, i64)>,
+    wrapped: bool,
 ) -> i32 {
-    let queued_buffers_size = buffer_size / BUFFER_COUNT as i64 * buffers_queued as i64;
-    let offset = cursor - queued_buffers_size + source_offset as i64;
-
-    // This is a bit of a pro hack to deal with when the buffers wrap around
-    // when looping... seems to be accurate though
-    let offset = if offset < 0 {
-        info.frames + offset
-    } else {
-        offset
-    };
+    let queued_buffers_size = buffer_frames * buffers_queued as i64;
+    let mut offset = cursor - queued_buffers_size + source_offset as i64;
+
+    // When the buffers wrap around the loop, the position is as far before
+    // the end of the loop as the offset is before its start
+    let (loop_start, loop_end) = loop_bounds(info.frames, loop_points);
+    if wrapped || offset < 0 {
+        while offset < loop_start && loop_end > loop_start {
+            offset = loop_end - (loop_start - offset);
+        }
+    }
 
     offset.try_into().unwrap_or(0)
 }
 
+// Convert a frame offset into the time from the start of the file
+fn frames_to_duration(info: &SndInfo, frames: i64) -> Duration {
+    let frames = frames.max(0) as u64;
+    let sample_rate = info.samplerate as u64;
+
+    let seconds = frames / sample_rate;
+    let nanoseconds = frames % sample_rate * 1_000_000_000 / sample_rate;
+
+    Duration::new(seconds, nanoseconds as u32)
+}
+
 // Sets the new cursor from offset in seconds with reasonable accuracy
 fn set_cursor_from_offset(info: &SndInfo, cursor: Arc<AtomicI64>, offset: f32) {
     let frames = info.frames as f32;
@@ -195,6 +417,15 @@ fn set_cursor_from_offset(info: &SndInfo, cursor: Arc<AtomicI64>, offset: f32) {
     );
 }
 
+// Open a music file, along with its size
+fn open_file<P: AsRef<Path>>(path: P) -> Result<(Box<SndFile>, Option<u64>), SoundError> {
+    let file_size = fs::metadata(path.as_ref()).ok().map(|m| m.len());
+    match SndFile::new(path, Read) {
+        Ok(file) => Ok((Box::new(file), file_size)),
+        Err(err) => Err(SoundError::LoadError(err)),
+    }
+}
+
 impl Music {
     /**
      * Create a new Music
@@ -206,278 +437,1480 @@ impl Music {
      * A `Result` containing Ok(Music) on success, Err(SoundError)
      * if there has been an error.
      */
-    pub fn new(path: &str) -> Result<Music, SoundError> {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Music, SoundError> {
         // Check that OpenAL is launched
         check_openal_context!(Err(SoundError::InvalidOpenALContext));
 
         // Retrieve File and Music datas
-        let file = match SndFile::new(path, Read) {
+        let (file, file_size) = open_file(path)?;
+        Music::from_sndfile(file, file_size, StreamConfig::default())
+    }
+
+    /**
+     * Create a new Music streamed with custom buffers
+     *
+     * See `StreamConfig`, the values below the minimum are raised to it.
+     *
+     * # Arguments
+     * * `path` - The path of the file to load the music
+     * * `stream_config` - The number and size of the buffers
+     *
+     * # Return
+     * A `Result` containing Ok(Music) on success, Err(SoundError)
+     * if there has been an error.
+     */
+    pub fn with_stream_config<P: AsRef<Path>>(
+        path: P,
+        stream_config: StreamConfig,
+    ) -> Result<Music, SoundError> {
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
+
+        let (file, file_size) = open_file(path)?;
+        Music::from_sndfile(file, file_size, stream_config.checked())
+    }
+
+    /**
+     * Create a new Music streamed from a reader
+     *
+     * The music is read as it plays, from the streaming thread, so any
+     * source of bytes can be used: a file inside an archive, a decrypted
+     * asset or a network buffer.
+     *
+     * # Argument
+     * * `reader` - The reader of the encoded music file
+     *
+     * # Return
+     * A `Result` containing Ok(Music) on success, Err(SoundError)
+     * if there has been an error, for instance Err(SoundError::SeekError)
+     * if the reader can't seek.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Music, SoundError};
+     * use std::io::Cursor;
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let bytes = std::fs::read("path/to/music.ogg").unwrap();
+     *     let mut msc = Music::from_reader(Cursor::new(bytes))?;
+     *     msc.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn from_reader<R: io::Read + Seek + Send + 'static>(
+        mut reader: R,
+    ) -> Result<Music, SoundError> {
+        // Check that OpenAL is launched
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
+
+        let file_size = decoder::reader_size(&mut reader)?;
+        let file = match SndFile::new_with_reader(reader) {
             Ok(file) => Box::new(file),
             Err(err) => {
                 return Err(SoundError::LoadError(err));
             }
         };
-        let infos = file.get_sndinfo();
+        Music::from_sndfile(file, Some(file_size), StreamConfig::default())
+    }
 
-        // create the source and the buffers
-        let mut source_id = 0;
-        let mut buffer_ids = [0; BUFFER_COUNT as usize];
-        // create the source
-        al::alGenSources(1, &mut source_id);
-        // create the buffers
-        al::alGenBuffers(BUFFER_COUNT, &mut buffer_ids[0]);
+    fn from_sndfile(
+        file: Box<SndFile>,
+        file_size: Option<u64>,
+        stream_config: StreamConfig,
+    ) -> Result<Music, SoundError> {
+        let track = Track::new(file, file_size)?;
+        let infos = track.infos.clone();
 
         // Retrieve format information
         let format = match al::get_channels_format(infos.channels) {
             Some(fmt) => fmt,
             None => {
-                return Err(SoundError::InvalidFormat);
+                return Err(SoundError::UnsupportedChannelCount(infos.channels));
             }
         };
 
+        // create the source and the buffers
+        let mut source_id = 0;
+        let mut buffer_ids = vec![0; stream_config.buffers];
+        // create the source
+        al::alGenSources(1, &mut source_id);
+        // create the buffers
+        al::alGenBuffers(stream_config.buffers as i32, &mut buffer_ids[0]);
+
         // Check if there is OpenAL internal error
         if let Some(err) = al::openal_has_error() {
             return Err(SoundError::InternalOpenALError(err));
         };
-
-        let sound_tags = get_sound_tags(&*file);
+        sources::register(
+            source_id,
+            Box::new(move || {
+                fade::cancel(source_id);
+                schedule::cancel(source_id);
+                al::alSourceStop(source_id);
+            }),
+        );
+        bus::add_source(source_id);
 
         Ok(Music {
             al_source: source_id,
             al_buffers: buffer_ids,
-            file: Some(file),
-            sample_to_read: 50000 * (infos.channels as i64),
-            file_infos: infos,
+            stream_config,
+            track: Arc::new(Mutex::new(Arc::new(track))),
+            queued: Arc::new(Mutex::new(None)),
+            sample_to_read: stream_config.buffer_frames as i64 * infos.channels as i64,
             sample_format: format,
-            sound_tags: sound_tags,
+            float_format: sample::float_format(infos.format, infos.channels),
             cursor: Arc::new(AtomicI64::new(0)),
+            last_position: 0,
+            start_time: None,
             state: Initial,
             is_looping: false,
             looping_sender: None,
+            loop_points: Arc::new(Mutex::new(None)),
+            queued_loops: Arc::new(AtomicU32::new(0)),
             offset_sender: None,
-            thread_handle: None,
+            stop_sender: None,
+            stats: Arc::new(Mutex::new(PlaybackStats::default())),
+            loop_callback: Arc::new(Mutex::new(None)),
+            complete_callback: Arc::new(Mutex::new(None)),
+            error: Arc::new(Mutex::new(None)),
+            error_callback: Arc::new(Mutex::new(None)),
+            auxiliary_send_auto: true,
+            doppler_enabled: true,
+            velocity: [0.0; 3],
+            auto_velocity: AutoVelocity::default(),
+            dsp: Arc::new(Mutex::new(StreamDsp::with_history(
+                stream_config.buffers + 1,
+            ))),
+            lyrics: Arc::new(Mutex::new(None)),
+            stream: None,
+            scrub: None,
         })
     }
 
-    fn process_music(&mut self) -> () {
-        let (chan, port) = channel();
-        let sample_t_r = self.sample_to_read;
-        let sample_rate = self.file_infos.samplerate;
-        let sample_format = self.sample_format;
-        let al_source = self.al_source;
-        let al_buffers = self.al_buffers;
+    /**
+     * Replace the file played by the Music, keeping its source and settings.
+     *
+     * The Music is stopped and rewound, the next call to `play` plays the
+     * new file from its start.
+     */
+    pub(crate) fn set_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SoundError> {
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
 
-        // create sample buffer and reserve the exact capacity we need
-        let mut samples: Vec<i16> = Vec::with_capacity(sample_t_r as usize);
+        let (file, file_size) = open_file(path)?;
+        let track = Track::new(file, file_size)?;
+        let format = match al::get_channels_format(track.infos.channels) {
+            Some(fmt) => fmt,
+            None => {
+                return Err(SoundError::UnsupportedChannelCount(track.infos.channels));
+            }
+        };
 
-        fill_buffer(
-            &mut samples,
-            &mut self.file.as_mut().unwrap(),
-            self.cursor.clone(),
-            self.is_looping,
-        );
+        self.halt();
+        self.sample_to_read = self.stream_config.buffer_frames as i64 * track.infos.channels as i64;
+        self.float_format = sample::float_format(track.infos.format, track.infos.channels);
+        // The previous file is closed with its track
+        *self.track.lock().unwrap() = Arc::new(track);
+        *self.queued.lock().unwrap() = None;
+        self.sample_format = format;
+        *self.loop_points.lock().unwrap() = None;
+        self.cursor.store(0, Ordering::Relaxed);
+        self.last_position = 0;
+        Ok(())
+    }
 
-        al::alBufferData(
-            al_buffers[0],
-            sample_format,
-            samples.as_ptr() as *mut c_void,
-            (mem::size_of::<i16>() * samples.len()) as i32,
-            sample_rate,
-        );
+    /**
+     * Create a new Music, giving up if opening the file takes too long.
+     *
+     * The file is opened on a background thread, see `ears::load_music`.
+     *
+     * # Arguments
+     * * `path` - The path of the file to load the music
+     * * `timeout` - The longest time to wait for the file to open
+     *
+     * # Return
+     * A `Result` containing Ok(Music) on success, Err(SoundError::Timeout)
+     * if the file didn't open in time, or another Err(SoundError) if there
+     * has been an error.
+     */
+    pub fn new_with_timeout<P: AsRef<Path>>(
+        path: P,
+        timeout: Duration,
+    ) -> Result<Music, SoundError> {
+        load::load_music(path).wait_timeout(timeout)
+    }
 
-        samples.clear();
+    /**
+     * Get the number and size of the buffers streaming the Music.
+     */
+    pub fn stream_config(&self) -> StreamConfig {
+        self.stream_config
+    }
 
-        fill_buffer(
-            &mut samples,
-            &mut self.file.as_mut().unwrap(),
-            self.cursor.clone(),
-            self.is_looping,
-        );
+    /**
+     * Get the format of the file played by the Music.
+     *
+     * # Return
+     * The container, codec, sample width, sample rate, channels, estimated
+     * bitrate and seekability of the file
+     */
+    pub fn stream_info(&self) -> StreamInfo {
+        let track = self.track.lock().unwrap();
+        stream_info::stream_info(&track.infos, track.size)
+    }
+
+    /// Get the sample rate of the file played, in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.track.lock().unwrap().infos.samplerate as u32
+    }
+
+    /// Get the number of channels of the file played.
+    pub fn channel_count(&self) -> usize {
+        self.track.lock().unwrap().infos.channels as usize
+    }
+
+    /// Get the number of frames of the file played.
+    pub fn frame_count(&self) -> u64 {
+        self.track.lock().unwrap().infos.frames.max(0) as u64
+    }
+
+    /**
+     * Queue a file to play right after the current one.
+     *
+     * The streaming thread reads the queued file as soon as the current one
+     * was read to its end, in the same buffers, so there is no gap between
+     * the two. Once it started, the queued file is the file of the Music:
+     * `get_tags`, `get_duration` and `get_offset` refer to it, and `play`
+     * plays it again after a `stop`.
+     *
+     * A queued file takes precedence over looping: a looping Music plays to
+     * its end once, then the queued file loops. Queuing another file
+     * replaces the previous one.
+     *
+     * # Argument
+     * * `path` - The path of the file to play next. It must have the same
+     *   number of channels and sample rate as the current one.
+     *
+     * # Return
+     * A `Result` containing Ok(()) on success, Err(SoundError) if the file
+     * couldn't be opened, or Err(SoundError::InvalidFormat) if its format
+     * doesn't match the current one.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Music, SoundError};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     // Play the intro once, then loop the body
+     *     let mut msc = Music::new("path/to/intro.ogg")?;
+     *     msc.set_looping(true);
+     *     msc.queue_next("path/to/body.ogg")?;
+     *     msc.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn queue_next<P: AsRef<Path>>(&mut self, path: P) -> Result<(), SoundError> {
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
+
+        let (file, file_size) = open_file(path)?;
+        let next = Track::new(file, file_size)?;
+        {
+            let track = self.track.lock().unwrap();
+            if next.infos.channels != track.infos.channels
+                || next.infos.samplerate != track.infos.samplerate
+            {
+                return Err(SoundError::InvalidFormat);
+            }
+        }
+        *self.queued.lock().unwrap() = Some(next);
+        Ok(())
+    }
+
+    /**
+     * Remove the file queued with `queue_next`.
+     *
+     * Has no effect once the queued file started.
+     */
+    pub fn clear_queued(&mut self) {
+        *self.queued.lock().unwrap() = None;
+    }
+
+    /**
+     * Check if a file is queued with `queue_next` and didn't start yet.
+     */
+    pub fn has_queued(&self) -> bool {
+        self.queued.lock().unwrap().is_some()
+    }
+
+    /**
+     * Loop over a section of the Music.
+     *
+     * Once the end frame is reached, a looping Music goes back to the start
+     * frame instead of the start of the file, so an intro can be played
+     * once before looping the body of a track. The section only applies
+     * while the Music is looping, see `set_looping`, and is cleared once a
+     * file queued with `queue_next` starts.
+     *
+     * # Arguments
+     * * `start_frame` - The first frame of the section
+     * * `end_frame` - The frame right after the section, at most the number
+     *   of frames of the file
+     *
+     * # Return
+     * true if the section was set, false if it is empty or goes past the
+     * end of the file
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Music, SoundError};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     // Play the first 4 seconds once, then loop the 8 next ones
+     *     msc.set_loop_points(4 * 44100, 12 * 44100);
+     *     msc.set_looping(true);
+     *     msc.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_loop_points(&mut self, start_frame: i64, end_frame: i64) -> bool {
+        let frames = self.track.lock().unwrap().infos.frames;
+        if start_frame < 0 || start_frame >= end_frame || end_frame > frames {
+            return false;
+        }
+        *self.loop_points.lock().unwrap() = Some((start_frame, end_frame));
+        true
+    }
+
+    /**
+     * Loop over a section of the Music, given in seconds.
+     *
+     * See `set_loop_points`.
+     *
+     * # Arguments
+     * * `start` - The start of the section, in seconds
+     * * `end` - The end of the section, in seconds
+     *
+     * # Return
+     * true if the section was set, false if it is empty or goes past the
+     * end of the file
+     */
+    pub fn set_loop_points_in_seconds(&mut self, start: f32, end: f32) -> bool {
+        let sample_rate = self.track.lock().unwrap().infos.samplerate as f32;
+        self.set_loop_points(
+            (start * sample_rate).round() as i64,
+            (end * sample_rate).round() as i64,
+        )
+    }
+
+    /**
+     * Get the section the Music loops over.
+     *
+     * # Return
+     * The start and end frames of the section, None if the whole file is
+     * looped over
+     */
+    pub fn get_loop_points(&self) -> Option<(i64, i64)> {
+        *self.loop_points.lock().unwrap()
+    }
+
+    /**
+     * Loop over the whole file again.
+     */
+    pub fn clear_loop_points(&mut self) {
+        *self.loop_points.lock().unwrap() = None;
+    }
+
+    /**
+     * Set a callback to be called each time a looping Music wraps around.
+     *
+     * The callback runs on the streaming thread once the end of the file has
+     * actually been played, and receives the number of loops completed so far.
+     * It should return quickly to avoid starving the sources, as the thread
+     * streams all the Musics.
+     *
+     * # Argument
+     * * `callback` - The function to call, replacing any previous one
+     *
+     * # Example
+     * ```no_run
+     * use ears::{Music, SoundError, AudioController};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     msc.set_loop_callback(|loops| println!("Looped {} times", loops));
+     *     msc.set_looping(true);
+     *     msc.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_loop_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(u32) + Send + 'static,
+    {
+        *self.loop_callback.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /**
+     * Remove the callback set with `set_loop_callback`.
+     */
+    pub fn clear_loop_callback(&mut self) {
+        *self.loop_callback.lock().unwrap() = None;
+    }
+
+    /**
+     * Set a callback to be called when the Music finishes playing.
+     *
+     * The callback runs on the streaming thread once the end of the file has
+     * been played. It isn't called when the Music is stopped, nor while it
+     * loops.
+     *
+     * # Argument
+     * * `callback` - The function to call, replacing any previous one
+     *
+     * # Example
+     * ```no_run
+     * use ears::{Music, SoundError, AudioController};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     msc.set_complete_callback(|| println!("Done playing"));
+     *     msc.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_complete_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        *self.complete_callback.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /**
+     * Remove the callback set with `set_complete_callback`.
+     */
+    pub fn clear_complete_callback(&mut self) {
+        *self.complete_callback.lock().unwrap() = None;
+    }
+
+    /**
+     * Take the error which stopped the streaming of the Music.
+     *
+     * The streaming stops when the file can't be read anymore, for instance
+     * when it is truncated, and when the playback device is lost. The
+     * complete callback isn't called then.
+     *
+     * # Return
+     * The last error since the previous call, None if there was none.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Music, SoundError};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     msc.play();
+     *     while msc.is_playing() {}
+     *     if let Some(err) = msc.take_error() {
+     *         println!("The music stopped: {}", err);
+     *     }
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn take_error(&mut self) -> Option<SoundError> {
+        self.error.lock().unwrap().take()
+    }
+
+    /**
+     * Set a callback to be called when the streaming of the Music fails.
+     *
+     * The callback runs on the streaming thread, with the error also kept
+     * for `take_error`.
+     *
+     * # Argument
+     * * `callback` - The function to call, replacing any previous one
+     */
+    pub fn set_error_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&SoundError) + Send + 'static,
+    {
+        *self.error_callback.lock().unwrap() = Some(Box::new(callback));
+    }
+
+    /**
+     * Remove the callback set with `set_error_callback`.
+     */
+    pub fn clear_error_callback(&mut self) {
+        *self.error_callback.lock().unwrap() = None;
+    }
+
+    /**
+     * Play the Music, returning a future resolving once it stops playing.
+     *
+     * Needs the `async` feature. See `PlaybackFuture`.
+     */
+    #[cfg(feature = "async")]
+    pub fn play_async(&mut self) -> PlaybackFuture<'_> {
+        self.play();
+        PlaybackFuture::new(self.al_source)
+    }
+
+    /**
+     * Load a Music on a background thread, to await it.
+     *
+     * Needs the `async` feature. The returned LoadHandle is a future, see
+     * `ears::load_music`.
+     *
+     * # Argument
+     * * `path` - The path of the music file
+     *
+     * # Example
+     * ```ignore
+     * use ears::{Music, SoundError};
+     *
+     * async fn load() -> Result<Music, SoundError> {
+     *     Music::load_async("path/to/music.ogg").await
+     * }
+     * ```
+     */
+    #[cfg(feature = "async")]
+    pub fn load_async<P: AsRef<Path>>(path: P) -> LoadHandle<Music> {
+        load::load_music(path)
+    }
+
+    /**
+     * Set lyrics to emit as the Music plays.
+     *
+     * The callback runs on the streaming thread each time the playback
+     * reaches a line, with an accuracy of about 50 milliseconds. When the
+     * Music loops, the lines up to the end of the loop are emitted before
+     * the ones from its start. After a seek only the line being sung at the
+     * new offset is emitted.
+     *
+     * # Arguments
+     * * `lyrics` - The lyrics of the Music, replacing any previous ones
+     * * `callback` - The function to call for each line
+     */
+    pub fn set_lyrics<F>(&mut self, lyrics: Lyrics, callback: F)
+    where
+        F: FnMut(&LyricLine) + Send + 'static,
+    {
+        *self.lyrics.lock().unwrap() = Some((lyrics, Box::new(callback)));
+    }
+
+    /**
+     * Remove the lyrics set with `set_lyrics`.
+     */
+    pub fn clear_lyrics(&mut self) {
+        *self.lyrics.lock().unwrap() = None;
+    }
+
+    /**
+     * Set a function processing the samples of the Music as they are streamed.
+     *
+     * The callback runs on the streaming thread on each buffer before it is
+     * given to OpenAL, and receives the interleaved samples, the number of
+     * channels and the sample rate. It can change the samples to apply its
+     * own effects, or copy them for a visualization. Files decoded to floats
+     * are converted to 16 bits around the callback.
+     *
+     * It should return quickly to avoid starving the sources, as the thread
+     * streams all the Musics.
+     *
+     * # Argument
+     * * `callback` - The function to call, replacing any previous one
+     *
+     * # Example
+     * ```no_run
+     * use ears::{Music, SoundError, AudioController};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     // Soft clipping
+     *     msc.set_buffer_callback(|samples, _channels, _sample_rate| {
+     *         for sample in samples.iter_mut() {
+     *             let x = *sample as f32 / i16::MAX as f32;
+     *             *sample = (x.tanh() * i16::MAX as f32) as i16;
+     *         }
+     *     });
+     *     msc.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_buffer_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(&mut [i16], usize, i32) + Send + 'static,
+    {
+        self.dsp.lock().unwrap().callback = Some(Box::new(callback));
+    }
+
+    /**
+     * Remove the callback set with `set_buffer_callback`.
+     */
+    pub fn clear_buffer_callback(&mut self) {
+        self.dsp.lock().unwrap().callback = None;
+    }
+
+    /**
+     * Compute the frequency spectrum of the audio being played.
+     *
+     * The analysis covers the 2048 frames played last, about 50
+     * milliseconds, so calling it every frame is enough to animate a
+     * visualizer or to make the game react to the beats.
+     *
+     * # Argument
+     * * `bands` - The number of frequency bands to compute
+     *
+     * # Return
+     * The magnitude of each band, logarithmically spaced from 20Hz to half the
+     * sample rate, where 1.0 is a full scale sine wave. All zeros if the
+     * Music is not playing.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Music, SoundError};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     msc.play();
+     *     while msc.is_playing() {
+     *         let bass = msc.spectrum(16)[..3].iter().cloned().fold(0.0, f32::max);
+     *         println!("{}", "#".repeat((bass * 40.0) as usize));
+     *     }
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn spectrum(&self, bands: usize) -> Vec<f32> {
+        check_openal_context!(vec![0.0; bands]);
+
+        if !self.is_playing() {
+            return vec![0.0; bands];
+        }
+        let mut sample_offset: i32 = 0;
+        al::alGetSourcei(self.al_source, ffi::AL_SAMPLE_OFFSET, &mut sample_offset);
+        let mut buffers_queued: i32 = 0;
+        al::alGetSourcei(self.al_source, ffi::AL_BUFFERS_QUEUED, &mut buffers_queued);
+
+        let samples = self.dsp.lock().unwrap().recent_samples(
+            buffers_queued.max(0) as usize,
+            sample_offset.max(0) as usize,
+            SPECTRUM_FRAMES,
+        );
+        let sample_rate = self.track.lock().unwrap().infos.samplerate as u32;
+        spectrum::band_magnitudes(&samples, sample_rate, bands)
+    }
+
+    /**
+     * Remove the center of a stereo Music, where vocals are usually mixed.
+     *
+     * This is the classic karaoke vocal removal: the content common to both
+     * channels is attenuated, which also affects centered instruments like
+     * bass or drums. Has no effect on mono and multi-channel files.
+     *
+     * The change applies to the samples streamed after the call, so it can
+     * take up to a couple of seconds to be heard.
+     *
+     * # Argument
+     * * `strength` - How much of the center to remove, from 0.0 (disabled,
+     *   the default) to 1.0 (fully removed)
+     */
+    pub fn set_center_removal(&mut self, strength: f32) {
+        self.dsp.lock().unwrap().center_removal = strength.clamp(0.0, 1.0);
+    }
+
+    /**
+     * Get how much of the center of the Music is removed.
+     *
+     * # Return
+     * The strength of the center removal between 0.0 and 1.0
+     */
+    pub fn get_center_removal(&self) -> f32 {
+        self.dsp.lock().unwrap().center_removal
+    }
+
+    /**
+     * Enter the scrub mode, to drag the playback position continuously.
+     *
+     * While scrubbing, the Music plays short grains around the position
+     * given to `scrub_to`, faster or slower, forward or backward, following
+     * how fast the position moves, like dragging the playhead of an audio
+     * editor. Nothing is heard while the position stays still.
+     *
+     * The normal playback is stopped, and resumes from the reached position
+     * with `play` after `stop_scrub`.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Music, SoundError};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut msc = Music::new("path/to/music.ogg")?;
+     *     msc.start_scrub();
+     *     // Drag the position over one second of audio, backward
+     *     for offset in (0..44100).rev().step_by(441) {
+     *         msc.scrub_to(offset);
+     *         std::thread::sleep(std::time::Duration::from_millis(10));
+     *     }
+     *     msc.stop_scrub();
+     *     msc.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn start_scrub(&mut self) {
+        check_openal_context!(());
+
+        if self.scrub.is_some() {
+            return;
+        }
+        self.halt();
+
+        // The scrub is stopped before the track can be replaced
+        let file = self.track.lock().unwrap().file.lock().unwrap().clone();
+        self.scrub = Some(Scrub::start(
+            file,
+            self.al_source,
+            self.sample_format,
+            self.last_position,
+            self.is_looping,
+        ));
+    }
+
+    /**
+     * Drag the playback position while scrubbing.
+     *
+     * Call it each time the position moves, for instance each time the mouse
+     * moves. Has no effect outside of the scrub mode.
+     *
+     * # Argument
+     * * `offset` - The frame to drag the playback position to
+     */
+    pub fn scrub_to(&mut self, offset: i32) {
+        if let Some(ref scrub) = self.scrub {
+            scrub.set_target(offset.into());
+        }
+    }
+
+    /**
+     * Leave the scrub mode.
+     *
+     * The Music is stopped, `play` resumes from the reached position. Calling
+     * `play` or `stop` while scrubbing also leaves the scrub mode.
+     */
+    pub fn stop_scrub(&mut self) {
+        if let Some(scrub) = self.scrub.take() {
+            let position = scrub.stop();
+            self.cursor.store(position, Ordering::Relaxed);
+        }
+    }
+
+    /**
+     * Check if the Music is in the scrub mode.
+     *
+     * # Return
+     * True if `start_scrub` was called and not `stop_scrub`, false otherwise
+     */
+    pub fn is_scrubbing(&self) -> bool {
+        self.scrub.is_some()
+    }
+
+    /**
+     * Stop the Music, keeping its position.
+     *
+     * Unlike `stop`, `get_offset` still reports where the Music was
+     * stopped, and `play` resumes from there.
+     */
+    pub fn stop_keep_position(&mut self) {
+        check_openal_context!(());
+
+        self.halt();
+        self.cursor.store(self.last_position, Ordering::Relaxed);
+    }
+
+    /**
+     * Start playing the Music after a delay, with its volume rising from
+     * silence.
+     *
+     * A Music still playing, e.g. fading out, keeps playing and its volume
+     * rises from where it is instead.
+     *
+     * # Arguments
+     * * `delay` - The delay before the Music starts
+     * * `duration` - The duration of the fade, 0 to start at full volume
+     */
+    pub(crate) fn fade_in_after(&mut self, delay: Duration, duration: Duration) {
+        check_openal_context!(());
+
+        let volume = fade::base_volume(self.al_source).unwrap_or_else(|| self.get_volume());
+        if self.is_playing() {
+            fade::start_after(
+                self.al_source,
+                self.get_volume(),
+                volume,
+                delay,
+                duration,
+                None,
+            );
+            return;
+        }
+        self.set_volume(0.0);
+        self.play_in(delay);
+        fade::start_after(self.al_source, 0.0, volume, delay, duration, None);
+    }
+
+    /**
+     * Fade the volume of the Music out after a delay, then stop it.
+     *
+     * # Arguments
+     * * `delay` - The delay before the fade starts
+     * * `duration` - The duration of the fade, 0 to stop right after the delay
+     */
+    pub(crate) fn fade_out_after(&mut self, delay: Duration, duration: Duration) {
+        let volume = fade::base_volume(self.al_source).unwrap_or_else(|| self.get_volume());
+        let source = self.al_source;
+        let stop_sender = self.stop_sender.clone();
+        let cursor = self.cursor.clone();
+        let stop: FadeEnd = Box::new(move || {
+            schedule::cancel(source);
+            if let Some(sender) = stop_sender {
+                sender.send(());
+            }
+            al::alSourceStop(source);
+            cursor.store(0, Ordering::Relaxed);
+        });
+        fade::start_after(
+            self.al_source,
+            self.get_volume(),
+            0.0,
+            delay,
+            duration,
+            Some((volume, stop)),
+        );
+    }
+
+    /**
+     * Play the Music from where it was last stopped.
+     *
+     * Resumes from the position reached before the last call to `stop` or
+     * `stop_keep_position`, or resumes a paused Music. Does nothing if the
+     * Music is playing.
+     */
+    pub fn play_from_last_position(&mut self) {
+        check_openal_context!(());
+
+        match self.get_state() {
+            Playing => {}
+            Paused => self.play(),
+            _ => {
+                self.cursor.store(self.last_position, Ordering::Relaxed);
+                self.play();
+            }
+        }
+    }
+
+    // Stop the playback and wait for the streaming thread, remembering the
+    // position reached.
+    fn halt(&mut self) {
+        fade::cancel(self.al_source);
+        schedule::cancel(self.al_source);
+        self.stop_scrub();
+        if let Some(ref sender) = self.stop_sender {
+            sender.send(());
+        }
+        let position = match self.get_state() {
+            Playing | Paused => Some(self.get_offset() as i64),
+            _ => None,
+        };
+        // Not collected by a SourceGroup: the stream only ends once the source stopped
+        al::alSourceStop(self.al_source);
+        if let Some(stream) = self.stream.take() {
+            stream.join();
+        }
+        self.last_position = position.unwrap_or_else(|| self.get_offset() as i64);
+    }
+
+    fn process_music(&mut self) -> () {
+        // Files of more than 16 bits are streamed as floats, when OpenAL supports them
+        match self.float_format {
+            Some(float_format) => self.stream::<f32>(float_format),
+            None => self.stream::<i16>(self.sample_format),
+        }
+    }
+
+    fn stream<S: Sample>(&mut self, sample_format: i32) {
+        let sample_t_r = self.sample_to_read;
+        let (mut reading, mut file_infos) = {
+            let track = self.track.lock().unwrap();
+            (track.clone(), track.infos.clone())
+        };
+        let sample_rate = file_infos.samplerate;
+        let al_source = self.al_source;
+        let channels = file_infos.channels as u64;
+        let buffer_frames = self.stream_config.buffer_frames as i64;
+        let buffer_duration = self.stream_config.buffer_duration(sample_rate);
+
+        // create sample buffer and reserve the exact capacity we need
+        let mut samples: Vec<S> = Vec::with_capacity(sample_t_r as usize);
+
+        // Frames and loops held by each buffer, to know what was played once processed
+        let mut buffer_contents: HashMap<u32, (u64, u32)> = HashMap::new();
+
+        // The queued track started in the buffers, and how many buffers are
+        // still to be played before it
+        let mut started = None;
+        for (index, buffer) in self.al_buffers.iter().enumerate() {
+            samples.clear();
+
+            let (loops, track) = match fill_buffer_queued(
+                &mut samples,
+                &mut reading,
+                self.cursor.clone(),
+                self.is_looping,
+                &self.loop_points,
+                &self.queued,
+            ) {
+                Ok(filled) => filled,
+                Err(err) => {
+                    report_error(
+                        SoundError::LoadError(err),
+                        &self.error,
+                        &self.error_callback,
+                    );
+                    return;
+                }
+            };
+            if let Some(track) = track {
+                started = Some((track, index as i32));
+            }
+            buffer_contents.insert(*buffer, (samples.len() as u64 / channels, loops));
+            self.dsp
+                .lock()
+                .unwrap()
+                .process(&mut samples, channels as usize, sample_rate);
+
+            al::alBufferData(
+                *buffer,
+                sample_format,
+                samples.as_ptr() as *mut c_void,
+                (mem::size_of::<S>() * samples.len()) as i32,
+                sample_rate,
+            );
+        }
+        self.queued_loops.store(
+            buffer_contents.values().map(|c| c.1).sum(),
+            Ordering::Relaxed,
+        );
+
+        // Queue the buffers
+        al::alSourceQueueBuffers(al_source, self.al_buffers.len() as i32, &self.al_buffers[0]);
+
+        // Start playing
+        schedule::play(al_source, self.start_time.take());
+
+        let (looping_sender, looping_receiver): (Sender<bool>, Receiver<bool>) = channel();
+        let (offset_sender, offset_receiver): (Sender<i32>, Receiver<i32>) = channel();
+        let (stop_sender, stop_receiver): (Sender<()>, Receiver<()>) = channel();
+
+        self.looping_sender = Some(looping_sender);
+        self.offset_sender = Some(offset_sender);
+        self.stop_sender = Some(stop_sender.clone());
+
+        let cursor = self.cursor.clone();
+        let stop_cursor = self.cursor.clone();
+        sources::register(
+            al_source,
+            Box::new(move || {
+                fade::cancel(al_source);
+                schedule::cancel(al_source);
+                stop_sender.send(()).ok();
+                al::alSourceStop(al_source);
+                stop_cursor.store(0, Ordering::Relaxed);
+            }),
+        );
+        let stats = self.stats.clone();
+        let loop_callback = self.loop_callback.clone();
+        let complete_callback = self.complete_callback.clone();
+        let error = self.error.clone();
+        let error_callback = self.error_callback.clone();
+        let lyrics = self.lyrics.clone();
+        let dsp = self.dsp.clone();
+        let current_track = self.track.clone();
+        let queued = self.queued.clone();
+        let is_looping_clone = self.is_looping.clone();
+        let loop_points = self.loop_points.clone();
+        let queued_loops = self.queued_loops.clone();
+
+        let mut status = ffi::AL_PLAYING;
+        let mut buffers_processed = 0;
+        let mut buffers_queued = 0;
+        let mut buf = 0;
+        let mut is_looping = is_looping_clone;
+        let mut offset_shift_restart = false;
+        let mut stopped_on_purpose = false;
+        // Where the lyrics were last emitted, None after a seek
+        let mut lyrics_position = Some(Duration::from_secs(0));
+
+        let update = Box::new(move || {
+            if status == ffi::AL_PLAYING {
+                if let Ok(new_is_looping) = looping_receiver.try_recv() {
+                    is_looping = new_is_looping;
+                }
+
+                if let Ok(offset) = offset_receiver.try_recv() {
+                    // Only the start of the flushed buffers was played
+                    let mut sample_offset = 0;
+                    al::alGetSourcei(al_source, ffi::AL_SAMPLE_OFFSET, &mut sample_offset);
+                    stats.lock().unwrap().frames_played += sample_offset.max(0) as u64;
+                    buffer_contents.clear();
+
+                    // If we shift the offset, we need to stop and restart the source
+                    // so that we can swap out the buffers in an instantaneous manner
+                    al::alSourceStop(al_source);
+                    offset_shift_restart = true;
+                    lyrics_position = None;
+                    cursor.store(offset.into(), Ordering::Relaxed);
+                }
+
+                al::alGetSourcei(al_source, ffi::AL_BUFFERS_QUEUED, &mut buffers_queued);
+
+                al::alGetSourcei(al_source, ffi::AL_BUFFERS_PROCESSED, &mut buffers_processed);
+
+                let mut frames_queued = 0;
+                for _ in 0..buffers_processed {
+                    al::alSourceUnqueueBuffers(al_source, 1, &mut buf);
+
+                    samples.clear();
+
+                    // The buffer before this one has been played
+                    if let Some((_, ref mut ahead)) = started {
+                        *ahead -= 1;
+                    }
+
+                    let (loops, track) = match fill_buffer_queued(
+                        &mut samples,
+                        &mut reading,
+                        cursor.clone(),
+                        is_looping,
+                        &loop_points,
+                        &queued,
+                    ) {
+                        Ok(filled) => filled,
+                        Err(err) => {
+                            report_error(SoundError::LoadError(err), &error, &error_callback);
+                            al::alSourceStop(al_source);
+                            offset_shift_restart = false;
+                            stopped_on_purpose = true;
+                            break;
+                        }
+                    };
+                    if let Some(track) = track {
+                        let mut ahead = 0;
+                        al::alGetSourcei(al_source, ffi::AL_BUFFERS_QUEUED, &mut ahead);
+                        started = Some((track, ahead));
+                    }
+                    let frames = samples.len() as u64 / channels;
+                    frames_queued += frames;
+                    dsp.lock()
+                        .unwrap()
+                        .process(&mut samples, channels as usize, sample_rate);
+
+                    let (played_frames, played_loops) = buffer_contents
+                        .insert(buf, (frames, loops))
+                        .unwrap_or((0, 0));
+
+                    let loops_completed = {
+                        let mut stats = stats.lock().unwrap();
+                        stats.frames_played += played_frames;
+                        stats.loops_completed += played_loops;
+                        stats.loops_completed
+                    };
+
+                    // The buffer where the cursor wrapped has been played
+                    if played_loops > 0 {
+                        if let Some(ref mut callback) = *loop_callback.lock().unwrap() {
+                            for i in (0..played_loops).rev() {
+                                callback(loops_completed - i);
+                            }
+                        }
+                    }
+
+                    al::alBufferData(
+                        buf,
+                        sample_format,
+                        samples.as_ptr() as *mut c_void,
+                        (mem::size_of::<S>() * samples.len()) as i32,
+                        sample_rate,
+                    );
+                    al::alSourceQueueBuffers(al_source, 1, &buf);
+                }
+                queued_loops.store(
+                    buffer_contents.values().map(|c| c.1).sum(),
+                    Ordering::Relaxed,
+                );
+
+                // Once the queued track is heard it becomes the track of the Music
+                if started.as_ref().is_some_and(|(_, ahead)| *ahead <= 0) {
+                    let (track, _) = started.take().unwrap();
+                    file_infos = track.infos.clone();
+                    *current_track.lock().unwrap() = track;
+                }
+
+                // After buffer refill restart
+                if offset_shift_restart {
+                    al::alSourcePlay(al_source);
+                    offset_shift_restart = false;
+                } else if frames_queued > 0 && al::alGetState(al_source) == ffi::AL_STOPPED {
+                    // The source ran out of data before we could refill it,
+                    // unless it was stopped on purpose resume playback.
+                    match stop_receiver.try_recv() {
+                        Err(TryRecvError::Empty) if !disconnect::is_device_connected() => {
+                            report_error(SoundError::DeviceLost, &error, &error_callback);
+                            stopped_on_purpose = true;
+                        }
+                        Err(TryRecvError::Empty) => {
+                            stats.lock().unwrap().last_underrun = Some(Instant::now());
+                            al::alSourcePlay(al_source);
+                        }
+                        _ => stopped_on_purpose = true,
+                    }
+                }
+
+                if let Some((ref lyrics, ref mut callback)) = *lyrics.lock().unwrap() {
+                    let mut sample_offset = 0;
+                    al::alGetSourcei(al_source, ffi::AL_SAMPLE_OFFSET, &mut sample_offset);
+                    al::alGetSourcei(al_source, ffi::AL_BUFFERS_QUEUED, &mut buffers_queued);
+                    let points = *loop_points.lock().unwrap();
+                    let offset = calculate_true_offset(
+                        &file_infos,
+                        cursor.load(Ordering::Relaxed),
+                        buffer_frames,
+                        buffers_queued,
+                        sample_offset,
+                        points,
+                        queued_loops.load(Ordering::Relaxed) > 0,
+                    );
+                    let now = frames_to_duration(&file_infos, offset as i64);
+
+                    let lines: Vec<&LyricLine> = match lyrics_position {
+                        Some(last) if last <= now => {
+                            lyrics.lines_between(last, now).iter().collect()
+                        }
+                        // Going backward means the music looped: finish the loop
+                        // before starting it again
+                        Some(last) => {
+                            let (start, end) = loop_bounds(file_infos.frames, points);
+                            let end = frames_to_duration(&file_infos, end);
+                            let start = frames_to_duration(&file_infos, start);
+                            lyrics
+                                .lines_between(last, end)
+                                .iter()
+                                .chain(lyrics.lines_between(start, now))
+                                .collect()
+                        }
+                        // After a seek only emit the line being sung
+                        None => lyrics.line_at(now).into_iter().collect(),
+                    };
+                    for line in lines {
+                        callback(line);
+                    }
+
+                    // Lines exactly at `now` are emitted next time, unless
+                    // line_at already did
+                    lyrics_position = match lyrics_position {
+                        Some(_) => Some(now),
+                        None => Some(now + Duration::from_nanos(1)),
+                    };
+                }
+            }
+            // Get source status
+            status = al::alGetState(al_source);
+            if status != ffi::AL_STOPPED {
+                return true;
+            }
+
+            al::alSourcei(al_source, ffi::AL_BUFFER, 0);
+
+            // The cursor is now in the queued track, even if it wasn't heard
+            if let Some((track, _)) = started.take() {
+                *current_track.lock().unwrap() = track;
+            }
+
+            if !stopped_on_purpose && stop_receiver.try_recv().is_err() {
+                if !disconnect::is_device_connected() {
+                    report_error(SoundError::DeviceLost, &error, &error_callback);
+                } else {
+                    // The buffers left in the queue were played to their end
+                    stats.lock().unwrap().frames_played +=
+                        buffer_contents.values().map(|c| c.0).sum::<u64>();
+                    if let Some(ref mut callback) = *complete_callback.lock().unwrap() {
+                        callback();
+                    }
+                }
+            }
+            false
+        });
+        self.stream = Some(stream_service::spawn(update, buffer_duration));
+    }
+}
+
+impl AudioTags for Music {
+    /**
+     * Get the tags of a Sound.
+     *
+     * # Return
+     * A borrowed pointer to the internal struct SoundTags
+     */
+    fn get_tags(&self) -> Tags {
+        self.track.lock().unwrap().tags.clone()
+    }
+}
+
+impl AudioController for Music {
+    /**
+     * Play or resume the Music.
+     *
+     * A stopped Music plays from the start, unless `set_offset` or
+     * `stop_keep_position` moved it somewhere else.
+     */
+    fn play(&mut self) -> () {
+        check_openal_context!(());
+
+        self.stop_scrub();
+        match self.get_state() {
+            Paused => {
+                schedule::play(self.al_source, self.start_time.take());
+                return;
+            }
+            _ => {
+                if self.is_playing() {
+                    self.stop();
+                    // wait a bit for openal terminate
+                    sleep(Duration::from_millis(50));
+                }
+                {
+                    let track = self.track.lock().unwrap();
+                    if self.cursor.load(Ordering::Relaxed) >= track.infos.frames {
+                        self.cursor.store(0, Ordering::Relaxed);
+                    }
+                    track.file.lock().unwrap().seek(0, SeekSet);
+                }
+                self.stats.lock().unwrap().times_played += 1;
+                self.process_music();
+            }
+        }
+    }
+
+    /**
+     * Play or resume the Music at a time on the device clock.
+     *
+     * The first buffers are queued right away, so the Music starts on time.
+     *
+     * # Argument
+     * * `time` - The time on the device clock, see `ears::device_clock`
+     */
+    fn play_at(&mut self, time: Duration) -> () {
+        self.start_time = Some(time);
+        self.play();
+        self.start_time = None;
+    }
+
+    /**
+     * Pause the Music.
+     */
+    fn pause(&mut self) -> () {
+        check_openal_context!(());
+
+        schedule::pause(self.al_source)
+    }
+
+    /**
+     * Stop the Music.
+     *
+     * The Music goes back to the start, use `stop_keep_position` to stop it
+     * where it is instead.
+     */
+    fn stop(&mut self) -> () {
+        check_openal_context!(());
+
+        self.halt();
+        self.cursor.store(0, Ordering::Relaxed);
+    }
+
+    /**
+     * Start playing the Music with its volume rising from silence.
+     *
+     * # Argument
+     * * `duration` - The duration of the fade
+     */
+    fn fade_in(&mut self, duration: Duration) -> () {
+        check_openal_context!(());
+
+        let volume = fade::base_volume(self.al_source).unwrap_or_else(|| self.get_volume());
+        self.set_volume(0.0);
+        if !self.is_playing() {
+            self.play();
+        }
+        fade::start(self.al_source, 0.0, volume, duration, None);
+    }
+
+    /**
+     * Fade the volume of the Music out, then stop it.
+     *
+     * # Argument
+     * * `duration` - The duration of the fade
+     */
+    fn fade_out(&mut self, duration: Duration) -> () {
+        check_openal_context!(());
+
+        self.fade_out_after(Duration::from_secs(0), duration);
+    }
+
+    /**
+     * Ramp the volume of the Music to a new value.
+     *
+     * # Arguments
+     * * `volume` - The volume at the end of the fade
+     * * `duration` - The duration of the fade
+     */
+    fn fade_to(&mut self, volume: f32, duration: Duration) -> () {
+        check_openal_context!(());
 
-        al::alBufferData(
-            al_buffers[1],
-            sample_format,
-            samples.as_ptr() as *mut c_void,
-            (mem::size_of::<i16>() * samples.len()) as i32,
-            sample_rate,
-        );
+        fade::start(self.al_source, self.get_volume(), volume, duration, None);
+    }
 
-        // Queue the buffers
-        al::alSourceQueueBuffers(al_source, 2, &al_buffers[0]);
+    /**
+     * Connect an effect to an auxiliary send of the Music
+     */
+    fn connect_send(&mut self, send: i32, effect: &dyn Effect) -> Result<(), ReverbEffectError> {
+        check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
+
+        let slot = effect.slot() as i32;
+        if !OpenAlData::efx_capable() {
+            return match slot {
+                ffi::AL_EFFECTSLOT_NULL => Ok(()),
+                _ => Err(ReverbEffectError::EfxUnsupported),
+            };
+        }
+        if send < 0 || send >= max_auxiliary_sends() {
+            return Err(ReverbEffectError::InvalidSend(send));
+        }
 
-        // Start playing
-        al::alSourcePlay(al_source);
+        occlusion::connect_send(self.al_source, send, slot);
 
-        let (looping_sender, looping_receiver): (Sender<bool>, Receiver<bool>) = channel();
-        let (offset_sender, offset_receiver): (Sender<i32>, Receiver<i32>) = channel();
+        // Check if there is OpenAL internal error
+        match al::openal_has_error() {
+            Some(err) => Err(ReverbEffectError::InternalOpenALError(err)),
+            None => Ok(()),
+        }
+    }
 
-        self.looping_sender = Some(looping_sender);
-        self.offset_sender = Some(offset_sender);
+    /**
+     * Filter the direct path of the Music, or remove the filter with None.
+     */
+    fn set_direct_filter(&mut self, filter: &Option<Filter>) -> Result<(), ReverbEffectError> {
+        check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
 
-        let cursor = self.cursor.clone();
-        let is_looping_clone = self.is_looping.clone();
+        if !OpenAlData::efx_capable() {
+            return match filter {
+                Some(_) => Err(ReverbEffectError::EfxUnsupported),
+                None => Ok(()),
+            };
+        }
 
-        let thread = thread::Builder::new().name(String::from("ears-music"));
-        self.thread_handle = Some(
-            thread
-                .spawn(move || {
-                    match OpenAlData::check_al_context() {
-                        Ok(_) => {}
-                        Err(err) => {
-                            println!("{}", err);
-                        }
-                    };
-                    let mut file: SndFile = port.recv().ok().unwrap();
-                    let mut status = ffi::AL_PLAYING;
-                    let mut buffers_processed = 0;
-                    let mut buffers_queued = 0;
-                    let mut buf = 0;
-                    let mut is_looping = is_looping_clone;
-                    let mut offset_shift_restart = false;
-
-                    while status != ffi::AL_STOPPED {
-                        // wait a bit
-                        sleep(Duration::from_millis(50));
-                        if status == ffi::AL_PLAYING {
-                            if let Ok(new_is_looping) = looping_receiver.try_recv() {
-                                is_looping = new_is_looping;
-                            }
+        occlusion::set_source_filter(self.al_source, filter);
 
-                            if let Ok(offset) = offset_receiver.try_recv() {
-                                // If we shift the offset, we need to stop and restart the source
-                                // so that we can swap out the buffers in an instantaneous manner
-                                al::alSourceStop(al_source);
-                                offset_shift_restart = true;
-                                cursor.store(offset.into(), Ordering::Relaxed);
-                            }
+        // Check if there is OpenAL internal error
+        match al::openal_has_error() {
+            Some(err) => Err(ReverbEffectError::InternalOpenALError(err)),
+            None => Ok(()),
+        }
+    }
 
-                            al::alGetSourcei(
-                                al_source,
-                                ffi::AL_BUFFERS_QUEUED,
-                                &mut buffers_queued,
-                            );
-
-                            al::alGetSourcei(
-                                al_source,
-                                ffi::AL_BUFFERS_PROCESSED,
-                                &mut buffers_processed,
-                            );
-
-                            for _ in 0..buffers_processed {
-                                al::alSourceUnqueueBuffers(al_source, 1, &mut buf);
-
-                                samples.clear();
-
-                                fill_buffer(&mut samples, &mut file, cursor.clone(), is_looping);
-
-                                al::alBufferData(
-                                    buf,
-                                    sample_format,
-                                    samples.as_ptr() as *mut c_void,
-                                    (mem::size_of::<i16>() * samples.len()) as i32,
-                                    sample_rate,
-                                );
-                                al::alSourceQueueBuffers(al_source, 1, &buf);
-                            }
+    /**
+     * Set how much the Music is occluded.
+     */
+    fn set_occlusion(&mut self, occlusion: f32) -> () {
+        check_openal_context!(());
 
-                            // After buffer refill restart
-                            if offset_shift_restart {
-                                al::alSourcePlay(al_source);
-                                offset_shift_restart = false;
-                            }
-                        }
-                        // Get source status
-                        status = al::alGetState(al_source);
-                    }
-                    al::alSourcei(al_source, ffi::AL_BUFFER, 0);
-                })
-                .unwrap(),
-        );
-        let file = self.file.as_ref().unwrap().clone();
-        chan.send(*file);
+        occlusion::set_source_occlusion(self.al_source, occlusion);
     }
-}
 
-impl AudioTags for Music {
     /**
-     * Get the tags of a Sound.
-     *
-     * # Return
-     * A borrowed pointer to the internal struct SoundTags
+     * Get how much the Music is occluded.
      */
-    fn get_tags(&self) -> Tags {
-        self.sound_tags.clone()
+    fn get_occlusion(&self) -> f32 {
+        occlusion::source_occlusion(self.al_source)
     }
-}
 
-impl AudioController for Music {
     /**
-     * Play or resume the Music.
+     * Set how much the Music is obstructed.
      */
-    fn play(&mut self) -> () {
+    fn set_obstruction(&mut self, obstruction: f32) -> () {
         check_openal_context!(());
 
-        match self.get_state() {
-            Paused => {
-                al::alSourcePlay(self.al_source);
-                return;
-            }
-            _ => {
-                if self.is_playing() {
-                    al::alSourceStop(self.al_source);
-                    // wait a bit for openal terminate
-                    sleep(Duration::from_millis(50));
-                }
-                self.file.as_mut().unwrap().seek(0, SeekSet);
-                self.process_music();
-            }
-        }
+        occlusion::set_source_obstruction(self.al_source, obstruction);
     }
 
     /**
-     * Pause the Music.
+     * Get how much the Music is obstructed.
      */
-    fn pause(&mut self) -> () {
+    fn get_obstruction(&self) -> f32 {
+        occlusion::source_obstruction(self.al_source)
+    }
+
+    /**
+     * Assign the Music to a bus, or remove it from its bus.
+     */
+    fn set_bus(&mut self, bus: Option<&Bus>) -> () {
         check_openal_context!(());
 
-        al::alSourcePause(self.al_source)
+        bus::set_source_bus(self.al_source, bus);
     }
 
     /**
-     * Stop the Music.
+     * Check if the Music is assigned to a bus.
      */
-    fn stop(&mut self) -> () {
+    fn is_in_bus(&self, bus: &Bus) -> bool {
+        bus::source_in_bus(self.al_source, bus)
+    }
+
+    /**
+     * Set the gain of the direct path of the Music.
+     */
+    fn set_dry_gain(&mut self, gain: f32) -> () {
         check_openal_context!(());
 
-        al::alSourceStop(self.al_source);
+        occlusion::set_source_dry_gain(self.al_source, gain);
+    }
+
+    /**
+     * Get the gain of the direct path of the Music.
+     */
+    fn get_dry_gain(&self) -> f32 {
+        occlusion::source_dry_gain(self.al_source)
     }
 
     /**
-     * Connect a ReverbEffect to the Music
+     * Set the gain of the auxiliary sends of the Music.
      */
-    fn connect(&mut self, reverb_effect: &Option<ReverbEffect>) {
+    fn set_send_gain(&mut self, gain: f32) -> () {
         check_openal_context!(());
 
-        match reverb_effect {
-            Some(reverb_effect) => {
-                al::alSource3i(
-                    self.al_source,
-                    ffi::AL_AUXILIARY_SEND_FILTER,
-                    reverb_effect.slot() as i32,
-                    0,
-                    ffi::AL_FILTER_NULL,
-                );
-            }
-            None => {
-                al::alSource3i(
-                    self.al_source,
-                    ffi::AL_AUXILIARY_SEND_FILTER,
-                    ffi::AL_EFFECTSLOT_NULL,
-                    0,
-                    ffi::AL_FILTER_NULL,
-                );
-            }
-        }
+        occlusion::set_source_send_gain(self.al_source, gain);
+    }
+
+    /**
+     * Get the gain of the auxiliary sends of the Music.
+     */
+    fn get_send_gain(&self) -> f32 {
+        occlusion::source_send_gain(self.al_source)
     }
 
     /**
@@ -544,14 +1977,56 @@ impl AudioController for Music {
         al::alGetSourcei(self.al_source, ffi::AL_BUFFERS_QUEUED, &mut buffers_queued);
 
         let cursor = self.cursor.load(Ordering::Relaxed);
-        let buffer_size = self.sample_to_read;
+        let buffer_frames = self.stream_config.buffer_frames as i64;
 
         calculate_true_offset(
-            &self.file_infos,
+            &self.track.lock().unwrap().infos,
             cursor,
-            buffer_size,
+            buffer_frames,
             buffers_queued,
             sample_offset,
+            self.get_loop_points(),
+            self.queued_loops.load(Ordering::Relaxed) > 0,
+        )
+    }
+
+    /**
+     * Get the precise playback position in the Music.
+     *
+     * # Return
+     * A tuple containing the playback position and the latency of the device,
+     * zero without the AL_SOFT_source_latency extension.
+     */
+    fn get_playback_position_precise(&self) -> (Duration, Duration) {
+        check_openal_context!((Duration::from_secs(0), Duration::from_secs(0)));
+
+        // The offset of the source is in its queue, which starts behind the cursor
+        let (offset, latency) =
+            source_latency::offset_latency(self.al_source).unwrap_or_else(|| {
+                let mut offset = 0.;
+                al::alGetSourcef(self.al_source, ffi::AL_SEC_OFFSET, &mut offset);
+                (offset as f64, 0.)
+            });
+
+        let mut buffers_queued: i32 = 0;
+        al::alGetSourcei(self.al_source, ffi::AL_BUFFERS_QUEUED, &mut buffers_queued);
+
+        let infos = self.track.lock().unwrap().infos.clone();
+        let frames = offset * infos.samplerate as f64;
+        let true_offset = calculate_true_offset(
+            &infos,
+            self.cursor.load(Ordering::Relaxed),
+            self.stream_config.buffer_frames as i64,
+            buffers_queued,
+            frames as i32,
+            self.get_loop_points(),
+            self.queued_loops.load(Ordering::Relaxed) > 0,
+        );
+        // Keep the fraction of frame lost by `calculate_true_offset`
+        let position = (true_offset as f64 + frames.fract()) / infos.samplerate as f64;
+        (
+            source_latency::seconds_to_duration(position),
+            source_latency::seconds_to_duration(latency),
         )
     }
 
@@ -568,7 +2043,10 @@ impl AudioController for Music {
     fn set_volume(&mut self, volume: f32) -> () {
         check_openal_context!(());
 
-        al::alSourcef(self.al_source, ffi::AL_GAIN, volume);
+        fade::cancel(self.al_source);
+        if !attenuation::set_source_volume(self.al_source, volume) {
+            bus::set_source_gain(self.al_source, volume);
+        }
     }
 
     /**
@@ -580,9 +2058,10 @@ impl AudioController for Music {
     fn get_volume(&self) -> f32 {
         check_openal_context!(0.);
 
-        let mut volume: f32 = 0.;
-        al::alGetSourcef(self.al_source, ffi::AL_GAIN, &mut volume);
-        volume
+        if let Some(volume) = attenuation::source_volume(self.al_source) {
+            return volume;
+        }
+        bus::source_gain(self.al_source)
     }
 
     /**
@@ -683,7 +2162,7 @@ impl AudioController for Music {
     fn set_pitch(&mut self, pitch: f32) -> () {
         check_openal_context!(());
 
-        al::alSourcef(self.al_source, ffi::AL_PITCH, pitch)
+        bus::set_source_pitch(self.al_source, pitch)
     }
 
     /**
@@ -695,9 +2174,7 @@ impl AudioController for Music {
     fn get_pitch(&self) -> f32 {
         check_openal_context!(0.);
 
-        let mut pitch = 0.;
-        al::alGetSourcef(self.al_source, ffi::AL_PITCH, &mut pitch);
-        pitch
+        bus::source_pitch(self.al_source)
     }
 
     /**
@@ -724,6 +2201,10 @@ impl AudioController for Music {
                 ffi::ALC_FALSE as i32,
             ),
         };
+        if !self.doppler_enabled {
+            listener::update_source_velocity(self.al_source, listener::get_velocity());
+        }
+        attenuation::update_source(self.al_source);
     }
 
     /**
@@ -763,6 +2244,10 @@ impl AudioController for Music {
         check_openal_context!(());
 
         al::alSourcefv(self.al_source, ffi::AL_POSITION, &position[0]);
+        attenuation::update_source(self.al_source);
+        if let Some(velocity) = self.auto_velocity.update(position, Instant::now()) {
+            self.set_velocity(velocity);
+        }
     }
 
     /**
@@ -810,6 +2295,44 @@ impl AudioController for Music {
         direction
     }
 
+    /**
+     * Set the velocity of the Music.
+     *
+     * The velocity is only used for the Doppler effect, it doesn't move the
+     * Music. Default velocity is [0.0, 0.0, 0.0].
+     *
+     * # Argument
+     * * `velocity` - A three dimensional vector of f32 containing the velocity
+     *   of the music [x, y, z].
+     */
+    fn set_velocity(&mut self, velocity: [f32; 3]) -> () {
+        check_openal_context!(());
+
+        self.velocity = velocity;
+        if self.doppler_enabled {
+            al::alSourcefv(self.al_source, ffi::AL_VELOCITY, &velocity[0]);
+        }
+    }
+
+    /**
+     * Get the velocity of the Music.
+     *
+     * # Return
+     * A three dimensional vector of f32 containing the velocity
+     * of the music [x, y, z].
+     */
+    fn get_velocity(&self) -> [f32; 3] {
+        check_openal_context!([0.0; 3]);
+
+        if !self.doppler_enabled {
+            return self.velocity;
+        }
+
+        let mut velocity: [f32; 3] = [0.0; 3];
+        al::alGetSourcefv(self.al_source, ffi::AL_VELOCITY, &mut velocity[0]);
+        velocity
+    }
+
     /**
      * Set the maximum distance of the Music.
      *
@@ -892,7 +2415,9 @@ impl AudioController for Music {
     fn set_attenuation(&mut self, attenuation: f32) -> () {
         check_openal_context!(());
 
-        al::alSourcef(self.al_source, ffi::AL_ROLLOFF_FACTOR, attenuation);
+        if !attenuation::set_source_rolloff(self.al_source, attenuation) {
+            al::alSourcef(self.al_source, ffi::AL_ROLLOFF_FACTOR, attenuation);
+        }
     }
 
     /**
@@ -904,11 +2429,112 @@ impl AudioController for Music {
     fn get_attenuation(&self) -> f32 {
         check_openal_context!(1.);
 
+        if let Some(attenuation) = attenuation::source_rolloff(self.al_source) {
+            return attenuation;
+        }
         let mut attenuation = 0.;
         al::alGetSourcef(self.al_source, ffi::AL_ROLLOFF_FACTOR, &mut attenuation);
         attenuation
     }
 
+    /**
+     * Set the sound cone of the Music.
+     *
+     * Inside the inner angle around the direction of the Music its volume
+     * is unchanged, outside of the outer angle it is multiplied by
+     * `outer_volume`, and interpolated in between.
+     *
+     * The default cone is [360.0, 360.0, 0.0], so the cone has no effect.
+     *
+     * # Arguments
+     * * `inner_angle` - The inner angle in degrees in the range [0.0, 360.0]
+     * * `outer_angle` - The outer angle in degrees in the range [0.0, 360.0]
+     * * `outer_volume` - The volume outside of the outer angle in the range
+     *   [0.0, 1.0]
+     */
+    fn set_cone(&mut self, inner_angle: f32, outer_angle: f32, outer_volume: f32) -> () {
+        check_openal_context!(());
+
+        al::alSourcef(self.al_source, ffi::AL_CONE_INNER_ANGLE, inner_angle);
+        al::alSourcef(self.al_source, ffi::AL_CONE_OUTER_ANGLE, outer_angle);
+        al::alSourcef(self.al_source, ffi::AL_CONE_OUTER_GAIN, outer_volume);
+    }
+
+    fn get_cone(&self) -> (f32, f32, f32) {
+        check_openal_context!((360., 360., 0.));
+
+        let mut inner_angle = 0.;
+        let mut outer_angle = 0.;
+        let mut outer_volume = 0.;
+        al::alGetSourcef(self.al_source, ffi::AL_CONE_INNER_ANGLE, &mut inner_angle);
+        al::alGetSourcef(self.al_source, ffi::AL_CONE_OUTER_ANGLE, &mut outer_angle);
+        al::alGetSourcef(self.al_source, ffi::AL_CONE_OUTER_GAIN, &mut outer_volume);
+        (inner_angle, outer_angle, outer_volume)
+    }
+
+    fn set_auxiliary_send_auto(&mut self, enabled: bool) -> () {
+        check_openal_context!(());
+
+        // The auxiliary sends only exist with EFX
+        if !OpenAlData::efx_capable() {
+            return;
+        }
+        self.auxiliary_send_auto = enabled;
+        al::alSourcei(
+            self.al_source,
+            ffi::AL_AUXILIARY_SEND_FILTER_GAIN_AUTO,
+            enabled as i32,
+        );
+        al::alSourcei(
+            self.al_source,
+            ffi::AL_AUXILIARY_SEND_FILTER_GAINHF_AUTO,
+            enabled as i32,
+        );
+    }
+
+    fn get_auxiliary_send_auto(&self) -> bool {
+        self.auxiliary_send_auto
+    }
+
+    fn set_doppler_enabled(&mut self, enabled: bool) -> () {
+        check_openal_context!(());
+
+        self.doppler_enabled = enabled;
+        listener::set_doppler_exempt(self.al_source, !enabled);
+        if enabled {
+            al::alSourcefv(self.al_source, ffi::AL_VELOCITY, &self.velocity[0]);
+        }
+    }
+
+    fn is_doppler_enabled(&self) -> bool {
+        self.doppler_enabled
+    }
+
+    /**
+     * Compute the velocity of the Music from its positions, see
+     * `AudioController::set_auto_velocity`.
+     */
+    fn set_auto_velocity(&mut self, enabled: bool) {
+        self.auto_velocity.set_enabled(enabled);
+    }
+
+    /**
+     * Check if the velocity of the Music is computed from its positions.
+     */
+    fn is_auto_velocity(&self) -> bool {
+        self.auto_velocity.is_enabled()
+    }
+
+    fn set_attenuation_curve(&mut self, curve: Option<AttenuationCurve>) -> () {
+        check_openal_context!(());
+
+        attenuation::set_source_curve(self.al_source, curve);
+    }
+
+    fn get_attenuation_curve(&self) -> Option<AttenuationCurve> {
+        attenuation::source_curve(self.al_source)
+    }
+
     /**
      * Enable or disable direct channel mode for a Music.
      *
@@ -969,30 +2595,131 @@ impl AudioController for Music {
         }
     }
 
+    fn set_spatialized(&mut self, spatialize: Spatialize) -> () {
+        check_openal_context!(());
+
+        if OpenAlData::source_spatialize_capable() {
+            al::alSourcei(
+                self.al_source,
+                ffi::AL_SOURCE_SPATIALIZE_SOFT,
+                spatialize.value(),
+            );
+        }
+    }
+
+    fn get_spatialized(&self) -> Spatialize {
+        check_openal_context!(Spatialize::Auto);
+
+        match OpenAlData::source_spatialize_capable() {
+            true => {
+                let mut value = 0;
+                al::alGetSourcei(self.al_source, ffi::AL_SOURCE_SPATIALIZE_SOFT, &mut value);
+                Spatialize::from_value(value)
+            }
+            false => Spatialize::Auto,
+        }
+    }
+
+    fn set_stereo_angles(&mut self, left: f32, right: f32) -> () {
+        check_openal_context!(());
+
+        if OpenAlData::stereo_angles_capable() {
+            let angles = [left.to_radians(), right.to_radians()];
+            al::alSourcefv(self.al_source, ffi::AL_STEREO_ANGLES, &angles[0]);
+        }
+    }
+
+    fn get_stereo_angles(&self) -> (f32, f32) {
+        check_openal_context!((30., -30.));
+
+        match OpenAlData::stereo_angles_capable() {
+            true => {
+                let mut angles = [0.; 2];
+                al::alGetSourcefv(self.al_source, ffi::AL_STEREO_ANGLES, &mut angles[0]);
+                (angles[0].to_degrees(), angles[1].to_degrees())
+            }
+            false => (30., -30.),
+        }
+    }
+
+    fn set_resampler(&mut self, index: i32) -> () {
+        check_openal_context!(());
+
+        if OpenAlData::source_resampler_capable() {
+            al::alSourcei(self.al_source, ffi::AL_SOURCE_RESAMPLER_SOFT, index);
+        }
+    }
+
+    fn get_resampler(&self) -> i32 {
+        check_openal_context!(0);
+
+        match OpenAlData::source_resampler_capable() {
+            true => {
+                let mut index = 0;
+                al::alGetSourcei(self.al_source, ffi::AL_SOURCE_RESAMPLER_SOFT, &mut index);
+                index
+            }
+            false => 0,
+        }
+    }
+
     /**
      * Returns the duration of the Music.
      */
     fn get_duration(&self) -> Duration {
-        let frames = self.file_infos.frames as u64;
-        let sample_rate = self.file_infos.samplerate as u64;
+        let infos = self.track.lock().unwrap().infos.clone();
+        let frames = infos.frames as u64;
+        let sample_rate = infos.samplerate as u64;
 
         let seconds = frames / sample_rate;
         let nanoseconds = frames % sample_rate * 1_000_000_000 / sample_rate;
 
         Duration::new(seconds, nanoseconds as u32)
     }
+
+    fn get_stats(&self) -> PlaybackStats {
+        let mut stats = *self.stats.lock().unwrap();
+
+        // Frames of the queued buffers are only added once processed
+        if self.get_state() != Stopped {
+            let mut sample_offset: i32 = 0;
+            al::alGetSourcei(self.al_source, ffi::AL_SAMPLE_OFFSET, &mut sample_offset);
+            stats.frames_played += sample_offset as u64;
+        }
+        stats
+    }
 }
 
 impl Drop for Music {
     /// Destroy all the resources of the Music.
     fn drop(&mut self) -> () {
-        self.stop();
-        if let Some(handle) = self.thread_handle.take() {
-            handle.join();
+        let created = OpenAlData::is_created();
+        if created {
+            self.stop();
+        } else {
+            // The source can't stop the stream anymore
+            self.stop_scrub();
+            if let Some(stream) = self.stream.take() {
+                stream.cancel();
+            }
+        }
+        if !self.doppler_enabled {
+            listener::set_doppler_exempt(self.al_source, false);
+        }
+        attenuation::set_source_curve(self.al_source, None);
+        occlusion::remove_source(self.al_source);
+        bus::remove_source(self.al_source);
+        sources::unregister(self.al_source);
+        if let Some(stream) = self.stream.take() {
+            stream.join();
+        }
+        // The objects were deleted with the context
+        if !created {
+            return;
         }
         unsafe {
             al::alSourcei(self.al_source, ffi::AL_BUFFER, 0);
-            ffi::alDeleteBuffers(2, &mut self.al_buffers[0]);
+            ffi::alDeleteBuffers(self.al_buffers.len() as i32, &mut self.al_buffers[0]);
             ffi::alDeleteSources(1, &mut self.al_source);
         }
     }
@@ -1003,8 +2730,37 @@ mod test {
     #![allow(non_snake_case)]
 
     use audio_controller::AudioController;
-    use music::Music;
+    use music::{calculate_true_offset, Music};
+    use sndfile::SndInfo;
     use states::State::{Paused, Playing, Stopped};
+    use std::thread::sleep;
+    use std::time::Duration;
+    use stream_config::StreamConfig;
+
+    #[test]
+    fn music_calculate_true_offset_loop_points_OK() -> () {
+        let info = SndInfo {
+            frames: 1000,
+            samplerate: 44100,
+            channels: 2,
+            format: 0,
+            sections: 1,
+            seekable: 1,
+        };
+
+        // Playing the intro, before the loop
+        assert_eq!(
+            calculate_true_offset(&info, 450, 100, 2, 50, Some((400, 800)), false),
+            300
+        );
+        // The cursor wrapped from 800 back to 400 in the queued buffers
+        assert_eq!(
+            calculate_true_offset(&info, 450, 100, 2, 50, Some((400, 800)), true),
+            700
+        );
+        // Looping over the whole file
+        assert_eq!(calculate_true_offset(&info, 50, 100, 2, 0, None, true), 850);
+    }
 
     #[test]
     #[ignore]
@@ -1021,6 +2777,22 @@ mod test {
         assert!(msc.is_err());
     }
 
+    #[test]
+    #[ignore]
+    fn music_with_stream_config_OK() -> () {
+        let config = StreamConfig {
+            buffers: 4,
+            buffer_frames: 4096,
+        };
+        let mut msc = Music::with_stream_config("res/music.ogg", config).unwrap();
+        assert_eq!(msc.stream_config(), config);
+
+        msc.play();
+        sleep(Duration::from_millis(500));
+        assert_eq!(msc.get_state(), Playing);
+        msc.stop();
+    }
+
     #[test]
     #[ignore]
     fn music_play_OK() -> () {
@@ -1053,6 +2825,24 @@ mod test {
         msc.stop();
     }
 
+    #[test]
+    #[ignore]
+    fn music_stop_keep_position_OK() -> () {
+        let mut msc = Music::new("res/music.ogg").expect("Cannot create Music");
+
+        msc.play();
+        sleep(Duration::from_millis(200));
+        msc.stop_keep_position();
+        assert_eq!(msc.get_state() as i32, Stopped as i32);
+        assert!(msc.get_offset() > 0);
+
+        msc.stop();
+        assert_eq!(msc.get_offset(), 0);
+        msc.play_from_last_position();
+        assert_eq!(msc.get_state() as i32, Playing as i32);
+        msc.stop();
+    }
+
     #[test]
     #[ignore]
     fn music_is_playing_TRUE() -> () {
@@ -1193,4 +2983,32 @@ mod test {
         println!("{}", &msc.get_attenuation());
         assert_eq!(&msc.get_attenuation(), &0.5f32);
     }
+
+    #[test]
+    #[ignore]
+    fn music_set_loop_points_OK() -> () {
+        let mut msc = Music::new("res/music.ogg").expect("Cannot create Music");
+
+        assert!(msc.set_loop_points(100, 200));
+        assert_eq!(msc.get_loop_points(), Some((100, 200)));
+        assert!(!msc.set_loop_points(200, 100));
+        assert!(!msc.set_loop_points(0, i64::MAX));
+        assert_eq!(msc.get_loop_points(), Some((100, 200)));
+        msc.clear_loop_points();
+        assert_eq!(msc.get_loop_points(), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn music_queue_next_OK() -> () {
+        let mut msc = Music::new("res/shot.wav").expect("Cannot create Music");
+
+        msc.queue_next("res/shot.wav")
+            .expect("Cannot queue the next file");
+        assert!(msc.has_queued());
+        msc.play();
+        while msc.is_playing() {}
+        assert!(!msc.has_queued());
+        assert_eq!(msc.get_state(), Stopped);
+    }
 }
@@ -0,0 +1,357 @@
+//! Occlusion, obstruction and wet/dry gains of sources, built on the EFX
+//! filters.
+
+use filter::{Filter, FilterType};
+use internal::OpenAlData;
+use openal::{al, ffi};
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The occlusion and auxiliary sends of the sources
+    static ref SOURCES: Mutex<HashMap<u32, Occlusion>> = Mutex::new(HashMap::new());
+}
+
+/// The settings of a filter set with `set_direct_filter`.
+#[derive(Clone, Copy)]
+struct UserFilter {
+    filter_type: FilterType,
+    gain: f32,
+    gain_lf: f32,
+    gain_hf: f32,
+}
+
+/// How much a source is muffled, and the filters doing it.
+struct Occlusion {
+    occlusion: f32,
+    obstruction: f32,
+    /// The gain of the direct path
+    dry_gain: f32,
+    /// The gain of the auxiliary sends
+    send_gain: f32,
+    /// The filter set by the user on the direct path, combined with the others
+    user: Option<UserFilter>,
+    /// The filter of the direct path, muffled by both
+    direct: Option<Filter>,
+    /// The filter of the auxiliary sends, only muffled by the occlusion
+    send: Option<Filter>,
+    /// The effect slot connected to each auxiliary send by the source
+    own_slots: HashMap<i32, i32>,
+    /// The effect slot connected to each auxiliary send by the bus of the source
+    bus_slots: HashMap<i32, i32>,
+}
+
+impl Default for Occlusion {
+    fn default() -> Occlusion {
+        Occlusion {
+            occlusion: 0.0,
+            obstruction: 0.0,
+            dry_gain: 1.0,
+            send_gain: 1.0,
+            user: None,
+            direct: None,
+            send: None,
+            own_slots: HashMap::new(),
+            bus_slots: HashMap::new(),
+        }
+    }
+}
+
+impl Occlusion {
+    /// The effect slot of an auxiliary send: the one of the source, else the one of its bus.
+    fn slot(&self, send: i32) -> i32 {
+        self.own_slots
+            .get(&send)
+            .or_else(|| self.bus_slots.get(&send))
+            .cloned()
+            .unwrap_or(ffi::AL_EFFECTSLOT_NULL)
+    }
+
+    /// The connected auxiliary sends.
+    fn sends(&self) -> Vec<i32> {
+        let mut sends: Vec<i32> = self
+            .own_slots
+            .keys()
+            .chain(self.bus_slots.keys())
+            .cloned()
+            .collect();
+        sends.sort();
+        sends.dedup();
+        sends
+    }
+}
+
+/**
+ * The gains of the low pass filter muffling a source.
+ *
+ * # Argument
+ * * `amount` - How much the source is muffled, between 0.0 and 1.0
+ *
+ * # Return
+ * The gain of all the frequencies and the additional gain of the high
+ * frequencies: the high frequencies go away faster than the volume.
+ */
+fn low_pass_gains(amount: f32) -> (f32, f32) {
+    let amount = amount.clamp(0.0, 1.0);
+    (1.0 - 0.5 * amount, (1.0 - amount) * (1.0 - amount))
+}
+
+/// Combine a gain with the low pass gains of several amounts.
+fn combined_gains(gain: f32, amounts: &[f32]) -> (f32, f32) {
+    amounts.iter().fold((gain, 1.0), |(gain, gain_hf), amount| {
+        let (g, g_hf) = low_pass_gains(*amount);
+        (gain * g, gain_hf * g_hf)
+    })
+}
+
+/// Create a low pass filter with a gain and the combined gains of several amounts.
+fn low_pass(gain: f32, amounts: &[f32]) -> Option<Filter> {
+    if gain >= 1.0 && amounts.iter().all(|amount| *amount <= 0.0) {
+        return None;
+    }
+    let (gain, gain_hf) = combined_gains(gain, amounts);
+    Filter::low_pass(gain, gain_hf).ok()
+}
+
+/**
+ * Create the filter of the direct path, the filter set by the user
+ * attenuated like `low_pass`.
+ *
+ * A high pass filter becomes a band pass filter to also attenuate the high
+ * frequencies.
+ */
+fn direct_filter(user: Option<UserFilter>, gain: f32, amounts: &[f32]) -> Option<Filter> {
+    let user = match user {
+        Some(user) => user,
+        None => return low_pass(gain, amounts),
+    };
+    let (gain, gain_hf) = combined_gains(gain, amounts);
+    let filter = match user.filter_type {
+        FilterType::LowPass => Filter::low_pass(user.gain * gain, user.gain_hf * gain_hf),
+        FilterType::HighPass if gain >= 1.0 && gain_hf >= 1.0 => {
+            Filter::high_pass(user.gain, user.gain_lf)
+        }
+        FilterType::HighPass => Filter::band_pass(user.gain * gain, user.gain_lf, gain_hf),
+        FilterType::BandPass => {
+            Filter::band_pass(user.gain * gain, user.gain_lf, user.gain_hf * gain_hf)
+        }
+    };
+    filter.ok()
+}
+
+fn filter_id(filter: &Option<Filter>) -> i32 {
+    match filter {
+        Some(filter) => filter.id() as i32,
+        None => ffi::AL_FILTER_NULL,
+    }
+}
+
+/// Apply the filters to the direct path and to all the connected sends.
+fn update(source: u32, occlusion: &mut Occlusion) {
+    if !OpenAlData::efx_capable() {
+        return;
+    }
+    occlusion.direct = direct_filter(
+        occlusion.user,
+        occlusion.dry_gain,
+        &[occlusion.occlusion, occlusion.obstruction],
+    );
+    occlusion.send = low_pass(occlusion.send_gain, &[occlusion.occlusion]);
+
+    al::alSourcei(source, ffi::AL_DIRECT_FILTER, filter_id(&occlusion.direct));
+    let send_filter = filter_id(&occlusion.send);
+    for send in occlusion.sends() {
+        al::alSource3i(
+            source,
+            ffi::AL_AUXILIARY_SEND_FILTER,
+            occlusion.slot(send),
+            send,
+            send_filter,
+        );
+    }
+}
+
+/**
+ * Set the filter of the direct path of a source, combined with its
+ * occlusion, obstruction and dry gain.
+ */
+pub(crate) fn set_source_filter(source: u32, filter: &Option<Filter>) {
+    let mut sources = SOURCES.lock().unwrap();
+    let entry = sources.entry(source).or_default();
+    entry.user = filter.as_ref().map(|filter| UserFilter {
+        filter_type: filter.filter_type(),
+        gain: filter.get_gain(),
+        gain_lf: filter.get_gain_lf(),
+        gain_hf: filter.get_gain_hf(),
+    });
+    update(source, entry);
+}
+
+/**
+ * Set how much a source is occluded, e.g. heard from another room.
+ *
+ * Both the direct path and the auxiliary sends are muffled.
+ */
+pub(crate) fn set_source_occlusion(source: u32, occlusion: f32) {
+    let mut sources = SOURCES.lock().unwrap();
+    let entry = sources.entry(source).or_default();
+    entry.occlusion = occlusion.clamp(0.0, 1.0);
+    update(source, entry);
+}
+
+/// Get how much a source is occluded.
+pub(crate) fn source_occlusion(source: u32) -> f32 {
+    SOURCES
+        .lock()
+        .unwrap()
+        .get(&source)
+        .map_or(0.0, |entry| entry.occlusion)
+}
+
+/**
+ * Set how much a source is obstructed, e.g. behind a pillar in the same
+ * room.
+ *
+ * Only the direct path is muffled, the auxiliary sends still reach the
+ * effects of the room.
+ */
+pub(crate) fn set_source_obstruction(source: u32, obstruction: f32) {
+    let mut sources = SOURCES.lock().unwrap();
+    let entry = sources.entry(source).or_default();
+    entry.obstruction = obstruction.clamp(0.0, 1.0);
+    update(source, entry);
+}
+
+/// Get how much a source is obstructed.
+pub(crate) fn source_obstruction(source: u32) -> f32 {
+    SOURCES
+        .lock()
+        .unwrap()
+        .get(&source)
+        .map_or(0.0, |entry| entry.obstruction)
+}
+
+/// Set the gain of the direct path of a source, what is heard without effects.
+pub(crate) fn set_source_dry_gain(source: u32, gain: f32) {
+    let mut sources = SOURCES.lock().unwrap();
+    let entry = sources.entry(source).or_default();
+    entry.dry_gain = gain.clamp(0.0, 1.0);
+    update(source, entry);
+}
+
+/// Get the gain of the direct path of a source.
+pub(crate) fn source_dry_gain(source: u32) -> f32 {
+    SOURCES
+        .lock()
+        .unwrap()
+        .get(&source)
+        .map_or(1.0, |entry| entry.dry_gain)
+}
+
+/// Set the gain of all the auxiliary sends of a source, what reaches the effects.
+pub(crate) fn set_source_send_gain(source: u32, gain: f32) {
+    let mut sources = SOURCES.lock().unwrap();
+    let entry = sources.entry(source).or_default();
+    entry.send_gain = gain.clamp(0.0, 1.0);
+    update(source, entry);
+}
+
+/// Get the gain of the auxiliary sends of a source.
+pub(crate) fn source_send_gain(source: u32) -> f32 {
+    SOURCES
+        .lock()
+        .unwrap()
+        .get(&source)
+        .map_or(1.0, |entry| entry.send_gain)
+}
+
+/**
+ * Connect an effect slot to an auxiliary send of a source, filtered by the
+ * occlusion of the source.
+ *
+ * The slot takes precedence over the one connected to the same send by the
+ * bus of the source.
+ *
+ * # Arguments
+ * * `source` - The source to connect
+ * * `send` - The index of the auxiliary send
+ * * `slot` - The effect slot, or AL_EFFECTSLOT_NULL to disconnect the send
+ */
+pub(crate) fn connect_send(source: u32, send: i32, slot: i32) {
+    connect(source, send, slot, false);
+}
+
+/**
+ * Connect the effect slot of a bus to an auxiliary send of one of its
+ * sources, unless the source connected its own slot to the send.
+ *
+ * # Arguments
+ * * `source` - The source to connect
+ * * `send` - The index of the auxiliary send
+ * * `slot` - The effect slot, or AL_EFFECTSLOT_NULL to disconnect the send
+ */
+pub(crate) fn connect_bus_send(source: u32, send: i32, slot: i32) {
+    connect(source, send, slot, true);
+}
+
+fn connect(source: u32, send: i32, slot: i32, bus: bool) {
+    let mut sources = SOURCES.lock().unwrap();
+    let entry = sources.entry(source).or_default();
+    let slots = match bus {
+        true => &mut entry.bus_slots,
+        false => &mut entry.own_slots,
+    };
+    match slot {
+        ffi::AL_EFFECTSLOT_NULL => slots.remove(&send),
+        _ => slots.insert(send, slot),
+    };
+    al::alSource3i(
+        source,
+        ffi::AL_AUXILIARY_SEND_FILTER,
+        entry.slot(send),
+        send,
+        filter_id(&entry.send),
+    );
+}
+
+/// Forget a source being deleted.
+pub(crate) fn remove_source(source: u32) {
+    SOURCES.lock().unwrap().remove(&source);
+}
+
+/// Forget all the sources and their filters, when the context is cleaned up.
+pub(crate) fn clear() {
+    let sources = mem::take(&mut *SOURCES.lock().unwrap());
+    drop(sources);
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{combined_gains, low_pass, low_pass_gains};
+
+    #[test]
+    fn occlusion_low_pass_gains_OK() -> () {
+        assert_eq!(low_pass_gains(0.0), (1.0, 1.0));
+        assert_eq!(low_pass_gains(0.5), (0.75, 0.25));
+        assert_eq!(low_pass_gains(1.0), (0.5, 0.0));
+    }
+
+    #[test]
+    fn occlusion_low_pass_gains_out_of_range_FAIL() -> () {
+        assert_eq!(low_pass_gains(-1.0), (1.0, 1.0));
+        assert_eq!(low_pass_gains(2.0), (0.5, 0.0));
+    }
+
+    #[test]
+    fn occlusion_combined_gains_OK() -> () {
+        assert_eq!(combined_gains(0.5, &[0.5, 0.0]), (0.375, 0.25));
+    }
+
+    #[test]
+    fn occlusion_low_pass_unfiltered_OK() -> () {
+        assert!(low_pass(1.0, &[0.0, 0.0]).is_none());
+    }
+}
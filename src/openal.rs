@@ -38,6 +38,30 @@ pub mod ffi {
     pub type ALCboolean = c_char;
     pub type ALCdevicePtr = intptr_t;
     pub type ALCcontextPtr = intptr_t;
+    pub type GetStringiFn = unsafe extern "C" fn(param: i32, index: i32) -> *const c_char;
+    pub type GetDeviceStringiFn =
+        unsafe extern "C" fn(device: ALCdevicePtr, param: i32, index: i32) -> *const c_char;
+    pub type GetInteger64vFn =
+        unsafe extern "C" fn(device: ALCdevicePtr, pname: i32, size: i32, values: *mut i64);
+    pub type GetSourcedvFn = unsafe extern "C" fn(source: u32, param: i32, values: *mut f64);
+    pub type SourcePlayAtTimeFn = unsafe extern "C" fn(source: u32, start_time: i64);
+    pub type ReopenDeviceFn = unsafe extern "C" fn(
+        device: ALCdevicePtr,
+        devicename: *const c_char,
+        attribs: *const i32,
+    ) -> ALCboolean;
+    pub type EventProcFn = extern "C" fn(
+        event_type: i32,
+        object: u32,
+        param: u32,
+        length: i32,
+        message: *const c_char,
+        user_param: *mut c_void,
+    );
+    pub type EventControlFn =
+        unsafe extern "C" fn(count: i32, types: *const i32, enable: ALboolean);
+    pub type EventCallbackFn =
+        unsafe extern "C" fn(callback: Option<EventProcFn>, user_param: *mut c_void);
     pub const AL_TRUE: ALboolean = 1;
     pub const AL_FALSE: ALboolean = 0;
     pub const ALC_TRUE: ALCboolean = 1;
@@ -59,7 +83,11 @@ pub mod ffi {
     pub const AL_ROLLOFF_FACTOR: i32 = 0x1021;
     pub const AL_SEC_OFFSET: i32 = 0x1024;
     pub const AL_SAMPLE_OFFSET: i32 = 0x1025;
+    pub const AL_SEC_OFFSET_LATENCY_SOFT: i32 = 0x1201;
     pub const AL_BYTE_OFFSET: i32 = 0x1026;
+    pub const AL_CONE_INNER_ANGLE: i32 = 0x1001;
+    pub const AL_CONE_OUTER_ANGLE: i32 = 0x1002;
+    pub const AL_CONE_OUTER_GAIN: i32 = 0x1022;
 
     /// Sound format
     pub const AL_FORMAT_MONO16: i32 = 0x1101;
@@ -68,25 +96,67 @@ pub mod ffi {
     pub const AL_FORMAT_61CHN16: i32 = 0x120E;
     pub const AL_FORMAT_71CHN16: i32 = 0x1211;
     pub const AL_FORMAT_QUAD16: i32 = 0x1205;
+    pub const AL_FORMAT_MONO_FLOAT32: i32 = 0x10010;
+    pub const AL_FORMAT_STEREO_FLOAT32: i32 = 0x10011;
+    pub const AL_FORMAT_QUAD32: i32 = 0x1206;
+    pub const AL_FORMAT_51CHN32: i32 = 0x120C;
+    pub const AL_FORMAT_61CHN32: i32 = 0x120F;
+    pub const AL_FORMAT_71CHN32: i32 = 0x1212;
+    pub const AL_FORMAT_MONO_IMA4: i32 = 0x1300;
+    pub const AL_FORMAT_STEREO_IMA4: i32 = 0x1301;
+    pub const AL_FORMAT_MONO_MSADPCM_SOFT: i32 = 0x1302;
+    pub const AL_FORMAT_STEREO_MSADPCM_SOFT: i32 = 0x1303;
+    pub const AL_FORMAT_MONO_MULAW_EXT: i32 = 0x10014;
+    pub const AL_FORMAT_STEREO_MULAW_EXT: i32 = 0x10015;
+
+    /// Buffer params
+    pub const AL_UNPACK_BLOCK_ALIGNMENT_SOFT: i32 = 0x200C;
 
     /// Source params
     pub const AL_BUFFER: i32 = 0x1009;
     pub const AL_BUFFERS_PROCESSED: i32 = 0x1016;
     pub const AL_BUFFERS_QUEUED: i32 = 0x1015;
+    pub const AL_STEREO_ANGLES: i32 = 0x1030;
     pub const AL_DIRECT_CHANNELS_SOFT: i32 = 0x1033;
+    pub const AL_NUM_RESAMPLERS_SOFT: i32 = 0x1210;
+    pub const AL_DEFAULT_RESAMPLER_SOFT: i32 = 0x1211;
+    pub const AL_SOURCE_RESAMPLER_SOFT: i32 = 0x1212;
+    pub const AL_RESAMPLER_NAME_SOFT: i32 = 0x1213;
+    pub const AL_SOURCE_SPATIALIZE_SOFT: i32 = 0x1214;
+    pub const AL_AUTO_SOFT: i32 = 0x0002;
+
+    /// Events
+    pub const AL_EVENT_TYPE_BUFFER_COMPLETED_SOFT: i32 = 0x19A4;
+    pub const AL_EVENT_TYPE_SOURCE_STATE_CHANGED_SOFT: i32 = 0x19A5;
 
     /// Source object extensions
     pub const AL_DIRECT_FILTER: i32 = 0x20005;
     pub const AL_AUXILIARY_SEND_FILTER: i32 = 0x20006;
     pub const AL_AIR_ABSORPTION_FACTOR: i32 = 0x20007;
+    pub const AL_ROOM_ROLLOFF_FACTOR: i32 = 0x20008;
+    pub const AL_AUXILIARY_SEND_FILTER_GAIN_AUTO: i32 = 0x2000B;
+    pub const AL_AUXILIARY_SEND_FILTER_GAINHF_AUTO: i32 = 0x2000C;
 
     /// Effects
     pub const AL_EFFECT_NULL: i32 = 0x0000;
     pub const AL_EFFECT_TYPE: i32 = 0x8001;
     pub const AL_EFFECT_REVERB: i32 = 0x0001;
+    pub const AL_EFFECT_CHORUS: i32 = 0x0002;
+    pub const AL_EFFECT_DISTORTION: i32 = 0x0003;
+    pub const AL_EFFECT_ECHO: i32 = 0x0004;
+    pub const AL_EFFECT_FLANGER: i32 = 0x0005;
+    pub const AL_EFFECT_FREQUENCY_SHIFTER: i32 = 0x0006;
+    pub const AL_EFFECT_VOCAL_MORPHER: i32 = 0x0007;
+    pub const AL_EFFECT_PITCH_SHIFTER: i32 = 0x0008;
+    pub const AL_EFFECT_RING_MODULATOR: i32 = 0x0009;
+    pub const AL_EFFECT_AUTOWAH: i32 = 0x000A;
+    pub const AL_EFFECT_COMPRESSOR: i32 = 0x000B;
+    pub const AL_EFFECT_EQUALIZER: i32 = 0x000C;
+    pub const AL_EFFECT_EAXREVERB: i32 = 0x8000;
     pub const AL_EFFECTSLOT_NULL: i32 = 0x0000;
     pub const AL_EFFECTSLOT_EFFECT: i32 = 0x0001;
     pub const AL_EFFECTSLOT_AUXILIARY_SEND_AUTO: i32 = 0x0003;
+    pub const AL_EFFECTSLOT_TARGET_SOFT: i32 = 0x199C;
     pub const AL_REVERB_DENSITY: i32 = 0x0001;
     pub const AL_REVERB_DIFFUSION: i32 = 0x0002;
     pub const AL_REVERB_GAIN: i32 = 0x0003;
@@ -100,9 +170,83 @@ pub mod ffi {
     pub const AL_REVERB_AIR_ABSORPTION_GAINHF: i32 = 0x000B;
     pub const AL_REVERB_ROOM_ROLLOFF_FACTOR: i32 = 0x000C;
     pub const AL_REVERB_DECAY_HFLIMIT: i32 = 0x000D;
+    pub const AL_EAXREVERB_DENSITY: i32 = 0x0001;
+    pub const AL_EAXREVERB_DIFFUSION: i32 = 0x0002;
+    pub const AL_EAXREVERB_GAIN: i32 = 0x0003;
+    pub const AL_EAXREVERB_GAINHF: i32 = 0x0004;
+    pub const AL_EAXREVERB_GAINLF: i32 = 0x0005;
+    pub const AL_EAXREVERB_DECAY_TIME: i32 = 0x0006;
+    pub const AL_EAXREVERB_DECAY_HFRATIO: i32 = 0x0007;
+    pub const AL_EAXREVERB_DECAY_LFRATIO: i32 = 0x0008;
+    pub const AL_EAXREVERB_REFLECTIONS_GAIN: i32 = 0x0009;
+    pub const AL_EAXREVERB_REFLECTIONS_DELAY: i32 = 0x000A;
+    pub const AL_EAXREVERB_REFLECTIONS_PAN: i32 = 0x000B;
+    pub const AL_EAXREVERB_LATE_REVERB_GAIN: i32 = 0x000C;
+    pub const AL_EAXREVERB_LATE_REVERB_DELAY: i32 = 0x000D;
+    pub const AL_EAXREVERB_LATE_REVERB_PAN: i32 = 0x000E;
+    pub const AL_EAXREVERB_ECHO_TIME: i32 = 0x000F;
+    pub const AL_EAXREVERB_ECHO_DEPTH: i32 = 0x0010;
+    pub const AL_EAXREVERB_MODULATION_TIME: i32 = 0x0011;
+    pub const AL_EAXREVERB_MODULATION_DEPTH: i32 = 0x0012;
+    pub const AL_EAXREVERB_AIR_ABSORPTION_GAINHF: i32 = 0x0013;
+    pub const AL_EAXREVERB_HFREFERENCE: i32 = 0x0014;
+    pub const AL_EAXREVERB_LFREFERENCE: i32 = 0x0015;
+    pub const AL_EAXREVERB_ROOM_ROLLOFF_FACTOR: i32 = 0x0016;
+    pub const AL_EAXREVERB_DECAY_HFLIMIT: i32 = 0x0017;
+    pub const AL_ECHO_DELAY: i32 = 0x0001;
+    pub const AL_ECHO_LRDELAY: i32 = 0x0002;
+    pub const AL_ECHO_DAMPING: i32 = 0x0003;
+    pub const AL_ECHO_FEEDBACK: i32 = 0x0004;
+    pub const AL_ECHO_SPREAD: i32 = 0x0005;
+    pub const AL_CHORUS_WAVEFORM: i32 = 0x0001;
+    pub const AL_CHORUS_PHASE: i32 = 0x0002;
+    pub const AL_CHORUS_RATE: i32 = 0x0003;
+    pub const AL_CHORUS_DEPTH: i32 = 0x0004;
+    pub const AL_CHORUS_FEEDBACK: i32 = 0x0005;
+    pub const AL_CHORUS_DELAY: i32 = 0x0006;
+    pub const AL_CHORUS_WAVEFORM_SINUSOID: i32 = 0;
+    pub const AL_CHORUS_WAVEFORM_TRIANGLE: i32 = 1;
+    pub const AL_FLANGER_WAVEFORM: i32 = 0x0001;
+    pub const AL_FLANGER_PHASE: i32 = 0x0002;
+    pub const AL_FLANGER_RATE: i32 = 0x0003;
+    pub const AL_FLANGER_DEPTH: i32 = 0x0004;
+    pub const AL_FLANGER_FEEDBACK: i32 = 0x0005;
+    pub const AL_FLANGER_DELAY: i32 = 0x0006;
+    pub const AL_DISTORTION_EDGE: i32 = 0x0001;
+    pub const AL_DISTORTION_GAIN: i32 = 0x0002;
+    pub const AL_DISTORTION_LOWPASS_CUTOFF: i32 = 0x0003;
+    pub const AL_DISTORTION_EQCENTER: i32 = 0x0004;
+    pub const AL_DISTORTION_EQBANDWIDTH: i32 = 0x0005;
+    pub const AL_RING_MODULATOR_FREQUENCY: i32 = 0x0001;
+    pub const AL_RING_MODULATOR_HIGHPASS_CUTOFF: i32 = 0x0002;
+    pub const AL_RING_MODULATOR_WAVEFORM: i32 = 0x0003;
+    pub const AL_RING_MODULATOR_SINUSOID: i32 = 0;
+    pub const AL_RING_MODULATOR_SAWTOOTH: i32 = 1;
+    pub const AL_RING_MODULATOR_SQUARE: i32 = 2;
+    pub const AL_EQUALIZER_LOW_GAIN: i32 = 0x0001;
+    pub const AL_EQUALIZER_LOW_CUTOFF: i32 = 0x0002;
+    pub const AL_EQUALIZER_MID1_GAIN: i32 = 0x0003;
+    pub const AL_EQUALIZER_MID1_CENTER: i32 = 0x0004;
+    pub const AL_EQUALIZER_MID1_WIDTH: i32 = 0x0005;
+    pub const AL_EQUALIZER_MID2_GAIN: i32 = 0x0006;
+    pub const AL_EQUALIZER_MID2_CENTER: i32 = 0x0007;
+    pub const AL_EQUALIZER_MID2_WIDTH: i32 = 0x0008;
+    pub const AL_EQUALIZER_HIGH_GAIN: i32 = 0x0009;
+    pub const AL_EQUALIZER_HIGH_CUTOFF: i32 = 0x000A;
 
     // Filters
     pub const AL_FILTER_NULL: i32 = 0x0000;
+    pub const AL_FILTER_TYPE: i32 = 0x8001;
+    pub const AL_FILTER_LOWPASS: i32 = 0x0001;
+    pub const AL_FILTER_HIGHPASS: i32 = 0x0002;
+    pub const AL_FILTER_BANDPASS: i32 = 0x0003;
+    pub const AL_LOWPASS_GAIN: i32 = 0x0001;
+    pub const AL_LOWPASS_GAINHF: i32 = 0x0002;
+    pub const AL_HIGHPASS_GAIN: i32 = 0x0001;
+    pub const AL_HIGHPASS_GAINLF: i32 = 0x0002;
+    pub const AL_BANDPASS_GAIN: i32 = 0x0001;
+    pub const AL_BANDPASS_GAINLF: i32 = 0x0002;
+    pub const AL_BANDPASS_GAINHF: i32 = 0x0003;
 
     /// Error identifiers
     pub const AL_NO_ERROR: i32 = 0;
@@ -119,8 +263,52 @@ pub mod ffi {
     pub const AL_PAUSED: i32 = 0x1013;
     pub const AL_STOPPED: i32 = 0x1014;
 
+    /// Doppler
+    pub const AL_DOPPLER_FACTOR: i32 = 0xC000;
+    pub const AL_SPEED_OF_SOUND: i32 = 0xC003;
+
+    /// Strings
+    pub const AL_VENDOR: i32 = 0xB001;
+    pub const AL_VERSION: i32 = 0xB002;
+    pub const AL_RENDERER: i32 = 0xB003;
+    pub const AL_EXTENSIONS: i32 = 0xB004;
+
     /// ALC
+    pub const ALC_CAPTURE_DEVICE_SPECIFIER: i32 = 0x310;
+    pub const ALC_CAPTURE_DEFAULT_DEVICE_SPECIFIER: i32 = 0x311;
     pub const ALC_CAPTURE_SAMPLES: i32 = 0x312;
+    pub const ALC_CONNECTED: i32 = 0x313;
+    pub const ALC_DEFAULT_DEVICE_SPECIFIER: i32 = 0x1004;
+    pub const ALC_DEVICE_SPECIFIER: i32 = 0x1005;
+    pub const ALC_EXTENSIONS: i32 = 0x1006;
+    pub const ALC_FREQUENCY: i32 = 0x1007;
+    pub const ALC_REFRESH: i32 = 0x1008;
+    pub const ALC_MONO_SOURCES: i32 = 0x1010;
+    pub const ALC_STEREO_SOURCES: i32 = 0x1011;
+    pub const ALC_MAX_AUXILIARY_SENDS: i32 = 0x20003;
+
+    /// ALC_SOFT_HRTF
+    pub const ALC_DONT_CARE_SOFT: i32 = 0x0002;
+    pub const ALC_HRTF_SOFT: i32 = 0x1992;
+    pub const ALC_HRTF_STATUS_SOFT: i32 = 0x1993;
+    pub const ALC_NUM_HRTF_SPECIFIERS_SOFT: i32 = 0x1994;
+    pub const ALC_HRTF_SPECIFIER_SOFT: i32 = 0x1995;
+    pub const ALC_HRTF_ID_SOFT: i32 = 0x1996;
+    pub const ALC_HRTF_DISABLED_SOFT: i32 = 0x0000;
+    pub const ALC_HRTF_ENABLED_SOFT: i32 = 0x0001;
+    pub const ALC_HRTF_DENIED_SOFT: i32 = 0x0002;
+    pub const ALC_HRTF_REQUIRED_SOFT: i32 = 0x0003;
+    pub const ALC_HRTF_HEADPHONES_DETECTED_SOFT: i32 = 0x0004;
+    pub const ALC_HRTF_UNSUPPORTED_FORMAT_SOFT: i32 = 0x0005;
+
+    /// ALC_ENUMERATE_ALL_EXT
+    pub const ALC_DEFAULT_ALL_DEVICES_SPECIFIER: i32 = 0x1012;
+    pub const ALC_ALL_DEVICES_SPECIFIER: i32 = 0x1013;
+
+    /// ALC_SOFT_device_clock
+    pub const ALC_DEVICE_CLOCK_SOFT: i32 = 0x1600;
+    pub const ALC_DEVICE_LATENCY_SOFT: i32 = 0x1601;
+    pub const ALC_DEVICE_CLOCK_LATENCY_SOFT: i32 = 0x1602;
 
     extern "C" {
         /// Context functions
@@ -128,10 +316,12 @@ pub mod ffi {
         pub fn alcMakeContextCurrent(context: ALCcontextPtr) -> ALCboolean;
         pub fn alcDestroyContext(context: ALCcontextPtr);
         pub fn alcGetCurrentContext() -> ALCcontextPtr;
+        pub fn alcGetContextsDevice(context: ALCcontextPtr) -> ALCdevicePtr;
 
         /// Device functions
         pub fn alcOpenDevice(devicename: *mut c_char) -> ALCdevicePtr;
         pub fn alcCloseDevice(device: ALCdevicePtr) -> ALCboolean;
+        pub fn alcGetProcAddress(device: ALCdevicePtr, funcname: *const c_char) -> *mut c_void;
 
         /// Listener functions
         pub fn alListenerf(param: i32, value: f32) -> ();
@@ -153,8 +343,12 @@ pub mod ffi {
         pub fn alSource3i(source: u32, param: i32, value1: i32, value2: i32, value3: i32);
         pub fn alSourcef(source: u32, param: i32, value: f32) -> ();
         pub fn alSourcePlay(source: u32) -> ();
+        pub fn alSourcePlayv(n: i32, sources: *const u32) -> ();
         pub fn alSourcePause(source: u32) -> ();
+        pub fn alSourcePausev(n: i32, sources: *const u32) -> ();
         pub fn alSourceStop(source: u32) -> ();
+        pub fn alSourceStopv(n: i32, sources: *const u32) -> ();
+        pub fn alSourceRewind(source: u32) -> ();
         pub fn alGetSourcei(source: u32, param: i32, value: *mut i32) -> ();
         pub fn alGetSourcef(source: u32, param: i32, value: *mut f32) -> ();
         pub fn alSourcefv(source: u32, param: i32, value: *const f32) -> ();
@@ -184,6 +378,28 @@ pub mod ffi {
         pub fn alAuxiliaryEffectSloti(source: u32, param: i32, value: u32) -> ();
         pub fn alEffecti(source: u32, param: i32, value: i32);
         pub fn alEffectf(source: u32, param: i32, value: f32);
+        pub fn alEffectfv(source: u32, param: i32, values: *const f32);
+
+        /// Filters functions
+        pub fn alGenFilters(n: i32, filters: *mut u32) -> ();
+        pub fn alDeleteFilters(n: i32, filters: *mut u32) -> ();
+        pub fn alFilteri(filter: u32, param: i32, value: i32);
+        pub fn alFilterf(filter: u32, param: i32, value: f32);
+
+        /// Deferred updates functions
+        pub fn alDeferUpdatesSOFT() -> ();
+        pub fn alProcessUpdatesSOFT() -> ();
+
+        /// State functions
+        pub fn alGetInteger(param: i32) -> i32;
+        pub fn alGetFloat(param: i32) -> f32;
+        pub fn alDopplerFactor(value: f32) -> ();
+        pub fn alSpeedOfSound(value: f32) -> ();
+        pub fn alGetProcAddress(fname: *const c_char) -> *mut c_void;
+
+        /// Strings
+        pub fn alGetString(param: i32) -> *const c_char;
+        pub fn alcGetString(device: ALCdevicePtr, param: i32) -> *const c_char;
 
         /// extension check
         pub fn alIsExtensionPresent(extension: *const c_char) -> ALboolean;
@@ -199,6 +415,7 @@ pub mod ffi {
             size: i32,
             freq: i32,
         ) -> ();
+        pub fn alBufferi(buffer: u32, param: i32, value: i32) -> ();
 
         /// Error
         pub fn alGetError() -> i32;
@@ -223,6 +440,12 @@ pub mod al {
         }
     }
 
+    pub fn alBufferi(buffer: u32, param: i32, value: i32) {
+        unsafe {
+            ffi::alBufferi(buffer, param, value);
+        }
+    }
+
     pub fn alSourceQueueBuffers(source: u32, nb: i32, buffers: *const u32) -> () {
         unsafe {
             ffi::alSourceQueueBuffers(source, nb, buffers);
@@ -235,6 +458,12 @@ pub mod al {
         }
     }
 
+    pub fn alSourcePlayv(sources: &[u32]) {
+        unsafe {
+            ffi::alSourcePlayv(sources.len() as i32, sources.as_ptr());
+        }
+    }
+
     pub fn alGetSourcei(source: u32, param: i32, value: *mut i32) -> () {
         unsafe {
             ffi::alGetSourcei(source, param, value);
@@ -279,12 +508,30 @@ pub mod al {
         }
     }
 
+    pub fn alSourcePausev(sources: &[u32]) {
+        unsafe {
+            ffi::alSourcePausev(sources.len() as i32, sources.as_ptr());
+        }
+    }
+
     pub fn alSourceStop(source: u32) -> () {
         unsafe {
             ffi::alSourceStop(source);
         }
     }
 
+    pub fn alSourceStopv(sources: &[u32]) {
+        unsafe {
+            ffi::alSourceStopv(sources.len() as i32, sources.as_ptr());
+        }
+    }
+
+    pub fn alSourceRewind(source: u32) {
+        unsafe {
+            ffi::alSourceRewind(source);
+        }
+    }
+
     pub fn alSourceUnqueueBuffers(source: u32, nb: i32, buffers: *mut u32) -> () {
         unsafe {
             ffi::alSourceUnqueueBuffers(source, nb, buffers);
@@ -382,14 +629,96 @@ pub mod al {
         }
     }
 
+    pub fn alEffectfv(source: u32, param: i32, values: *const f32) {
+        unsafe {
+            ffi::alEffectfv(source, param, values);
+        }
+    }
+
+    /// Filters functions
+    pub fn alGenFilters(n: i32, filters: *mut u32) {
+        unsafe {
+            ffi::alGenFilters(n, filters);
+        }
+    }
+
+    pub fn alFilteri(filter: u32, param: i32, value: i32) {
+        unsafe {
+            ffi::alFilteri(filter, param, value);
+        }
+    }
+
+    pub fn alFilterf(filter: u32, param: i32, value: f32) {
+        unsafe {
+            ffi::alFilterf(filter, param, value);
+        }
+    }
+
+    pub fn alGetFloat(param: i32) -> f32 {
+        unsafe { ffi::alGetFloat(param) }
+    }
+
+    pub fn alDopplerFactor(value: f32) {
+        unsafe {
+            ffi::alDopplerFactor(value);
+        }
+    }
+
+    pub fn alSpeedOfSound(value: f32) {
+        unsafe {
+            ffi::alSpeedOfSound(value);
+        }
+    }
+
+    pub fn alDeferUpdatesSOFT() {
+        unsafe {
+            ffi::alDeferUpdatesSOFT();
+        }
+    }
+
+    pub fn alProcessUpdatesSOFT() {
+        unsafe {
+            ffi::alProcessUpdatesSOFT();
+        }
+    }
+
     /// Any error that can happen during an OpenAL call.
     pub struct AlError(i32);
 
+    /// The code of an AlError, to match on it.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum AlErrorCode {
+        /// An invalid name, like a deleted source, was passed to OpenAL.
+        InvalidName,
+        /// An invalid enum was passed to OpenAL.
+        InvalidEnum,
+        /// An invalid value was passed to OpenAL.
+        InvalidValue,
+        /// The call isn't allowed in the current state.
+        InvalidOperation,
+        /// OpenAL ran out of memory.
+        OutOfMemory,
+        /// Another error code.
+        Other(i32),
+    }
+
     impl AlError {
         /// Create a new AlError from one of the ffi::AL_* enum values.
         pub fn new(err: i32) -> AlError {
             AlError(err)
         }
+
+        /// Get the code of the error.
+        pub fn code(&self) -> AlErrorCode {
+            match self.0 {
+                ffi::AL_INVALID_NAME => AlErrorCode::InvalidName,
+                ffi::AL_INVALID_ENUM => AlErrorCode::InvalidEnum,
+                ffi::AL_INVALID_VALUE => AlErrorCode::InvalidValue,
+                ffi::AL_INVALID_OPERATION => AlErrorCode::InvalidOperation,
+                ffi::AL_OUT_OF_MEMORY => AlErrorCode::OutOfMemory,
+                code => AlErrorCode::Other(code),
+            }
+        }
     }
 
     impl fmt::Display for AlError {
@@ -429,9 +758,9 @@ pub mod al {
             1 => Some(ffi::AL_FORMAT_MONO16),
             2 => Some(ffi::AL_FORMAT_STEREO16),
             4 => Some(ffi::AL_FORMAT_QUAD16),
-            5 => Some(ffi::AL_FORMAT_51CHN16),
-            6 => Some(ffi::AL_FORMAT_61CHN16),
-            7 => Some(ffi::AL_FORMAT_71CHN16),
+            6 => Some(ffi::AL_FORMAT_51CHN16),
+            7 => Some(ffi::AL_FORMAT_61CHN16),
+            8 => Some(ffi::AL_FORMAT_71CHN16),
             _ => return None,
         }
     }
@@ -0,0 +1,116 @@
+//! Futures resolving when Audio Sources stop playing, for async code.
+
+use openal::{al, ffi};
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// How often the awaited sources are checked
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+lazy_static! {
+    /// The wakers of the awaited sources, and whether the watcher thread runs
+    static ref AWAITED_SOURCES: Mutex<(HashMap<u32, Vec<Waker>>, bool)> =
+        Mutex::new((HashMap::new(), false));
+}
+
+/**
+ * A future resolving once a Sound or a Music stops playing.
+ *
+ * It resolves when the Audio Source plays to its end, and when it is
+ * stopped. A paused Audio Source is still awaited. The future borrows the
+ * Audio Source, so it can't be dropped while being awaited.
+ *
+ * # Example
+ * ```ignore
+ * use ears::Sound;
+ *
+ * async fn announce() -> Result<(), ears::SoundError> {
+ *     let mut sound = Sound::load_async("res/explosion.wav").await?;
+ *     sound.play_async().await;
+ *     println!("Done playing");
+ *     Ok(())
+ * }
+ * ```
+ */
+#[must_use = "futures do nothing unless awaited"]
+pub struct PlaybackFuture<'a> {
+    source: u32,
+    /// The Audio Source owning the source
+    _owner: PhantomData<&'a mut ()>,
+}
+
+impl<'a> PlaybackFuture<'a> {
+    pub(crate) fn new(source: u32) -> PlaybackFuture<'a> {
+        PlaybackFuture {
+            source,
+            _owner: PhantomData,
+        }
+    }
+}
+
+fn is_stopped(source: u32) -> bool {
+    let state = al::alGetState(source);
+    state != ffi::AL_PLAYING && state != ffi::AL_PAUSED
+}
+
+impl<'a> Future for PlaybackFuture<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context) -> Poll<()> {
+        let mut awaited = AWAITED_SOURCES.lock().unwrap();
+        // Checked with the lock, so the watcher can't miss the waker
+        if is_stopped(self.source) {
+            return Poll::Ready(());
+        }
+        let wakers = awaited.0.entry(self.source).or_default();
+        if !wakers.iter().any(|waker| waker.will_wake(context.waker())) {
+            wakers.push(context.waker().clone());
+        }
+        if !awaited.1 {
+            awaited.1 = true;
+            thread::Builder::new()
+                .name(String::from("ears-async"))
+                .spawn(watch_sources)
+                .unwrap();
+        }
+        Poll::Pending
+    }
+}
+
+fn watch_sources() {
+    loop {
+        sleep(POLL_INTERVAL);
+
+        let (stopped, done): (Vec<Waker>, bool) = {
+            let mut awaited = AWAITED_SOURCES.lock().unwrap();
+            let sources: Vec<u32> = awaited
+                .0
+                .keys()
+                .cloned()
+                .filter(|source| is_stopped(*source))
+                .collect();
+            let stopped = sources
+                .iter()
+                .filter_map(|source| awaited.0.remove(source))
+                .flatten()
+                .collect();
+            if awaited.0.is_empty() {
+                awaited.1 = false;
+            }
+            (stopped, !awaited.1)
+        };
+
+        // The wakers are called without the lock, as they may poll the futures
+        stopped.into_iter().for_each(Waker::wake);
+        if done {
+            return;
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Playback statistics of a Sound or a Music.
+
+use std::time::Instant;
+
+/**
+ * Totals accumulated by an Audio Source since its creation.
+ *
+ * Useful for analytics and debugging, to answer questions like "did that
+ * sound actually play?".
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PlaybackStats {
+    /// Number of frames that have been played by the source.
+    pub frames_played: u64,
+    /// Number of times the source was started (resuming from pause doesn't count).
+    pub times_played: u32,
+    /// Number of loops completed by the source, only tracked for Music.
+    pub loops_completed: u32,
+    /// When the source last ran out of data to play, only tracked for Music.
+    pub last_underrun: Option<Instant>,
+}
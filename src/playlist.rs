@@ -0,0 +1,534 @@
+//! Sequential playback of several music files.
+
+use audio_controller::AudioController;
+use error::SoundError;
+use music::Music;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/**
+ * Play music files back-to-back.
+ *
+ * A Playlist owns a list of tracks and plays them one after the other,
+ * in order or shuffled, optionally starting over once the last one ended.
+ *
+ * All the tracks are streamed on a single Music, so a Playlist only uses
+ * one OpenAL source and one streaming thread whatever its length. The
+ * tracks are opened when they start playing.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{Playlist, SoundError};
+ *
+ * fn main() -> Result<(), SoundError> {
+ *     let mut playlist = Playlist::new();
+ *     playlist.push("path/to/intro.ogg");
+ *     playlist.push("path/to/level.ogg");
+ *     playlist.push("path/to/outro.flac");
+ *     playlist.set_shuffle(true);
+ *     playlist.set_repeat(true);
+ *     playlist.play()?;
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct Playlist {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    /// The paths of the tracks, in the order they were added
+    tracks: Vec<PathBuf>,
+    /// The order the tracks are played in, as indices into `tracks`
+    order: Vec<usize>,
+    /// The position in `order` of the current track
+    position: Option<usize>,
+    shuffle: bool,
+    repeat: bool,
+    /// The Music playing the tracks, created with the first one
+    music: Option<Music>,
+    /// Increased each time a track starts, to ignore completions of previous tracks
+    generation: Arc<AtomicU64>,
+    /// Where the Music reports its completions
+    completion_sender: Sender<u64>,
+    /// The state of the random generator used to shuffle
+    seed: u64,
+}
+
+impl Playlist {
+    /**
+     * Create an empty Playlist.
+     */
+    pub fn new() -> Playlist {
+        let (completion_sender, completion_receiver) = channel();
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        let inner = Arc::new(Mutex::new(Inner {
+            tracks: Vec::new(),
+            order: Vec::new(),
+            position: None,
+            shuffle: false,
+            repeat: false,
+            music: None,
+            generation: Arc::new(AtomicU64::new(0)),
+            completion_sender,
+            seed: seed | 1,
+        }));
+        spawn_advance_thread(Arc::downgrade(&inner), completion_receiver);
+        Playlist { inner }
+    }
+
+    /**
+     * Add a track at the end of the Playlist.
+     *
+     * # Argument
+     * * `path` - The path of the music file
+     */
+    pub fn push<P: AsRef<Path>>(&mut self, path: P) {
+        let mut inner = self.inner.lock().unwrap();
+        let index = inner.tracks.len();
+        inner.tracks.push(path.as_ref().to_path_buf());
+        // A shuffled track goes anywhere after the current one
+        let first = inner.position.map_or(0, |p| p + 1);
+        let at = match inner.shuffle {
+            true => first + (inner.random() % (inner.order.len() - first + 1) as u64) as usize,
+            false => inner.order.len(),
+        };
+        inner.order.insert(at, index);
+    }
+
+    /**
+     * Get the number of tracks of the Playlist.
+     */
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().tracks.len()
+    }
+
+    /**
+     * Check if the Playlist has no track.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * Get the track being played.
+     *
+     * # Return
+     * The index of the current track, in the order the tracks were added,
+     * or None if the Playlist didn't start or played to its end
+     */
+    pub fn current_track(&self) -> Option<usize> {
+        let inner = self.inner.lock().unwrap();
+        inner.position.map(|p| inner.order[p])
+    }
+
+    /**
+     * Shuffle the tracks or play them in order.
+     *
+     * The current track keeps playing, the order change applies to the
+     * next ones.
+     *
+     * # Argument
+     * * `shuffle` - true to play the tracks in a random order
+     */
+    pub fn set_shuffle(&mut self, shuffle: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.shuffle = shuffle;
+        let current = inner.position.map(|p| inner.order[p]);
+        inner.reorder(current);
+    }
+
+    /**
+     * Check if the tracks are shuffled.
+     */
+    pub fn is_shuffled(&self) -> bool {
+        self.inner.lock().unwrap().shuffle
+    }
+
+    /**
+     * Start over once the last track ended.
+     *
+     * A shuffled Playlist is shuffled again each time it starts over.
+     *
+     * # Argument
+     * * `repeat` - true to play the tracks again and again
+     */
+    pub fn set_repeat(&mut self, repeat: bool) {
+        self.inner.lock().unwrap().repeat = repeat;
+    }
+
+    /**
+     * Check if the Playlist starts over once the last track ended.
+     */
+    pub fn is_repeating(&self) -> bool {
+        self.inner.lock().unwrap().repeat
+    }
+
+    /**
+     * Play or resume the Playlist.
+     *
+     * A Playlist which didn't start, or played to its end, starts from its
+     * first track. A stopped Playlist plays its current track from the start.
+     *
+     * # Return
+     * A `Result` containing Ok(()) on success, Err(SoundError) if the track
+     * couldn't be opened.
+     */
+    pub fn play(&mut self) -> Result<(), SoundError> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.order.is_empty() {
+            return Ok(());
+        }
+        match (inner.position, inner.music.as_mut()) {
+            (Some(_), Some(music)) => {
+                music.play();
+                Ok(())
+            }
+            _ => inner.play_position(0),
+        }
+    }
+
+    /**
+     * Pause the Playlist.
+     */
+    pub fn pause(&mut self) {
+        if let Some(ref mut music) = self.inner.lock().unwrap().music {
+            music.pause();
+        }
+    }
+
+    /**
+     * Stop the Playlist, rewinding its current track.
+     */
+    pub fn stop(&mut self) {
+        if let Some(ref mut music) = self.inner.lock().unwrap().music {
+            music.stop();
+        }
+    }
+
+    /**
+     * Check if the Playlist is playing.
+     */
+    pub fn is_playing(&self) -> bool {
+        match self.inner.lock().unwrap().music {
+            Some(ref music) => music.is_playing(),
+            None => false,
+        }
+    }
+
+    /**
+     * Play the next track.
+     *
+     * After the last track, a repeating Playlist starts over, otherwise it
+     * stops.
+     *
+     * # Return
+     * A `Result` containing Ok(()) on success, Err(SoundError) if the track
+     * couldn't be opened.
+     */
+    pub fn skip(&mut self) -> Result<(), SoundError> {
+        self.inner.lock().unwrap().play_next()
+    }
+
+    /**
+     * Play the previous track.
+     *
+     * Before the first track, a repeating Playlist goes to its last track,
+     * otherwise the first track starts over.
+     *
+     * # Return
+     * A `Result` containing Ok(()) on success, Err(SoundError) if the track
+     * couldn't be opened.
+     */
+    pub fn previous(&mut self) -> Result<(), SoundError> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.previous_position() {
+            Some(position) => inner.play_position(position),
+            None => Ok(()),
+        }
+    }
+
+    /**
+     * Play a track.
+     *
+     * # Argument
+     * * `index` - The index of the track, in the order the tracks were added
+     *
+     * # Return
+     * A `Result` containing Ok(()) on success, Err(SoundError) if the track
+     * couldn't be opened. Does nothing if there is no such track.
+     */
+    pub fn play_track(&mut self, index: usize) -> Result<(), SoundError> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.order.iter().position(|i| *i == index) {
+            Some(position) => inner.play_position(position),
+            None => Ok(()),
+        }
+    }
+
+    /**
+     * Set the volume of the Playlist.
+     *
+     * The volume applies to all the tracks, see `AudioController::set_volume`.
+     *
+     * # Argument
+     * * `volume` - The new volume of the Playlist
+     */
+    pub fn set_volume(&mut self, volume: f32) {
+        if let Some(ref mut music) = self.inner.lock().unwrap().music {
+            music.set_volume(volume);
+        }
+    }
+
+    /**
+     * Get the volume of the Playlist.
+     *
+     * # Return
+     * The volume of the Playlist, 1.0 if no track was played yet
+     */
+    pub fn get_volume(&self) -> f32 {
+        match self.inner.lock().unwrap().music {
+            Some(ref music) => music.get_volume(),
+            None => 1.0,
+        }
+    }
+}
+
+impl Default for Playlist {
+    fn default() -> Playlist {
+        Playlist::new()
+    }
+}
+
+impl Inner {
+    /// Play the track at a position of the order.
+    fn play_position(&mut self, position: usize) -> Result<(), SoundError> {
+        let path = self.tracks[self.order[position]].clone();
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        // A track which can't be opened is still the current one, so that
+        // the next one comes after it
+        self.position = Some(position);
+        let opened = match self.music {
+            Some(ref mut music) => music.set_file(&path),
+            None => Music::new(&path).map(|mut music| {
+                let generation = self.generation.clone();
+                let sender = self.completion_sender.clone();
+                music.set_complete_callback(move || {
+                    sender.send(generation.load(Ordering::Relaxed)).ok();
+                });
+                self.music = Some(music);
+            }),
+        };
+        if let Some(ref mut music) = self.music {
+            match opened {
+                Ok(()) => music.play(),
+                Err(_) => music.stop(),
+            }
+        }
+        opened
+    }
+
+    /// Play the next track, or stop after the last one.
+    fn play_next(&mut self) -> Result<(), SoundError> {
+        match self.next_position() {
+            Some(position) => self.play_position(position),
+            None => {
+                self.position = None;
+                if let Some(ref mut music) = self.music {
+                    music.stop();
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// The position of the track after the current one, shuffling again on repeat.
+    fn next_position(&mut self) -> Option<usize> {
+        let next = self.position.map_or(0, |p| p + 1);
+        if next < self.order.len() {
+            return Some(next);
+        }
+        match self.repeat && !self.order.is_empty() {
+            true => {
+                self.reorder(None);
+                Some(0)
+            }
+            false => None,
+        }
+    }
+
+    /// The position of the track before the current one.
+    fn previous_position(&self) -> Option<usize> {
+        match self.position {
+            Some(0) | None if self.repeat => self.order.len().checked_sub(1),
+            Some(0) | None => match self.order.is_empty() {
+                true => None,
+                false => Some(0),
+            },
+            Some(p) => Some(p - 1),
+        }
+    }
+
+    /**
+     * Compute the order of the tracks again, after the shuffle setting
+     * changed or before starting over.
+     *
+     * A shuffled order starts with the `current` track, if any.
+     */
+    fn reorder(&mut self, current: Option<usize>) {
+        let mut order: Vec<usize> = (0..self.tracks.len()).collect();
+        if self.shuffle {
+            // Fisher-Yates
+            for i in (1..order.len()).rev() {
+                let j = (self.random() % (i as u64 + 1)) as usize;
+                order.swap(i, j);
+            }
+            if let Some(current) = current {
+                let at = order.iter().position(|i| *i == current).unwrap();
+                order.remove(at);
+                order.insert(0, current);
+            }
+        }
+        self.position = current.and_then(|current| order.iter().position(|i| *i == current));
+        self.order = order;
+    }
+
+    /// A pseudo random number, xorshift64.
+    fn random(&mut self) -> u64 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+/**
+ * Start the thread playing the next track each time one ends.
+ *
+ * The tracks can't be changed by the completion callback itself, as it runs
+ * on the streaming thread of the Music which is joined when the file changes.
+ * The thread ends with the Playlist, once the Music and its callback are
+ * dropped.
+ */
+fn spawn_advance_thread(inner: Weak<Mutex<Inner>>, completions: Receiver<u64>) {
+    thread::Builder::new()
+        .name(String::from("ears-playlist"))
+        .spawn(move || {
+            while let Ok(generation) = completions.recv() {
+                let inner = match inner.upgrade() {
+                    Some(inner) => inner,
+                    None => break,
+                };
+                let mut inner = inner.lock().unwrap();
+                if inner.generation.load(Ordering::Relaxed) != generation {
+                    continue;
+                }
+                // Skip the tracks which can't be opened anymore
+                for _ in 0..inner.order.len() {
+                    if inner.play_next().is_ok() {
+                        break;
+                    }
+                }
+            }
+        })
+        .unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::Playlist;
+
+    fn playlist(tracks: usize) -> Playlist {
+        let mut playlist = Playlist::new();
+        for i in 0..tracks {
+            playlist.push(format!("track{}.ogg", i));
+        }
+        playlist
+    }
+
+    #[test]
+    fn playlist_next_position_OK() -> () {
+        let playlist = playlist(3);
+        let mut inner = playlist.inner.lock().unwrap();
+
+        assert_eq!(inner.next_position(), Some(0));
+        inner.position = Some(2);
+        assert_eq!(inner.next_position(), None);
+        inner.repeat = true;
+        assert_eq!(inner.next_position(), Some(0));
+    }
+
+    #[test]
+    fn playlist_previous_position_OK() -> () {
+        let playlist = playlist(3);
+        let mut inner = playlist.inner.lock().unwrap();
+
+        inner.position = Some(1);
+        assert_eq!(inner.previous_position(), Some(0));
+        inner.position = Some(0);
+        assert_eq!(inner.previous_position(), Some(0));
+        inner.repeat = true;
+        assert_eq!(inner.previous_position(), Some(2));
+    }
+
+    #[test]
+    fn playlist_shuffle_OK() -> () {
+        let mut playlist = playlist(20);
+        playlist.inner.lock().unwrap().position = Some(4);
+
+        playlist.set_shuffle(true);
+        {
+            let inner = playlist.inner.lock().unwrap();
+            assert_eq!(inner.position, Some(0));
+            assert_eq!(inner.order[0], 4);
+            let mut sorted = inner.order.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..20).collect::<Vec<_>>());
+        }
+        assert_eq!(playlist.current_track(), Some(4));
+
+        playlist.set_shuffle(false);
+        assert_eq!(
+            playlist.inner.lock().unwrap().order,
+            (0..20).collect::<Vec<_>>()
+        );
+        assert_eq!(playlist.current_track(), Some(4));
+    }
+
+    #[test]
+    fn playlist_push_shuffled_OK() -> () {
+        let mut playlist = playlist(3);
+        playlist.set_shuffle(true);
+        playlist.inner.lock().unwrap().position = Some(1);
+
+        playlist.push("track3.ogg");
+        let inner = playlist.inner.lock().unwrap();
+        assert_eq!(inner.order.len(), 4);
+        assert!(inner.order[2..].contains(&3));
+    }
+
+    #[test]
+    #[ignore]
+    fn playlist_skip_OK() -> () {
+        let mut playlist = Playlist::new();
+        playlist.push("res/music.ogg");
+        playlist.push("res/shot.wav");
+
+        playlist.play().expect("Cannot play the playlist");
+        assert_eq!(playlist.current_track(), Some(0));
+        playlist.skip().expect("Cannot skip");
+        assert_eq!(playlist.current_track(), Some(1));
+        assert!(playlist.is_playing());
+        playlist.previous().expect("Cannot go back");
+        assert_eq!(playlist.current_track(), Some(0));
+    }
+}
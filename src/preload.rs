@@ -0,0 +1,241 @@
+//! Load sound files in the background, for loading screens.
+
+use error::SoundError;
+use sound_data::SoundData;
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// The maximum number of threads loading files at the same time
+const MAX_WORKERS: usize = 4;
+
+lazy_static! {
+    /// The preloaded SoundDatas, used by `Sound::new`
+    static ref CACHE: Mutex<HashMap<PathBuf, Arc<Mutex<SoundData>>>> = Mutex::new(HashMap::new());
+}
+
+/// The progress of a preloading started with `preload`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PreloadProgress {
+    /// The number of files done loading, including the ones that failed
+    pub loaded: usize,
+    /// The number of files to load
+    pub total: usize,
+    /// The file which most recently started loading
+    pub current_file: Option<PathBuf>,
+    /// The number of files that failed to load
+    pub failed: usize,
+}
+
+impl PreloadProgress {
+    /// Get the fraction of the files done loading, between 0.0 and 1.0.
+    pub fn fraction(&self) -> f32 {
+        match self.total {
+            0 => 1.0,
+            total => self.loaded as f32 / total as f32,
+        }
+    }
+}
+
+struct PreloadState {
+    progress: PreloadProgress,
+    errors: Vec<(PathBuf, SoundError)>,
+}
+
+/**
+ * Handle on a preloading started with `preload`.
+ *
+ * Dropping the handle doesn't stop the loading.
+ */
+pub struct PreloadHandle {
+    state: Arc<Mutex<PreloadState>>,
+    workers: Vec<thread::JoinHandle<()>>,
+}
+
+impl PreloadHandle {
+    /// Get the current progress of the loading.
+    pub fn progress(&self) -> PreloadProgress {
+        self.state.lock().unwrap().progress.clone()
+    }
+
+    /// Check if all the files are done loading.
+    pub fn is_done(&self) -> bool {
+        let progress = self.progress();
+        progress.loaded == progress.total
+    }
+
+    /**
+     * Take the errors of the files that failed to load so far.
+     *
+     * # Return
+     * The path of each file that failed with its error, only once.
+     */
+    pub fn take_errors(&self) -> Vec<(PathBuf, SoundError)> {
+        mem::take(&mut self.state.lock().unwrap().errors)
+    }
+
+    /**
+     * Block until all the files are done loading.
+     *
+     * # Return
+     * The errors of the files that failed to load and weren't taken yet.
+     */
+    pub fn wait(self) -> Vec<(PathBuf, SoundError)> {
+        for worker in self.workers {
+            worker.join();
+        }
+        mem::take(&mut self.state.lock().unwrap().errors)
+    }
+}
+
+/**
+ * Load sound files in the background.
+ *
+ * The files are loaded on worker threads into a cache: once loaded, a
+ * `Sound::new` with the same path uses the cached samples instead of
+ * reading the file again.
+ *
+ * # Argument
+ * * `paths` - The paths of the files to load
+ *
+ * # Return
+ * A PreloadHandle to follow the loading.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Sound};
+ *
+ * let loading = ears::preload(&["res/shot.wav", "res/explosion.wav"]);
+ * while !loading.is_done() {
+ *     let progress = loading.progress();
+ *     println!("Loading {:?}: {:.0}%", progress.current_file, progress.fraction() * 100.);
+ * }
+ * for (path, err) in loading.take_errors() {
+ *     println!("Cannot load {}: {}", path.display(), err);
+ * }
+ *
+ * // Doesn't read the file again
+ * let mut shot = Sound::new("res/shot.wav").unwrap();
+ * shot.play();
+ * ```
+ */
+pub fn preload<I, P>(paths: I) -> PreloadHandle
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    load_with(paths, |path| match cached(path) {
+        Some(_) => Ok(()),
+        None => SoundData::new(path).map(|data| {
+            CACHE
+                .lock()
+                .unwrap()
+                .insert(path.to_path_buf(), Arc::new(Mutex::new(data)));
+        }),
+    })
+}
+
+/// Load files in the background, giving each loaded file to `store`.
+pub(crate) fn load_with<I, P, F>(paths: I, store: F) -> PreloadHandle
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+    F: Fn(&Path) -> Result<(), SoundError> + Send + Sync + 'static,
+{
+    let queue: VecDeque<PathBuf> = paths
+        .into_iter()
+        .map(|p| p.as_ref().to_path_buf())
+        .collect();
+    let state = Arc::new(Mutex::new(PreloadState {
+        progress: PreloadProgress {
+            total: queue.len(),
+            ..Default::default()
+        },
+        errors: Vec::new(),
+    }));
+
+    let worker_count = queue.len().min(MAX_WORKERS);
+    let queue = Arc::new(Mutex::new(queue));
+    let store = Arc::new(store);
+    let workers = (0..worker_count)
+        .map(|_| {
+            let queue = queue.clone();
+            let state = state.clone();
+            let store = store.clone();
+            thread::Builder::new()
+                .name(String::from("ears-preload"))
+                .spawn(move || load_queue(&queue, &state, &*store))
+                .unwrap()
+        })
+        .collect();
+
+    PreloadHandle { state, workers }
+}
+
+fn load_queue<F>(queue: &Mutex<VecDeque<PathBuf>>, state: &Mutex<PreloadState>, store: &F)
+where
+    F: Fn(&Path) -> Result<(), SoundError>,
+{
+    loop {
+        let next = queue.lock().unwrap().pop_front();
+        let path = match next {
+            Some(path) => path,
+            None => break,
+        };
+        state.lock().unwrap().progress.current_file = Some(path.clone());
+
+        let result = store(&path);
+
+        let mut state = state.lock().unwrap();
+        state.progress.loaded += 1;
+        if let Err(err) = result {
+            state.progress.failed += 1;
+            state.errors.push((path, err));
+        }
+    }
+}
+
+/// Get the preloaded SoundData of a file, if any.
+pub(crate) fn cached(path: &Path) -> Option<Arc<Mutex<SoundData>>> {
+    CACHE.lock().unwrap().get(path).cloned()
+}
+
+/**
+ * Remove all the preloaded files from the cache.
+ *
+ * The samples are freed once no Sound uses them anymore.
+ */
+pub fn clear_preload_cache() {
+    CACHE.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{preload, PreloadProgress};
+
+    #[test]
+    fn preload_progress_fraction_OK() -> () {
+        let progress = PreloadProgress {
+            loaded: 1,
+            total: 4,
+            ..Default::default()
+        };
+
+        assert_eq!(progress.fraction(), 0.25);
+        assert_eq!(PreloadProgress::default().fraction(), 1.0);
+    }
+
+    #[test]
+    #[ignore]
+    fn preload_OK() -> () {
+        let loading = preload(&["res/shot.wav", "res/not_a_file.wav"]);
+        let errors = loading.wait();
+
+        assert_eq!(errors.len(), 1);
+        assert!(super::cached("res/shot.wav".as_ref()).is_some());
+    }
+}
@@ -1,4 +1,14 @@
-#[derive(Debug)]
+#[cfg(feature = "serde")]
+use serde_json;
+#[cfg(feature = "serde")]
+use std::error::Error;
+#[cfg(feature = "serde")]
+use std::path::Path;
+#[cfg(feature = "serde")]
+use std::{fmt, fs, io};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ReverbProperties {
     pub density: f32,
     pub diffusion: f32,
@@ -318,3 +328,126 @@ impl ReverbProperties {
         }
     }
 }
+
+/// All possible errors when loading a custom reverb preset.
+#[cfg(feature = "serde")]
+pub enum ReverbPresetError {
+    /// Error while reading the file.
+    IoError(io::Error),
+
+    /// The preset isn't valid JSON, or misses some properties.
+    InvalidJson(serde_json::Error),
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for ReverbPresetError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                ReverbPresetError::IoError(err) => format!("error while reading file: {}", err),
+                ReverbPresetError::InvalidJson(err) => format!("invalid preset: {}", err),
+            }
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Debug for ReverbPresetError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, fmt)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error for ReverbPresetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReverbPresetError::IoError(err) => Some(err),
+            ReverbPresetError::InvalidJson(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ReverbPreset {
+    /**
+     * Load the properties of a custom preset from JSON.
+     *
+     * The JSON object has a field for each field of `ReverbProperties`,
+     * see `ReverbProperties::to_json` to start from a built-in preset.
+     *
+     * # Argument
+     * * `json` - The JSON of the preset
+     *
+     * # Return
+     * A `Result` containing Ok(ReverbProperties) on success,
+     * Err(ReverbPresetError) if the JSON isn't a valid preset.
+     */
+    pub fn from_json(json: &str) -> Result<ReverbProperties, ReverbPresetError> {
+        serde_json::from_str(json).map_err(ReverbPresetError::InvalidJson)
+    }
+
+    /**
+     * Load the properties of a custom preset from a JSON file.
+     *
+     * # Argument
+     * * `path` - The path of the preset file
+     *
+     * # Return
+     * A `Result` containing Ok(ReverbProperties) on success,
+     * Err(ReverbPresetError) if the file can't be read or isn't a valid
+     * preset.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{ReverbEffect, ReverbPreset};
+     * use std::error::Error;
+     *
+     * fn main() -> Result<(), Box<dyn Error>> {
+     *     let properties = ReverbPreset::from_file("presets/dungeon.json")?;
+     *     let effect = ReverbEffect::preset(properties)?;
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<ReverbProperties, ReverbPresetError> {
+        let json = fs::read_to_string(path).map_err(ReverbPresetError::IoError)?;
+        Self::from_json(&json)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl ReverbProperties {
+    /// Serialize the properties to pretty printed JSON, to save a preset file.
+    pub fn to_json(&self) -> String {
+        // A struct of numbers always serializes
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    #![allow(non_snake_case)]
+
+    use presets::ReverbPreset;
+
+    #[test]
+    fn presets_json_round_trip_OK() -> () {
+        let properties = ReverbPreset::Cave.properties();
+
+        let loaded = ReverbPreset::from_json(&properties.to_json()).expect("Invalid preset");
+        assert_eq!(loaded, properties);
+    }
+
+    #[test]
+    fn presets_from_json_missing_property_FAIL() -> () {
+        assert!(ReverbPreset::from_json("{\"density\": 1.0}").is_err());
+    }
+
+    #[test]
+    fn presets_from_file_missing_FAIL() -> () {
+        assert!(ReverbPreset::from_file("res/missing_preset.json").is_err());
+    }
+}
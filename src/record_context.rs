@@ -25,11 +25,27 @@ use openal::ffi;
 #[derive(Clone, PartialEq, Debug, Copy)]
 pub struct RecordContext {
     capt_device: ffi::ALCdevicePtr,
+    channels: i32,
+    sample_rate: i32,
 }
 
-pub fn new(capt_device: ffi::ALCdevicePtr) -> RecordContext {
+impl RecordContext {
+    /// Get the number of channels captured by the input device.
+    pub fn channels(&self) -> i32 {
+        self.channels
+    }
+
+    /// Get the sample rate of the input device, in Hz.
+    pub fn sample_rate(&self) -> i32 {
+        self.sample_rate
+    }
+}
+
+pub fn new(capt_device: ffi::ALCdevicePtr, channels: i32, sample_rate: i32) -> RecordContext {
     RecordContext {
-        capt_device: capt_device,
+        capt_device,
+        channels,
+        sample_rate,
     }
 }
 
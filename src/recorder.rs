@@ -21,24 +21,127 @@
 
 //! Record audio
 
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use std::vec::Vec;
 use std::{mem, thread};
 
+use agc::{Agc, AgcSettings};
+use audio_tags::{self, Tags};
+use clock::{self, DeviceClock};
+use error::SoundError;
+use gate::{self, Gate, GateSettings};
+use logging::{self, LogLevel};
 use openal::ffi;
 use record_context;
 use record_context::RecordContext;
-use sndfile::FormatType::{FormatPcm16, FormatWav};
+use sndfile::FormatType::{
+    FormatFlac, FormatFloat, FormatOgg, FormatPcm16, FormatPcm24, FormatPcmS8, FormatPcmU8,
+    FormatVorbis, FormatWav,
+};
 use sndfile::OpenMode::Write;
-use sndfile::{SndFile, SndInfo};
+use sndfile::{SndFile, SndFileError, SndInfo};
+use sound::Sound;
+use sound_data::SoundData;
+use spectrum;
 use std::intrinsics::transmute;
 
+/// Number of recent samples kept for the frequency analysis
+const ANALYSIS_SIZE: usize = 2048;
+
+/// Duration of the recent audio the input levels are measured on, in seconds
+const LEVEL_WINDOW: f32 = 0.05;
+
+/// A function receiving the audio captured by a Recorder
+type StreamCallback = Box<dyn FnMut(&[i16]) + Send>;
+
+/// The sample format of the files saved by a Recorder.
+///
+/// It only sets how the samples are written: the capture itself is always
+/// 16 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordFormat {
+    /// 8 bits unsigned integers
+    Pcm8,
+    /// 16 bits signed integers
+    Pcm16,
+    /// 24 bits signed integers
+    Pcm24,
+    /// 32 bits floats
+    Float,
+}
+
+impl RecordFormat {
+    fn sndfile_format(self) -> i32 {
+        let sub = match self {
+            RecordFormat::Pcm8 => FormatPcmU8,
+            RecordFormat::Pcm16 => FormatPcm16,
+            RecordFormat::Pcm24 => FormatPcm24,
+            RecordFormat::Float => FormatFloat,
+        };
+        (FormatWav as i32) | (sub as i32)
+    }
+}
+
+/// The container and encoding of a file saved by a Recorder.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AudioFormat {
+    /// Uncompressed WAV, with a sample format
+    Wav(RecordFormat),
+    /// Lossless FLAC, with a sample format other than RecordFormat::Float
+    Flac(RecordFormat),
+    /// Lossy Ogg Vorbis
+    OggVorbis,
+}
+
+impl AudioFormat {
+    /// The libsndfile format, None if the sample format can't be used.
+    fn sndfile_format(self) -> Option<i32> {
+        match self {
+            AudioFormat::Wav(format) => Some(format.sndfile_format()),
+            AudioFormat::Flac(format) => {
+                let sub = match format {
+                    RecordFormat::Pcm8 => FormatPcmS8,
+                    RecordFormat::Pcm16 => FormatPcm16,
+                    RecordFormat::Pcm24 => FormatPcm24,
+                    RecordFormat::Float => return None,
+                };
+                Some((FormatFlac as i32) | (sub as i32))
+            }
+            AudioFormat::OggVorbis => Some((FormatOgg as i32) | (FormatVorbis as i32)),
+        }
+    }
+}
+
+/// A chunk of audio captured by a Recorder.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaptureChunk {
+    /// The captured samples, interleaved if there are several channels
+    pub samples: Vec<i16>,
+    /// The number of channels of the samples, as set by `Recorder::set_channel_map`
+    pub channels: usize,
+    /// The index of the first frame since the recording started
+    pub position: u64,
+    /**
+     * When the first sample entered the capture device, on the clock of the
+     * playback device, see `ears::device_clock`.
+     *
+     * If the devices don't support the ALC_SOFT_device_clock extension, this
+     * is the time since the recording started, computed from `position`.
+     */
+    pub timestamp: Duration,
+}
+
 /**
  * Record audio
  *
  * This class provide easy audio recording using. The Recorder allow the user
  * to record sound, then save it in a file, or create a SoundData object to play the
- * recorded sound in the same program.
+ * recorded sound in the same program (see `to_sound_data`).
  * A special context, RecordContext is needed to create the Recorder object.
  * The Recorder work in it's own task.
  *
@@ -70,24 +173,74 @@ pub struct Recorder {
     stop_sender: Option<Sender<bool>>,
     data_receiver: Option<Receiver<Vec<i16>>>,
     samples: Vec<i16>,
+    recent_samples: Arc<Mutex<VecDeque<i16>>>,
+    /// The RMS and peak levels of the recent input
+    levels: Arc<Mutex<(f32, f32)>>,
+    chunk_sender: Arc<Mutex<Option<Sender<CaptureChunk>>>>,
+    stream_callback: Arc<Mutex<Option<StreamCallback>>>,
+    monitor_sender: Arc<Mutex<Option<Sender<Vec<i16>>>>>,
+    agc: Arc<Mutex<Option<Agc>>>,
+    gate: Arc<Mutex<Option<Gate>>>,
+    channel_map: Vec<usize>,
+    recorded_channels: usize,
+    format: RecordFormat,
+    tags: Tags,
+    paused: Arc<AtomicBool>,
+    recorded_frames: Arc<AtomicU64>,
 }
 
 impl Recorder {
     /// Create a new audio recorder
     pub fn new(record_context: RecordContext) -> Recorder {
+        let input_channels = record_context.channels() as usize;
         Recorder {
             ctxt: record_context,
             stop_sender: None,
             data_receiver: None,
             samples: Vec::new(),
+            recent_samples: Arc::new(Mutex::new(VecDeque::with_capacity(ANALYSIS_SIZE))),
+            levels: Arc::new(Mutex::new((0.0, 0.0))),
+            chunk_sender: Arc::new(Mutex::new(None)),
+            stream_callback: Arc::new(Mutex::new(None)),
+            monitor_sender: Arc::new(Mutex::new(None)),
+            agc: Arc::new(Mutex::new(None)),
+            gate: Arc::new(Mutex::new(None)),
+            channel_map: (0..input_channels).collect(),
+            recorded_channels: input_channels,
+            format: RecordFormat::Pcm16,
+            tags: Tags::default(),
+            paused: Arc::new(AtomicBool::new(false)),
+            recorded_frames: Arc::new(AtomicU64::new(0)),
         }
     }
 
     pub fn start(&mut self) {
+        self.start_recording(None);
+    }
+
+    /// Start the recording thread, stopping it after `frame_limit` frames if any.
+    fn start_recording(&mut self, frame_limit: Option<u64>) {
         let (stop_sender, stop_receiver) = channel();
         let (data_sender, data_receiver) = channel();
         let r_c = self.ctxt.clone();
+        let recent_samples = self.recent_samples.clone();
+        let levels = self.levels.clone();
+        let chunk_sender = self.chunk_sender.clone();
+        let stream_callback = self.stream_callback.clone();
+        let monitor_sender = self.monitor_sender.clone();
+        let agc = self.agc.clone();
+        let gate = self.gate.clone();
+        let channel_map = self.channel_map.clone();
+        let input_channels = self.ctxt.channels() as usize;
+        let output_channels = channel_map.len();
+        let sample_rate = self.ctxt.sample_rate() as i64;
+        let paused = self.paused.clone();
+        let recorded_frames = self.recorded_frames.clone();
 
+        paused.store(false, Ordering::SeqCst);
+        recorded_frames.store(0, Ordering::SeqCst);
+        *levels.lock().unwrap() = (0.0, 0.0);
+        self.recorded_channels = output_channels;
         self.stop_sender = Some(stop_sender);
         self.data_receiver = Some(data_receiver);
 
@@ -99,10 +252,27 @@ impl Recorder {
                 unsafe {
                     ffi::alcCaptureStart(ctxt);
                 }
+                let device_clock = DeviceClock::new(ctxt);
+                let playback_clock = DeviceClock::playback();
                 let mut available_samples = 0;
                 let mut samples: Vec<i16> = Vec::new();
+                let mut capture_paused = false;
+                let window_size =
+                    ((sample_rate as f32 * LEVEL_WINDOW) as usize).max(1) * output_channels;
+                let mut level_window: VecDeque<i16> = VecDeque::with_capacity(window_size);
 
                 while !terminate {
+                    let pause = paused.load(Ordering::SeqCst);
+                    if pause != capture_paused {
+                        capture_paused = pause;
+                        unsafe {
+                            match pause {
+                                true => ffi::alcCaptureStop(ctxt),
+                                false => ffi::alcCaptureStart(ctxt),
+                            }
+                        }
+                    }
+
                     unsafe {
                         ffi::alcGetIntegerv(
                             ctxt,
@@ -113,72 +283,840 @@ impl Recorder {
                     };
 
                     if available_samples != 0 {
-                        let tmp_buf = vec![0i16; available_samples as usize];
+                        let capture_buf = vec![0i16; available_samples as usize * input_channels];
+                        // The capture latency, with the playback clock read at the same time
+                        let clock_latency = device_clock
+                            .map(|clock| clock.clock_latency().1)
+                            .and_then(|latency| {
+                                playback_clock.map(|clock| (clock.clock_latency().0, latency))
+                            });
                         unsafe {
-                            ffi::alcCaptureSamples(ctxt, transmute(&tmp_buf[0]), available_samples);
+                            ffi::alcCaptureSamples(
+                                ctxt,
+                                transmute(&capture_buf[0]),
+                                available_samples,
+                            );
+                        }
+                        let mut tmp_buf = map_channels(&capture_buf, input_channels, &channel_map);
+                        if let Some(ref mut agc) = *agc.lock().unwrap() {
+                            agc.process(&mut tmp_buf, sample_rate as u32 * output_channels as u32);
+                        }
+                        level_window.extend(tmp_buf.iter().cloned());
+                        let excess = level_window.len().saturating_sub(window_size);
+                        level_window.drain(..excess);
+                        *levels.lock().unwrap() = measure_levels(level_window.iter().cloned());
+                        {
+                            let mut monitor_sender = monitor_sender.lock().unwrap();
+                            let sent = match *monitor_sender {
+                                Some(ref sender) => sender.send(tmp_buf.clone()).is_ok(),
+                                None => true,
+                            };
+                            if !sent {
+                                *monitor_sender = None;
+                            }
+                        }
+                        if let Some(ref mut gate) = *gate.lock().unwrap() {
+                            tmp_buf = gate.process(&tmp_buf, output_channels, sample_rate as u32);
+                        }
+                        if let Some(limit) = frame_limit {
+                            let recorded = (samples.len() / output_channels) as u64;
+                            let remaining = limit.saturating_sub(recorded) as usize;
+                            if tmp_buf.len() >= remaining * output_channels {
+                                tmp_buf.truncate(remaining * output_channels);
+                                terminate = true;
+                            }
+                        }
+                        {
+                            let mut chunk_sender = chunk_sender.lock().unwrap();
+                            if let Some(ref sender) = *chunk_sender {
+                                let position = (samples.len() / output_channels) as u64;
+                                let timestamp = match clock_latency {
+                                    Some((clock, latency)) => clock::capture_start_time(
+                                        clock,
+                                        latency,
+                                        available_samples as i64,
+                                        sample_rate,
+                                    ),
+                                    None => Duration::from_nanos(
+                                        position * 1_000_000_000 / sample_rate as u64,
+                                    ),
+                                };
+                                let chunk = CaptureChunk {
+                                    samples: tmp_buf.clone(),
+                                    channels: output_channels,
+                                    position,
+                                    timestamp,
+                                };
+                                if sender.send(chunk).is_err() {
+                                    *chunk_sender = None;
+                                }
+                            }
+                        }
+                        if let Some(ref mut callback) = *stream_callback.lock().unwrap() {
+                            callback(&tmp_buf);
+                        }
+                        {
+                            let mut recent_samples = recent_samples.lock().unwrap();
+                            recent_samples.extend(tmp_buf.chunks(output_channels).map(|frame| {
+                                (frame.iter().map(|s| *s as i32).sum::<i32>()
+                                    / output_channels as i32) as i16
+                            }));
+                            let excess = recent_samples.len().saturating_sub(ANALYSIS_SIZE);
+                            recent_samples.drain(..excess);
                         }
                         samples.extend(tmp_buf.into_iter());
+                        recorded_frames
+                            .store((samples.len() / output_channels) as u64, Ordering::SeqCst);
                     }
 
-                    match stop_receiver.try_recv() {
-                        Ok(_) => {
-                            unsafe {
-                                ffi::alcCaptureStop(ctxt);
-                            }
-                            terminate = true;
-                        }
-                        _ => {}
+                    if stop_receiver.try_recv().is_ok() {
+                        terminate = true;
                     }
                 }
+                unsafe {
+                    ffi::alcCaptureStop(ctxt);
+                }
                 data_sender.send(samples);
             })
             .unwrap();
     }
 
+    /**
+     * Record for a duration, then stop.
+     *
+     * This blocks until exactly `duration` of audio has been captured, the
+     * time spent paused not being counted. Use `start`, `elapsed` and `stop`
+     * to record without blocking.
+     *
+     * # Argument
+     * * `duration` - The duration of the recording
+     *
+     * # Return
+     * true if the recording succeeded, false otherwise
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     * use std::time::Duration;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.record_for(Duration::from_secs(5));
+     *     recorder.save_to_file("five_seconds");
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn record_for(&mut self, duration: Duration) -> bool {
+        let frames = duration.as_secs_f64() * self.ctxt.sample_rate() as f64;
+        self.start_recording(Some(frames.round() as u64));
+        self.receive_samples()
+    }
+
+    /**
+     * Pause the recording.
+     *
+     * The capture device stops capturing until `resume` is called, so the
+     * recording is made of the segments captured in between, with nothing
+     * in place of the pauses.
+     */
+    pub fn pause(&mut self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    /**
+     * Resume a recording paused with `pause`.
+     */
+    pub fn resume(&mut self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /**
+     * Check if the recording is paused.
+     */
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /**
+     * Get the duration of the audio recorded so far.
+     *
+     * The time spent paused is not counted. After `stop`, this is the
+     * duration of the whole recording.
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     * use std::time::Duration;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.start();
+     *     while recorder.elapsed() < Duration::from_secs(10) {
+     *         // Show a level meter, check if the user pressed pause...
+     *     }
+     *     recorder.stop();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn elapsed(&self) -> Duration {
+        let frames = self.recorded_frames.load(Ordering::SeqCst);
+        Duration::from_secs_f64(frames as f64 / self.ctxt.sample_rate() as f64)
+    }
+
+    /**
+     * Receive the audio in chunks while it's being captured.
+     *
+     * Each chunk is timestamped with the clock of the playback device, so the
+     * captured audio can be aligned with the played audio (see
+     * `ears::device_clock`) and with game events, for instance in a replay
+     * system. Only the last receiver gets the chunks, and the samples are
+     * still kept for `save_to_file`.
+     *
+     * # Return
+     * The receiving end of the chunks captured from now on.
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     let chunks = recorder.chunks();
+     *     recorder.start();
+     *
+     *     for chunk in chunks.iter().take(100) {
+     *         println!("{} samples at {:?}", chunk.samples.len(), chunk.timestamp);
+     *     }
+     *     recorder.stop();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn chunks(&mut self) -> Receiver<CaptureChunk> {
+        let (sender, receiver) = channel();
+        *self.chunk_sender.lock().unwrap() = Some(sender);
+        receiver
+    }
+
+    /**
+     * Start recording, giving the audio to a callback while it's being
+     * captured.
+     *
+     * The callback is called from the recording thread with each block of
+     * captured samples, interleaved if several channels are recorded, so it
+     * should return quickly. Use `chunks` instead to receive the audio on
+     * another thread. The samples are still kept for `save_to_file`, and the
+     * callback is removed by `stop`.
+     *
+     * # Argument
+     * * `callback` - The function receiving the captured samples
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.start_streaming(|samples| {
+     *         let peak = samples.iter().map(|s| (*s as i32).abs()).max().unwrap_or(0);
+     *         println!("Peak level: {}", peak);
+     *     });
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn start_streaming<F>(&mut self, callback: F)
+    where
+        F: FnMut(&[i16]) + Send + 'static,
+    {
+        *self.stream_callback.lock().unwrap() = Some(Box::new(callback));
+        self.start();
+    }
+
+    /// Send the captured audio to a Monitor, replacing the previous one.
+    pub(crate) fn set_monitor(&mut self, sender: Sender<Vec<i16>>) {
+        *self.monitor_sender.lock().unwrap() = Some(sender);
+    }
+
+    /**
+     * Choose which input channels are recorded, and in which order.
+     *
+     * With a capture device opened with several channels (see
+     * `ears::init_in_with_channels`), each channel of the recording takes the
+     * samples of one input channel. An input channel can be used several
+     * times, or not at all. It applies to the next recordings, by default all
+     * the input channels are recorded in order.
+     *
+     * # Argument
+     * * `map` - The input channel of each recorded channel, starting at 0
+     *
+     * # Return
+     * true if the map was set, false if it is empty or uses an input channel
+     * the capture device doesn't have
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     // An interface with 4 inputs, a microphone on the third and a guitar
+     *     // on the first
+     *     let mut recorder = Recorder::new(ears::init_in_with_channels(4)?);
+     *     // Record them to the left and right channels of a stereo file
+     *     recorder.set_channel_map(&[2, 0]);
+     *     recorder.start();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_channel_map(&mut self, map: &[usize]) -> bool {
+        let input_channels = self.ctxt.channels() as usize;
+        if map.is_empty() || map.iter().any(|channel| *channel >= input_channels) {
+            false
+        } else {
+            self.channel_map = map.to_vec();
+            true
+        }
+    }
+
+    /**
+     * Get the input channel of each recorded channel.
+     *
+     * # Return
+     * The map given to `set_channel_map`, all the input channels in order by
+     * default
+     */
+    pub fn get_channel_map(&self) -> &[usize] {
+        &self.channel_map
+    }
+
+    /**
+     * Get the sample rate of the recordings, the one of the capture device.
+     *
+     * See `ears::init_in_with` to choose it.
+     */
+    pub fn sample_rate(&self) -> i32 {
+        self.ctxt.sample_rate()
+    }
+
+    /**
+     * Set the sample format of the files written by `save_to_file`.
+     *
+     * The capture device is always opened with a 16 bits format, whatever
+     * the sample format, and the audio is converted when saved: 8 bits files
+     * are smaller, while the float and 24 bits ones suit further editing but
+     * hold no more precision than the 16 bits capture.
+     *
+     * # Argument
+     * * `format` - The sample format. Default is RecordFormat::Pcm16.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{RecordFormat, Recorder};
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in_with(None, 48000, 2)?);
+     *     recorder.set_format(RecordFormat::Float);
+     *     recorder.start();
+     *     recorder.stop();
+     *     // A 48kHz stereo float file
+     *     recorder.save_to_file("take");
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_format(&mut self, format: RecordFormat) {
+        self.format = format;
+    }
+
+    /**
+     * Get the sample format of the files written by `save_to_file`.
+     */
+    pub fn get_format(&self) -> RecordFormat {
+        self.format
+    }
+
+    /**
+     * Set the tags written in the files saved by `save_to_file`.
+     *
+     * The tags which are None aren't written.
+     *
+     * # Argument
+     * * `tags` - The tags, like the title and the artist of the recording
+     *
+     * # Example
+     * ```no_run
+     * use ears::{Recorder, Tags};
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.set_tags(&Tags {
+     *         title: Some("Interview".to_string()),
+     *         comment: Some("Take 2".to_string()),
+     *         ..Default::default()
+     *     });
+     *     recorder.start();
+     *     recorder.stop();
+     *     recorder.save_to_file("interview");
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_tags(&mut self, tags: &Tags) {
+        self.tags = tags.clone();
+    }
+
+    /**
+     * Get the tags written in the files saved by `save_to_file`.
+     */
+    pub fn get_tags(&self) -> Tags {
+        self.tags.clone()
+    }
+
+    /**
+     * Enable or disable the automatic gain control of the Recorder.
+     *
+     * The automatic gain control adapts the gain of the input toward a
+     * target level, so voice recordings stay intelligible with both quiet
+     * and loud speakers. It applies to everything captured after the call.
+     *
+     * # Argument
+     * * `settings` - The settings of the automatic gain control, or None to
+     *   disable it (the default)
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AgcSettings, Recorder};
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.set_agc(Some(AgcSettings::default()));
+     *     recorder.start();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_agc(&mut self, settings: Option<AgcSettings>) {
+        *self.agc.lock().unwrap() = settings.map(Agc::new);
+    }
+
+    /**
+     * Get the settings of the automatic gain control of the Recorder.
+     *
+     * # Return
+     * The settings given to `set_agc`, None if it is disabled
+     */
+    pub fn get_agc(&self) -> Option<AgcSettings> {
+        self.agc.lock().unwrap().as_ref().map(|agc| agc.settings())
+    }
+
+    /**
+     * Enable or disable the noise gate of the Recorder.
+     *
+     * With the noise gate, only the input louder than a threshold is
+     * recorded, along with the silences shorter than the hold time: the
+     * recording skips from one sentence to the next, which suits
+     * push-to-talk and voice memos. It applies to everything captured after
+     * the call, and after the automatic gain control.
+     *
+     * # Argument
+     * * `settings` - The settings of the noise gate, or None to disable it
+     *   (the default)
+     *
+     * # Example
+     * ```no_run
+     * use ears::{GateSettings, Recorder};
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.set_gate(Some(GateSettings::default()));
+     *     recorder.start();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_gate(&mut self, settings: Option<GateSettings>) {
+        *self.gate.lock().unwrap() = settings.map(Gate::new);
+    }
+
+    /**
+     * Get the settings of the noise gate of the Recorder.
+     *
+     * # Return
+     * The settings given to `set_gate`, None if it is disabled
+     */
+    pub fn get_gate(&self) -> Option<GateSettings> {
+        self.gate
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|gate| gate.settings())
+    }
+
+    /**
+     * Remove the leading and trailing silences of the recorded audio.
+     *
+     * Call it after `stop`, before `save_to_file` or `to_sound_data`. The
+     * audio is cut at the first and last blocks of 10 milliseconds louder
+     * than the threshold.
+     *
+     * # Argument
+     * * `threshold` - The RMS level under which the audio is a silence,
+     *   where 1.0 is a full scale square wave
+     *
+     * # Return
+     * true if some audio remains, false if everything was a silence
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     * use std::time::Duration;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.record_for(Duration::from_secs(10));
+     *     if recorder.trim_silence(0.02) {
+     *         recorder.save_to_file("memo");
+     *     }
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn trim_silence(&mut self, threshold: f32) -> bool {
+        let bounds = gate::sound_bounds(
+            &self.samples,
+            self.recorded_channels,
+            self.ctxt.sample_rate() as u32,
+            threshold,
+        );
+        self.samples.truncate(bounds.end);
+        self.samples.drain(..bounds.start);
+        !self.samples.is_empty()
+    }
+
+    /**
+     * Compute the frequency spectrum of the most recently captured audio.
+     *
+     * The analysis covers about the last 50 milliseconds of input, so calling
+     * it every frame while recording is enough to animate a visualizer or to
+     * detect a whistle or someone blowing into the microphone.
+     *
+     * # Argument
+     * * `bands` - The number of frequency bands to compute
+     *
+     * # Return
+     * The magnitude of each band, logarithmically spaced from 20Hz to half the
+     * sample rate, where 1.0 is a full scale sine wave. All zeros if nothing
+     * was recorded.
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.start();
+     *
+     *     loop {
+     *         let spectrum = recorder.spectrum(16);
+     *         if spectrum[10..].iter().any(|band| *band > 0.5) {
+     *             println!("Someone is whistling!");
+     *         }
+     *     }
+     * }
+     * ```
+     */
+    pub fn spectrum(&self, bands: usize) -> Vec<f32> {
+        let recent_samples: Vec<i16> = self
+            .recent_samples
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect();
+        spectrum::band_magnitudes(&recent_samples, self.ctxt.sample_rate() as u32, bands)
+    }
+
+    /**
+     * Get the level of the input, while recording.
+     *
+     * The level is measured by the recording thread on the last 50
+     * milliseconds of captured audio, so calling it every frame is enough to
+     * animate a VU meter.
+     *
+     * # Return
+     * The RMS level, where 1.0 is a full scale square wave. 0.0 if nothing
+     * was recorded.
+     *
+     * # Example
+     * ```no_run
+     * use ears::Recorder;
+     *
+     * fn main() -> Result<(), ears::OpenAlContextError> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.start();
+     *
+     *     loop {
+     *         let bars = (recorder.current_level() * 40.0) as usize;
+     *         let clipping = if recorder.peak_level() >= 1.0 { " CLIP" } else { "" };
+     *         println!("[{:<40}]{}", "#".repeat(bars), clipping);
+     *     }
+     * }
+     * ```
+     */
+    pub fn current_level(&self) -> f32 {
+        self.levels.lock().unwrap().0
+    }
+
+    /**
+     * Get the peak level of the input, while recording.
+     *
+     * # Return
+     * The highest absolute sample of the last 50 milliseconds of captured
+     * audio, where 1.0 means the input is clipping.
+     */
+    pub fn peak_level(&self) -> f32 {
+        self.levels.lock().unwrap().1
+    }
+
     pub fn stop(&mut self) -> bool {
         match self.stop_sender {
             Some(ref s_c) => {
                 s_c.send(true);
-                match self.data_receiver {
-                    Some(ref d_p) => {
-                        self.samples = d_p.recv().ok().unwrap();
-                        true
-                    }
-                    None => false,
-                }
+                self.receive_samples()
             }
             None => false,
         }
     }
 
-    pub fn save_to_file(&mut self, filename: &str) -> bool {
-        if self.samples.len() == 0 {
-            false
-        } else {
-            let infos = Box::new(SndInfo {
-                frames: self.samples.len() as i64,
-                samplerate: 44100,
-                channels: 1,
-                format: (FormatPcm16 | FormatWav) as i32,
-                sections: 0,
-                seekable: 0,
-            });
-
-            let mut file_ext = String::new();
-            file_ext.push_str(filename);
-            file_ext.push_str(".wav");
-            match SndFile::new_with_info(file_ext.as_ref(), Write, infos) {
-                Ok(mut f) => {
-                    let len = self.samples.len() as i64;
-                    f.write_i16(&mut self.samples[..], len);
-                    f.close();
-                    true
-                }
-                Err(e) => {
-                    println!("{}", e);
-                    false
-                }
+    /// Wait for the recording thread to end, and keep the samples it recorded.
+    fn receive_samples(&mut self) -> bool {
+        match self.data_receiver.take() {
+            Some(d_p) => {
+                self.samples = d_p.recv().ok().unwrap();
+                *self.stream_callback.lock().unwrap() = None;
+                true
             }
+            None => false,
         }
     }
+
+    /**
+     * Create a SoundData with the recorded audio, to play it without
+     * writing it to a file.
+     *
+     * # Return
+     * A `Result` containing Ok(SoundData) on success, Err(SoundError)
+     * if there has been an error, for instance Err(SoundError::InvalidFormat)
+     * if nothing was recorded.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Recorder, Sound};
+     * use std::sync::{Arc, Mutex};
+     *
+     * fn main() -> Result<(), Box<dyn std::error::Error>> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.start();
+     *     std::thread::sleep(std::time::Duration::from_secs(3));
+     *     recorder.stop();
+     *
+     *     let data = recorder.to_sound_data()?;
+     *     let mut replay = Sound::new_with_data(Arc::new(Mutex::new(data)))?;
+     *     replay.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn to_sound_data(&self) -> Result<SoundData, SoundError> {
+        if self.samples.is_empty() {
+            return Err(SoundError::InvalidFormat);
+        }
+        SoundData::from_samples(
+            &self.samples,
+            self.recorded_channels as i32,
+            self.ctxt.sample_rate(),
+        )
+    }
+
+    /**
+     * Create a Sound playing the recorded audio.
+     *
+     * # Return
+     * A `Result` containing Ok(Sound) on success, Err(SoundError)
+     * if there has been an error, for instance Err(SoundError::InvalidFormat)
+     * if nothing was recorded.
+     */
+    pub fn to_sound(&self) -> Result<Sound, SoundError> {
+        Sound::new_with_data(Arc::new(Mutex::new(self.to_sound_data()?)))
+    }
+
+    /**
+     * Save the recorded audio to a file, in a format.
+     *
+     * Unlike `save_to_file`, no extension is added to the path. The tags set
+     * with `set_tags` are written in the file.
+     *
+     * # Arguments
+     * * `path` - The path of the file to write
+     * * `format` - The container and encoding of the file
+     *
+     * # Return
+     * `Ok(())` if the file has been written, Err(SoundError) otherwise, for
+     * instance Err(SoundError::InvalidFormat) if nothing was recorded or
+     * the format can't be used.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioFormat, RecordFormat, Recorder};
+     *
+     * fn main() -> Result<(), Box<dyn std::error::Error>> {
+     *     let mut recorder = Recorder::new(ears::init_in()?);
+     *     recorder.start();
+     *     std::thread::sleep(std::time::Duration::from_secs(3));
+     *     recorder.stop();
+     *
+     *     recorder.save_to_file_with_format("take.flac", AudioFormat::Flac(RecordFormat::Pcm24))?;
+     *     recorder.save_to_file_with_format("take.ogg", AudioFormat::OggVorbis)?;
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn save_to_file_with_format<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        format: AudioFormat,
+    ) -> Result<(), SoundError> {
+        let format = match format.sndfile_format() {
+            Some(format) if !self.samples.is_empty() => format,
+            _ => return Err(SoundError::InvalidFormat),
+        };
+        let infos = Box::new(SndInfo {
+            frames: (self.samples.len() / self.recorded_channels) as i64,
+            samplerate: self.ctxt.sample_rate(),
+            channels: self.recorded_channels as i32,
+            format,
+            sections: 0,
+            seekable: 0,
+        });
+
+        let mut file = SndFile::new_with_info(path, Write, infos).map_err(SoundError::SaveError)?;
+        // Some formats need the tags before the samples
+        if let Err(err) = audio_tags::set_sound_tags(&mut file, &self.tags) {
+            file.close();
+            return Err(SoundError::SaveError(err));
+        }
+        let len = self.samples.len() as i64;
+        let written = file.write_i16(&mut self.samples[..], len);
+        file.close();
+        match written == len {
+            true => Ok(()),
+            false => Err(SoundError::SaveError(SndFileError::new(
+                "cannot write all the samples".to_string(),
+            ))),
+        }
+    }
+
+    pub fn save_to_file<P: AsRef<Path>>(&mut self, filename: P) -> bool {
+        let mut file_ext = filename.as_ref().as_os_str().to_owned();
+        file_ext.push(".wav");
+        let format = AudioFormat::Wav(self.format);
+        match self.save_to_file_with_format(file_ext, format) {
+            Ok(()) => true,
+            Err(SoundError::InvalidFormat) => false,
+            Err(e) => {
+                logging::log(LogLevel::Error, &e.to_string());
+                false
+            }
+        }
+    }
+}
+
+/// Measure the RMS and peak levels of samples, 1.0 being full scale.
+fn measure_levels<I: Iterator<Item = i16>>(samples: I) -> (f32, f32) {
+    let (mut sum, mut peak, mut count) = (0.0, 0.0f32, 0);
+    for sample in samples {
+        let value = (sample as f32 / 32767.0).abs().min(1.0);
+        sum += value * value;
+        peak = peak.max(value);
+        count += 1;
+    }
+    match count {
+        0 => (0.0, 0.0),
+        _ => ((sum / count as f32).sqrt(), peak),
+    }
+}
+
+/// Pick the recorded channels out of interleaved captured frames.
+fn map_channels(input: &[i16], input_channels: usize, map: &[usize]) -> Vec<i16> {
+    input
+        .chunks(input_channels)
+        .flat_map(|frame| map.iter().map(move |channel| frame[*channel]))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{map_channels, measure_levels, AudioFormat, RecordFormat, Recorder};
+    use init_in;
+    use sndfile::FormatType::{FormatFlac, FormatFloat, FormatPcmS8, FormatPcmU8, FormatWav};
+    use std::time::Duration;
+
+    #[test]
+    fn recorder_map_channels_OK() -> () {
+        let input = [0, 1, 2, 3, 10, 11, 12, 13];
+
+        assert_eq!(map_channels(&input, 4, &[2, 0]), vec![2, 0, 12, 10]);
+        assert_eq!(map_channels(&input, 4, &[1]), vec![1, 11]);
+        assert_eq!(
+            map_channels(&input, 4, &[3, 3, 3]),
+            vec![3, 3, 3, 13, 13, 13]
+        );
+    }
+
+    #[test]
+    fn recorder_measure_levels_OK() -> () {
+        let (rms, peak) = measure_levels([16383i16, -16383, 32767, -32768].iter().cloned());
+
+        assert!((rms - 0.79).abs() < 0.01);
+        assert_eq!(peak, 1.0);
+        assert_eq!(measure_levels(Vec::new().into_iter()), (0.0, 0.0));
+    }
+
+    #[test]
+    fn recorder_audio_format_OK() -> () {
+        assert_eq!(
+            AudioFormat::Flac(RecordFormat::Pcm8).sndfile_format(),
+            Some((FormatFlac as i32) | (FormatPcmS8 as i32))
+        );
+        assert_eq!(
+            AudioFormat::Flac(RecordFormat::Float).sndfile_format(),
+            None
+        );
+    }
+
+    #[test]
+    fn recorder_record_format_OK() -> () {
+        assert_eq!(
+            RecordFormat::Pcm8.sndfile_format(),
+            (FormatWav as i32) | (FormatPcmU8 as i32)
+        );
+        assert_eq!(
+            RecordFormat::Float.sndfile_format(),
+            (FormatWav as i32) | (FormatFloat as i32)
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn recorder_record_for_OK() -> () {
+        let mut recorder = Recorder::new(init_in().unwrap());
+
+        assert!(recorder.record_for(Duration::from_millis(500)));
+        assert!((recorder.elapsed().as_secs_f64() - 0.5).abs() < 0.001);
+    }
 }
@@ -0,0 +1,67 @@
+//! Resamplers of the AL_SOFT_source_resampler extension.
+
+use internal::OpenAlData;
+use openal::ffi;
+use std::ffi::{CStr, CString};
+use std::mem;
+
+/**
+ * List the resamplers the device provides.
+ *
+ * Sources play at the device sample rate through a resampler, from the
+ * fastest (like "Nearest" or "Linear") to the highest quality (like
+ * "11th order Sinc"). Pass the index of a resampler in this list to
+ * `AudioController::set_resampler`.
+ *
+ * # Return
+ * The names of the resamplers, empty if the AL_SOFT_source_resampler
+ * extension is not present.
+ *
+ * # Example
+ * ```no_run
+ * for (index, name) in ears::resamplers().iter().enumerate() {
+ *     println!("{}: {}", index, name);
+ * }
+ * ```
+ */
+pub fn resamplers() -> Vec<String> {
+    check_openal_context!(Vec::new());
+
+    if !OpenAlData::source_resampler_capable() {
+        return Vec::new();
+    }
+
+    let name = CString::new("alGetStringiSOFT").unwrap();
+    let get_stringi = unsafe { ffi::alGetProcAddress(name.as_ptr()) };
+    if get_stringi.is_null() {
+        return Vec::new();
+    }
+    let get_stringi: ffi::GetStringiFn = unsafe { mem::transmute(get_stringi) };
+
+    let count = unsafe { ffi::alGetInteger(ffi::AL_NUM_RESAMPLERS_SOFT) };
+    (0..count)
+        .map(|index| unsafe {
+            let name = get_stringi(ffi::AL_RESAMPLER_NAME_SOFT, index);
+            match name.is_null() {
+                true => String::new(),
+                false => CStr::from_ptr(name).to_string_lossy().into_owned(),
+            }
+        })
+        .collect()
+}
+
+/**
+ * Get the index of the resampler sources use by default.
+ *
+ * # Return
+ * The index of the default resampler in `resamplers()`, 0 if the
+ * AL_SOFT_source_resampler extension is not present.
+ */
+pub fn default_resampler() -> i32 {
+    check_openal_context!(0);
+
+    match OpenAlData::source_resampler_capable() {
+        true => unsafe { ffi::alGetInteger(ffi::AL_DEFAULT_RESAMPLER_SOFT) },
+        false => 0,
+    }
+}
@@ -1,14 +1,25 @@
+use effect;
 use internal::OpenAlData;
+use logging::{self, LogLevel};
 use openal::{al, ffi};
-use presets::ReverbProperties;
+use presets::{ReverbPreset, ReverbProperties};
 use std::error::Error;
 use std::fmt;
 
-/// All possible errors when opening a Music.
+/// All possible errors when creating or connecting a ReverbEffect.
 pub enum ReverbEffectError {
     /// Happens when OpenAL failed to load for some reason.
     InvalidOpenALContext,
 
+    /// The device doesn't support the EFX extension.
+    EfxUnsupported,
+
+    /// The auxiliary send doesn't exist on the device.
+    InvalidSend(i32),
+
+    /// The device doesn't support chaining effects.
+    ChainingUnsupported,
+
     /// Internal OpenAL error.
     InternalOpenALError(al::AlError),
 }
@@ -20,6 +31,12 @@ impl fmt::Display for ReverbEffectError {
             "{}",
             match self {
                 ReverbEffectError::InvalidOpenALContext => "invalid OpenAL context".to_string(),
+                ReverbEffectError::EfxUnsupported =>
+                    "the device doesn't support effects".to_string(),
+                ReverbEffectError::InvalidSend(send) =>
+                    format!("the auxiliary send {} doesn't exist", send),
+                ReverbEffectError::ChainingUnsupported =>
+                    "the device doesn't support chaining effects".to_string(),
                 ReverbEffectError::InternalOpenALError(err) =>
                     format!("internal OpenAL error: {}", err),
             }
@@ -37,6 +54,9 @@ impl Error for ReverbEffectError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             ReverbEffectError::InvalidOpenALContext => None,
+            ReverbEffectError::EfxUnsupported => None,
+            ReverbEffectError::InvalidSend(_) => None,
+            ReverbEffectError::ChainingUnsupported => None,
             ReverbEffectError::InternalOpenALError(err) => Some(err),
         }
     }
@@ -60,9 +80,10 @@ impl Error for ReverbEffectError {
  * # Examples
  * ```no_run
  * extern crate ears;
- * use ears::{ReverbEffect, ReverbPreset, Sound, SoundError, AudioController};
+ * use ears::{ReverbEffect, ReverbPreset, Sound, AudioController};
+ * use std::error::Error;
  *
- * fn main() -> Result<(), SoundError> {
+ * fn main() -> Result<(), Box<dyn Error>> {
  *    // Create an effect (in this case, using a preset)
  *    let effect = ReverbEffect::preset(ReverbPreset::Cave.properties()).ok();
  *
@@ -70,7 +91,7 @@ impl Error for ReverbEffectError {
  *    let mut sound = Sound::new("path/to/my/sound.ogg")?;
  *
  *    // Connect the sound to the effect
- *    sound.connect(&effect);
+ *    sound.connect(&effect)?;
  *
  *    // Play it
  *    sound.play();
@@ -78,8 +99,8 @@ impl Error for ReverbEffectError {
  *    // Wait until the sound stopped playing
  *    while sound.is_playing() {}
  *
- *    // If you want to disconnect an Effect, just pass None
- *    sound.connect(&None);
+ *    // Disconnect the effect
+ *    sound.disconnect()?;
  *    Ok(())
  * }
  * ```
@@ -87,15 +108,39 @@ impl Error for ReverbEffectError {
 pub struct ReverbEffect {
     effect_id: u32,
     effect_slot_id: u32,
+    eax: bool,
+    properties: ReverbProperties,
 }
 
 impl ReverbEffect {
     pub fn new() -> Result<ReverbEffect, ReverbEffectError> {
+        Self::create(false)
+    }
+
+    /**
+     * Create an EAX reverb effect.
+     *
+     * The EAX reverb has extra parameters over the standard reverb: the
+     * panning of the reflections and of the late reverb, an echo, a
+     * modulation and a gain of the low frequencies. If the device doesn't
+     * support it, a standard reverb is created instead and the extra
+     * parameters are ignored, see `is_eax`.
+     *
+     * # Return
+     * A `Result` containing Ok(ReverbEffect) on success,
+     * Err(ReverbEffectError) if the device doesn't support effects or there
+     * has been an error.
+     */
+    pub fn new_eax() -> Result<ReverbEffect, ReverbEffectError> {
+        Self::create(true)
+    }
+
+    fn create(eax: bool) -> Result<ReverbEffect, ReverbEffectError> {
         check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
 
-        // Can't seem to find a way to query whether or not EFX extension is available
-        // or not... or if that's even necessary, so just assume it's available
-        // and have the error checking sort the rest out.
+        if !OpenAlData::efx_capable() {
+            return Err(ReverbEffectError::EfxUnsupported);
+        }
 
         // Create the auxiliary effect slot
         let mut effect_slot_id = 0;
@@ -105,54 +150,114 @@ impl ReverbEffect {
         let mut effect_id = 0;
         al::alGenEffects(1, &mut effect_id);
 
-        // Assume only "standard reverb" for now. May add EAX reverb at some point.
-        al::alEffecti(effect_id, ffi::AL_EFFECT_TYPE, ffi::AL_EFFECT_REVERB);
+        // Fall back to the standard reverb if the EAX reverb is not supported
+        let mut eax = eax;
+        if eax {
+            al::alEffecti(effect_id, ffi::AL_EFFECT_TYPE, ffi::AL_EFFECT_EAXREVERB);
+            eax = al::openal_has_error().is_none();
+        }
+        if !eax {
+            al::alEffecti(effect_id, ffi::AL_EFFECT_TYPE, ffi::AL_EFFECT_REVERB);
+        }
 
         // Check if there is OpenAL internal error
         if let Some(err) = al::openal_has_error() {
             return Err(ReverbEffectError::InternalOpenALError(err));
         };
 
+        effect::register_slot(effect_slot_id, effect_id);
         Ok(ReverbEffect {
             effect_id,
             effect_slot_id,
+            eax,
+            // The default properties of OpenAL
+            properties: ReverbPreset::Generic.properties(),
         })
     }
 
     pub fn preset(reverb_properties: ReverbProperties) -> Result<ReverbEffect, ReverbEffectError> {
-        match Self::new() {
-            Ok(mut effect) => {
-                effect.set_density(reverb_properties.density);
-                effect.set_diffusion(reverb_properties.diffusion);
-                effect.set_gain(reverb_properties.gain);
-                effect.set_gainhf(reverb_properties.gainhf);
-                effect.set_decay_time(reverb_properties.decay_time);
-                effect.set_decay_hfratio(reverb_properties.decay_hfratio);
-                effect.set_reflections_gain(reverb_properties.reflections_gain);
-                effect.set_reflections_delay(reverb_properties.reflections_delay);
-                effect.set_late_reverb_gain(reverb_properties.late_reverb_gain);
-                effect.set_late_reverb_delay(reverb_properties.late_reverb_delay);
-                effect.set_air_absorption_gainhf(reverb_properties.air_absorption_gainhf);
-                effect.set_room_rolloff_factor(reverb_properties.room_rolloff_factor);
-                effect.set_decay_hflimit(reverb_properties.decay_hflimit);
-
-                // Check if there is OpenAL internal error
-                if let Some(err) = al::openal_has_error() {
-                    return Err(ReverbEffectError::InternalOpenALError(err));
-                };
-
-                effect.update_slot();
-
-                Ok(effect)
-            }
-            Err(e) => Err(e),
-        }
+        Self::new()?.apply(reverb_properties)
+    }
+
+    /**
+     * Create an EAX reverb effect from a preset, using all of its
+     * properties.
+     *
+     * Falls back to a standard reverb like `new_eax`.
+     *
+     * # Argument
+     * * `reverb_properties` - The properties of the reverb
+     *
+     * # Example
+     * ```no_run
+     * use ears::{ReverbEffect, ReverbPreset};
+     *
+     * let effect = ReverbEffect::eax_preset(ReverbPreset::Underwater.properties()).unwrap();
+     * if !effect.is_eax() {
+     *     println!("EAX reverb unsupported, using the standard reverb");
+     * }
+     * ```
+     */
+    pub fn eax_preset(
+        reverb_properties: ReverbProperties,
+    ) -> Result<ReverbEffect, ReverbEffectError> {
+        Self::new_eax()?.apply(reverb_properties)
+    }
+
+    /// Whether the effect is an EAX reverb, or a standard reverb.
+    pub fn is_eax(&self) -> bool {
+        self.eax
+    }
+
+    /// Set all the properties of the reverb, and load the effect in the slot.
+    fn apply(
+        mut self,
+        reverb_properties: ReverbProperties,
+    ) -> Result<ReverbEffect, ReverbEffectError> {
+        self.set_density(reverb_properties.density);
+        self.set_diffusion(reverb_properties.diffusion);
+        self.set_gain(reverb_properties.gain);
+        self.set_gainhf(reverb_properties.gainhf);
+        self.set_decay_time(reverb_properties.decay_time);
+        self.set_decay_hfratio(reverb_properties.decay_hfratio);
+        self.set_reflections_gain(reverb_properties.reflections_gain);
+        self.set_reflections_delay(reverb_properties.reflections_delay);
+        self.set_late_reverb_gain(reverb_properties.late_reverb_gain);
+        self.set_late_reverb_delay(reverb_properties.late_reverb_delay);
+        self.set_air_absorption_gainhf(reverb_properties.air_absorption_gainhf);
+        self.set_room_rolloff_factor(reverb_properties.room_rolloff_factor);
+        self.set_decay_hflimit(reverb_properties.decay_hflimit != 0);
+
+        // The extra parameters of the EAX reverb
+        self.set_gainlf(reverb_properties.gainlf);
+        self.set_decay_lfratio(reverb_properties.decay_lfratio);
+        self.set_reflections_pan(reverb_properties.reflections_pan);
+        self.set_late_reverb_pan(reverb_properties.late_reverb_pan);
+        self.set_echo_time(reverb_properties.echo_time);
+        self.set_echo_depth(reverb_properties.echo_depth);
+        self.set_modulation_time(reverb_properties.modulation_time);
+        self.set_modulation_depth(reverb_properties.modulation_depth);
+        self.set_hf_reference(reverb_properties.hf_reference);
+        self.set_lf_reference(reverb_properties.lf_reference);
+
+        // Check if there is OpenAL internal error
+        if let Some(err) = al::openal_has_error() {
+            return Err(ReverbEffectError::InternalOpenALError(err));
+        };
+
+        Ok(self)
     }
 
     pub fn slot(&self) -> u32 {
         self.effect_slot_id
     }
 
+    /// Get all the properties of the reverb.
+    pub fn get_properties(&self) -> ReverbProperties {
+        self.properties
+    }
+
+    // The slot keeps a copy of the effect, load it again after a change
     fn update_slot(&mut self) {
         check_openal_context!(());
         al::alAuxiliaryEffectSloti(
@@ -162,100 +267,507 @@ impl ReverbEffect {
         );
     }
 
-    fn set_density(&mut self, density: f32) {
-        check_openal_context!(());
-        al::alEffectf(self.effect_id, ffi::AL_REVERB_DENSITY, density);
+    /// The parameter of the standard reverb, or of the EAX reverb.
+    fn param(&self, reverb: i32, eax_reverb: i32) -> i32 {
+        match self.eax {
+            true => eax_reverb,
+            false => reverb,
+        }
     }
 
-    fn set_diffusion(&mut self, diffusion: f32) {
+    /// Set a float parameter of the effect, and load it in the slot.
+    fn set_f(&mut self, param: i32, value: f32) {
         check_openal_context!(());
-        al::alEffectf(self.effect_id, ffi::AL_REVERB_DIFFUSION, diffusion);
+        al::alEffectf(self.effect_id, param, value);
+        self.update_slot();
     }
 
-    fn set_gain(&mut self, gain: f32) {
-        check_openal_context!(());
-        al::alEffectf(self.effect_id, ffi::AL_REVERB_GAIN, gain);
+    /**
+     * Set the density of the reverb, a lower density gives a thinner, more
+     * colored sound.
+     *
+     * # Argument
+     * * `density` - The density, between 0.0 and 1.0. Default is 1.0.
+     */
+    pub fn set_density(&mut self, density: f32) {
+        self.properties.density = density.clamp(0.0, 1.0);
+        let param = self.param(ffi::AL_REVERB_DENSITY, ffi::AL_EAXREVERB_DENSITY);
+        self.set_f(param, self.properties.density);
     }
 
-    fn set_gainhf(&mut self, gainhf: f32) {
-        check_openal_context!(());
-        al::alEffectf(self.effect_id, ffi::AL_REVERB_GAINHF, gainhf);
+    /// Get the density of the reverb.
+    pub fn get_density(&self) -> f32 {
+        self.properties.density
     }
 
-    fn set_decay_time(&mut self, decay_time: f32) {
-        check_openal_context!(());
-        al::alEffectf(self.effect_id, ffi::AL_REVERB_DECAY_TIME, decay_time);
+    /**
+     * Set the diffusion of the reverb, a lower diffusion gives a grainier
+     * sound.
+     *
+     * # Argument
+     * * `diffusion` - The diffusion, between 0.0 and 1.0. Default is 1.0.
+     */
+    pub fn set_diffusion(&mut self, diffusion: f32) {
+        self.properties.diffusion = diffusion.clamp(0.0, 1.0);
+        let param = self.param(ffi::AL_REVERB_DIFFUSION, ffi::AL_EAXREVERB_DIFFUSION);
+        self.set_f(param, self.properties.diffusion);
     }
 
-    fn set_decay_hfratio(&mut self, decay_hfratio: f32) {
-        check_openal_context!(());
-        al::alEffectf(self.effect_id, ffi::AL_REVERB_DECAY_HFRATIO, decay_hfratio);
+    /// Get the diffusion of the reverb.
+    pub fn get_diffusion(&self) -> f32 {
+        self.properties.diffusion
     }
 
-    fn set_reflections_gain(&mut self, reflections_gain: f32) {
-        check_openal_context!(());
-        al::alEffectf(
-            self.effect_id,
+    /**
+     * Set the volume of the reverb.
+     *
+     * # Argument
+     * * `gain` - The gain, between 0.0 and 1.0. Default is 0.32.
+     */
+    pub fn set_gain(&mut self, gain: f32) {
+        self.properties.gain = gain.clamp(0.0, 1.0);
+        let param = self.param(ffi::AL_REVERB_GAIN, ffi::AL_EAXREVERB_GAIN);
+        self.set_f(param, self.properties.gain);
+    }
+
+    /// Get the volume of the reverb.
+    pub fn get_gain(&self) -> f32 {
+        self.properties.gain
+    }
+
+    /**
+     * Set the additional gain of the high frequencies of the reverb.
+     *
+     * # Argument
+     * * `gainhf` - The gain, between 0.0 and 1.0. Default is 0.89.
+     */
+    pub fn set_gainhf(&mut self, gainhf: f32) {
+        self.properties.gainhf = gainhf.clamp(0.0, 1.0);
+        let param = self.param(ffi::AL_REVERB_GAINHF, ffi::AL_EAXREVERB_GAINHF);
+        self.set_f(param, self.properties.gainhf);
+    }
+
+    /// Get the additional gain of the high frequencies of the reverb.
+    pub fn get_gainhf(&self) -> f32 {
+        self.properties.gainhf
+    }
+
+    /**
+     * Set how long the late reverb takes to fade out.
+     *
+     * # Argument
+     * * `decay_time` - The time in seconds, between 0.1 and 20.0. Default is 1.49.
+     */
+    pub fn set_decay_time(&mut self, decay_time: f32) {
+        self.properties.decay_time = decay_time.clamp(0.1, 20.0);
+        let param = self.param(ffi::AL_REVERB_DECAY_TIME, ffi::AL_EAXREVERB_DECAY_TIME);
+        self.set_f(param, self.properties.decay_time);
+    }
+
+    /// Get how long the late reverb takes to fade out, in seconds.
+    pub fn get_decay_time(&self) -> f32 {
+        self.properties.decay_time
+    }
+
+    /**
+     * Set the decay time of the high frequencies, relative to `decay_time`.
+     *
+     * # Argument
+     * * `decay_hfratio` - The ratio, between 0.1 and 2.0. Default is 0.83.
+     */
+    pub fn set_decay_hfratio(&mut self, decay_hfratio: f32) {
+        self.properties.decay_hfratio = decay_hfratio.clamp(0.1, 2.0);
+        let param = self.param(
+            ffi::AL_REVERB_DECAY_HFRATIO,
+            ffi::AL_EAXREVERB_DECAY_HFRATIO,
+        );
+        self.set_f(param, self.properties.decay_hfratio);
+    }
+
+    /// Get the decay time of the high frequencies, relative to `decay_time`.
+    pub fn get_decay_hfratio(&self) -> f32 {
+        self.properties.decay_hfratio
+    }
+
+    /**
+     * Set the volume of the early reflections.
+     *
+     * # Argument
+     * * `reflections_gain` - The gain, between 0.0 and 3.16. Default is 0.05.
+     */
+    pub fn set_reflections_gain(&mut self, reflections_gain: f32) {
+        self.properties.reflections_gain = reflections_gain.clamp(0.0, 3.16);
+        let param = self.param(
             ffi::AL_REVERB_REFLECTIONS_GAIN,
-            reflections_gain,
+            ffi::AL_EAXREVERB_REFLECTIONS_GAIN,
         );
+        self.set_f(param, self.properties.reflections_gain);
     }
 
-    fn set_reflections_delay(&mut self, reflections_delay: f32) {
-        check_openal_context!(());
-        al::alEffectf(
-            self.effect_id,
+    /// Get the volume of the early reflections.
+    pub fn get_reflections_gain(&self) -> f32 {
+        self.properties.reflections_gain
+    }
+
+    /**
+     * Set the delay between the sound and the early reflections.
+     *
+     * # Argument
+     * * `reflections_delay` - The delay in seconds, between 0.0 and 0.3. Default is 0.007.
+     */
+    pub fn set_reflections_delay(&mut self, reflections_delay: f32) {
+        self.properties.reflections_delay = reflections_delay.clamp(0.0, 0.3);
+        let param = self.param(
             ffi::AL_REVERB_REFLECTIONS_DELAY,
-            reflections_delay,
+            ffi::AL_EAXREVERB_REFLECTIONS_DELAY,
         );
+        self.set_f(param, self.properties.reflections_delay);
     }
 
-    fn set_late_reverb_gain(&mut self, late_reverb_gain: f32) {
-        check_openal_context!(());
-        al::alEffectf(
-            self.effect_id,
+    /// Get the delay of the early reflections, in seconds.
+    pub fn get_reflections_delay(&self) -> f32 {
+        self.properties.reflections_delay
+    }
+
+    /**
+     * Set the volume of the late reverb.
+     *
+     * # Argument
+     * * `late_reverb_gain` - The gain, between 0.0 and 10.0. Default is 1.26.
+     */
+    pub fn set_late_reverb_gain(&mut self, late_reverb_gain: f32) {
+        self.properties.late_reverb_gain = late_reverb_gain.clamp(0.0, 10.0);
+        let param = self.param(
             ffi::AL_REVERB_LATE_REVERB_GAIN,
-            late_reverb_gain,
+            ffi::AL_EAXREVERB_LATE_REVERB_GAIN,
         );
+        self.set_f(param, self.properties.late_reverb_gain);
     }
 
-    fn set_late_reverb_delay(&mut self, late_reverb_delay: f32) {
-        check_openal_context!(());
-        al::alEffectf(
-            self.effect_id,
+    /// Get the volume of the late reverb.
+    pub fn get_late_reverb_gain(&self) -> f32 {
+        self.properties.late_reverb_gain
+    }
+
+    /**
+     * Set the delay between the early reflections and the late reverb.
+     *
+     * # Argument
+     * * `late_reverb_delay` - The delay in seconds, between 0.0 and 0.1. Default is 0.011.
+     */
+    pub fn set_late_reverb_delay(&mut self, late_reverb_delay: f32) {
+        self.properties.late_reverb_delay = late_reverb_delay.clamp(0.0, 0.1);
+        let param = self.param(
             ffi::AL_REVERB_LATE_REVERB_DELAY,
-            late_reverb_delay,
+            ffi::AL_EAXREVERB_LATE_REVERB_DELAY,
         );
+        self.set_f(param, self.properties.late_reverb_delay);
     }
 
-    fn set_air_absorption_gainhf(&mut self, air_absorption_gainhf: f32) {
-        check_openal_context!(());
-        al::alEffectf(
-            self.effect_id,
+    /// Get the delay of the late reverb, in seconds.
+    pub fn get_late_reverb_delay(&self) -> f32 {
+        self.properties.late_reverb_delay
+    }
+
+    /**
+     * Set how much the air absorbs the high frequencies of the reflections.
+     *
+     * # Argument
+     * * `air_absorption_gainhf` - The gain per meter, between 0.892 and 1.0. Default is 0.994.
+     */
+    pub fn set_air_absorption_gainhf(&mut self, air_absorption_gainhf: f32) {
+        self.properties.air_absorption_gainhf = air_absorption_gainhf.clamp(0.892, 1.0);
+        let param = self.param(
             ffi::AL_REVERB_AIR_ABSORPTION_GAINHF,
-            air_absorption_gainhf,
+            ffi::AL_EAXREVERB_AIR_ABSORPTION_GAINHF,
         );
+        self.set_f(param, self.properties.air_absorption_gainhf);
     }
 
-    fn set_room_rolloff_factor(&mut self, room_rolloff_factor: f32) {
-        check_openal_context!(());
-        al::alEffectf(
-            self.effect_id,
+    /// Get how much the air absorbs the high frequencies of the reflections.
+    pub fn get_air_absorption_gainhf(&self) -> f32 {
+        self.properties.air_absorption_gainhf
+    }
+
+    /**
+     * Set how fast the reverb fades with the distance of the Audio Sources.
+     *
+     * # Argument
+     * * `room_rolloff_factor` - The rolloff factor, between 0.0 and 10.0. Default is 0.0.
+     */
+    pub fn set_room_rolloff_factor(&mut self, room_rolloff_factor: f32) {
+        self.properties.room_rolloff_factor = room_rolloff_factor.clamp(0.0, 10.0);
+        let param = self.param(
             ffi::AL_REVERB_ROOM_ROLLOFF_FACTOR,
-            room_rolloff_factor,
+            ffi::AL_EAXREVERB_ROOM_ROLLOFF_FACTOR,
         );
+        self.set_f(param, self.properties.room_rolloff_factor);
+    }
+
+    /// Get how fast the reverb fades with the distance.
+    pub fn get_room_rolloff_factor(&self) -> f32 {
+        self.properties.room_rolloff_factor
     }
 
-    fn set_decay_hflimit(&mut self, decay_hflimit: i32) {
+    /**
+     * Set whether the high frequencies decay as fast as the air absorbs them,
+     * which limits `decay_hfratio`.
+     *
+     * # Argument
+     * * `decay_hflimit` - Whether the decay of the high frequencies is
+     *   limited. Default is true.
+     */
+    pub fn set_decay_hflimit(&mut self, decay_hflimit: bool) {
         check_openal_context!(());
-        al::alEffecti(self.effect_id, ffi::AL_REVERB_DECAY_HFLIMIT, decay_hflimit);
+        self.properties.decay_hflimit = decay_hflimit as i32;
+        let param = self.param(
+            ffi::AL_REVERB_DECAY_HFLIMIT,
+            ffi::AL_EAXREVERB_DECAY_HFLIMIT,
+        );
+        al::alEffecti(self.effect_id, param, self.properties.decay_hflimit);
+        self.update_slot();
+    }
+
+    /// Get whether the decay of the high frequencies is limited.
+    pub fn get_decay_hflimit(&self) -> bool {
+        self.properties.decay_hflimit != 0
+    }
+
+    /**
+     * Set the additional gain of the low frequencies of the reverb.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `gainlf` - The gain, between 0.0 and 1.0. Default is 1.0.
+     */
+    pub fn set_gainlf(&mut self, gainlf: f32) {
+        self.properties.gainlf = gainlf.clamp(0.0, 1.0);
+        if self.eax {
+            self.set_f(ffi::AL_EAXREVERB_GAINLF, self.properties.gainlf);
+        }
+    }
+
+    /// Get the additional gain of the low frequencies of the reverb.
+    pub fn get_gainlf(&self) -> f32 {
+        self.properties.gainlf
+    }
+
+    /**
+     * Set the decay time of the low frequencies, relative to `decay_time`.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `decay_lfratio` - The ratio, between 0.1 and 2.0. Default is 1.0.
+     */
+    pub fn set_decay_lfratio(&mut self, decay_lfratio: f32) {
+        self.properties.decay_lfratio = decay_lfratio.clamp(0.1, 2.0);
+        if self.eax {
+            self.set_f(
+                ffi::AL_EAXREVERB_DECAY_LFRATIO,
+                self.properties.decay_lfratio,
+            );
+        }
+    }
+
+    /// Get the decay time of the low frequencies, relative to `decay_time`.
+    pub fn get_decay_lfratio(&self) -> f32 {
+        self.properties.decay_lfratio
+    }
+
+    /**
+     * Set the direction the early reflections come from.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `reflections_pan` - The direction, a vector of length up to 1.0, longer to focus
+     *   the early reflections in that direction. Default is [0.0, 0.0, 0.0], all around.
+     */
+    pub fn set_reflections_pan(&mut self, reflections_pan: [f32; 3]) {
+        check_openal_context!();
+        self.properties.reflections_pan = reflections_pan;
+        if self.eax {
+            al::alEffectfv(
+                self.effect_id,
+                ffi::AL_EAXREVERB_REFLECTIONS_PAN,
+                reflections_pan.as_ptr(),
+            );
+            self.update_slot();
+        }
+    }
+
+    /// Get the direction the early reflections come from.
+    pub fn get_reflections_pan(&self) -> [f32; 3] {
+        self.properties.reflections_pan
+    }
+
+    /**
+     * Set the direction the late reverb come from.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `late_reverb_pan` - The direction, a vector of length up to 1.0, longer to focus
+     *   the late reverb in that direction. Default is [0.0, 0.0, 0.0], all around.
+     */
+    pub fn set_late_reverb_pan(&mut self, late_reverb_pan: [f32; 3]) {
+        check_openal_context!();
+        self.properties.late_reverb_pan = late_reverb_pan;
+        if self.eax {
+            al::alEffectfv(
+                self.effect_id,
+                ffi::AL_EAXREVERB_LATE_REVERB_PAN,
+                late_reverb_pan.as_ptr(),
+            );
+            self.update_slot();
+        }
+    }
+
+    /// Get the direction the late reverb come from.
+    pub fn get_late_reverb_pan(&self) -> [f32; 3] {
+        self.properties.late_reverb_pan
+    }
+
+    /**
+     * Set the delay between the echoes of the late reverb.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `echo_time` - The time in seconds, between 0.075 and 0.25. Default is 0.25.
+     */
+    pub fn set_echo_time(&mut self, echo_time: f32) {
+        self.properties.echo_time = echo_time.clamp(0.075, 0.25);
+        if self.eax {
+            self.set_f(ffi::AL_EAXREVERB_ECHO_TIME, self.properties.echo_time);
+        }
+    }
+
+    /// Get the delay between the echoes, in seconds.
+    pub fn get_echo_time(&self) -> f32 {
+        self.properties.echo_time
+    }
+
+    /**
+     * Set how strong the echoes of the late reverb are.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `echo_depth` - The depth, between 0.0 and 1.0. Default is 0.0.
+     */
+    pub fn set_echo_depth(&mut self, echo_depth: f32) {
+        self.properties.echo_depth = echo_depth.clamp(0.0, 1.0);
+        if self.eax {
+            self.set_f(ffi::AL_EAXREVERB_ECHO_DEPTH, self.properties.echo_depth);
+        }
+    }
+
+    /// Get how strong the echoes are.
+    pub fn get_echo_depth(&self) -> f32 {
+        self.properties.echo_depth
+    }
+
+    /**
+     * Set the period of the pitch modulation of the late reverb.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `modulation_time` - The time in seconds, between 0.04 and 4.0. Default is 0.25.
+     */
+    pub fn set_modulation_time(&mut self, modulation_time: f32) {
+        self.properties.modulation_time = modulation_time.clamp(0.04, 4.0);
+        if self.eax {
+            self.set_f(
+                ffi::AL_EAXREVERB_MODULATION_TIME,
+                self.properties.modulation_time,
+            );
+        }
+    }
+
+    /// Get the period of the pitch modulation, in seconds.
+    pub fn get_modulation_time(&self) -> f32 {
+        self.properties.modulation_time
+    }
+
+    /**
+     * Set how much the pitch of the late reverb is modulated.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `modulation_depth` - The depth, between 0.0 and 1.0. Default is 0.0.
+     */
+    pub fn set_modulation_depth(&mut self, modulation_depth: f32) {
+        self.properties.modulation_depth = modulation_depth.clamp(0.0, 1.0);
+        if self.eax {
+            self.set_f(
+                ffi::AL_EAXREVERB_MODULATION_DEPTH,
+                self.properties.modulation_depth,
+            );
+        }
+    }
+
+    /// Get how much the pitch of the late reverb is modulated.
+    pub fn get_modulation_depth(&self) -> f32 {
+        self.properties.modulation_depth
+    }
+
+    /**
+     * Set the frequency above which `gainhf` applies.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `hf_reference` - The frequency in Hz, between 1000.0 and 20000.0. Default is 5000.0.
+     */
+    pub fn set_hf_reference(&mut self, hf_reference: f32) {
+        self.properties.hf_reference = hf_reference.clamp(1000.0, 20000.0);
+        if self.eax {
+            self.set_f(ffi::AL_EAXREVERB_HFREFERENCE, self.properties.hf_reference);
+        }
+    }
+
+    /// Get the frequency above which `gainhf` applies, in Hz.
+    pub fn get_hf_reference(&self) -> f32 {
+        self.properties.hf_reference
+    }
+
+    /**
+     * Set the frequency below which `gainlf` applies.
+     *
+     * Only used by the EAX reverb, see `is_eax`.
+     *
+     * # Argument
+     * * `lf_reference` - The frequency in Hz, between 20.0 and 1000.0. Default is 250.0.
+     */
+    pub fn set_lf_reference(&mut self, lf_reference: f32) {
+        self.properties.lf_reference = lf_reference.clamp(20.0, 1000.0);
+        if self.eax {
+            self.set_f(ffi::AL_EAXREVERB_LFREFERENCE, self.properties.lf_reference);
+        }
+    }
+
+    /// Get the frequency below which `gainlf` applies, in Hz.
+    pub fn get_lf_reference(&self) -> f32 {
+        self.properties.lf_reference
     }
 }
 
 impl Drop for ReverbEffect {
     // Delete the Effect Object and Auxiliary Effect Slot Object
     fn drop(&mut self) -> () {
-        check_openal_context!(());
+        // A bus may still hold the slot
+        if !effect::release_slot(self.effect_slot_id) {
+            return;
+        }
+        // The objects were deleted with the context
+        if !OpenAlData::is_created() {
+            return;
+        }
 
         // Disconnect the effect and slot
         al::alAuxiliaryEffectSloti(
@@ -273,9 +785,56 @@ impl Drop for ReverbEffect {
         //
         // TODO: this could probably be avoided with some better design
         if let Some(err) = al::openal_has_error() {
-            eprintln!("Ears failed to drop ReverbEffect completely, one or more source is probably still referencing it: {}", err);
-            eprintln!("\tEffect Object: {}", self.effect_id);
-            eprintln!("\tAuxiliary Effect Slot: {}", self.effect_slot_id);
+            let message = format!(
+                "Ears failed to drop ReverbEffect completely, one or more source is probably still referencing it: {}\n\tEffect Object: {}\n\tAuxiliary Effect Slot: {}",
+                err, self.effect_id, self.effect_slot_id
+            );
+            logging::log(LogLevel::Warning, &message);
         };
     }
 }
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use presets::ReverbPreset;
+    use reverb_effect::ReverbEffect;
+
+    #[test]
+    #[ignore]
+    fn reverb_effect_eax_preset_OK() -> () {
+        let effect = ReverbEffect::eax_preset(ReverbPreset::Cave.properties())
+            .expect("Cannot create the effect");
+
+        assert!(effect.slot() != 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn reverb_effect_preset_is_not_eax_OK() -> () {
+        let effect = ReverbEffect::preset(ReverbPreset::Cave.properties())
+            .expect("Cannot create the effect");
+
+        assert!(!effect.is_eax());
+    }
+
+    #[test]
+    #[ignore]
+    fn reverb_effect_set_decay_time_OK() -> () {
+        let mut effect = ReverbEffect::new().expect("Cannot create the effect");
+
+        effect.set_decay_time(5.0);
+        assert_eq!(effect.get_decay_time(), 5.0);
+        assert_eq!(effect.get_properties().decay_time, 5.0);
+    }
+
+    #[test]
+    #[ignore]
+    fn reverb_effect_set_reflections_pan_not_eax_OK() -> () {
+        let mut effect = ReverbEffect::new().expect("Cannot create the effect");
+
+        effect.set_reflections_pan([1.0, 0.0, 0.0]);
+        assert_eq!(effect.get_reflections_pan(), [1.0, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,146 @@
+//! The ring modulator effect.
+
+use effect::{Effect, EffectSlot};
+use openal::ffi;
+use reverb_effect::ReverbEffectError;
+
+/// The shape of the carrier of a ring modulator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RingModulatorWaveform {
+    Sinusoid,
+    Sawtooth,
+    Square,
+}
+
+impl RingModulatorWaveform {
+    fn al_waveform(self) -> i32 {
+        match self {
+            RingModulatorWaveform::Sinusoid => ffi::AL_RING_MODULATOR_SINUSOID,
+            RingModulatorWaveform::Sawtooth => ffi::AL_RING_MODULATOR_SAWTOOTH,
+            RingModulatorWaveform::Square => ffi::AL_RING_MODULATOR_SQUARE,
+        }
+    }
+}
+
+/**
+ * A ring modulator effect, multiplying the Audio Sources connected to it
+ * with a carrier.
+ *
+ * A low frequency carrier gives a tremolo, a higher one a metallic,
+ * robotic sound.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, RingModulatorEffect, Sound};
+ * use std::error::Error;
+ *
+ * fn main() -> Result<(), Box<dyn Error>> {
+ *     let mut robot = RingModulatorEffect::new()?;
+ *     robot.set_frequency(60.0);
+ *
+ *     let mut snd = Sound::new("path/to/my/voice.ogg")?;
+ *     snd.connect(&robot)?;
+ *     snd.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+pub struct RingModulatorEffect {
+    effect: EffectSlot,
+    frequency: f32,
+    highpass_cutoff: f32,
+    waveform: RingModulatorWaveform,
+}
+
+impl RingModulatorEffect {
+    /**
+     * Create a ring modulator effect with the default settings.
+     *
+     * # Return
+     * A `Result` containing Ok(RingModulatorEffect) on success,
+     * Err(ReverbEffectError) if the device doesn't support effects or there
+     * has been an error.
+     */
+    pub fn new() -> Result<RingModulatorEffect, ReverbEffectError> {
+        Ok(RingModulatorEffect {
+            effect: EffectSlot::new(ffi::AL_EFFECT_RING_MODULATOR)?,
+            frequency: 440.0,
+            highpass_cutoff: 800.0,
+            waveform: RingModulatorWaveform::Sinusoid,
+        })
+    }
+
+    /**
+     * Set the frequency of the carrier.
+     *
+     * # Argument
+     * * `frequency` - The frequency in Hz, between 0.0 and 8000.0. Default is 440.0.
+     */
+    pub fn set_frequency(&mut self, frequency: f32) {
+        self.frequency = frequency.clamp(0.0, 8000.0);
+        self.effect
+            .set_f(ffi::AL_RING_MODULATOR_FREQUENCY, self.frequency);
+    }
+
+    /// Get the frequency of the carrier, in Hz.
+    pub fn get_frequency(&self) -> f32 {
+        self.frequency
+    }
+
+    /**
+     * Set the cutoff frequency of the high pass filter applied before the
+     * modulation.
+     *
+     * # Argument
+     * * `highpass_cutoff` - The frequency in Hz, between 0.0 and 24000.0. Default is 800.0.
+     */
+    pub fn set_highpass_cutoff(&mut self, highpass_cutoff: f32) {
+        self.highpass_cutoff = highpass_cutoff.clamp(0.0, 24000.0);
+        self.effect
+            .set_f(ffi::AL_RING_MODULATOR_HIGHPASS_CUTOFF, self.highpass_cutoff);
+    }
+
+    /// Get the cutoff frequency of the high pass filter, in Hz.
+    pub fn get_highpass_cutoff(&self) -> f32 {
+        self.highpass_cutoff
+    }
+
+    /**
+     * Set the shape of the carrier.
+     *
+     * # Argument
+     * * `waveform` - The waveform. Default is `RingModulatorWaveform::Sinusoid`.
+     */
+    pub fn set_waveform(&mut self, waveform: RingModulatorWaveform) {
+        self.waveform = waveform;
+        self.effect
+            .set_i(ffi::AL_RING_MODULATOR_WAVEFORM, waveform.al_waveform());
+    }
+
+    /// Get the shape of the carrier.
+    pub fn get_waveform(&self) -> RingModulatorWaveform {
+        self.waveform
+    }
+}
+
+impl Effect for RingModulatorEffect {
+    fn slot(&self) -> u32 {
+        self.effect.slot()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use ring_modulator_effect::{RingModulatorEffect, RingModulatorWaveform};
+
+    #[test]
+    #[ignore]
+    fn ring_modulator_effect_set_waveform_OK() -> () {
+        let mut ring = RingModulatorEffect::new().expect("Cannot create the effect");
+
+        ring.set_waveform(RingModulatorWaveform::Square);
+        assert_eq!(ring.get_waveform(), RingModulatorWaveform::Square);
+    }
+}
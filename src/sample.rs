@@ -0,0 +1,137 @@
+//! The types the samples are decoded to, 16 bits or 32 bits float.
+
+use internal::OpenAlData;
+use openal::ffi;
+use sndfile::FormatType::{FormatDouble, FormatFloat, FormatPcm24, FormatPcm32, FormatSubMask};
+use sndfile::SndFile;
+
+/// A type samples can be decoded to and given to OpenAL.
+pub(crate) trait Sample: Copy + Default + Send + 'static {
+    /// The value of a full scale sample, as a float.
+    const FULL_SCALE: f32;
+
+    /// Read interleaved samples from a file, returning how many were read.
+    fn read(file: &mut SndFile, samples: &mut [Self], items: i64) -> i64;
+
+    /// Convert the sample to a float, in the range of the type.
+    fn to_f32(self) -> f32;
+
+    /// Convert a float in the range of the type to a sample.
+    fn from_f32(value: f32) -> Self;
+
+    /// Call a function on the samples converted to 16 bits, then convert them back.
+    fn with_i16<F: FnOnce(&mut [i16])>(samples: &mut [Self], f: F);
+}
+
+impl Sample for i16 {
+    const FULL_SCALE: f32 = 32768.0;
+
+    fn read(file: &mut SndFile, samples: &mut [i16], items: i64) -> i64 {
+        file.read_i16(samples, items)
+    }
+
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(value: f32) -> i16 {
+        value as i16
+    }
+
+    fn with_i16<F: FnOnce(&mut [i16])>(samples: &mut [i16], f: F) {
+        f(samples)
+    }
+}
+
+impl Sample for f32 {
+    const FULL_SCALE: f32 = 1.0;
+
+    fn read(file: &mut SndFile, samples: &mut [f32], items: i64) -> i64 {
+        file.read_f32(samples, items)
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> f32 {
+        value
+    }
+
+    fn with_i16<F: FnOnce(&mut [i16])>(samples: &mut [f32], f: F) {
+        let mut converted: Vec<i16> = samples
+            .iter()
+            .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+            .collect();
+        f(&mut converted);
+        for (sample, value) in samples.iter_mut().zip(converted) {
+            *sample = value as f32 / i16::MAX as f32;
+        }
+    }
+}
+
+/**
+ * Get the float format to decode a file to, so its samples aren't truncated
+ * to 16 bits.
+ *
+ * # Arguments
+ * * `format` - The libsndfile format of the file
+ * * `channels` - The number of channels the samples are decoded to
+ *
+ * # Return
+ * The OpenAL float format of the file, None if the file holds 16 bits or
+ * less per sample, or if the OpenAL implementation doesn't support float
+ * samples with this number of channels.
+ */
+pub(crate) fn float_format(format: i32, channels: i32) -> Option<i32> {
+    if !is_high_resolution(format) || !OpenAlData::extension_present("AL_EXT_FLOAT32") {
+        return None;
+    }
+    match channels {
+        1 => Some(ffi::AL_FORMAT_MONO_FLOAT32),
+        2 => Some(ffi::AL_FORMAT_STEREO_FLOAT32),
+        _ if !OpenAlData::extension_present("AL_EXT_MCFORMATS") => None,
+        4 => Some(ffi::AL_FORMAT_QUAD32),
+        6 => Some(ffi::AL_FORMAT_51CHN32),
+        7 => Some(ffi::AL_FORMAT_61CHN32),
+        8 => Some(ffi::AL_FORMAT_71CHN32),
+        _ => None,
+    }
+}
+
+/// Check if a file holds more than 16 bits per sample.
+fn is_high_resolution(format: i32) -> bool {
+    let encoding = format & FormatSubMask as i32;
+    [FormatPcm24, FormatPcm32, FormatFloat, FormatDouble]
+        .iter()
+        .any(|high| *high as i32 == encoding)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{is_high_resolution, Sample};
+    use sndfile::FormatType::{
+        FormatFlac, FormatFloat, FormatOgg, FormatPcm16, FormatPcm24, FormatVorbis, FormatWav,
+    };
+
+    #[test]
+    fn sample_is_high_resolution_OK() -> () {
+        assert!(is_high_resolution(FormatWav as i32 | FormatFloat as i32));
+        assert!(is_high_resolution(FormatFlac as i32 | FormatPcm24 as i32));
+    }
+
+    #[test]
+    fn sample_is_high_resolution_FAIL() -> () {
+        assert!(!is_high_resolution(FormatWav as i32 | FormatPcm16 as i32));
+        assert!(!is_high_resolution(FormatOgg as i32 | FormatVorbis as i32));
+    }
+
+    #[test]
+    fn sample_conversion_OK() -> () {
+        assert_eq!(i16::from_f32(40000.0), 32767);
+        assert_eq!(i16::from_f32(-1.5), -1);
+        assert_eq!(f32::from_f32(0.25).to_f32(), 0.25);
+    }
+}
@@ -0,0 +1,230 @@
+//! Playback started at a given time on the clock of the device.
+
+use clock;
+use internal::OpenAlData;
+use libc::c_void;
+use openal::{al, ffi};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::mem;
+use std::sync::Mutex;
+use std::thread;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// The longest the scheduling thread sleeps, to notice earlier starts
+const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+lazy_static! {
+    /// The sources waiting to start without the AL_SOFT_source_start_delay
+    /// extension, and whether the scheduling thread runs
+    static ref SCHEDULED: Mutex<(HashMap<u32, Instant>, bool)> =
+        Mutex::new((HashMap::new(), false));
+}
+
+thread_local! {
+    /// The sources started by a SourceGroup, played together once collected
+    static COLLECTED: RefCell<Option<Vec<u32>>> = const { RefCell::new(None) };
+
+    /// The sources paused or stopped by a SourceGroup, halted together once
+    /// collected
+    static HALTED: RefCell<Option<Vec<u32>>> = const { RefCell::new(None) };
+}
+
+/**
+ * Start playing a source, now or at a time on the device clock.
+ *
+ * With the AL_SOFT_source_start_delay extension the device starts the
+ * source at the exact sample, otherwise a background thread plays it as
+ * close to the time as the system allows. A time in the past plays the
+ * source right away.
+ *
+ * A source waiting to start is rewound, unless it is paused: until then it
+ * is in the initial state instead of looking like it stopped playing.
+ *
+ * # Arguments
+ * * `source` - The source to play
+ * * `time` - The time on the device clock, see `ears::device_clock`, or
+ *   `None` to play now
+ */
+pub(crate) fn play(source: u32, time: Option<Duration>) {
+    cancel(source);
+    if time.is_some() || is_collecting() {
+        rewind(source);
+    }
+    let time = match time {
+        Some(time) => time,
+        None if collect(source) => return,
+        None => return al::alSourcePlay(source),
+    };
+
+    if let Some(play_at_time) = play_at_time_fn() {
+        return unsafe { play_at_time(source, time.as_nanos() as i64) };
+    }
+
+    let delay = time.saturating_sub(clock::device_clock().unwrap_or(time));
+    if delay.is_zero() {
+        return al::alSourcePlay(source);
+    }
+    let mut scheduled = SCHEDULED.lock().unwrap();
+    scheduled.0.insert(source, Instant::now() + delay);
+    if !scheduled.1 {
+        scheduled.1 = true;
+        thread::Builder::new()
+            .name(String::from("ears-schedule"))
+            .spawn(start_scheduled)
+            .unwrap();
+    }
+}
+
+/**
+ * Start playing, at once, all the sources the action starts.
+ *
+ * The sources the action plays now on this thread are collected instead of
+ * being started, then started by a single alSourcePlayv call so they begin
+ * on the same sample.
+ */
+pub(crate) fn play_together<F: FnOnce()>(action: F) {
+    COLLECTED.with(|collected| *collected.borrow_mut() = Some(Vec::new()));
+    action();
+    let sources = COLLECTED.with(|collected| collected.borrow_mut().take().unwrap_or_default());
+    if !sources.is_empty() {
+        al::alSourcePlayv(&sources);
+    }
+}
+
+/// Pause a source, or keep it to pause with `pause_together`.
+pub(crate) fn pause(source: u32) {
+    cancel(source);
+    if !halt_collected(source) {
+        al::alSourcePause(source);
+    }
+}
+
+/// Stop a source, or keep it to stop with `stop_together`.
+pub(crate) fn stop(source: u32) {
+    cancel(source);
+    if !halt_collected(source) {
+        al::alSourceStop(source);
+    }
+}
+
+/**
+ * Pause, at once, all the sources the action pauses.
+ *
+ * The sources the action pauses on this thread are collected, then paused
+ * by a single alSourcePausev call so they stop on the same sample.
+ */
+pub(crate) fn pause_together<F: FnOnce()>(action: F) {
+    let sources = collect_halted(action);
+    if !sources.is_empty() {
+        al::alSourcePausev(&sources);
+    }
+}
+
+/**
+ * Stop, at once, all the sources the action stops.
+ *
+ * The sources the action stops on this thread are collected, then stopped
+ * by a single alSourceStopv call.
+ */
+pub(crate) fn stop_together<F: FnOnce()>(action: F) {
+    let sources = collect_halted(action);
+    if !sources.is_empty() {
+        al::alSourceStopv(&sources);
+    }
+}
+
+/// Run the action, getting the sources it paused or stopped.
+fn collect_halted<F: FnOnce()>(action: F) -> Vec<u32> {
+    HALTED.with(|halted| *halted.borrow_mut() = Some(Vec::new()));
+    action();
+    HALTED.with(|halted| halted.borrow_mut().take().unwrap_or_default())
+}
+
+/// Keep a source to halt with the others, if they are collected.
+fn halt_collected(source: u32) -> bool {
+    HALTED.with(|halted| match *halted.borrow_mut() {
+        Some(ref mut sources) => {
+            sources.push(source);
+            true
+        }
+        None => false,
+    })
+}
+
+/// Check if `play_together` is collecting the sources started on this thread.
+fn is_collecting() -> bool {
+    COLLECTED.with(|collected| collected.borrow().is_some())
+}
+
+/// Move a source which isn't paused to the initial state.
+fn rewind(source: u32) {
+    if al::alGetState(source) != ffi::AL_PAUSED {
+        al::alSourceRewind(source);
+    }
+}
+
+/// Keep a source to start with `play_together`, if it is collecting.
+fn collect(source: u32) -> bool {
+    COLLECTED.with(|collected| match *collected.borrow_mut() {
+        Some(ref mut sources) => {
+            sources.push(source);
+            true
+        }
+        None => false,
+    })
+}
+
+/// Forget the scheduled start of a source, which was stopped or deleted.
+pub(crate) fn cancel(source: u32) {
+    SCHEDULED.lock().unwrap().0.remove(&source);
+}
+
+/// Forget all the scheduled starts, when the context is cleaned up.
+pub(crate) fn clear() {
+    SCHEDULED.lock().unwrap().0.clear();
+}
+
+/// Get alSourcePlayAtTimeSOFT, if the device supports it.
+fn play_at_time_fn() -> Option<ffi::SourcePlayAtTimeFn> {
+    if !OpenAlData::source_start_delay_capable() {
+        return None;
+    }
+
+    let name = CString::new("alSourcePlayAtTimeSOFT").unwrap();
+    let play_at_time = unsafe { ffi::alGetProcAddress(name.as_ptr()) };
+    if play_at_time.is_null() {
+        return None;
+    }
+    Some(unsafe { mem::transmute::<*mut c_void, ffi::SourcePlayAtTimeFn>(play_at_time) })
+}
+
+fn start_scheduled() {
+    loop {
+        let next = {
+            let mut scheduled = SCHEDULED.lock().unwrap();
+            let now = Instant::now();
+            scheduled.0.retain(|source, start| {
+                if *start > now {
+                    return true;
+                }
+                al::alSourcePlay(*source);
+                false
+            });
+            match scheduled.0.values().min() {
+                Some(next) => *next,
+                None => {
+                    scheduled.1 = false;
+                    return;
+                }
+            }
+        };
+
+        sleep(
+            next.saturating_duration_since(Instant::now())
+                .min(MAX_SLEEP),
+        );
+    }
+}
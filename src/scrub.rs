@@ -0,0 +1,223 @@
+//! Scrubbing of a Music: playing short grains around a moving position.
+
+use libc::c_void;
+use openal::{al, ffi};
+use sndfile::SeekMode::SeekSet;
+use sndfile::SndFile;
+use std::mem;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::mpsc::{channel, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// The duration of each grain, in milliseconds
+const GRAIN_MS: i64 = 30;
+/// The number of grains queued on the source
+const GRAIN_COUNT: usize = 3;
+/// The fastest playback rate, in both directions
+const MAX_RATE: f64 = 4.0;
+/// Below this playback rate the position is considered still, and nothing is heard
+const MIN_RATE: f64 = 0.05;
+
+/// The thread playing the grains of a scrubbed Music.
+pub(crate) struct Scrub {
+    target: Arc<AtomicI64>,
+    stop_sender: Sender<()>,
+    thread: thread::JoinHandle<i64>,
+}
+
+impl Scrub {
+    /**
+     * Start scrubbing on a source.
+     *
+     * # Arguments
+     * * `file` - The file to read the grains from
+     * * `source` - The source to queue the grains on, with no buffer attached
+     * * `format` - The OpenAL format of the samples of the file
+     * * `position` - The frame to start from
+     * * `is_looping` - Whether the position wraps around the ends of the file
+     */
+    pub(crate) fn start(
+        mut file: SndFile,
+        source: u32,
+        format: i32,
+        position: i64,
+        is_looping: bool,
+    ) -> Scrub {
+        let target = Arc::new(AtomicI64::new(position));
+        let (stop_sender, stop_receiver) = channel();
+        let thread_target = target.clone();
+
+        let thread = thread::Builder::new()
+            .name(String::from("ears-scrub"))
+            .spawn(move || {
+                let infos = file.get_sndinfo();
+                let grain_frames = (infos.samplerate as i64 * GRAIN_MS / 1000) as usize;
+                let mut buffers = [0u32; GRAIN_COUNT];
+                al::alGenBuffers(GRAIN_COUNT as i32, &mut buffers[0]);
+                let mut free_buffers = buffers.to_vec();
+                let mut position = position as f64;
+
+                while let Err(TryRecvError::Empty) = stop_receiver.try_recv() {
+                    let mut processed = 0;
+                    al::alGetSourcei(source, ffi::AL_BUFFERS_PROCESSED, &mut processed);
+                    for _ in 0..processed {
+                        let mut buffer = 0;
+                        al::alSourceUnqueueBuffers(source, 1, &mut buffer);
+                        free_buffers.push(buffer);
+                    }
+
+                    for buffer in free_buffers.drain(..) {
+                        let target = thread_target.load(Ordering::Relaxed) as f64;
+                        let rate =
+                            ((target - position) / grain_frames as f64).clamp(-MAX_RATE, MAX_RATE);
+                        let grain = match rate.abs() < MIN_RATE {
+                            true => vec![0; grain_frames * infos.channels as usize],
+                            false => read_grain(&mut file, position, rate, grain_frames),
+                        };
+
+                        position += rate * grain_frames as f64;
+                        position = match is_looping {
+                            true => position.rem_euclid(infos.frames as f64),
+                            false => position.clamp(0.0, infos.frames as f64),
+                        };
+
+                        al::alBufferData(
+                            buffer,
+                            format,
+                            grain.as_ptr() as *mut c_void,
+                            mem::size_of_val(&grain[..]) as i32,
+                            infos.samplerate,
+                        );
+                        al::alSourceQueueBuffers(source, 1, &buffer);
+                    }
+
+                    if al::alGetState(source) != ffi::AL_PLAYING {
+                        al::alSourcePlay(source);
+                    }
+                    sleep(Duration::from_millis(5));
+                }
+
+                al::alSourceStop(source);
+                al::alSourcei(source, ffi::AL_BUFFER, 0);
+                unsafe {
+                    ffi::alDeleteBuffers(GRAIN_COUNT as i32, &mut buffers[0]);
+                }
+                position as i64
+            })
+            .unwrap();
+
+        Scrub {
+            target,
+            stop_sender,
+            thread,
+        }
+    }
+
+    /// Set the frame the playback position is dragged to.
+    pub(crate) fn set_target(&self, position: i64) {
+        self.target.store(position, Ordering::Relaxed);
+    }
+
+    /**
+     * Stop scrubbing.
+     *
+     * # Return
+     * The frame reached by the playback position.
+     */
+    pub(crate) fn stop(self) -> i64 {
+        self.stop_sender.send(()).ok();
+        self.thread.join().unwrap_or(0)
+    }
+}
+
+/// Read a grain of a file, played at a rate from a position.
+fn read_grain(file: &mut SndFile, position: f64, rate: f64, grain_frames: usize) -> Vec<i16> {
+    let infos = file.get_sndinfo();
+    let channels = infos.channels as usize;
+
+    // The frames covered by the grain, in either direction
+    let end = position + rate * grain_frames as f64;
+    let first = (position.min(end).floor() as i64).max(0);
+    let last = (position.max(end).ceil() as i64 + 1).min(infos.frames);
+
+    let mut input = vec![0i16; (last - first).max(0) as usize * channels];
+    if !input.is_empty() {
+        file.seek(first, SeekSet);
+        let len = input.len() as i64;
+        file.read_i16(&mut input[..], len);
+    }
+
+    resample(
+        &input,
+        channels,
+        position - first as f64,
+        rate,
+        grain_frames,
+    )
+}
+
+/**
+ * Resample interleaved samples with linear interpolation.
+ *
+ * # Arguments
+ * * `input` - The interleaved samples to read from
+ * * `channels` - The number of channels of the samples
+ * * `start` - The input frame of the first output frame
+ * * `step` - The input frames between two output frames, negative to go backward
+ * * `frames` - The number of output frames
+ *
+ * # Return
+ * The interleaved output samples, silent outside of the input.
+ */
+fn resample(input: &[i16], channels: usize, start: f64, step: f64, frames: usize) -> Vec<i16> {
+    let input_frames = input.len() / channels;
+    let mut output = vec![0i16; frames * channels];
+    for (i, frame) in output.chunks_mut(channels).enumerate() {
+        let x = start + step * i as f64;
+        if input_frames == 0 || x < 0.0 || x > (input_frames - 1) as f64 {
+            continue;
+        }
+        let index = x.floor() as usize;
+        let next = (index + 1).min(input_frames - 1);
+        let fraction = x - index as f64;
+        for (channel, sample) in frame.iter_mut().enumerate() {
+            let a = input[index * channels + channel] as f64;
+            let b = input[next * channels + channel] as f64;
+            *sample = (a + (b - a) * fraction) as i16;
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::resample;
+
+    #[test]
+    fn scrub_resample_forward_OK() -> () {
+        let input = [0, 100, 200, 300, 400];
+
+        assert_eq!(resample(&input, 1, 1.0, 0.5, 4), vec![100, 150, 200, 250]);
+        assert_eq!(resample(&input, 1, 0.0, 2.0, 4), vec![0, 200, 400, 0]);
+    }
+
+    #[test]
+    fn scrub_resample_backward_OK() -> () {
+        let input = [0, 10, 100, 110, 200, 210];
+
+        assert_eq!(
+            resample(&input, 2, 2.0, -1.0, 3),
+            vec![200, 210, 100, 110, 0, 10]
+        );
+    }
+
+    #[test]
+    fn scrub_resample_empty_FAIL() -> () {
+        assert_eq!(resample(&[], 2, 0.0, 1.0, 2), vec![0; 4]);
+    }
+}
@@ -28,20 +28,45 @@
 * format the application program has requested or supplied and the file's data
 * format. The application programmer can remain blissfully unaware of issues
 * such as file endian-ness and data format
+*
+* With the pure-rust feature, the files are read and written by Rust crates
+* instead, see sndfile_rust.rs.
 */
 
 #![allow(dead_code)]
 
 //use std::str::from_utf8;
-use std::ffi::CStr;
-use std::ffi::CString;
+#[cfg(not(feature = "pure-rust"))]
+use libc::c_void;
+#[cfg(all(feature = "mp3", not(feature = "pure-rust")))]
+use mp3;
+#[cfg(not(feature = "pure-rust"))]
+use std::ffi::{CStr, CString};
 use std::fmt;
 use std::i32::*;
 use std::intrinsics::transmute;
+#[cfg(not(feature = "pure-rust"))]
+use std::io::SeekFrom;
+use std::io::{Read, Seek};
 use std::ops::BitOr;
+#[cfg(not(feature = "pure-rust"))]
+use std::path::Path;
+#[cfg(not(feature = "pure-rust"))]
 use std::ptr;
+#[cfg(not(feature = "pure-rust"))]
+use std::slice;
+#[cfg(not(feature = "pure-rust"))]
 use std::str::*;
+#[cfg(not(feature = "pure-rust"))]
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "pure-rust")]
+#[path = "sndfile_rust.rs"]
+mod pure;
+#[cfg(feature = "pure-rust")]
+pub use self::pure::{lib_version, SndFile};
 
+#[cfg(not(feature = "pure-rust"))]
 #[doc(hidden)]
 mod libsndfile {
     #[link(name = "sndfile")]
@@ -142,6 +167,7 @@ pub enum SeekMode {
 /// * FormatOgg - Xiph OGG container
 /// * FormatMpc2k - Akai MPC 2000 sampler
 /// * FormatRf64 - RF64 WAV file
+/// * FormatMpeg - MPEG-1/2 audio stream
 /// * FormatPcmS8 - Signed 8 bit data
 /// * FormatPcm16 - Signed 16 bit data
 /// * FormatPcm24 - Signed 24 bit data
@@ -165,6 +191,10 @@ pub enum SeekMode {
 /// * FormatDpcm8 - 8 bit differential PCM (XI only)
 /// * FormatDpcm16 - 16 bit differential PCM (XI only)
 /// * FormatVorbis - Xiph Vorbis encoding
+/// * FormatOpus - Xiph/Skype Opus encoding
+/// * FormatMpegLayerI - MPEG-1 Audio Layer I
+/// * FormatMpegLayerII - MPEG-1 Audio Layer II
+/// * FormatMpegLayerIII - MPEG-2 Audio Layer III
 /// * EndianFile - Default file endian-ness
 /// * EndianLittle - Force little endian-ness
 /// * EndianBig - Force big endian-ness
@@ -197,6 +227,7 @@ pub enum FormatType {
     FormatOgg = ffi::SF_FORMAT_OGG as isize,
     FormatMpc2k = ffi::SF_FORMAT_MPC2K as isize,
     FormatRf64 = ffi::SF_FORMAT_RF64 as isize,
+    FormatMpeg = ffi::SF_FORMAT_MPEG as isize,
     FormatPcmS8 = ffi::SF_FORMAT_PCM_S8 as isize,
     FormatPcm16 = ffi::SF_FORMAT_PCM_16 as isize,
     FormatPcm24 = ffi::SF_FORMAT_PCM_24 as isize,
@@ -220,6 +251,10 @@ pub enum FormatType {
     FormatDpcm8 = ffi::SF_FORMAT_DPCM_8 as isize,
     FormatDpcm16 = ffi::SF_FORMAT_DPCM_16 as isize,
     FormatVorbis = ffi::SF_FORMAT_VORBIS as isize,
+    FormatOpus = ffi::SF_FORMAT_OPUS as isize,
+    FormatMpegLayerI = ffi::SF_FORMAT_MPEG_LAYER_I as isize,
+    FormatMpegLayerII = ffi::SF_FORMAT_MPEG_LAYER_II as isize,
+    FormatMpegLayerIII = ffi::SF_FORMAT_MPEG_LAYER_III as isize,
     EndianFile = ffi::SF_ENDIAN_FILE as isize,
     EndianLittle = ffi::SF_ENDIAN_LITTLE as isize,
     EndianBig = ffi::SF_ENDIAN_BIG as isize,
@@ -236,45 +271,229 @@ impl BitOr for FormatType {
     //fn bitor(self, rhs: RHS) -> Self::Output;
 }
 
-/// All possible errors when opening a SndFile.
-pub struct SndFileError(String);
+/// All possible errors when opening a SndFile, with the message of libsndfile.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SndFileError {
+    /// The format of the file is not recognized.
+    UnrecognizedFormat(String),
+    /// The operating system failed to open the file, for example because it doesn't exist.
+    SystemError(String),
+    /// The file is corrupt or truncated.
+    MalformedFile(String),
+    /// The file uses an encoding libsndfile doesn't support.
+    UnsupportedEncoding(String),
+    /// Any other error, like an invalid path.
+    Other(String),
+}
 
 impl SndFileError {
     /// Create a new SndFileError with the given error.
     pub fn new(err: String) -> SndFileError {
-        SndFileError(err)
+        SndFileError::Other(err)
     }
-}
 
-impl fmt::Display for SndFileError {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}", self.0)
+    /// Create a SndFileError from a libsndfile error code and message.
+    #[cfg(not(feature = "pure-rust"))]
+    fn from_code(code: i32, message: String) -> SndFileError {
+        match code {
+            ffi::SF_ERR_UNRECOGNISED_FORMAT => SndFileError::UnrecognizedFormat(message),
+            ffi::SF_ERR_SYSTEM => SndFileError::SystemError(message),
+            ffi::SF_ERR_MALFORMED_FILE => SndFileError::MalformedFile(message),
+            ffi::SF_ERR_UNSUPPORTED_ENCODING => SndFileError::UnsupportedEncoding(message),
+            _ => SndFileError::Other(message),
+        }
+    }
+
+    /// Get the error of the last file which failed to open.
+    #[cfg(not(feature = "pure-rust"))]
+    fn last_open_error() -> SndFileError {
+        let message = unsafe {
+            from_utf8(CStr::from_ptr(ffi::sf_strerror(0) as *const _).to_bytes())
+                .unwrap()
+                .to_owned()
+        };
+        SndFileError::from_code(unsafe { ffi::sf_error(0) }, message)
+    }
+
+    /// Get the message describing the error.
+    pub fn message(&self) -> &str {
+        match self {
+            SndFileError::UnrecognizedFormat(message)
+            | SndFileError::SystemError(message)
+            | SndFileError::MalformedFile(message)
+            | SndFileError::UnsupportedEncoding(message)
+            | SndFileError::Other(message) => message,
+        }
     }
 }
 
-impl fmt::Debug for SndFileError {
+impl fmt::Display for SndFileError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        write!(fmt, "{}", self.0)
+        write!(fmt, "{}", self.message())
     }
 }
 
 impl std::error::Error for SndFileError {}
 
+/// A reader libsndfile can read from, see `SndFile::new_with_reader`.
+pub trait SndReader: Read + Seek + Send {}
+
+impl<R: Read + Seek + Send> SndReader for R {}
+
+/// The reader behind a SndFile opened with virtual IO callbacks.
+#[cfg(not(feature = "pure-rust"))]
+type VirtualReader = Mutex<Box<dyn SndReader>>;
+
 /// SndFile object, used to load/store sound from a file path or an fd.
+#[cfg(not(feature = "pure-rust"))]
 pub struct SndFile {
     handle: ffi::SNDFILEhandle, //*const ffi::SNDFILE,
     info: Box<SndInfo>,
+    /// The reader of a virtual file, which must live as long as the handle
+    reader: Option<Arc<VirtualReader>>,
 }
 
+#[cfg(not(feature = "pure-rust"))]
 impl Clone for SndFile {
     fn clone(&self) -> SndFile {
         SndFile {
             handle: self.handle,
             info: self.info.clone(),
+            reader: self.reader.clone(),
+        }
+    }
+}
+
+#[cfg(not(feature = "pure-rust"))]
+fn virtual_reader<'a>(user_data: *mut c_void) -> std::sync::MutexGuard<'a, Box<dyn SndReader>> {
+    let reader = unsafe { &*(user_data as *const VirtualReader) };
+    reader.lock().unwrap()
+}
+
+#[cfg(not(feature = "pure-rust"))]
+extern "C" fn vio_get_filelen(user_data: *mut c_void) -> i64 {
+    let mut reader = virtual_reader(user_data);
+    let length = reader.stream_position().and_then(|position| {
+        let length = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(position))?;
+        Ok(length)
+    });
+    length.map_or(-1, |length| length as i64)
+}
+
+#[cfg(not(feature = "pure-rust"))]
+extern "C" fn vio_seek(offset: i64, whence: i32, user_data: *mut c_void) -> i64 {
+    let from = match whence {
+        0 => SeekFrom::Start(offset.max(0) as u64),
+        1 => SeekFrom::Current(offset),
+        _ => SeekFrom::End(offset),
+    };
+    virtual_reader(user_data)
+        .seek(from)
+        .map_or(-1, |position| position as i64)
+}
+
+#[cfg(not(feature = "pure-rust"))]
+extern "C" fn vio_read(ptr: *mut c_void, count: i64, user_data: *mut c_void) -> i64 {
+    let buffer = unsafe { slice::from_raw_parts_mut(ptr as *mut u8, count.max(0) as usize) };
+    let mut reader = virtual_reader(user_data);
+    let mut read = 0;
+    // libsndfile expects short reads only at the end of the file
+    while read < buffer.len() {
+        match reader.read(&mut buffer[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(ref err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(_) => break,
         }
     }
+    read as i64
 }
 
+#[cfg(not(feature = "pure-rust"))]
+extern "C" fn vio_write(_ptr: *const c_void, _count: i64, _user_data: *mut c_void) -> i64 {
+    0
+}
+
+#[cfg(not(feature = "pure-rust"))]
+extern "C" fn vio_tell(user_data: *mut c_void) -> i64 {
+    virtual_reader(user_data)
+        .stream_position()
+        .map_or(-1, |position| position as i64)
+}
+
+/// Open a file with the native path encoding of the platform.
+#[cfg(all(unix, not(feature = "pure-rust")))]
+fn open_path(
+    path: &Path,
+    mode: OpenMode,
+    info: &mut SndInfo,
+) -> Result<ffi::SNDFILEhandle, SndFileError> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| SndFileError::new("The path contains a nul byte".to_owned()))?;
+    Ok(unsafe { ffi::sf_open(c_path.as_ptr() as *mut _, mode as i32, info) })
+}
+
+/// Open a file with the native path encoding of the platform.
+#[cfg(all(windows, not(feature = "pure-rust")))]
+fn open_path(
+    path: &Path,
+    mode: OpenMode,
+    info: &mut SndInfo,
+) -> Result<ffi::SNDFILEhandle, SndFileError> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let mut w_path: Vec<u16> = path.as_os_str().encode_wide().collect();
+    if w_path.contains(&0) {
+        return Err(SndFileError::new(
+            "The path contains a nul character".to_owned(),
+        ));
+    }
+    w_path.push(0);
+    Ok(unsafe { ffi::sf_wchar_open(w_path.as_ptr(), mode as i32, info) })
+}
+
+/// Open a file with the native path encoding of the platform.
+#[cfg(not(any(unix, windows, feature = "pure-rust")))]
+fn open_path(
+    path: &Path,
+    mode: OpenMode,
+    info: &mut SndInfo,
+) -> Result<ffi::SNDFILEhandle, SndFileError> {
+    use std::ffi::CString;
+
+    let path = path
+        .to_str()
+        .ok_or_else(|| SndFileError::new("The path is not valid unicode".to_owned()))?;
+    let c_path = CString::new(path)
+        .map_err(|_| SndFileError::new("The path contains a nul byte".to_owned()))?;
+    Ok(unsafe { ffi::sf_open(c_path.as_ptr() as *mut _, mode as i32, info) })
+}
+
+/**
+ * Get the version of the libsndfile library in use.
+ *
+ * Return a string like "libsndfile-1.0.31".
+ */
+#[cfg(not(feature = "pure-rust"))]
+pub fn lib_version() -> String {
+    let mut buffer = [0u8; 128];
+    let len = unsafe {
+        ffi::sf_command(
+            0,
+            ffi::SFC_GET_LIB_VERSION,
+            buffer.as_mut_ptr() as *mut _,
+            buffer.len() as i32,
+        )
+    };
+    let len = (len.max(0) as usize).min(buffer.len());
+    String::from_utf8_lossy(&buffer[..len]).into_owned()
+}
+
+#[cfg(not(feature = "pure-rust"))]
 impl SndFile {
     /**
      * Construct SndFile object with the path to the music and a mode to open it.
@@ -286,8 +505,8 @@ impl SndFile {
      * Return Ok() containing the SndFile on success, a SndFileError representation of
      * the error otherwise.
      */
-    pub fn new(path: &str, mode: OpenMode) -> Result<SndFile, SndFileError> {
-        let mut info = Box::new(SndInfo {
+    pub fn new<P: AsRef<Path>>(path: P, mode: OpenMode) -> Result<SndFile, SndFileError> {
+        let info = Box::new(SndInfo {
             frames: 0,
             samplerate: 0,
             channels: 0,
@@ -295,21 +514,7 @@ impl SndFile {
             sections: 0,
             seekable: 0,
         });
-        let c_path = CString::new(path).unwrap();
-        let tmp_sndfile =
-            { unsafe { ffi::sf_open(c_path.as_ptr() as *mut _, mode as i32, &mut *info) } };
-        if tmp_sndfile == 0 {
-            Err(SndFileError::new(unsafe {
-                from_utf8(CStr::from_ptr(ffi::sf_strerror(0) as *const _).to_bytes())
-                    .unwrap()
-                    .to_owned()
-            }))
-        } else {
-            Ok(SndFile {
-                handle: tmp_sndfile,
-                info: info,
-            })
-        }
+        SndFile::new_with_info(path, mode, info)
     }
 
     /**
@@ -323,24 +528,85 @@ impl SndFile {
      * Return Ok() containing the SndFile on success, a SndFileError representation of
      * the error otherwise.
      */
-    pub fn new_with_info(
-        path: &str,
+    pub fn new_with_info<P: AsRef<Path>>(
+        path: P,
         mode: OpenMode,
         mut info: Box<SndInfo>,
     ) -> Result<SndFile, SndFileError> {
-        let c_path = CString::new(path).unwrap();
-        let tmp_sndfile =
-            { unsafe { ffi::sf_open(c_path.as_ptr() as *mut _, mode as i32, &mut *info) } };
+        #[cfg(feature = "mp3")]
+        {
+            if let OpenMode::Read = mode {
+                if mp3::is_mp3_file(path.as_ref()) {
+                    return SndFile::new_with_reader(mp3::Mp3Reader::open(path.as_ref())?);
+                }
+            }
+        }
+        let tmp_sndfile = open_path(path.as_ref(), mode, &mut info)?;
+        if tmp_sndfile == 0 {
+            Err(SndFileError::last_open_error())
+        } else {
+            Ok(SndFile {
+                handle: tmp_sndfile,
+                info,
+                reader: None,
+            })
+        }
+    }
+
+    /**
+     * Construct SndFile object reading the music from a reader.
+     *
+     * # Argument
+     * * reader - The reader of the encoded music, like a file in an archive
+     *
+     * Return Ok() containing the SndFile on success, a SndFileError representation of
+     * the error otherwise.
+     */
+    pub fn new_with_reader<R: SndReader + 'static>(
+        #[cfg_attr(not(feature = "mp3"), allow(unused_mut))] mut reader: R,
+    ) -> Result<SndFile, SndFileError> {
+        #[cfg(feature = "mp3")]
+        {
+            if mp3::is_mp3(&mut reader) {
+                return SndFile::new_with_virtual(Box::new(mp3::Mp3Reader::new(reader)?));
+            }
+        }
+        SndFile::new_with_virtual(Box::new(reader))
+    }
+
+    /// Open a reader with the virtual IO callbacks of libsndfile.
+    fn new_with_virtual(reader: Box<dyn SndReader>) -> Result<SndFile, SndFileError> {
+        let mut info = Box::new(SndInfo {
+            frames: 0,
+            samplerate: 0,
+            channels: 0,
+            format: 0,
+            sections: 0,
+            seekable: 0,
+        });
+        let reader = Arc::new(Mutex::new(reader));
+        let mut callbacks = ffi::SF_VIRTUAL_IO {
+            get_filelen: vio_get_filelen,
+            seek: vio_seek,
+            read: vio_read,
+            write: vio_write,
+            tell: vio_tell,
+        };
+        let tmp_sndfile = unsafe {
+            ffi::sf_open_virtual(
+                &mut callbacks,
+                OpenMode::Read as i32,
+                &mut *info,
+                Arc::as_ptr(&reader) as *mut c_void,
+            )
+        };
         if tmp_sndfile == 0 {
-            Err(SndFileError::new(unsafe {
-                from_utf8(CStr::from_ptr(ffi::sf_strerror(0) as *const _).to_bytes())
-                    .unwrap()
-                    .to_owned()
-            }))
+            Err(SndFileError::last_open_error())
         } else {
             Ok(SndFile {
                 handle: tmp_sndfile,
                 info: info,
+                reader: Some(reader),
             })
         }
     }
@@ -371,15 +637,12 @@ impl SndFile {
             false => unsafe { ffi::sf_open_fd(fd, mode as i32, &mut *info, ffi::SF_FALSE) },
         };
         if tmp_sndfile == 0 {
-            Err(SndFileError::new(unsafe {
-                from_utf8(CStr::from_ptr(ffi::sf_strerror(0) as *const _).to_bytes())
-                    .unwrap()
-                    .to_owned()
-            }))
+            Err(SndFileError::last_open_error())
         } else {
             Ok(SndFile {
                 handle: tmp_sndfile,
                 info: info,
+                reader: None,
             })
         }
     }
@@ -418,8 +681,11 @@ impl SndFile {
      * Return NoError on success, an other error code otherwise
      */
     pub fn set_string(&mut self, string_type: StringSoundType, string: String) -> Error {
-        //let c_string = CString::new(string).unwrap();
-        unsafe { ffi::sf_set_string(self.handle, string_type as i32, string.as_ptr() as *mut _) }
+        let c_string = match CString::new(string) {
+            Ok(c_string) => c_string,
+            Err(_) => return Error::UnsupportedEncoding,
+        };
+        unsafe { ffi::sf_set_string(self.handle, string_type as i32, c_string.as_ptr() as *mut _) }
     }
 
     /**
@@ -677,7 +943,26 @@ impl SndFile {
      * Return the last error as a variant of the enum Error.
      */
     pub fn error(&self) -> Error {
-        unsafe { ffi::sf_error(self.handle) }
+        match unsafe { ffi::sf_error(self.handle) } {
+            ffi::SF_ERR_NO_ERROR => Error::NoError,
+            ffi::SF_ERR_UNRECOGNISED_FORMAT => Error::UnrecognizedFormat,
+            ffi::SF_ERR_SYSTEM => Error::SystemError,
+            ffi::SF_ERR_UNSUPPORTED_ENCODING => Error::UnsupportedEncoding,
+            // libsndfile's internal errors are about the content of the file
+            _ => Error::MalformedFile,
+        }
+    }
+
+    /**
+     * Get the last error, with its message
+     *
+     * Return None if no error happened.
+     */
+    pub fn last_error(&self) -> Option<SndFileError> {
+        match unsafe { ffi::sf_error(self.handle) } {
+            ffi::SF_ERR_NO_ERROR => None,
+            code => Some(SndFileError::from_code(code, self.string_error())),
+        }
     }
 
     /**
@@ -708,3 +993,55 @@ impl SndFile {
         }
     }
 }
+
+#[cfg(all(test, not(feature = "pure-rust")))]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{
+        ffi, vio_get_filelen, vio_read, vio_seek, vio_tell, SndFileError, SndReader, VirtualReader,
+    };
+    use libc::c_void;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    #[test]
+    fn sndfile_error_from_code_OK() -> () {
+        let err = SndFileError::from_code(ffi::SF_ERR_SYSTEM, "No such file".to_string());
+
+        assert_eq!(err, SndFileError::SystemError("No such file".to_string()));
+        assert_eq!(err.message(), "No such file");
+        assert_eq!(
+            SndFileError::from_code(ffi::SF_ERR_MALFORMED_FILE, String::new()),
+            SndFileError::MalformedFile(String::new())
+        );
+        assert_eq!(
+            SndFileError::from_code(42, String::new()),
+            SndFileError::Other(String::new())
+        );
+    }
+
+    #[test]
+    fn sndfile_virtual_io_OK() -> () {
+        let boxed: Box<dyn SndReader> = Box::new(Cursor::new((0..10).collect::<Vec<u8>>()));
+        let reader: VirtualReader = Mutex::new(boxed);
+        let user_data = &reader as *const VirtualReader as *mut c_void;
+        let mut buffer = [0u8; 4];
+
+        assert_eq!(vio_get_filelen(user_data), 10);
+        assert_eq!(vio_seek(3, 0, user_data), 3);
+        assert_eq!(
+            vio_read(buffer.as_mut_ptr() as *mut c_void, 4, user_data),
+            4
+        );
+        assert_eq!(buffer, [3, 4, 5, 6]);
+        assert_eq!(vio_tell(user_data), 7);
+        assert_eq!(vio_seek(-1, 1, user_data), 6);
+        assert_eq!(vio_seek(-2, 2, user_data), 8);
+        assert_eq!(
+            vio_read(buffer.as_mut_ptr() as *mut c_void, 4, user_data),
+            2
+        );
+        assert_eq!(&buffer[..2], [8, 9]);
+    }
+}
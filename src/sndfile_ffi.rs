@@ -48,6 +48,9 @@ pub const SF_STR_LICENSE: SF_STR = 0x08;
 pub const SF_STR_TRACKNUMBER: SF_STR = 0x09;
 pub const SF_STR_GENRE: SF_STR = 0x10;
 
+pub type SF_COMMAND = i32;
+pub const SFC_GET_LIB_VERSION: SF_COMMAND = 0x1000;
+
 pub type SF_BOOL = i32;
 pub const SF_FALSE: SF_BOOL = 0;
 pub const SF_TRUE: SF_BOOL = 1;
@@ -108,6 +111,8 @@ pub const SF_FORMAT_MPC2K: FORMAT_TYPE = 0x210000;
 /// Akai MPC 2000 sampler
 pub const SF_FORMAT_RF64: FORMAT_TYPE = 0x220000;
 /// RF64 WAV file
+pub const SF_FORMAT_MPEG: FORMAT_TYPE = 0x230000;
+/// MPEG-1/2 audio stream
 /* Subtypes from here on. */
 pub const SF_FORMAT_PCM_S8: FORMAT_TYPE = 0x0001;
 /// Signed 8 bit data
@@ -155,6 +160,14 @@ pub const SF_FORMAT_DPCM_16: FORMAT_TYPE = 0x0051;
 /// 16 bit differential PCM (XI only)
 pub const SF_FORMAT_VORBIS: FORMAT_TYPE = 0x0060;
 /// Xiph Vorbis encoding
+pub const SF_FORMAT_OPUS: FORMAT_TYPE = 0x0064;
+/// Xiph/Skype Opus encoding
+pub const SF_FORMAT_MPEG_LAYER_I: FORMAT_TYPE = 0x0080;
+/// MPEG-1 Audio Layer I
+pub const SF_FORMAT_MPEG_LAYER_II: FORMAT_TYPE = 0x0081;
+/// MPEG-1 Audio Layer II
+pub const SF_FORMAT_MPEG_LAYER_III: FORMAT_TYPE = 0x0082;
+/// MPEG-2 Audio Layer III
 
 /* Endian-ness options. */
 
@@ -174,6 +187,23 @@ pub const SF_FORMAT_ENDMASK: FORMAT_TYPE = 0x30000000;
 pub type SNDFILE = c_void;
 pub type SNDFILEhandle = intptr_t;
 
+pub type sf_vio_get_filelen = extern "C" fn(user_data: *mut c_void) -> i64;
+pub type sf_vio_seek = extern "C" fn(offset: i64, whence: i32, user_data: *mut c_void) -> i64;
+pub type sf_vio_read = extern "C" fn(ptr: *mut c_void, count: i64, user_data: *mut c_void) -> i64;
+pub type sf_vio_write =
+    extern "C" fn(ptr: *const c_void, count: i64, user_data: *mut c_void) -> i64;
+pub type sf_vio_tell = extern "C" fn(user_data: *mut c_void) -> i64;
+
+/// Callbacks used by sf_open_virtual instead of file operations
+#[repr(C)]
+pub struct SF_VIRTUAL_IO {
+    pub get_filelen: sf_vio_get_filelen,
+    pub seek: sf_vio_seek,
+    pub read: sf_vio_read,
+    pub write: sf_vio_write,
+    pub tell: sf_vio_tell,
+}
+
 #[repr(C)]
 pub struct FormatInfo {
     pub format: i32,
@@ -183,18 +213,26 @@ pub struct FormatInfo {
 
 extern "C" {
     pub fn sf_open(path: *mut c_char, mode: SF_MODE, info: *mut SndInfo) -> SNDFILEhandle;
+    #[cfg(windows)]
+    pub fn sf_wchar_open(path: *const u16, mode: SF_MODE, info: *mut SndInfo) -> SNDFILEhandle;
     pub fn sf_open_fd(
         fd: i32,
         mode: SF_MODE,
         info: *mut SndInfo,
         close_desc: SF_BOOL,
     ) -> SNDFILEhandle;
+    pub fn sf_open_virtual(
+        sfvirtual: *mut SF_VIRTUAL_IO,
+        mode: SF_MODE,
+        info: *mut SndInfo,
+        user_data: *mut c_void,
+    ) -> SNDFILEhandle;
     pub fn sf_format_check(info: *mut SndInfo) -> SF_BOOL;
 
     pub fn sf_seek(sndfile: SNDFILEhandle, frames: i64, whence: i32) -> i64;
-    pub fn sf_command(sndfile: SNDFILEhandle, cmd: i32, data: *mut c_void, datasize: i32) -> Error;
+    pub fn sf_command(sndfile: SNDFILEhandle, cmd: i32, data: *mut c_void, datasize: i32) -> i32;
 
-    pub fn sf_error(sndfile: SNDFILEhandle) -> Error;
+    pub fn sf_error(sndfile: SNDFILEhandle) -> SF_ERR;
     pub fn sf_strerror(sndfile: SNDFILEhandle) -> *mut c_char;
     pub fn sf_error_number(errnum: i32) -> *mut c_char;
 
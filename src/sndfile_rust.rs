@@ -0,0 +1,1018 @@
+//! The SndFile of the pure-rust feature, decoding the files with symphonia
+//! and writing WAV files with hound, without libsndfile.
+
+use hound::{self, SampleFormat, WavSpec, WavWriter};
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::FormatType::{
+    self, FormatAiff, FormatAlaw, FormatApcm, FormatDouble, FormatFlac, FormatFloat,
+    FormatImaAdpcm, FormatMpeg, FormatMpegLayerIII, FormatOgg, FormatPcm16, FormatPcm24,
+    FormatPcm32, FormatPcmS8, FormatPcmU8, FormatSubMask, FormatTypeMask, FormatUlaw, FormatVorbis,
+    FormatWav,
+};
+use super::StringSoundType::{
+    Album, Artist, Comment, Copyright, Date, Genre, License, Software, Title, TrackNumber,
+};
+use super::{Error, OpenMode, SeekMode, SndFileError, SndInfo, SndReader, StringSoundType};
+use symphonia;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{self, CodecType, DecoderOptions};
+use symphonia::core::errors::Error as DecodeError;
+use symphonia::core::formats::{self, FormatOptions, FormatReader, SeekTo};
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::{MetadataOptions, StandardTagKey, Tag};
+use symphonia::core::probe::Hint;
+use symphonia_io::{count_frames, decode_error, is_end_of_stream, ReaderSource};
+
+/// A decoder of audio files, read by the SndFiles opened for reading.
+pub(crate) trait Decoder: Send {
+    /// The format of the decoded file.
+    fn info(&self) -> SndInfo;
+
+    /// Get a tag of the file, None if it has none.
+    fn tag(&self, string_type: StringSoundType) -> Option<String>;
+
+    /// Decode the next interleaved samples, returning how many were read, 0 at the end.
+    fn read(&mut self, samples: &mut [f32]) -> Result<usize, SndFileError>;
+
+    /// Move before a frame, returning the frame reached.
+    fn seek(&mut self, frame: u64) -> Result<u64, SndFileError>;
+}
+
+/// A Decoder of all the formats symphonia reads.
+struct SymphoniaDecoder {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn codecs::Decoder>,
+    track_id: u32,
+    info: SndInfo,
+    tags: Vec<Tag>,
+    /// The last decoded samples, interleaved
+    decoded: Vec<f32>,
+    /// The number of decoded samples already read
+    consumed: usize,
+    /// The frame seeked to, before which the decoded samples are skipped
+    seeked: u64,
+    /// Whether the decoder has read all the packets
+    ended: bool,
+}
+
+impl SymphoniaDecoder {
+    /**
+     * Open an audio file.
+     *
+     * # Arguments
+     * * `source` - The encoded file
+     * * `extension` - The extension of the file, if known
+     */
+    fn new(
+        source: Box<dyn MediaSource>,
+        extension: Option<&str>,
+    ) -> Result<SymphoniaDecoder, SndFileError> {
+        let stream = MediaSourceStream::new(source, Default::default());
+        let mut hint = Hint::new();
+        if let Some(extension) = extension {
+            hint.with_extension(extension);
+        }
+        let mut probed = symphonia::default::get_probe()
+            .format(
+                &hint,
+                stream,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(|err| match err {
+                DecodeError::Unsupported(message) => {
+                    SndFileError::UnrecognizedFormat(message.to_string())
+                }
+                err => decode_error(err),
+            })?;
+
+        let mut tags: Vec<Tag> = probed
+            .metadata
+            .get()
+            .and_then(|metadata| metadata.current().map(|revision| revision.tags().to_vec()))
+            .unwrap_or_default();
+        if let Some(revision) = probed.format.metadata().current() {
+            tags.extend_from_slice(revision.tags());
+        }
+
+        let mut format = probed.format;
+        let track = format
+            .default_track()
+            .ok_or_else(|| SndFileError::UnrecognizedFormat("no audio stream".to_string()))?;
+        let track_id = track.id;
+        let params = track.codec_params.clone();
+        let decoder = symphonia::default::get_codecs()
+            .make(&params, &DecoderOptions::default())
+            .map_err(decode_error)?;
+
+        let channels = params.channels.map_or(0, |channels| channels.count()) as i32;
+        let sample_rate = params.sample_rate.unwrap_or(0) as i32;
+        if channels == 0 || sample_rate == 0 {
+            return Err(SndFileError::MalformedFile(
+                "the audio stream has no format".to_string(),
+            ));
+        }
+        let frames = match params.n_frames {
+            Some(frames) => frames,
+            None => count_frames(&mut *format, track_id)?,
+        };
+
+        Ok(SymphoniaDecoder {
+            format,
+            decoder,
+            track_id,
+            info: SndInfo {
+                frames: frames as i64,
+                samplerate: sample_rate,
+                channels,
+                format: sndfile_format(params.codec, params.bits_per_sample, extension),
+                sections: 1,
+                seekable: 1,
+            },
+            tags,
+            decoded: Vec::new(),
+            consumed: 0,
+            seeked: 0,
+            ended: false,
+        })
+    }
+
+    /// Decode the next packet, returning false at the end of the stream.
+    fn decode_next(&mut self) -> Result<bool, SndFileError> {
+        while !self.ended {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(ref err) if is_end_of_stream(err) => {
+                    self.ended = true;
+                    break;
+                }
+                Err(err) => return Err(decode_error(err)),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // A corrupt packet is skipped
+                Err(DecodeError::DecodeError(_)) => continue,
+                Err(err) => return Err(decode_error(err)),
+            };
+            let mut samples = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+            samples.copy_interleaved_ref(decoded);
+
+            self.decoded.clear();
+            self.decoded.extend_from_slice(samples.samples());
+            // The stream may restart before the frame seeked to
+            let skipped = self.seeked.saturating_sub(packet.ts()) * self.info.channels as u64;
+            self.consumed = (skipped as usize).min(self.decoded.len());
+            if self.consumed < self.decoded.len() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl Decoder for SymphoniaDecoder {
+    fn info(&self) -> SndInfo {
+        self.info.clone()
+    }
+
+    fn tag(&self, string_type: StringSoundType) -> Option<String> {
+        let key = match string_type {
+            Title => StandardTagKey::TrackTitle,
+            Copyright => StandardTagKey::Copyright,
+            Software => StandardTagKey::Encoder,
+            Artist => StandardTagKey::Artist,
+            Comment => StandardTagKey::Comment,
+            Date => StandardTagKey::Date,
+            Album => StandardTagKey::Album,
+            License => StandardTagKey::License,
+            TrackNumber => StandardTagKey::TrackNumber,
+            Genre => StandardTagKey::Genre,
+        };
+        self.tags
+            .iter()
+            .find(|tag| tag.std_key == Some(key))
+            .map(|tag| tag.value.to_string())
+    }
+
+    fn read(&mut self, samples: &mut [f32]) -> Result<usize, SndFileError> {
+        let mut read = 0;
+        while read < samples.len() {
+            if self.consumed == self.decoded.len() && !self.decode_next()? {
+                break;
+            }
+            let available = &self.decoded[self.consumed..];
+            let count = available.len().min(samples.len() - read);
+            samples[read..read + count].copy_from_slice(&available[..count]);
+            self.consumed += count;
+            read += count;
+        }
+        Ok(read)
+    }
+
+    fn seek(&mut self, frame: u64) -> Result<u64, SndFileError> {
+        let seeked = self.format.seek(
+            formats::SeekMode::Accurate,
+            SeekTo::TimeStamp {
+                ts: frame,
+                track_id: self.track_id,
+            },
+        );
+        match seeked {
+            Ok(_) => self.ended = false,
+            // Seeking to the end of the stream
+            Err(DecodeError::SeekError(_)) => self.ended = true,
+            Err(err) => return Err(decode_error(err)),
+        }
+        self.decoder.reset();
+        self.decoded.clear();
+        self.consumed = 0;
+        self.seeked = frame;
+        Ok(frame)
+    }
+}
+
+/// The libsndfile format of a stream, from its codec.
+fn sndfile_format(codec: CodecType, bits_per_sample: Option<u32>, extension: Option<&str>) -> i32 {
+    let container = match extension.map(str::to_lowercase).as_deref() {
+        Some("aif") | Some("aiff") => FormatAiff,
+        _ => FormatWav,
+    };
+    let (major, sub) = match codec {
+        codecs::CODEC_TYPE_FLAC => (FormatFlac, pcm_format(bits_per_sample)),
+        codecs::CODEC_TYPE_VORBIS => (FormatOgg, FormatVorbis),
+        codecs::CODEC_TYPE_MP3 => (FormatMpeg, FormatMpegLayerIII),
+        codecs::CODEC_TYPE_PCM_U8 => (container, FormatPcmU8),
+        codecs::CODEC_TYPE_PCM_F32LE | codecs::CODEC_TYPE_PCM_F32BE => (container, FormatFloat),
+        codecs::CODEC_TYPE_PCM_F64LE | codecs::CODEC_TYPE_PCM_F64BE => (container, FormatDouble),
+        codecs::CODEC_TYPE_PCM_ALAW => (container, FormatAlaw),
+        codecs::CODEC_TYPE_PCM_MULAW => (container, FormatUlaw),
+        codecs::CODEC_TYPE_ADPCM_IMA_WAV => (container, FormatImaAdpcm),
+        codecs::CODEC_TYPE_ADPCM_MS => (container, FormatApcm),
+        _ => (container, pcm_format(bits_per_sample)),
+    };
+    (major as i32) | (sub as i32)
+}
+
+/// The libsndfile format of integer samples.
+fn pcm_format(bits_per_sample: Option<u32>) -> FormatType {
+    match bits_per_sample.unwrap_or(16) {
+        0..=8 => FormatPcmS8,
+        9..=16 => FormatPcm16,
+        17..=24 => FormatPcm24,
+        _ => FormatPcm32,
+    }
+}
+
+/// The WAV format of a file to write, None if hound can't write it.
+fn wav_spec(info: &SndInfo) -> Option<WavSpec> {
+    if info.format & (FormatTypeMask as i32) != FormatWav as i32
+        || info.channels <= 0
+        || info.samplerate <= 0
+    {
+        return None;
+    }
+    let (bits_per_sample, sample_format) = match info.format & (FormatSubMask as i32) {
+        sub if sub == FormatPcmU8 as i32 => (8, SampleFormat::Int),
+        sub if sub == FormatPcm16 as i32 => (16, SampleFormat::Int),
+        sub if sub == FormatPcm24 as i32 => (24, SampleFormat::Int),
+        sub if sub == FormatPcm32 as i32 => (32, SampleFormat::Int),
+        sub if sub == FormatFloat as i32 => (32, SampleFormat::Float),
+        _ => return None,
+    };
+    Some(WavSpec {
+        channels: info.channels as u16,
+        sample_rate: info.samplerate as u32,
+        bits_per_sample,
+        sample_format,
+    })
+}
+
+/// Convert a sample between -1.0 and 1.0 to an integer of a number of bits.
+fn to_int(sample: f64, bits: u16) -> i64 {
+    let scale = (1i64 << (bits - 1)) as f64;
+    (sample * scale).round().clamp(-scale, scale - 1.) as i64
+}
+
+fn write_sample(writer: &mut WavWriter<BufWriter<File>>, sample: f64) -> hound::Result<()> {
+    match writer.spec() {
+        WavSpec {
+            sample_format: SampleFormat::Float,
+            ..
+        } => writer.write_sample(sample as f32),
+        WavSpec {
+            bits_per_sample: 8, ..
+        } => writer.write_sample(to_int(sample, 8) as i8),
+        WavSpec {
+            bits_per_sample: 16,
+            ..
+        } => writer.write_sample(to_int(sample, 16) as i16),
+        WavSpec {
+            bits_per_sample, ..
+        } => writer.write_sample(to_int(sample, bits_per_sample) as i32),
+    }
+}
+
+fn write_error(err: hound::Error) -> SndFileError {
+    match err {
+        hound::Error::IoError(err) => SndFileError::SystemError(err.to_string()),
+        err => SndFileError::Other(err.to_string()),
+    }
+}
+
+/// The stream of a SndFile.
+enum Stream {
+    Reader(Box<dyn Decoder>),
+    Writer(WavWriter<BufWriter<File>>),
+    Closed,
+}
+
+struct Inner {
+    stream: Stream,
+    /// The position of the next read, in samples
+    position: u64,
+    /// The last error of the stream
+    error: Option<SndFileError>,
+}
+
+/// SndFile object, used to load/store sound from a file path or an fd.
+#[derive(Clone)]
+pub struct SndFile {
+    inner: Arc<Mutex<Inner>>,
+    info: SndInfo,
+}
+
+/**
+ * Get the version of the libsndfile library in use.
+ *
+ * Return an empty string, as the pure-rust feature doesn't use libsndfile.
+ */
+pub fn lib_version() -> String {
+    String::new()
+}
+
+impl SndFile {
+    /**
+     * Construct SndFile object with the path to the music and a mode to open it.
+     *
+     * # Arguments
+     * * path - The path to load the music
+     * * mode - The mode to open the music
+     *
+     * Return Ok() containing the SndFile on success, a SndFileError representation of
+     * the error otherwise.
+     */
+    pub fn new<P: AsRef<Path>>(path: P, mode: OpenMode) -> Result<SndFile, SndFileError> {
+        let info = Box::new(SndInfo {
+            frames: 0,
+            samplerate: 0,
+            channels: 0,
+            format: 0,
+            sections: 0,
+            seekable: 0,
+        });
+        SndFile::new_with_info(path, mode, info)
+    }
+
+    /**
+     * Construct SndFile object with the path to the music and a mode to open it.
+     *
+     * Only the WAV files of integer or float samples can be written.
+     *
+     * # Arguments
+     * * path - The path to load the music
+     * * mode - The mode to open the music
+     * * info - The SndInfo to pass to the file
+     *
+     * Return Ok() containing the SndFile on success, a SndFileError representation of
+     * the error otherwise.
+     */
+    pub fn new_with_info<P: AsRef<Path>>(
+        path: P,
+        mode: OpenMode,
+        info: Box<SndInfo>,
+    ) -> Result<SndFile, SndFileError> {
+        let path = path.as_ref();
+        match mode {
+            OpenMode::Read => {
+                let file = File::open(path).map_err(system_error)?;
+                let extension = path.extension().and_then(|extension| extension.to_str());
+                let decoder = SymphoniaDecoder::new(Box::new(file), extension)?;
+                Ok(SndFile::new_with_decoder(Box::new(decoder)))
+            }
+            OpenMode::Write => {
+                let spec = wav_spec(&info).ok_or_else(|| {
+                    SndFileError::UnsupportedEncoding(
+                        "only WAV files can be written without libsndfile".to_string(),
+                    )
+                })?;
+                let writer = WavWriter::create(path, spec).map_err(write_error)?;
+                Ok(SndFile {
+                    inner: Arc::new(Mutex::new(Inner {
+                        stream: Stream::Writer(writer),
+                        position: 0,
+                        error: None,
+                    })),
+                    info: *info,
+                })
+            }
+            OpenMode::ReadWrite => Err(SndFileError::UnsupportedEncoding(
+                "files can't be read and written without libsndfile".to_string(),
+            )),
+        }
+    }
+
+    /**
+     * Construct SndFile object reading the music from a reader.
+     *
+     * # Argument
+     * * reader - The reader of the encoded music, like a file in an archive
+     *
+     * Return Ok() containing the SndFile on success, a SndFileError representation of
+     * the error otherwise.
+     */
+    pub fn new_with_reader<R: SndReader + 'static>(reader: R) -> Result<SndFile, SndFileError> {
+        let source = Box::new(ReaderSource(Mutex::new(reader)));
+        let decoder = SymphoniaDecoder::new(source, None)?;
+        Ok(SndFile::new_with_decoder(Box::new(decoder)))
+    }
+
+    fn new_with_decoder(decoder: Box<dyn Decoder>) -> SndFile {
+        SndFile {
+            info: decoder.info(),
+            inner: Arc::new(Mutex::new(Inner {
+                stream: Stream::Reader(decoder),
+                position: 0,
+                error: None,
+            })),
+        }
+    }
+
+    /**
+     * Construct SndFile object with the fd of the file containing the music
+     * and a mode to open it.
+     *
+     * Only the unix fds can be read, and none can be written.
+     *
+     * # Arguments
+     * * fd - The fd to load the music
+     * * mode - The mode to open the music
+     * * close_desc - Should SndFile close the fd at exit?
+     *
+     * Return Ok() containing the SndFile on success, a SndFileError representation
+     * of the error otherwise.
+     */
+    pub fn new_with_fd(fd: i32, mode: OpenMode, close_desc: bool) -> Result<SndFile, SndFileError> {
+        #[cfg(unix)]
+        {
+            use libc;
+            use std::os::unix::io::FromRawFd;
+
+            if let OpenMode::Read = mode {
+                // The file is closed with the SndFile, so it gets a copy of the fd to keep
+                let fd = if close_desc {
+                    fd
+                } else {
+                    unsafe { libc::dup(fd) }
+                };
+                if fd < 0 {
+                    return Err(system_error(io::Error::last_os_error()));
+                }
+                let file = unsafe { File::from_raw_fd(fd) };
+                let decoder = SymphoniaDecoder::new(Box::new(file), None)?;
+                return Ok(SndFile::new_with_decoder(Box::new(decoder)));
+            }
+        }
+        let _ = (fd, mode, close_desc);
+        Err(SndFileError::UnsupportedEncoding(
+            "this fd can't be opened without libsndfile".to_string(),
+        ))
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap()
+    }
+
+    /// Return the SndInfo struct of the current music.
+    pub fn get_sndinfo(&self) -> SndInfo {
+        self.info.clone()
+    }
+
+    /**
+     * Retrieve a tag contained by the music.
+     *
+     * # Argument
+     * * string_type - The type of the tag to retrieve
+     *
+     * Return Some(String) if the tag is found, None otherwise.
+     */
+    pub fn get_string(&self, string_type: StringSoundType) -> Option<String> {
+        match self.inner().stream {
+            Stream::Reader(ref decoder) => decoder.tag(string_type),
+            _ => None,
+        }
+    }
+
+    /**
+     * Set a tag on the music file.
+     *
+     * The tags can't be written without libsndfile.
+     *
+     * Return UnsupportedEncoding.
+     */
+    pub fn set_string(&mut self, _string_type: StringSoundType, _string: String) -> Error {
+        Error::UnsupportedEncoding
+    }
+
+    /**
+     * Check if the format of the SndInfo struct is valid.
+     *
+     * # Argument
+     * * info - The SndInfo struct to test
+     *
+     * Return true if a file can be written with this format, false otherwise.
+     */
+    pub fn check_format(info: &mut SndInfo) -> bool {
+        wav_spec(info).is_some()
+    }
+
+    /**
+     * Close the SndFile object.
+     *
+     * A file being written is finalized, which is also done when the last
+     * clone of the SndFile is dropped.
+     *
+     * Return NoError if destruction success, an other error code otherwise.
+     */
+    pub fn close(&self) -> Error {
+        let mut inner = self.inner();
+        match std::mem::replace(&mut inner.stream, Stream::Closed) {
+            Stream::Writer(writer) => match writer.finalize() {
+                Ok(()) => Error::NoError,
+                Err(err) => {
+                    inner.error = Some(write_error(err));
+                    Error::SystemError
+                }
+            },
+            _ => Error::NoError,
+        }
+    }
+
+    /**
+     * If the file is opened Write, write all the buffered samples to the
+     * file. If the file is opened Read no action is taken.
+     */
+    pub fn write_sync(&mut self) {
+        if let Stream::Writer(ref mut writer) = self.inner().stream {
+            let _ = writer.flush();
+        }
+    }
+
+    pub fn seek(&mut self, frames: i64, whence: SeekMode) -> i64 {
+        let channels = self.info.channels.max(1) as u64;
+        let mut inner = self.inner();
+        let frame = match whence {
+            SeekMode::SeekSet => frames,
+            SeekMode::SeekCur => (inner.position / channels) as i64 + frames,
+            SeekMode::SeekEnd => self.info.frames + frames,
+        };
+        if frame < 0 || frame > self.info.frames {
+            return -1;
+        }
+        let seeked = match inner.stream {
+            Stream::Reader(ref mut decoder) => decoder.seek(frame as u64),
+            _ => return -1,
+        };
+        match seeked {
+            Ok(frame) => {
+                inner.position = frame * channels;
+                frame as i64
+            }
+            Err(err) => {
+                inner.error = Some(err);
+                -1
+            }
+        }
+    }
+
+    /// Read items, converted from floats.
+    fn read_items<T>(&mut self, array: &mut [T], items: i64, convert: fn(f32) -> T) -> i64 {
+        let mut samples = vec![0.; (items.max(0) as usize).min(array.len())];
+        let mut inner = self.inner();
+        let read = match inner.stream {
+            Stream::Reader(ref mut decoder) => decoder.read(&mut samples),
+            _ => return 0,
+        };
+        match read {
+            Ok(read) => {
+                for (item, sample) in array.iter_mut().zip(&samples[..read]) {
+                    *item = convert(*sample);
+                }
+                inner.position += read as u64;
+                read as i64
+            }
+            Err(err) => {
+                inner.error = Some(err);
+                0
+            }
+        }
+    }
+
+    /// Write items, converted to floats.
+    fn write_items<T: Copy>(&mut self, array: &[T], items: i64, convert: fn(T) -> f64) -> i64 {
+        let items = (items.max(0) as usize).min(array.len());
+        let mut inner = self.inner();
+        let Inner {
+            ref mut stream,
+            ref mut error,
+            ..
+        } = *inner;
+        let writer = match stream {
+            Stream::Writer(ref mut writer) => writer,
+            _ => return 0,
+        };
+        for (written, item) in array[..items].iter().enumerate() {
+            if let Err(err) = write_sample(writer, convert(*item)) {
+                *error = Some(write_error(err));
+                return written as i64;
+            }
+        }
+        items as i64
+    }
+
+    /**
+     * Read items of type i16
+     *
+     * # Arguments
+     * * array - The array to fill with the items.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of items.
+     */
+    pub fn read_i16(&mut self, array: &mut [i16], items: i64) -> i64 {
+        self.read_items(array, items, |sample| to_int(sample as f64, 16) as i16)
+    }
+
+    /**
+     * Read items of type i32
+     *
+     * # Arguments
+     * * array - The array to fill with the items.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of items.
+     */
+    pub fn read_int(&mut self, array: &mut [i32], items: i64) -> i64 {
+        self.read_items(array, items, |sample| to_int(sample as f64, 32) as i32)
+    }
+
+    /**
+     * Read items of type f32
+     *
+     * # Arguments
+     * * array - The array to fill with the items.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of items.
+     */
+    pub fn read_f32(&mut self, array: &mut [f32], items: i64) -> i64 {
+        self.read_items(array, items, |sample| sample)
+    }
+
+    /**
+     * Read items of type f64
+     *
+     * # Arguments
+     * * array - The array to fill with the items.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of items.
+     */
+    pub fn read_f64(&mut self, array: &mut [f64], items: i64) -> i64 {
+        self.read_items(array, items, |sample| sample as f64)
+    }
+
+    /**
+     * Read frames of type i16
+     *
+     * # Arguments
+     * * array - The array to fill with the frames.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of frames.
+     */
+    pub fn readf_i16(&mut self, array: &mut [i16], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.read_i16(array, frames * channels) / channels
+    }
+
+    /**
+     * Read frames of type i32
+     *
+     * # Arguments
+     * * array - The array to fill with the frames.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of frames.
+     */
+    pub fn readf_int(&mut self, array: &mut [i32], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.read_int(array, frames * channels) / channels
+    }
+
+    /**
+     * Read frames of type f32
+     *
+     * # Arguments
+     * * array - The array to fill with the frames.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of frames.
+     */
+    pub fn readf_f32(&mut self, array: &mut [f32], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.read_f32(array, frames * channels) / channels
+    }
+
+    /**
+     * Read frames of type f64
+     *
+     * # Arguments
+     * * array - The array to fill with the frames.
+     * * items - The max capacity of the array.
+     *
+     * Return the count of frames.
+     */
+    pub fn readf_f64(&mut self, array: &mut [f64], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.read_f64(array, frames * channels) / channels
+    }
+
+    /**
+     * Write items of type i16
+     *
+     * # Arguments
+     * * array - The array of items to write.
+     * * items - The number of items to write.
+     *
+     * Return the count of wrote items.
+     */
+    pub fn write_i16(&mut self, array: &mut [i16], items: i64) -> i64 {
+        self.write_items(array, items, |item| item as f64 / 32768.)
+    }
+
+    /**
+     * Write items of type i32
+     *
+     * # Arguments
+     * * array - The array of items to write.
+     * * items - The number of items to write.
+     *
+     * Return the count of wrote items.
+     */
+    pub fn write_int(&mut self, array: &mut [i32], items: i64) -> i64 {
+        self.write_items(array, items, |item| item as f64 / 2147483648.)
+    }
+
+    /**
+     * Write items of type f32
+     *
+     * # Arguments
+     * * array - The array of items to write.
+     * * items - The number of items to write.
+     *
+     * Return the count of wrote items.
+     */
+    pub fn write_f32(&mut self, array: &mut [f32], items: i64) -> i64 {
+        self.write_items(array, items, |item| item as f64)
+    }
+
+    /**
+     * Write items of type f64
+     *
+     * # Arguments
+     * * array - The array of items to write.
+     * * items - The number of items to write.
+     *
+     * Return the count of wrote items.
+     */
+    pub fn write_f64(&mut self, array: &mut [f64], items: i64) -> i64 {
+        self.write_items(array, items, |item| item)
+    }
+
+    /**
+     * Write frames of type i16
+     *
+     * # Arguments
+     * * array - The array of frames to write.
+     * * items - The number of frames to write.
+     *
+     * Return the count of wrote frames.
+     */
+    pub fn writef_i16(&mut self, array: &mut [i16], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.write_i16(array, frames * channels) / channels
+    }
+
+    /**
+     * Write frames of type i32
+     *
+     * # Arguments
+     * * array - The array of frames to write.
+     * * items - The number of frames to write.
+     *
+     * Return the count of wrote frames.
+     */
+    pub fn writef_int(&mut self, array: &mut [i32], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.write_int(array, frames * channels) / channels
+    }
+
+    /**
+     * Write frames of type f32
+     *
+     * # Arguments
+     * * array - The array of frames to write.
+     * * items - The number of frames to write.
+     *
+     * Return the count of wrote frames.
+     */
+    pub fn writef_f32(&mut self, array: &mut [f32], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.write_f32(array, frames * channels) / channels
+    }
+
+    /**
+     * Write frames of type f64
+     *
+     * # Arguments
+     * * array - The array of frames to write.
+     * * items - The number of frames to write.
+     *
+     * Return the count of wrote frames.
+     */
+    pub fn writef_f64(&mut self, array: &mut [f64], frames: i64) -> i64 {
+        let channels = self.info.channels as i64;
+        self.write_f64(array, frames * channels) / channels
+    }
+
+    /**
+     * Get the last error
+     *
+     * Return the last error as a variant of the enum Error.
+     */
+    pub fn error(&self) -> Error {
+        match self.inner().error {
+            None => Error::NoError,
+            Some(SndFileError::UnrecognizedFormat(_)) => Error::UnrecognizedFormat,
+            Some(SndFileError::SystemError(_)) => Error::SystemError,
+            Some(SndFileError::UnsupportedEncoding(_)) => Error::UnsupportedEncoding,
+            Some(_) => Error::MalformedFile,
+        }
+    }
+
+    /**
+     * Get the last error, with its message
+     *
+     * Return None if no error happened.
+     */
+    pub fn last_error(&self) -> Option<SndFileError> {
+        self.inner().error.clone()
+    }
+
+    /**
+     * Get the last error as a string
+     *
+     * Return an owned str containing the last error.
+     */
+    pub fn string_error(&self) -> String {
+        match self.inner().error {
+            Some(ref err) => err.message().to_owned(),
+            None => SndFile::error_number(Error::NoError),
+        }
+    }
+
+    /**
+     * Get an error as a string from a variant of enum Error
+     *
+     * Return an owned str containing the error.
+     */
+    pub fn error_number(error_num: Error) -> String {
+        let message = match error_num {
+            Error::NoError => "No Error.",
+            Error::UnrecognizedFormat => "Format not recognised.",
+            Error::SystemError => "System error.",
+            Error::MalformedFile => "Supported file format but file is malformed.",
+            Error::UnsupportedEncoding => "Supported file format but unsupported encoding.",
+        };
+        message.to_owned()
+    }
+}
+
+fn system_error(err: io::Error) -> SndFileError {
+    SndFileError::SystemError(err.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{sndfile_format, to_int, SndFile};
+    use sndfile::FormatType::{FormatFloat, FormatOgg, FormatPcm16, FormatVorbis, FormatWav};
+    use sndfile::OpenMode::{Read, Write};
+    use sndfile::SeekMode::{SeekCur, SeekSet};
+    use sndfile::{SndFileError, SndInfo};
+    use std::env;
+    use std::fs;
+    use symphonia::core::codecs;
+
+    #[test]
+    fn sndfile_rust_format_OK() -> () {
+        assert_eq!(
+            sndfile_format(codecs::CODEC_TYPE_PCM_S16LE, Some(16), Some("wav")),
+            (FormatWav as i32) | (FormatPcm16 as i32)
+        );
+        assert_eq!(
+            sndfile_format(codecs::CODEC_TYPE_VORBIS, None, None),
+            (FormatOgg as i32) | (FormatVorbis as i32)
+        );
+    }
+
+    #[test]
+    fn sndfile_rust_to_int_OK() -> () {
+        assert_eq!(to_int(0.5, 16), 16384);
+        assert_eq!(to_int(1., 16), 32767);
+        assert_eq!(to_int(-1., 8), -128);
+    }
+
+    #[test]
+    fn sndfile_rust_read_seek_OK() -> () {
+        let mut file = SndFile::new("res/explosion.wav", Read).unwrap();
+        let info = file.get_sndinfo();
+        let mut start = vec![0i16; 64 * info.channels as usize];
+        let mut again = start.clone();
+
+        assert!(info.frames > 64);
+        assert_eq!(file.readf_i16(&mut start, 64), 64);
+        assert_eq!(file.seek(-32, SeekCur), 32);
+        assert_eq!(file.seek(0, SeekSet), 0);
+        assert_eq!(file.readf_i16(&mut again, 64), 64);
+        assert_eq!(start, again);
+    }
+
+    #[test]
+    fn sndfile_rust_read_ogg_OK() -> () {
+        let mut file = SndFile::new("res/music.ogg", Read).unwrap();
+        let info = file.get_sndinfo();
+        let mut samples = vec![0f32; 1024];
+
+        assert_eq!(info.format, (FormatOgg as i32) | (FormatVorbis as i32));
+        assert!(info.frames > 0);
+        assert_eq!(file.read_f32(&mut samples, 1024), 1024);
+        assert!(file.last_error().is_none());
+    }
+
+    #[test]
+    fn sndfile_rust_open_FAIL() -> () {
+        match SndFile::new("res/missing.wav", Read) {
+            Err(SndFileError::SystemError(_)) => {}
+            _ => panic!("expected a system error"),
+        }
+        match SndFile::new("res/LICENSE", Read) {
+            Err(SndFileError::UnrecognizedFormat(_)) => {}
+            _ => panic!("expected an unrecognized format"),
+        }
+    }
+
+    #[test]
+    fn sndfile_rust_write_OK() -> () {
+        let path = env::temp_dir().join("ears_sndfile_rust_write.wav");
+        let info = Box::new(SndInfo {
+            frames: 0,
+            samplerate: 22050,
+            channels: 2,
+            format: (FormatWav as i32) | (FormatFloat as i32),
+            sections: 0,
+            seekable: 0,
+        });
+        let mut samples = vec![0.25f32, -0.5, 0.75, -1.];
+
+        let mut file = SndFile::new_with_info(&path, Write, info).unwrap();
+        assert_eq!(file.writef_f32(&mut samples, 2), 2);
+        file.close();
+
+        let mut file = SndFile::new(&path, Read).unwrap();
+        let mut read = vec![0f32; 4];
+        assert_eq!(file.get_sndinfo().frames, 2);
+        assert_eq!(file.get_sndinfo().samplerate, 22050);
+        assert_eq!(file.read_f32(&mut read, 4), 4);
+        assert_eq!(read, samples);
+        fs::remove_file(path).unwrap();
+    }
+}
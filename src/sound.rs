@@ -21,18 +21,41 @@
 
 //! Play Sounds easily.
 
+use std::path::Path;
 use std::sync::Arc;
 use std::sync::Mutex;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use attenuation::{self, AttenuationCurve};
 use audio_controller::AudioController;
 use audio_tags::{AudioTags, Tags};
+use auto_velocity::AutoVelocity;
+use bus::{self, Bus};
+use completion::{self, SharedCompleteCallback};
+use effect::Effect;
+use efx::max_auxiliary_sends;
 use error::SoundError;
+use fade::{self, FadeEnd};
+use filter::Filter;
 use internal::OpenAlData;
+use listener;
+use load;
+#[cfg(feature = "async")]
+use load::LoadHandle;
+use logging::{self, LogLevel};
+use occlusion;
 use openal::{al, ffi};
-use reverb_effect::ReverbEffect;
+#[cfg(feature = "async")]
+use playback_future::PlaybackFuture;
+use playback_stats::PlaybackStats;
+use preload;
+use reverb_effect::ReverbEffectError;
+use schedule;
 use sound_data; //::*;//{SoundData};
 use sound_data::SoundData;
+use source_latency;
+use sources;
+use spatialize::Spatialize;
 use states::State;
 use states::State::{Initial, Paused, Playing, Stopped};
 
@@ -67,6 +90,20 @@ pub struct Sound {
     al_source: u32,
     /// The SoundData associated to the Sound.
     sound_data: Arc<Mutex<SoundData>>,
+    /// Playback statistics, not including the current run
+    stats: PlaybackStats,
+    /// Whether the frames of the current run are already in the statistics
+    run_accounted: bool,
+    /// Whether the reverb send level is computed from the distance
+    auxiliary_send_auto: bool,
+    /// Whether the Doppler effect applies to the Sound
+    doppler_enabled: bool,
+    /// The velocity of the Sound, kept while the Doppler effect is disabled
+    velocity: [f32; 3],
+    /// Computes the velocity from the positions, when enabled
+    auto_velocity: AutoVelocity,
+    /// Called once the Sound played to its end
+    complete_callback: SharedCompleteCallback,
 }
 
 impl Sound {
@@ -75,6 +112,9 @@ impl Sound {
      *
      * Create a new struct and an associated SoundData.
      *
+     * If the file was loaded with `ears::preload`, the preloaded SoundData
+     * is shared instead of reading the file again.
+     *
      * # Argument
      * `path` - The path of the sound file to create the SoundData.
      *
@@ -90,10 +130,78 @@ impl Sound {
      *                  .expect("Cannot load the sound from a file!");
      * ```
      */
-    pub fn new(path: &str) -> Result<Sound, SoundError> {
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<Sound, SoundError> {
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
+
+        let sound_data = match preload::cached(path.as_ref()) {
+            Some(sound_data) => sound_data,
+            None => Arc::new(Mutex::new(SoundData::new(path)?)),
+        };
+        Sound::new_with_data(sound_data)
+    }
+
+    /**
+     * Create a new Sound, giving up if loading the file takes too long.
+     *
+     * The file is loaded on a background thread, see `ears::load_sound`.
+     *
+     * # Arguments
+     * `path` - The path of the sound file to create the SoundData.
+     * `timeout` - The longest time to wait for the loading
+     *
+     * # Return
+     * A `Result` containing Ok(Sound) on success, Err(SoundError::Timeout)
+     * if the loading didn't end in time, or another Err(SoundError) if there
+     * has been an error.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{Sound, SoundError};
+     * use std::time::Duration;
+     *
+     * match Sound::new_with_timeout("//server/share/shot.wav", Duration::from_secs(2)) {
+     *     Ok(sound) => println!("Loaded"),
+     *     Err(SoundError::Timeout) => println!("The network share is too slow"),
+     *     Err(err) => println!("{}", err),
+     * }
+     * ```
+     */
+    pub fn new_with_timeout<P: AsRef<Path>>(
+        path: P,
+        timeout: Duration,
+    ) -> Result<Sound, SoundError> {
+        load::load_sound(path).wait_timeout(timeout)
+    }
+
+    /**
+     * Create a new struct and an associated SoundData downmixed to mono.
+     *
+     * Use it to position stereo sound files in three dimensional space, as
+     * OpenAL doesn't spatialize multi-channel sounds.
+     *
+     * # Argument
+     * `path` - The path of the sound file to create the SoundData.
+     *
+     * # Return
+     * A `Result` containing Ok(Sound) on success, Err(SoundError)
+     * if there has been an error.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{Sound, SoundError, AudioController};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let mut snd = Sound::new_mono("path/to/stereo/sound.ogg")?;
+     *     snd.set_position([5.0, 0.0, 0.0]);
+     *     snd.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn new_mono<P: AsRef<Path>>(path: P) -> Result<Sound, SoundError> {
         check_openal_context!(Err(SoundError::InvalidOpenALContext));
 
-        let sound_data = SoundData::new(path)?;
+        let sound_data = SoundData::new_mono(path)?;
         let sound_data = Arc::new(Mutex::new(sound_data));
         Sound::new_with_data(sound_data)
     }
@@ -140,9 +248,26 @@ impl Sound {
         if let Some(err) = al::openal_has_error() {
             return Err(SoundError::InternalOpenALError(err));
         };
+        sources::register(
+            source_id,
+            Box::new(move || {
+                completion::unwatch(source_id);
+                fade::cancel(source_id);
+                schedule::cancel(source_id);
+                al::alSourceStop(source_id);
+            }),
+        );
+        bus::add_source(source_id);
         Ok(Sound {
             al_source: source_id,
             sound_data,
+            stats: PlaybackStats::default(),
+            run_accounted: true,
+            auxiliary_send_auto: true,
+            doppler_enabled: true,
+            velocity: [0.0; 3],
+            auto_velocity: AutoVelocity::default(),
+            complete_callback: Arc::new(Mutex::new(None)),
         })
     }
 
@@ -191,6 +316,8 @@ impl Sound {
             return;
         }
 
+        self.account_current_run();
+
         // set the buffer
         {
             // we are not expecting threads to ever fail while holding the lock, so we `unwrap()`
@@ -204,6 +331,167 @@ impl Sound {
         self.sound_data = sound_data
     }
 
+    /**
+     * Replace the samples played by the Sound.
+     *
+     * The Sound is stopped if it is playing or paused. Everything else is
+     * kept: volume, position, effects and all the other settings of the
+     * Sound, so pooled Sounds can be reused for different clips without
+     * creating new sources.
+     *
+     * # Argument
+     * `sound_data` - The new samples to play
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Sound, SoundData};
+     *
+     * fn main() -> Result<(), ears::SoundError> {
+     *     let mut snd = Sound::new("path/to/footstep.ogg")?;
+     *     snd.set_position([2.0, 0.0, 0.0]);
+     *     snd.play();
+     *
+     *     // Later, reuse the same Sound at the same position
+     *     snd.set_data(SoundData::new("path/to/jump.ogg")?);
+     *     snd.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_data(&mut self, sound_data: SoundData) {
+        check_openal_context!(());
+
+        match self.get_state() {
+            Playing | Paused => self.stop(),
+            _ => {}
+        }
+        self.set_datas(Arc::new(Mutex::new(sound_data)));
+    }
+
+    /// Get the sample rate of the sound datas, in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.sound_data.lock().unwrap().sample_rate()
+    }
+
+    /// Get the number of channels of the sound datas.
+    pub fn channel_count(&self) -> usize {
+        self.sound_data.lock().unwrap().channel_count()
+    }
+
+    /// Get the number of frames of the sound datas.
+    pub fn frame_count(&self) -> u64 {
+        self.sound_data.lock().unwrap().frame_count()
+    }
+
+    /**
+     * Get the waveform of the Sound, to draw it.
+     *
+     * # Argument
+     * * `points` - The number of points to compute, like the width in
+     *   pixels of the drawing
+     *
+     * # Return
+     * The peak amplitude of each point, between 0.0 and 1.0, from the start
+     * to the end of the Sound. All zeros for the files kept compressed in
+     * memory.
+     *
+     * # Example
+     * ```no_run
+     * fn main() -> Result<(), ears::SoundError> {
+     *     let snd = ears::Sound::new("path/to/the/sound.ogg")?;
+     *     for peak in snd.waveform(80) {
+     *         println!("{}", "#".repeat((peak * 20.0) as usize));
+     *     }
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn waveform(&self, points: usize) -> Vec<f32> {
+        self.sound_data.lock().unwrap().waveform(points)
+    }
+
+    /**
+     * Set a callback to be called when the Sound finishes playing.
+     *
+     * The callback runs on a background thread shared by all the Sounds,
+     * shortly after the Sound played to its end. It isn't called when the
+     * Sound is stopped, nor for a looping Sound. It should return quickly to
+     * avoid delaying the callbacks of other Sounds.
+     *
+     * # Argument
+     * * `callback` - The function to call, replacing any previous one
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Sound, SoundError};
+     * use std::sync::mpsc::channel;
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     let (sender, receiver) = channel();
+     *     let mut snd = Sound::new("path/to/sound.ogg")?;
+     *     snd.set_complete_callback(move || {
+     *         sender.send(()).ok();
+     *     });
+     *     snd.play();
+     *
+     *     receiver.recv().ok();
+     *     println!("Done playing");
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn set_complete_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        *self.complete_callback.lock().unwrap() = Some(Box::new(callback));
+        if self.is_playing() {
+            completion::watch(self.al_source, self.complete_callback.clone());
+        }
+    }
+
+    /**
+     * Remove the callback set with `set_complete_callback`.
+     */
+    pub fn clear_complete_callback(&mut self) {
+        completion::unwatch(self.al_source);
+        *self.complete_callback.lock().unwrap() = None;
+    }
+
+    /**
+     * Play the Sound, returning a future resolving once it stops playing.
+     *
+     * Needs the `async` feature. See `PlaybackFuture`.
+     */
+    #[cfg(feature = "async")]
+    pub fn play_async(&mut self) -> PlaybackFuture<'_> {
+        self.play();
+        PlaybackFuture::new(self.al_source)
+    }
+
+    /**
+     * Load a Sound on a background thread, to await it.
+     *
+     * Needs the `async` feature. The returned LoadHandle is a future, see
+     * `ears::load_sound`.
+     *
+     * # Argument
+     * * `path` - The path of the sound file
+     *
+     * # Example
+     * ```ignore
+     * use ears::{Sound, SoundError};
+     *
+     * async fn load() -> Result<Sound, SoundError> {
+     *     Sound::load_async("path/to/shot.wav").await
+     * }
+     * ```
+     */
+    #[cfg(feature = "async")]
+    pub fn load_async<P: AsRef<Path>>(path: P) -> LoadHandle<Sound> {
+        load::load_sound(path)
+    }
+
     /**
     * This is a multiplier on the amount of Air Absorption applied to the Source.
     * The air absorption factor is multiplied by an internal Air Absorption Gain
@@ -237,34 +525,47 @@ impl Sound {
         factor
     }
 
-    /**
-     * Set the velocity of a Sound.
-     *
-     * Default velocity is [0.0, 0.0, 0.0].
-     *
-     * # Argument
-     * * `velocity` - A three dimensional vector of f32 containing the velocity
-     * of the sound [x, y, z].
-     */
-    pub fn set_velocity(&mut self, velocity: [f32; 3]) -> () {
-        check_openal_context!(());
+    // Frames played by the current run which aren't in the statistics yet.
+    // A looping Sound only reports its position in the current iteration.
+    fn pending_frames(&self) -> u64 {
+        if self.run_accounted {
+            return 0;
+        }
+        match self.get_state() {
+            Playing | Paused => self.get_offset() as u64,
+            Stopped => {
+                let sound_data = self.sound_data.lock().unwrap();
+                sound_data::get_sndinfo(&sound_data).frames as u64
+            }
+            Initial => 0,
+        }
+    }
 
-        al::alSourcefv(self.al_source, ffi::AL_VELOCITY, &velocity[0]);
+    fn account_current_run(&mut self) {
+        self.stats.frames_played += self.pending_frames();
+        self.run_accounted = true;
     }
 
-    /**
-     * Get the velocity of a Sound.
-     *
-     * # Return
-     * A three dimensional vector of f32 containing the velocity
-     * of the sound [x, y, z].
-     */
-    pub fn get_velocity(&self) -> [f32; 3] {
-        check_openal_context!([0.0; 3]);
+    // Play or resume the Sound, now or at a time on the device clock.
+    fn start(&mut self, time: Option<Duration>) -> Result<(), SoundError> {
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
 
-        let mut velocity: [f32; 3] = [0.0; 3];
-        al::alGetSourcefv(self.al_source, ffi::AL_VELOCITY, &mut velocity[0]);
-        velocity
+        if self.get_state() != Paused {
+            self.account_current_run();
+            self.stats.times_played += 1;
+            self.run_accounted = false;
+        }
+
+        schedule::play(self.al_source, time);
+
+        if self.complete_callback.lock().unwrap().is_some() {
+            completion::watch(self.al_source, self.complete_callback.clone());
+        }
+
+        match al::openal_has_error() {
+            None => Ok(()),
+            Some(err) => Err(SoundError::InternalOpenALError(err)),
+        }
     }
 }
 
@@ -300,11 +601,34 @@ impl AudioController for Sound {
     fn play(&mut self) -> () {
         check_openal_context!(());
 
-        al::alSourcePlay(self.al_source);
+        if let Err(err) = self.try_play() {
+            logging::log(LogLevel::Error, &err.to_string());
+        }
+    }
+
+    /**
+     * Play or resume the Sound, reporting the errors.
+     *
+     * # Return
+     * `Ok(())` on success, Err(SoundError::InvalidOpenALContext) if there is
+     * no OpenAL context or Err(SoundError::InternalOpenALError) if OpenAL
+     * failed.
+     */
+    fn try_play(&mut self) -> Result<(), SoundError> {
+        self.start(None)
+    }
 
-        match al::openal_has_error() {
-            None => {}
-            Some(err) => println!("Internal OpenAL error: {}", err),
+    /**
+     * Play or resume the Sound at a time on the device clock.
+     *
+     * # Argument
+     * * `time` - The time on the device clock, see `ears::device_clock`
+     */
+    fn play_at(&mut self, time: Duration) -> () {
+        check_openal_context!(());
+
+        if let Err(err) = self.start(Some(time)) {
+            logging::log(LogLevel::Error, &err.to_string());
         }
     }
 
@@ -327,7 +651,7 @@ impl AudioController for Sound {
     fn pause(&mut self) -> () {
         check_openal_context!(());
 
-        al::alSourcePause(self.al_source)
+        schedule::pause(self.al_source)
     }
 
     /**
@@ -349,49 +673,219 @@ impl AudioController for Sound {
     fn stop(&mut self) -> () {
         check_openal_context!(());
 
-        al::alSourceStop(self.al_source)
+        self.account_current_run();
+        completion::unwatch(self.al_source);
+        fade::cancel(self.al_source);
+        schedule::stop(self.al_source)
+    }
+
+    /**
+     * Start playing the Sound with its volume rising from silence.
+     *
+     * # Argument
+     * * `duration` - The duration of the fade
+     */
+    fn fade_in(&mut self, duration: Duration) -> () {
+        check_openal_context!(());
+
+        let volume = fade::base_volume(self.al_source).unwrap_or_else(|| self.get_volume());
+        self.set_volume(0.0);
+        if !self.is_playing() {
+            self.play();
+        }
+        fade::start(self.al_source, 0.0, volume, duration, None);
+    }
+
+    /**
+     * Fade the volume of the Sound out, then stop it.
+     *
+     * # Argument
+     * * `duration` - The duration of the fade
+     */
+    fn fade_out(&mut self, duration: Duration) -> () {
+        check_openal_context!(());
+
+        let volume = fade::base_volume(self.al_source).unwrap_or_else(|| self.get_volume());
+        let source = self.al_source;
+        let stop: FadeEnd = Box::new(move || {
+            completion::unwatch(source);
+            al::alSourceStop(source);
+        });
+        fade::start(
+            self.al_source,
+            self.get_volume(),
+            0.0,
+            duration,
+            Some((volume, stop)),
+        );
+    }
+
+    /**
+     * Ramp the volume of the Sound to a new value.
+     *
+     * # Arguments
+     * * `volume` - The volume at the end of the fade
+     * * `duration` - The duration of the fade
+     */
+    fn fade_to(&mut self, volume: f32, duration: Duration) -> () {
+        check_openal_context!(());
+
+        fade::start(self.al_source, self.get_volume(), volume, duration, None);
     }
 
     /**
-     * Connect a ReverbEffect to the Sound
+     * Connect an effect to an auxiliary send of the Sound
+     *
+     * # Arguments
+     * * `send` - The index of the auxiliary send, below `max_auxiliary_sends()`
+     * * `effect` - The effect to connect, or an `Option` of an effect which
+     *   is None to disconnect the send
+     *
+     * # Return
+     * `Ok(())` on success, a ReverbEffectError if the device doesn't support
+     * effects, the send doesn't exist or the connection failed.
      *
      * # Example
      * ```no_run
-     * use ears::{Sound, SoundError, ReverbEffect, ReverbPreset, AudioController};
+     * use ears::{EchoEffect, Sound, ReverbEffect, ReverbPreset, AudioController};
+     * use std::error::Error;
      *
-     * fn main() -> Result<(), SoundError> {
-     *     let reverb_effect = ReverbEffect::preset(ReverbPreset::Sewerpipe.properties()).ok();
+     * fn main() -> Result<(), Box<dyn Error>> {
+     *     ears::init_with_auxiliary_sends(2)?;
+     *     let cave = ReverbEffect::preset(ReverbPreset::Cave.properties()).ok();
+     *     let echo = EchoEffect::new()?;
      *     let mut snd = Sound::new("path/to/sound.ogg")?;
-     *     snd.connect(&reverb_effect);
+     *     snd.connect_send(0, &cave)?;
+     *     snd.connect_send(1, &echo)?;
      *     Ok(())
      * }
      * ```
      */
-    fn connect(&mut self, reverb_effect: &Option<ReverbEffect>) {
-        check_openal_context!(());
+    fn connect_send(&mut self, send: i32, effect: &dyn Effect) -> Result<(), ReverbEffectError> {
+        check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
+
+        let slot = effect.slot() as i32;
+        if !OpenAlData::efx_capable() {
+            return match slot {
+                ffi::AL_EFFECTSLOT_NULL => Ok(()),
+                _ => Err(ReverbEffectError::EfxUnsupported),
+            };
+        }
+        if send < 0 || send >= max_auxiliary_sends() {
+            return Err(ReverbEffectError::InvalidSend(send));
+        }
 
-        match reverb_effect {
-            Some(reverb_effect) => {
-                al::alSource3i(
-                    self.al_source,
-                    ffi::AL_AUXILIARY_SEND_FILTER,
-                    reverb_effect.slot() as i32,
-                    0,
-                    ffi::AL_FILTER_NULL,
-                );
-            }
-            None => {
-                al::alSource3i(
-                    self.al_source,
-                    ffi::AL_AUXILIARY_SEND_FILTER,
-                    ffi::AL_EFFECTSLOT_NULL,
-                    0,
-                    ffi::AL_FILTER_NULL,
-                );
-            }
+        occlusion::connect_send(self.al_source, send, slot);
+
+        // Check if there is OpenAL internal error
+        match al::openal_has_error() {
+            Some(err) => Err(ReverbEffectError::InternalOpenALError(err)),
+            None => Ok(()),
         }
     }
 
+    /**
+     * Filter the direct path of the Sound, or remove the filter with None.
+     */
+    fn set_direct_filter(&mut self, filter: &Option<Filter>) -> Result<(), ReverbEffectError> {
+        check_openal_context!(Err(ReverbEffectError::InvalidOpenALContext));
+
+        if !OpenAlData::efx_capable() {
+            return match filter {
+                Some(_) => Err(ReverbEffectError::EfxUnsupported),
+                None => Ok(()),
+            };
+        }
+
+        occlusion::set_source_filter(self.al_source, filter);
+
+        // Check if there is OpenAL internal error
+        match al::openal_has_error() {
+            Some(err) => Err(ReverbEffectError::InternalOpenALError(err)),
+            None => Ok(()),
+        }
+    }
+
+    /**
+     * Set how much the Sound is occluded.
+     */
+    fn set_occlusion(&mut self, occlusion: f32) -> () {
+        check_openal_context!(());
+
+        occlusion::set_source_occlusion(self.al_source, occlusion);
+    }
+
+    /**
+     * Get how much the Sound is occluded.
+     */
+    fn get_occlusion(&self) -> f32 {
+        occlusion::source_occlusion(self.al_source)
+    }
+
+    /**
+     * Set how much the Sound is obstructed.
+     */
+    fn set_obstruction(&mut self, obstruction: f32) -> () {
+        check_openal_context!(());
+
+        occlusion::set_source_obstruction(self.al_source, obstruction);
+    }
+
+    /**
+     * Get how much the Sound is obstructed.
+     */
+    fn get_obstruction(&self) -> f32 {
+        occlusion::source_obstruction(self.al_source)
+    }
+
+    /**
+     * Assign the Sound to a bus, or remove it from its bus.
+     */
+    fn set_bus(&mut self, bus: Option<&Bus>) -> () {
+        check_openal_context!(());
+
+        bus::set_source_bus(self.al_source, bus);
+    }
+
+    /**
+     * Check if the Sound is assigned to a bus.
+     */
+    fn is_in_bus(&self, bus: &Bus) -> bool {
+        bus::source_in_bus(self.al_source, bus)
+    }
+
+    /**
+     * Set the gain of the direct path of the Sound.
+     */
+    fn set_dry_gain(&mut self, gain: f32) -> () {
+        check_openal_context!(());
+
+        occlusion::set_source_dry_gain(self.al_source, gain);
+    }
+
+    /**
+     * Get the gain of the direct path of the Sound.
+     */
+    fn get_dry_gain(&self) -> f32 {
+        occlusion::source_dry_gain(self.al_source)
+    }
+
+    /**
+     * Set the gain of the auxiliary sends of the Sound.
+     */
+    fn set_send_gain(&mut self, gain: f32) -> () {
+        check_openal_context!(());
+
+        occlusion::set_source_send_gain(self.al_source, gain);
+    }
+
+    /**
+     * Get the gain of the auxiliary sends of the Sound.
+     */
+    fn get_send_gain(&self) -> f32 {
+        occlusion::source_send_gain(self.al_source)
+    }
+
     /**
      * Check if the Sound is playing or not.
      *
@@ -485,6 +979,30 @@ impl AudioController for Sound {
         offset
     }
 
+    /**
+     * Get the precise playback position of the Sound.
+     *
+     * # Return
+     * A tuple containing the playback position and the latency of the device,
+     * zero without the AL_SOFT_source_latency extension.
+     */
+    fn get_playback_position_precise(&self) -> (Duration, Duration) {
+        check_openal_context!((Duration::from_secs(0), Duration::from_secs(0)));
+
+        let (offset, latency) = match source_latency::offset_latency(self.al_source) {
+            Some(offset_latency) => offset_latency,
+            None => {
+                let mut offset = 0.;
+                al::alGetSourcef(self.al_source, ffi::AL_SEC_OFFSET, &mut offset);
+                (offset as f64, 0.)
+            }
+        };
+        (
+            source_latency::seconds_to_duration(offset),
+            source_latency::seconds_to_duration(latency),
+        )
+    }
+
     /**
      * Set the volume of the Sound.
      *
@@ -498,7 +1016,10 @@ impl AudioController for Sound {
     fn set_volume(&mut self, volume: f32) -> () {
         check_openal_context!(());
 
-        al::alSourcef(self.al_source, ffi::AL_GAIN, volume);
+        fade::cancel(self.al_source);
+        if !attenuation::set_source_volume(self.al_source, volume) {
+            bus::set_source_gain(self.al_source, volume);
+        }
     }
 
     /**
@@ -510,9 +1031,10 @@ impl AudioController for Sound {
     fn get_volume(&self) -> f32 {
         check_openal_context!(0.);
 
-        let mut volume: f32 = 0.;
-        al::alGetSourcef(self.al_source, ffi::AL_GAIN, &mut volume);
-        volume
+        if let Some(volume) = attenuation::source_volume(self.al_source) {
+            return volume;
+        }
+        bus::source_gain(self.al_source)
     }
 
     /**
@@ -624,7 +1146,7 @@ impl AudioController for Sound {
     fn set_pitch(&mut self, pitch: f32) -> () {
         check_openal_context!(());
 
-        al::alSourcef(self.al_source, ffi::AL_PITCH, pitch)
+        bus::set_source_pitch(self.al_source, pitch)
     }
 
     /**
@@ -636,9 +1158,7 @@ impl AudioController for Sound {
     fn get_pitch(&self) -> f32 {
         check_openal_context!(0.);
 
-        let mut pitch = 0.;
-        al::alGetSourcef(self.al_source, ffi::AL_PITCH, &mut pitch);
-        pitch
+        bus::source_pitch(self.al_source)
     }
 
     /**
@@ -665,6 +1185,10 @@ impl AudioController for Sound {
                 ffi::ALC_FALSE as i32,
             ),
         };
+        if !self.doppler_enabled {
+            listener::update_source_velocity(self.al_source, listener::get_velocity());
+        }
+        attenuation::update_source(self.al_source);
     }
 
     /**
@@ -705,6 +1229,10 @@ impl AudioController for Sound {
         check_openal_context!(());
 
         al::alSourcefv(self.al_source, ffi::AL_POSITION, &position[0]);
+        attenuation::update_source(self.al_source);
+        if let Some(velocity) = self.auto_velocity.update(position, Instant::now()) {
+            self.set_velocity(velocity);
+        }
     }
 
     /**
@@ -723,33 +1251,71 @@ impl AudioController for Sound {
     }
 
     /**
-     * Set the direction of the Sound.
-     *
-     * Specifies the current direction in local space.
+     * Set the direction of the Sound.
+     *
+     * Specifies the current direction in local space.
+     *
+     * The default direction is: [0.0, 0.0, 0.0]
+     *
+     * # Argument
+     * `direction` - The new direction of the Sound.
+     */
+    fn set_direction(&mut self, direction: [f32; 3]) -> () {
+        check_openal_context!(());
+
+        al::alSourcefv(self.al_source, ffi::AL_DIRECTION, &direction[0]);
+    }
+
+    /**
+     * Get the direction of the Sound.
+     *
+     * # Return
+     * The current direction of the Sound.
+     */
+    fn get_direction(&self) -> [f32; 3] {
+        check_openal_context!([0.; 3]);
+
+        let mut direction: [f32; 3] = [0.; 3];
+        al::alGetSourcefv(self.al_source, ffi::AL_DIRECTION, &mut direction[0]);
+        direction
+    }
+
+    /**
+     * Set the velocity of the Sound.
      *
-     * The default direction is: [0.0, 0.0, 0.0]
+     * The velocity is only used for the Doppler effect, it doesn't move the
+     * Sound. Default velocity is [0.0, 0.0, 0.0].
      *
      * # Argument
-     * `direction` - The new direction of the Sound.
+     * * `velocity` - A three dimensional vector of f32 containing the velocity
+     *   of the sound [x, y, z].
      */
-    fn set_direction(&mut self, direction: [f32; 3]) -> () {
+    fn set_velocity(&mut self, velocity: [f32; 3]) -> () {
         check_openal_context!(());
 
-        al::alSourcefv(self.al_source, ffi::AL_DIRECTION, &direction[0]);
+        self.velocity = velocity;
+        if self.doppler_enabled {
+            al::alSourcefv(self.al_source, ffi::AL_VELOCITY, &velocity[0]);
+        }
     }
 
     /**
-     * Get the direction of the Sound.
+     * Get the velocity of the Sound.
      *
      * # Return
-     * The current direction of the Sound.
+     * A three dimensional vector of f32 containing the velocity
+     * of the sound [x, y, z].
      */
-    fn get_direction(&self) -> [f32; 3] {
-        check_openal_context!([0.; 3]);
+    fn get_velocity(&self) -> [f32; 3] {
+        check_openal_context!([0.0; 3]);
 
-        let mut direction: [f32; 3] = [0.; 3];
-        al::alGetSourcefv(self.al_source, ffi::AL_DIRECTION, &mut direction[0]);
-        direction
+        if !self.doppler_enabled {
+            return self.velocity;
+        }
+
+        let mut velocity: [f32; 3] = [0.0; 3];
+        al::alGetSourcefv(self.al_source, ffi::AL_VELOCITY, &mut velocity[0]);
+        velocity
     }
 
     /**
@@ -834,7 +1400,9 @@ impl AudioController for Sound {
     fn set_attenuation(&mut self, attenuation: f32) -> () {
         check_openal_context!(());
 
-        al::alSourcef(self.al_source, ffi::AL_ROLLOFF_FACTOR, attenuation);
+        if !attenuation::set_source_rolloff(self.al_source, attenuation) {
+            al::alSourcef(self.al_source, ffi::AL_ROLLOFF_FACTOR, attenuation);
+        }
     }
 
     /**
@@ -846,11 +1414,172 @@ impl AudioController for Sound {
     fn get_attenuation(&self) -> f32 {
         check_openal_context!(1.);
 
+        if let Some(attenuation) = attenuation::source_rolloff(self.al_source) {
+            return attenuation;
+        }
         let mut attenuation = 0.;
         al::alGetSourcef(self.al_source, ffi::AL_ROLLOFF_FACTOR, &mut attenuation);
         attenuation
     }
 
+    /**
+     * Set the sound cone of the Sound.
+     *
+     * Inside the inner angle around the direction of the Sound its volume
+     * is unchanged, outside of the outer angle it is multiplied by
+     * `outer_volume`, and interpolated in between.
+     *
+     * The default cone is [360.0, 360.0, 0.0], so the cone has no effect.
+     *
+     * # Arguments
+     * * `inner_angle` - The inner angle in degrees in the range [0.0, 360.0]
+     * * `outer_angle` - The outer angle in degrees in the range [0.0, 360.0]
+     * * `outer_volume` - The volume outside of the outer angle in the range
+     *   [0.0, 1.0]
+     */
+    fn set_cone(&mut self, inner_angle: f32, outer_angle: f32, outer_volume: f32) -> () {
+        check_openal_context!(());
+
+        al::alSourcef(self.al_source, ffi::AL_CONE_INNER_ANGLE, inner_angle);
+        al::alSourcef(self.al_source, ffi::AL_CONE_OUTER_ANGLE, outer_angle);
+        al::alSourcef(self.al_source, ffi::AL_CONE_OUTER_GAIN, outer_volume);
+    }
+
+    /**
+     * Get the sound cone of the Sound.
+     *
+     * # Return
+     * A tuple containing the inner angle, outer angle and outer volume.
+     */
+    fn get_cone(&self) -> (f32, f32, f32) {
+        check_openal_context!((360., 360., 0.));
+
+        let mut inner_angle = 0.;
+        let mut outer_angle = 0.;
+        let mut outer_volume = 0.;
+        al::alGetSourcef(self.al_source, ffi::AL_CONE_INNER_ANGLE, &mut inner_angle);
+        al::alGetSourcef(self.al_source, ffi::AL_CONE_OUTER_ANGLE, &mut outer_angle);
+        al::alGetSourcef(self.al_source, ffi::AL_CONE_OUTER_GAIN, &mut outer_volume);
+        (inner_angle, outer_angle, outer_volume)
+    }
+
+    /**
+     * Let OpenAL adjust the auxiliary sends of a Sound automatically.
+     *
+     * The value is passed to AL_AUXILIARY_SEND_FILTER_GAIN_AUTO and
+     * AL_AUXILIARY_SEND_FILTER_GAINHF_AUTO.
+     *
+     * The default is true.
+     *
+     * # Argument
+     * * `enabled` - false to leave the auxiliary sends unadjusted
+     */
+    fn set_auxiliary_send_auto(&mut self, enabled: bool) -> () {
+        check_openal_context!(());
+
+        // The auxiliary sends only exist with EFX
+        if !OpenAlData::efx_capable() {
+            return;
+        }
+        self.auxiliary_send_auto = enabled;
+        al::alSourcei(
+            self.al_source,
+            ffi::AL_AUXILIARY_SEND_FILTER_GAIN_AUTO,
+            enabled as i32,
+        );
+        al::alSourcei(
+            self.al_source,
+            ffi::AL_AUXILIARY_SEND_FILTER_GAINHF_AUTO,
+            enabled as i32,
+        );
+    }
+
+    /**
+     * Check if OpenAL adjusts the auxiliary sends of a Sound automatically.
+     *
+     * # Return
+     * True if the auxiliary sends are adjusted automatically, false otherwise
+     */
+    fn get_auxiliary_send_auto(&self) -> bool {
+        self.auxiliary_send_auto
+    }
+
+    /**
+     * Enable or disable the Doppler effect for a Sound.
+     *
+     * When disabled, the Sound moves with the listener as far as the Doppler
+     * effect is concerned, and the velocity given to `set_velocity` is only
+     * applied once the Doppler effect is enabled again.
+     *
+     * The default is true.
+     *
+     * # Argument
+     * * `enabled` - false to exempt the Sound from the Doppler effect
+     */
+    fn set_doppler_enabled(&mut self, enabled: bool) -> () {
+        check_openal_context!(());
+
+        self.doppler_enabled = enabled;
+        listener::set_doppler_exempt(self.al_source, !enabled);
+        if enabled {
+            al::alSourcefv(self.al_source, ffi::AL_VELOCITY, &self.velocity[0]);
+        }
+    }
+
+    /**
+     * Check if the Doppler effect applies to a Sound.
+     *
+     * # Return
+     * True if the Doppler effect applies to the Sound, false otherwise
+     */
+    fn is_doppler_enabled(&self) -> bool {
+        self.doppler_enabled
+    }
+
+    /**
+     * Compute the velocity of the Sound from its positions, see
+     * `AudioController::set_auto_velocity`.
+     */
+    fn set_auto_velocity(&mut self, enabled: bool) {
+        self.auto_velocity.set_enabled(enabled);
+    }
+
+    /**
+     * Check if the velocity of the Sound is computed from its positions.
+     */
+    fn is_auto_velocity(&self) -> bool {
+        self.auto_velocity.is_enabled()
+    }
+
+    /**
+     * Attenuate the Sound with a custom curve instead of the OpenAL
+     * distance model.
+     *
+     * The gain of the Sound is recomputed each time the Sound or the
+     * listener moves, as the volume of the Sound multiplied by the curve at
+     * their distance. The attenuation factor given to `set_attenuation` is
+     * restored once the curve is removed.
+     *
+     * # Argument
+     * * `curve` - The curve to use, or None to use the OpenAL distance model
+     *   again (the default)
+     */
+    fn set_attenuation_curve(&mut self, curve: Option<AttenuationCurve>) -> () {
+        check_openal_context!(());
+
+        attenuation::set_source_curve(self.al_source, curve);
+    }
+
+    /**
+     * Get the custom attenuation curve of the Sound.
+     *
+     * # Return
+     * The curve set with `set_attenuation_curve`, if any
+     */
+    fn get_attenuation_curve(&self) -> Option<AttenuationCurve> {
+        attenuation::source_curve(self.al_source)
+    }
+
     /**
      * Enable or disable direct channel mode for a Sound.
      *
@@ -911,22 +1640,142 @@ impl AudioController for Sound {
         }
     }
 
+    /**
+     * Choose whether a Sound is positioned in 3D.
+     *
+     * By default only mono Sounds are spatialized. Applies only when the
+     * AL_SOFT_source_spatialize extension exists.
+     *
+     * # Argument
+     * * `spatialize` - The spatialization mode, `Spatialize::Auto` by default
+     */
+    fn set_spatialized(&mut self, spatialize: Spatialize) -> () {
+        check_openal_context!(());
+
+        if OpenAlData::source_spatialize_capable() {
+            al::alSourcei(
+                self.al_source,
+                ffi::AL_SOURCE_SPATIALIZE_SOFT,
+                spatialize.value(),
+            );
+        }
+    }
+
+    /**
+     * Get the spatialization mode of a Sound.
+     *
+     * # Return
+     * The spatialization mode of the Sound
+     */
+    fn get_spatialized(&self) -> Spatialize {
+        check_openal_context!(Spatialize::Auto);
+
+        match OpenAlData::source_spatialize_capable() {
+            true => {
+                let mut value = 0;
+                al::alGetSourcei(self.al_source, ffi::AL_SOURCE_SPATIALIZE_SOFT, &mut value);
+                Spatialize::from_value(value)
+            }
+            false => Spatialize::Auto,
+        }
+    }
+
+    /**
+     * Set the angles of the channels of a stereo Sound.
+     *
+     * Applies only when the AL_EXT_STEREO_ANGLES extension exists and when
+     * the Sound is stereo. The default angles are [30.0, -30.0].
+     *
+     * # Arguments
+     * * `left` - The angle of the left channel in degrees, counter-clockwise
+     *   from the front
+     * * `right` - The angle of the right channel in degrees, counter-clockwise
+     *   from the front
+     */
+    fn set_stereo_angles(&mut self, left: f32, right: f32) -> () {
+        check_openal_context!(());
+
+        if OpenAlData::stereo_angles_capable() {
+            let angles = [left.to_radians(), right.to_radians()];
+            al::alSourcefv(self.al_source, ffi::AL_STEREO_ANGLES, &angles[0]);
+        }
+    }
+
+    /**
+     * Get the angles of the channels of a stereo Sound.
+     *
+     * # Return
+     * A tuple containing the left and right angles in degrees.
+     */
+    fn get_stereo_angles(&self) -> (f32, f32) {
+        check_openal_context!((30., -30.));
+
+        match OpenAlData::stereo_angles_capable() {
+            true => {
+                let mut angles = [0.; 2];
+                al::alGetSourcefv(self.al_source, ffi::AL_STEREO_ANGLES, &mut angles[0]);
+                (angles[0].to_degrees(), angles[1].to_degrees())
+            }
+            false => (30., -30.),
+        }
+    }
+
+    /**
+     * Select the resampler of a Sound.
+     *
+     * Applies only when the AL_SOFT_source_resampler extension exists.
+     *
+     * # Argument
+     * * `index` - The index of the resampler in `ears::resamplers()`
+     */
+    fn set_resampler(&mut self, index: i32) -> () {
+        check_openal_context!(());
+
+        if OpenAlData::source_resampler_capable() {
+            al::alSourcei(self.al_source, ffi::AL_SOURCE_RESAMPLER_SOFT, index);
+        }
+    }
+
+    /**
+     * Get the resampler of a Sound.
+     *
+     * # Return
+     * The index of the resampler in `ears::resamplers()`
+     */
+    fn get_resampler(&self) -> i32 {
+        check_openal_context!(0);
+
+        match OpenAlData::source_resampler_capable() {
+            true => {
+                let mut index = 0;
+                al::alGetSourcei(self.al_source, ffi::AL_SOURCE_RESAMPLER_SOFT, &mut index);
+                index
+            }
+            false => 0,
+        }
+    }
+
     /**
      * Returns the duration of the Sound.
      */
     fn get_duration(&self) -> Duration {
         // we are not expecting threads to ever fail while holding the lock, so we `unwrap()`
-        let sound_data = self.sound_data.lock().unwrap();
-        let data = sound_data;
-        let snd_info = sound_data::get_sndinfo(&data);
-
-        let frames = snd_info.frames as u64;
-        let sample_rate = snd_info.samplerate as u64;
-
-        let seconds = frames / sample_rate;
-        let nanoseconds = frames % sample_rate * 1_000_000_000 / sample_rate;
+        self.sound_data.lock().unwrap().get_duration()
+    }
 
-        Duration::new(seconds, nanoseconds as u32)
+    /**
+     * Get the playback statistics of the Sound.
+     *
+     * Loops of a looping Sound are not counted, and a Sound never underruns
+     * since its data is entirely loaded into memory.
+     *
+     * # Return
+     * The totals accumulated since the Sound was created.
+     */
+    fn get_stats(&self) -> PlaybackStats {
+        let mut stats = self.stats;
+        stats.frames_played += self.pending_frames();
+        stats
     }
 }
 
@@ -934,6 +1783,20 @@ impl AudioController for Sound {
 impl Drop for Sound {
     ///Destroy all the resources attached to the Sound.
     fn drop(&mut self) -> () {
+        if !self.doppler_enabled {
+            listener::set_doppler_exempt(self.al_source, false);
+        }
+        attenuation::set_source_curve(self.al_source, None);
+        occlusion::remove_source(self.al_source);
+        bus::remove_source(self.al_source);
+        sources::unregister(self.al_source);
+        completion::unwatch(self.al_source);
+        fade::cancel(self.al_source);
+        schedule::cancel(self.al_source);
+        // The objects were deleted with the context
+        if !OpenAlData::is_created() {
+            return;
+        }
         unsafe {
             ffi::alDeleteSources(1, &mut self.al_source);
         }
@@ -944,9 +1807,15 @@ impl Drop for Sound {
 mod test {
     #![allow(non_snake_case)]
 
+    use attenuation::AttenuationCurve;
     use audio_controller::AudioController;
     use sound::Sound;
+    use sound_data::SoundData;
+    use source_settings::SourceSettings;
+    use spatialize::Spatialize;
     use states::State::{Paused, Playing, Stopped};
+    use std::sync::mpsc::channel;
+    use std::time::Duration;
 
     #[test]
     #[ignore]
@@ -974,6 +1843,18 @@ mod test {
         snd.stop();
     }
 
+    #[test]
+    #[ignore]
+    fn sound_try_play_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.try_play().unwrap();
+        assert_eq!(snd.get_state() as i32, Playing as i32);
+        snd.try_set_volume(0.5).unwrap();
+        snd.try_stop().unwrap();
+        assert_eq!(snd.get_state() as i32, Stopped as i32);
+    }
+
     #[test]
     #[ignore]
     fn sound_pause_OK() -> () {
@@ -1167,6 +2048,33 @@ mod test {
         assert_eq!(snd.is_relative(), false);
     }
 
+    #[test]
+    #[ignore]
+    fn sound_complete_callback_OK() -> () {
+        let (sender, receiver) = channel();
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+        snd.set_complete_callback(move || {
+            sender.send(()).ok();
+        });
+
+        snd.play();
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_set_data_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+        snd.set_volume(0.5);
+        snd.play();
+
+        snd.set_data(SoundData::new("res/explosion.wav").expect("Cannot create sound data"));
+        assert_eq!(snd.get_state(), Stopped);
+        assert_eq!(snd.get_volume(), 0.5);
+        snd.play();
+        assert_eq!(snd.get_state(), Playing);
+    }
+
     // untill https://github.com/rust-lang/rust/issues/7622 is not fixed, slice comparsion is used
 
     #[test]
@@ -1236,6 +2144,97 @@ mod test {
         assert_eq!(snd.get_attenuation(), 0.5f32);
     }
 
+    #[test]
+    #[ignore]
+    fn sound_set_attenuation_curve_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.set_volume(0.8);
+        snd.set_attenuation_curve(Some(AttenuationCurve::from_fn(|distance| 1.0 / distance)));
+        snd.set_position([4.0, 0.0, 0.0]);
+        assert_eq!(snd.get_volume(), 0.8);
+        assert_eq!(snd.get_attenuation(), 1.0);
+
+        snd.set_attenuation_curve(None);
+        assert!(snd.get_attenuation_curve().is_none());
+        assert_eq!(snd.get_volume(), 0.8);
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_set_cone_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.set_cone(45., 90., 0.5);
+        assert_eq!(snd.get_cone(), (45., 90., 0.5));
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_set_auxiliary_send_auto_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.set_auxiliary_send_auto(false);
+        assert_eq!(snd.get_auxiliary_send_auto(), false);
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_set_doppler_enabled_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.set_velocity([1., 2., 3.]);
+        snd.set_doppler_enabled(false);
+        assert_eq!(snd.is_doppler_enabled(), false);
+        assert_eq!(snd.get_velocity(), [1., 2., 3.]);
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_set_spatialized_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.set_spatialized(Spatialize::Disabled);
+        assert_eq!(snd.get_spatialized(), Spatialize::Disabled);
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_set_stereo_angles_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.set_stereo_angles(10., -10.);
+        let (left, right) = snd.get_stereo_angles();
+        assert!((left - 10.).abs() < 0.001);
+        assert!((right + 10.).abs() < 0.001);
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_set_resampler_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+
+        snd.set_resampler(0);
+        assert_eq!(snd.get_resampler(), 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_apply_OK() -> () {
+        let mut snd = Sound::new("res/shot.wav").expect("Cannot create sound");
+        let settings = SourceSettings {
+            volume: 0.5,
+            pitch: 1.5,
+            looping: true,
+            ..Default::default()
+        };
+
+        snd.apply(&settings);
+        assert_eq!(snd.get_volume(), 0.5);
+        assert_eq!(snd.get_pitch(), 1.5);
+        assert_eq!(snd.is_looping(), true);
+    }
+
     #[test]
     #[ignore]
     #[should_panic]
@@ -0,0 +1,355 @@
+//! Named collections of sound files, loaded once and shared.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use error::SoundError;
+use preload::{self, PreloadHandle};
+use sndfile::SndFileError;
+use sound::Sound;
+use sound_data::SoundData;
+use sound_handle::{self, SoundHandle};
+
+/// The extensions of the files added by `SoundBank::from_directory`
+const AUDIO_EXTENSIONS: [&str; 9] = [
+    "wav", "ogg", "oga", "opus", "flac", "aif", "aiff", "au", "caf",
+];
+
+struct Entry {
+    path: PathBuf,
+    /// The samples, once loaded
+    data: Option<Arc<Mutex<SoundData>>>,
+}
+
+/**
+ * A collection of sound files, loaded once and shared by name.
+ *
+ * The files are loaded when first used, or in the background with
+ * `load_async`, and their SoundData is then shared by all the Sounds
+ * created from the bank. A SoundBank can be cloned to share it between
+ * threads.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, SoundBank};
+ *
+ * fn main() -> Result<(), ears::SoundError> {
+ *     // Names each file of the directory by its stem: "explosion", "shots"...
+ *     let bank = SoundBank::from_directory("res")?;
+ *     bank.load_async().wait();
+ *
+ *     bank.play("explosion")?;
+ *     let mut shots = bank.sound("shots")?;
+ *     shots.set_looping(true);
+ *     shots.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Clone, Default)]
+pub struct SoundBank {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl SoundBank {
+    /// Create an empty SoundBank.
+    pub fn new() -> SoundBank {
+        SoundBank::default()
+    }
+
+    /**
+     * Create a SoundBank with the sound files of a directory.
+     *
+     * Each file is named by its file name without the extension. The files
+     * aren't loaded yet, and the sub-directories are ignored.
+     *
+     * # Argument
+     * * `directory` - The path of the directory
+     *
+     * # Return
+     * A `Result` containing Ok(SoundBank) on success, Err(SoundError)
+     * if the directory can't be read.
+     */
+    pub fn from_directory<P: AsRef<Path>>(directory: P) -> Result<SoundBank, SoundError> {
+        let bank = SoundBank::new();
+        for entry in fs::read_dir(directory).map_err(io_error)? {
+            let path = entry.map_err(io_error)?.path();
+            let is_audio = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| {
+                    AUDIO_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+                });
+            let name = path.file_stem().and_then(|stem| stem.to_str());
+            if let (true, Some(name)) = (is_audio && path.is_file(), name) {
+                bank.add(name, &path);
+            }
+        }
+        Ok(bank)
+    }
+
+    /**
+     * Create a SoundBank from a manifest file.
+     *
+     * Each line of the manifest gives a name and the path of its file,
+     * relative to the manifest, as `name = path`. The empty lines and the
+     * ones starting with `#` are ignored.
+     *
+     * # Argument
+     * * `manifest` - The path of the manifest file
+     *
+     * # Return
+     * A `Result` containing Ok(SoundBank) on success, Err(SoundError)
+     * if the manifest can't be read or a line is invalid.
+     *
+     * # Example
+     * ```no_run
+     * // sounds.txt:
+     * // # Weapons
+     * // shot = weapons/shot.wav
+     * // reload = weapons/reload.ogg
+     * let bank = ears::SoundBank::from_manifest("res/sounds.txt").unwrap();
+     * ```
+     */
+    pub fn from_manifest<P: AsRef<Path>>(manifest: P) -> Result<SoundBank, SoundError> {
+        let text = fs::read_to_string(manifest.as_ref()).map_err(io_error)?;
+        let directory = manifest.as_ref().parent().unwrap_or_else(|| Path::new(""));
+        let bank = SoundBank::new();
+        for (name, path) in parse_manifest(&text)? {
+            bank.add(name, directory.join(path));
+        }
+        Ok(bank)
+    }
+
+    /**
+     * Add a sound file to the bank, without loading it.
+     *
+     * A sound already named so is replaced.
+     *
+     * # Arguments
+     * * `name` - The name of the sound
+     * * `path` - The path of the sound file
+     */
+    pub fn add<P: AsRef<Path>>(&self, name: &str, path: P) {
+        let entry = Entry {
+            path: path.as_ref().to_path_buf(),
+            data: None,
+        };
+        self.entries.lock().unwrap().insert(name.to_string(), entry);
+    }
+
+    /**
+     * Get the names of the sounds of the bank.
+     */
+    pub fn names(&self) -> Vec<String> {
+        self.entries.lock().unwrap().keys().cloned().collect()
+    }
+
+    /**
+     * Check if a sound is loaded.
+     */
+    pub fn is_loaded(&self, name: &str) -> bool {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(name)
+            .is_some_and(|entry| entry.data.is_some() || preload::cached(&entry.path).is_some())
+    }
+
+    /**
+     * Get the samples of a sound, loading them if needed.
+     *
+     * # Argument
+     * * `name` - The name of the sound
+     *
+     * # Return
+     * A `Result` containing Ok(SoundData) on success, Err(SoundError)
+     * if there has been an error, for instance Err(SoundError::UnknownName)
+     * if the bank has no sound of this name.
+     */
+    pub fn get(&self, name: &str) -> Result<Arc<Mutex<SoundData>>, SoundError> {
+        let path = {
+            let entries = self.entries.lock().unwrap();
+            match entries.get(name) {
+                Some(Entry {
+                    data: Some(data), ..
+                }) => return Ok(data.clone()),
+                Some(entry) => entry.path.clone(),
+                None => return Err(SoundError::UnknownName(name.to_string())),
+            }
+        };
+
+        // The file is loaded without the lock, so other sounds can be used meanwhile
+        let data = match preload::cached(&path) {
+            Some(data) => data,
+            None => Arc::new(Mutex::new(SoundData::new(&path)?)),
+        };
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get_mut(name) {
+            Some(entry) if entry.path == path => Ok(entry.data.get_or_insert(data).clone()),
+            _ => Ok(data),
+        }
+    }
+
+    /**
+     * Create a Sound playing a sound of the bank.
+     *
+     * # Argument
+     * * `name` - The name of the sound
+     */
+    pub fn sound(&self, name: &str) -> Result<Sound, SoundError> {
+        Sound::new_with_data(self.get(name)?)
+    }
+
+    /**
+     * Play a sound of the bank, without keeping the Sound alive.
+     *
+     * See `ears::play_sound`.
+     *
+     * # Argument
+     * * `name` - The name of the sound
+     */
+    pub fn play(&self, name: &str) -> Result<SoundHandle, SoundError> {
+        sound_handle::play_sound_data(self.get(name)?)
+    }
+
+    /**
+     * Load all the sounds of the bank in the background.
+     *
+     * The samples are kept by the bank, so `unload_unused` can free them
+     * again.
+     *
+     * # Return
+     * A PreloadHandle to follow the loading.
+     */
+    pub fn load_async(&self) -> PreloadHandle {
+        let paths: Vec<PathBuf> = self
+            .entries
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|entry| entry.data.is_none())
+            .map(|entry| entry.path.clone())
+            .collect();
+        let entries = self.entries.clone();
+        preload::load_with(paths, move |path| {
+            let data = match preload::cached(path) {
+                Some(data) => data,
+                None => Arc::new(Mutex::new(SoundData::new(path)?)),
+            };
+            // The same file can be added under several names
+            for entry in entries.lock().unwrap().values_mut() {
+                if entry.path == path && entry.data.is_none() {
+                    entry.data = Some(data.clone());
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /**
+     * Free the samples no Sound uses anymore.
+     *
+     * The sounds stay in the bank, and are loaded again when used.
+     *
+     * # Return
+     * The number of sounds unloaded.
+     */
+    pub fn unload_unused(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap();
+        let mut unloaded = 0;
+        for entry in entries.values_mut() {
+            if entry
+                .data
+                .as_ref()
+                .is_some_and(|data| Arc::strong_count(data) == 1)
+            {
+                entry.data = None;
+                unloaded += 1;
+            }
+        }
+        unloaded
+    }
+}
+
+fn io_error(err: std::io::Error) -> SoundError {
+    SoundError::LoadError(SndFileError::SystemError(err.to_string()))
+}
+
+/// Read the names and paths of a manifest.
+fn parse_manifest(text: &str) -> Result<Vec<(&str, &str)>, SoundError> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let mut parts = line.splitn(2, '=').map(str::trim);
+            match (parts.next(), parts.next()) {
+                (Some(name), Some(path)) if !name.is_empty() && !path.is_empty() => {
+                    Ok((name, path))
+                }
+                _ => Err(SoundError::LoadError(SndFileError::MalformedFile(format!(
+                    "invalid line {} of the manifest",
+                    i + 1
+                )))),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{parse_manifest, SoundBank};
+    use error::SoundError;
+
+    #[test]
+    fn sound_bank_parse_manifest_OK() -> () {
+        let manifest = "# Weapons\nshot = weapons/shot.wav\n\n  reload=reload.ogg  \n";
+
+        assert_eq!(
+            parse_manifest(manifest).unwrap(),
+            vec![("shot", "weapons/shot.wav"), ("reload", "reload.ogg")]
+        );
+    }
+
+    #[test]
+    fn sound_bank_parse_manifest_FAIL() -> () {
+        assert!(parse_manifest("shot = shot.wav\nreload\n").is_err());
+        assert!(parse_manifest("= shot.wav\n").is_err());
+    }
+
+    #[test]
+    fn sound_bank_from_directory_OK() -> () {
+        let bank = SoundBank::from_directory("res").unwrap();
+        let names = bank.names();
+
+        assert!(names.contains(&"explosion".to_string()));
+        assert!(names.contains(&"music".to_string()));
+        assert!(!names.contains(&"LICENSE".to_string()));
+        assert!(!bank.is_loaded("explosion"));
+    }
+
+    #[test]
+    fn sound_bank_unknown_name_FAIL() -> () {
+        match SoundBank::new().get("explosion") {
+            Err(SoundError::UnknownName(name)) => assert_eq!(name, "explosion"),
+            _ => panic!("expected an unknown name"),
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_bank_shared_data_OK() -> () {
+        let bank = SoundBank::new();
+        bank.add("explosion", "res/explosion.wav");
+
+        let sound = bank.sound("explosion").unwrap();
+        assert!(bank.is_loaded("explosion"));
+        assert_eq!(bank.unload_unused(), 0);
+        drop(sound);
+        assert_eq!(bank.unload_unused(), 1);
+    }
+}
@@ -23,14 +23,26 @@
 
 use libc::c_void;
 use std::mem;
+use std::path::Path;
+#[cfg(feature = "chromaprint")]
+use std::path::PathBuf;
+use std::time::Duration;
 use std::vec::Vec;
 
 use audio_tags::{get_sound_tags, AudioTags, Tags};
+use compressed_buffer;
 use error::SoundError;
+#[cfg(feature = "chromaprint")]
+use fingerprint::{self, Fingerprint, FingerprintError};
 use internal::OpenAlData;
+use limits;
+use logging::{self, LogLevel};
 use openal::{al, ffi};
+use sample::{self, Sample};
+use sndfile::FormatType::{FormatPcm16, FormatWav};
 use sndfile::OpenMode::Read;
 use sndfile::{SndFile, SndInfo};
+use spectrum;
 
 /**
  * Samples extracted from a file.
@@ -71,6 +83,11 @@ pub struct SoundData {
     nb_sample: i64,
     /// The OpenAl internal identifier for the buffer
     al_buffer: u32,
+    /// The peak amplitude envelope of the samples, empty if kept compressed
+    envelope: Vec<f32>,
+    /// The file the samples were loaded from
+    #[cfg(feature = "chromaprint")]
+    path: Option<PathBuf>,
 }
 
 impl SoundData {
@@ -81,6 +98,31 @@ impl SoundData {
      * file: samples and tags.
      * It's an easy way to share the same samples between man Sounds objects.
      *
+     * IMA ADPCM, MS ADPCM and mu-law WAV files are kept compressed in memory
+     * when the OpenAL implementation supports it. Files of more than 16 bits
+     * per sample, like 24 bits or float files, are decoded to 32 bits floats
+     * with the AL_EXT_FLOAT32 extension. Otherwise the samples are decoded to
+     * 16 bits PCM.
+     *
+     * # Arguments
+     * * `path` - The path of the file to load
+     *
+     * # Return
+     * A `Result` containing Ok(SoundData) on success, Err(SoundError)
+     * if there has been an error.
+     */
+    pub fn new<P: AsRef<Path>>(path: P) -> Result<SoundData, SoundError> {
+        SoundData::load(path.as_ref(), false)
+    }
+
+    /**
+     * Create a new SoundData, downmixing the file to mono.
+     *
+     * OpenAL only spatializes mono buffers: stereo and multi-channel sounds
+     * are not attenuated by distance nor panned according to their position.
+     * Averaging the channels of such files when loading them makes them
+     * behave like any other positioned sound.
+     *
      * # Arguments
      * * `path` - The path of the file to load
      *
@@ -88,7 +130,11 @@ impl SoundData {
      * A `Result` containing Ok(SoundData) on success, Err(SoundError)
      * if there has been an error.
      */
-    pub fn new(path: &str) -> Result<SoundData, SoundError> {
+    pub fn new_mono<P: AsRef<Path>>(path: P) -> Result<SoundData, SoundError> {
+        SoundData::load(path.as_ref(), true)
+    }
+
+    fn load(path: &Path, downmix: bool) -> Result<SoundData, SoundError> {
         check_openal_context!(Err(SoundError::InvalidOpenALContext));
 
         let mut file = match SndFile::new(path, Read) {
@@ -98,49 +144,310 @@ impl SoundData {
             }
         };
 
-        let infos = file.get_sndinfo();
+        // The file is closed whether its samples could be loaded or not
+        let loaded = SoundData::load_file(path, &mut file, downmix);
+        file.close();
+        loaded
+    }
 
-        let nb_sample = infos.channels as i64 * infos.frames;
+    fn load_file(path: &Path, file: &mut SndFile, downmix: bool) -> Result<SoundData, SoundError> {
+        let mut infos = file.get_sndinfo();
 
-        let mut samples = vec![0i16; nb_sample as usize];
-        file.read_i16(&mut samples[..], nb_sample as i64);
+        let declared_samples = limits::decoded_samples(&infos)?;
+        let mut nb_sample = declared_samples as i64;
+        let mut envelope = Vec::new();
+        let downmix = downmix && infos.channels > 1;
 
         let mut buffer_id = 0;
-        let len = mem::size_of::<i16>() * (samples.len());
+        al::alGenBuffers(1, &mut buffer_id);
 
-        // Retrieve format informations
-        let format = match al::get_channels_format(infos.channels) {
-            Some(fmt) => fmt,
-            None => {
-                return Err(SoundError::InvalidFormat);
+        let compressed = match downmix {
+            true => None,
+            false => compressed_buffer::load(path, &infos),
+        };
+
+        let uploaded = match compressed {
+            Some(mut compressed) => {
+                if let Some(alignment) = compressed.block_alignment {
+                    al::alBufferi(buffer_id, ffi::AL_UNPACK_BLOCK_ALIGNMENT_SOFT, alignment);
+                }
+                al::alBufferData(
+                    buffer_id,
+                    compressed.format,
+                    compressed.data.as_mut_ptr() as *mut c_void,
+                    compressed.data.len() as i32,
+                    infos.samplerate,
+                );
+                match al::openal_has_error() {
+                    None => true,
+                    Some(err) => {
+                        let message = format!(
+                            "cannot upload the compressed data of {}, decoding it: {}",
+                            path.display(),
+                            err
+                        );
+                        logging::log(LogLevel::Warning, &message);
+                        // A new buffer, without the block alignment
+                        unsafe { ffi::alDeleteBuffers(1, &mut buffer_id) };
+                        al::alGenBuffers(1, &mut buffer_id);
+                        false
+                    }
+                }
             }
+            None => false,
+        };
+
+        if !uploaded {
+            let channels = match downmix {
+                true => 1,
+                false => infos.channels,
+            };
+            // Files of more than 16 bits are decoded to floats, when OpenAL supports them
+            let decoded = match sample::float_format(infos.format, channels) {
+                Some(format) => decode::<f32>(
+                    file,
+                    &mut infos,
+                    declared_samples,
+                    downmix,
+                    buffer_id,
+                    format,
+                ),
+                None => match al::get_channels_format(channels) {
+                    Some(format) => decode::<i16>(
+                        file,
+                        &mut infos,
+                        declared_samples,
+                        downmix,
+                        buffer_id,
+                        format,
+                    ),
+                    None => Err(SoundError::UnsupportedChannelCount(channels)),
+                },
+            };
+            match decoded {
+                Ok((samples, decoded_envelope)) => {
+                    nb_sample = samples;
+                    envelope = decoded_envelope;
+                }
+                Err(err) => {
+                    unsafe { ffi::alDeleteBuffers(1, &mut buffer_id) };
+                    return Err(err);
+                }
+            }
+        }
+
+        if let Some(err) = al::openal_has_error() {
+            unsafe { ffi::alDeleteBuffers(1, &mut buffer_id) };
+            return Err(SoundError::InternalOpenALError(err));
         };
 
+        let sound_data = SoundData {
+            sound_tags: get_sound_tags(file),
+            snd_info: infos,
+            nb_sample,
+            al_buffer: buffer_id,
+            envelope,
+            #[cfg(feature = "chromaprint")]
+            path: Some(path.to_path_buf()),
+        };
+
+        Ok(sound_data)
+    }
+
+    /**
+     * Create a SoundData from interleaved 16 bits samples.
+     *
+     * Use it to play audio generated by the program or decoded by another
+     * crate, without writing it to a file first. The samples are copied.
+     *
+     * # Arguments
+     * * `samples` - The interleaved samples
+     * * `channels` - The number of channels of the samples: 1, 2, 4, 6, 7 or 8
+     * * `sample_rate` - The sample rate of the samples
+     *
+     * # Return
+     * A `Result` containing Ok(SoundData) on success, Err(SoundError)
+     * if there has been an error, for instance
+     * Err(SoundError::UnsupportedChannelCount) for an unsupported number of
+     * channels or Err(SoundError::InvalidFormat) for a number of samples
+     * which isn't a multiple of it.
+     *
+     * # Example
+     * ```no_run
+     * use ears::{AudioController, Sound, SoundData, SoundError};
+     * use std::sync::{Arc, Mutex};
+     *
+     * fn main() -> Result<(), SoundError> {
+     *     // A 200ms square wave bleep at 880Hz
+     *     let samples: Vec<i16> = (0..8820)
+     *         .map(|i| if (i / 25) % 2 == 0 { 8000 } else { -8000 })
+     *         .collect();
+     *     let data = SoundData::from_samples(&samples, 1, 44100)?;
+     *     let mut bleep = Sound::new_with_data(Arc::new(Mutex::new(data)))?;
+     *     bleep.play();
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn from_samples(
+        samples: &[i16],
+        channels: i32,
+        sample_rate: i32,
+    ) -> Result<SoundData, SoundError> {
+        check_openal_context!(Err(SoundError::InvalidOpenALContext));
+
+        let format = match al::get_channels_format(channels) {
+            Some(fmt) => fmt,
+            None => return Err(SoundError::UnsupportedChannelCount(channels)),
+        };
+        if !samples.len().is_multiple_of(channels as usize) || sample_rate <= 0 {
+            return Err(SoundError::InvalidFormat);
+        }
+
+        let mut buffer_id = 0;
         al::alGenBuffers(1, &mut buffer_id);
         al::alBufferData(
             buffer_id,
             format,
             samples.as_ptr() as *mut c_void,
-            len as i32,
-            infos.samplerate,
+            mem::size_of_val(samples) as i32,
+            sample_rate,
         );
 
         if let Some(err) = al::openal_has_error() {
+            unsafe { ffi::alDeleteBuffers(1, &mut buffer_id) };
             return Err(SoundError::InternalOpenALError(err));
         };
 
-        let sound_data = SoundData {
-            sound_tags: get_sound_tags(&file),
-            snd_info: infos,
-            nb_sample: nb_sample,
+        Ok(SoundData {
+            sound_tags: Tags::default(),
+            snd_info: SndInfo {
+                frames: samples.len() as i64 / channels as i64,
+                samplerate: sample_rate,
+                channels,
+                format: (FormatPcm16 | FormatWav) as i32,
+                sections: 1,
+                seekable: 1,
+            },
+            nb_sample: samples.len() as i64,
             al_buffer: buffer_id,
-        };
-        file.close();
+            envelope: spectrum::envelope(samples, channels as usize),
+            #[cfg(feature = "chromaprint")]
+            path: None,
+        })
+    }
 
-        Ok(sound_data)
+    /**
+     * Get the duration of the samples.
+     */
+    pub fn get_duration(&self) -> Duration {
+        let frames = self.frame_count();
+        let sample_rate = self.sample_rate() as u64;
+
+        let seconds = frames / sample_rate;
+        let nanoseconds = frames % sample_rate * 1_000_000_000 / sample_rate;
+
+        Duration::new(seconds, nanoseconds as u32)
+    }
+
+    /// Get the sample rate of the samples, in Hz.
+    pub fn sample_rate(&self) -> u32 {
+        self.snd_info.samplerate as u32
+    }
+
+    /// Get the number of channels of the samples, 1 once downmixed to mono.
+    pub fn channel_count(&self) -> usize {
+        self.snd_info.channels as usize
+    }
+
+    /**
+     * Get the waveform of the samples, to draw it.
+     *
+     * # Argument
+     * * `points` - The number of points to compute, like the width in
+     *   pixels of the drawing
+     *
+     * # Return
+     * The peak amplitude of each point, between 0.0 and 1.0, from the start
+     * to the end of the samples. All zeros for the files kept compressed in
+     * memory.
+     */
+    pub fn waveform(&self, points: usize) -> Vec<f32> {
+        spectrum::waveform(&self.envelope, points)
+    }
+
+    /// Get the number of frames of the samples.
+    pub fn frame_count(&self) -> u64 {
+        self.snd_info.frames.max(0) as u64
+    }
+
+    /**
+     * Compute the chromaprint fingerprint of the SoundData.
+     *
+     * The file the SoundData was loaded from is read again, see
+     * `ears::fingerprint_file`.
+     *
+     * # Return
+     * A `Result` containing Ok(Fingerprint) on success, Err(FingerprintError)
+     * if there has been an error or if the SoundData wasn't loaded from a
+     * file.
+     */
+    #[cfg(feature = "chromaprint")]
+    pub fn fingerprint(&self) -> Result<Fingerprint, FingerprintError> {
+        match self.path {
+            Some(ref path) => fingerprint::fingerprint_file(path),
+            None => Err(FingerprintError::NoSourceFile),
+        }
     }
 }
 
+// Decode the samples of a file into an OpenAL buffer, downmixed to mono or
+// not, returning the number of samples and their envelope.
+fn decode<S: Sample>(
+    file: &mut SndFile,
+    infos: &mut SndInfo,
+    declared_samples: usize,
+    downmix: bool,
+    buffer_id: u32,
+    format: i32,
+) -> Result<(i64, Vec<f32>), SoundError> {
+    let mut samples = Vec::new();
+    if samples.try_reserve_exact(declared_samples).is_err() {
+        return Err(SoundError::TooLarge);
+    }
+    samples.resize(declared_samples, S::default());
+    // The header may declare more frames than the file has
+    let read = S::read(file, &mut samples[..], declared_samples as i64);
+    samples.truncate(read.max(0) as usize);
+    samples.truncate(samples.len() - samples.len() % infos.channels as usize);
+    infos.frames = (samples.len() / infos.channels as usize) as i64;
+
+    if downmix {
+        samples = downmix_to_mono(&samples, infos.channels as usize);
+        infos.channels = 1;
+    }
+
+    al::alBufferData(
+        buffer_id,
+        format,
+        samples.as_ptr() as *mut c_void,
+        (mem::size_of::<S>() * samples.len()) as i32,
+        infos.samplerate,
+    );
+    let envelope = spectrum::envelope(&samples, infos.channels as usize);
+    Ok((samples.len() as i64, envelope))
+}
+
+// Average the channels of each interleaved frame.
+fn downmix_to_mono<S: Sample>(samples: &[S], channels: usize) -> Vec<S> {
+    samples
+        .chunks(channels)
+        .map(|frame| {
+            S::from_f32(frame.iter().map(|s| s.to_f32()).sum::<f32>() / frame.len() as f32)
+        })
+        .collect()
+}
+
 /**
  * Get the sound file infos.
  *
@@ -177,6 +484,10 @@ impl AudioTags for SoundData {
 impl Drop for SoundData {
     /// Destroy all the resources attached to the SoundData
     fn drop(&mut self) -> () {
+        // The objects were deleted with the context
+        if !OpenAlData::is_created() {
+            return;
+        }
         unsafe {
             ffi::alDeleteBuffers(1, &mut self.al_buffer);
         }
@@ -188,7 +499,7 @@ mod test {
     #![allow(non_snake_case)]
 
     #[allow(unused_variables)]
-    use sound_data::SoundData;
+    use sound_data::{downmix_to_mono, SoundData};
 
     #[test]
     #[ignore]
@@ -204,4 +515,39 @@ mod test {
         #![allow(unused_variables)]
         let snd_data = SoundData::new("toto.wav").unwrap();
     }
+
+    #[test]
+    #[ignore]
+    fn sounddata_create_mono_OK() -> () {
+        let snd_data = SoundData::new_mono("res/explosion.wav").unwrap();
+
+        assert_eq!(snd_data.snd_info.channels, 1);
+    }
+
+    #[test]
+    #[ignore]
+    fn sounddata_from_samples_OK() -> () {
+        let snd_data = SoundData::from_samples(&[0, 100, 200, 300], 2, 22050).unwrap();
+
+        assert_eq!(snd_data.snd_info.frames, 2);
+        assert_eq!(snd_data.snd_info.samplerate, 22050);
+        assert_eq!(snd_data.frame_count(), 2);
+        assert_eq!(snd_data.sample_rate(), 22050);
+        assert_eq!(snd_data.channel_count(), 2);
+        assert_eq!(snd_data.get_duration().as_micros(), 90);
+    }
+
+    #[test]
+    #[ignore]
+    fn sounddata_from_samples_FAIL() -> () {
+        assert!(SoundData::from_samples(&[0, 100, 200], 2, 44100).is_err());
+        assert!(SoundData::from_samples(&[0, 100, 200], 3, 44100).is_err());
+    }
+
+    #[test]
+    fn downmix_to_mono_OK() -> () {
+        let samples = [100, 300, -32768, -32768, 32767, 32767, 10, -10];
+
+        assert_eq!(downmix_to_mono(&samples, 2), vec![200, -32768, 32767, 0]);
+    }
 }
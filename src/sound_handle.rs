@@ -0,0 +1,248 @@
+//! Fire-and-forget playback of Sounds.
+
+use std::collections::HashMap;
+use std::mem;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use audio_controller::AudioController;
+use error::SoundError;
+use preload;
+use sound::Sound;
+use sound_data::SoundData;
+use source_settings::SourceSettings;
+use states::State;
+
+/// The number of finished Sounds kept to be reused
+const MAX_IDLE: usize = 16;
+
+#[derive(Default)]
+struct OneShots {
+    /// The Sounds playing, by handle id
+    playing: HashMap<u64, Sound>,
+    /// The finished Sounds, whose sources are reused
+    idle: Vec<Sound>,
+    next_id: u64,
+}
+
+lazy_static! {
+    static ref ONE_SHOTS: Mutex<OneShots> = Mutex::new(OneShots::default());
+}
+
+/**
+ * A handle on a Sound played with `play_sound`.
+ *
+ * The Sound belongs to __ears__, and is reclaimed once it finished playing:
+ * the handle can be dropped or kept, and does nothing once the Sound is
+ * done.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SoundHandle {
+    id: u64,
+}
+
+/**
+ * Play a sound file, without keeping the Sound alive.
+ *
+ * The file is loaded like with `Sound::new`, so preloading it with
+ * `ears::preload` avoids reading it on each call. The source of the Sound is
+ * reclaimed once it finished playing, and reused by the next calls.
+ *
+ * # Argument
+ * * `path` - The path of the sound file
+ *
+ * # Return
+ * A `Result` containing Ok(SoundHandle) on success, Err(SoundError)
+ * if there has been an error.
+ *
+ * # Example
+ * ```no_run
+ * fn main() -> Result<(), ears::SoundError> {
+ *     ears::preload(vec!["res/explosion.wav"]).wait();
+ *
+ *     let explosion = ears::play_sound("res/explosion.wav")?;
+ *     explosion.set_position([10.0, 0.0, -5.0]);
+ *     Ok(())
+ * }
+ * ```
+ */
+pub fn play_sound<P: AsRef<Path>>(path: P) -> Result<SoundHandle, SoundError> {
+    let sound_data = match preload::cached(path.as_ref()) {
+        Some(sound_data) => sound_data,
+        None => Arc::new(Mutex::new(SoundData::new(path)?)),
+    };
+    play_sound_data(sound_data)
+}
+
+/**
+ * Play a SoundData, without keeping the Sound alive.
+ *
+ * # Argument
+ * * `sound_data` - The samples to play
+ *
+ * # Return
+ * A `Result` containing Ok(SoundHandle) on success, Err(SoundError)
+ * if there has been an error.
+ */
+pub fn play_sound_data(sound_data: Arc<Mutex<SoundData>>) -> Result<SoundHandle, SoundError> {
+    let (idle, id) = {
+        let mut one_shots = ONE_SHOTS.lock().unwrap();
+        reclaim_stopped(&mut one_shots);
+        let id = one_shots.next_id;
+        one_shots.next_id += 1;
+        (one_shots.idle.pop(), id)
+    };
+
+    let mut sound = match idle {
+        Some(mut sound) => {
+            sound.apply(&SourceSettings::default());
+            sound.set_datas(sound_data);
+            sound
+        }
+        None => Sound::new_with_data(sound_data)?,
+    };
+    sound.set_complete_callback(move || release(id));
+
+    let mut one_shots = ONE_SHOTS.lock().unwrap();
+    sound.play();
+    one_shots.playing.insert(id, sound);
+    Ok(SoundHandle { id })
+}
+
+/// Move the Sounds stopped without completing, e.g. by `stop_all`, to the idle ones.
+fn reclaim_stopped(one_shots: &mut OneShots) {
+    let stopped: Vec<u64> = one_shots
+        .playing
+        .iter()
+        .filter(|(_, sound)| sound.get_state() == State::Stopped)
+        .map(|(id, _)| *id)
+        .collect();
+    for id in stopped {
+        let sound = one_shots.playing.remove(&id).unwrap();
+        if one_shots.idle.len() < MAX_IDLE {
+            one_shots.idle.push(sound);
+        }
+    }
+}
+
+/// Reclaim a Sound which finished playing, called by its complete callback.
+fn release(id: u64) {
+    let dropped = {
+        let mut one_shots = ONE_SHOTS.lock().unwrap();
+        match one_shots.playing.remove(&id) {
+            Some(sound) if one_shots.idle.len() < MAX_IDLE => {
+                one_shots.idle.push(sound);
+                None
+            }
+            sound => sound,
+        }
+    };
+    drop(dropped);
+}
+
+/// Delete all the Sounds, when the context is cleaned up.
+pub(crate) fn clear() {
+    let one_shots = mem::take(&mut *ONE_SHOTS.lock().unwrap());
+    drop(one_shots);
+}
+
+impl SoundHandle {
+    /**
+     * Use the Sound of the handle, while it is playing.
+     *
+     * The function must not play nor stop other handles, as they share a
+     * lock with this one.
+     *
+     * # Argument
+     * * `function` - The function called with the Sound
+     *
+     * # Return
+     * The result of the function, None if the Sound finished playing.
+     *
+     * # Example
+     * ```no_run
+     * use ears::AudioController;
+     *
+     * fn main() -> Result<(), ears::SoundError> {
+     *     let engine = ears::play_sound("res/engine.ogg")?;
+     *     engine.with(|sound| sound.set_air_absorption_factor(2.0));
+     *     Ok(())
+     * }
+     * ```
+     */
+    pub fn with<F, R>(&self, function: F) -> Option<R>
+    where
+        F: FnOnce(&mut Sound) -> R,
+    {
+        let mut one_shots = ONE_SHOTS.lock().unwrap();
+        one_shots.playing.get_mut(&self.id).map(function)
+    }
+
+    /**
+     * Check if the Sound is still playing.
+     */
+    pub fn is_playing(&self) -> bool {
+        self.with(|sound| sound.is_playing()).unwrap_or(false)
+    }
+
+    /**
+     * Stop the Sound, which is then reclaimed.
+     */
+    pub fn stop(&self) {
+        let mut one_shots = ONE_SHOTS.lock().unwrap();
+        if let Some(sound) = one_shots.playing.get_mut(&self.id) {
+            sound.stop();
+        }
+        reclaim_stopped(&mut one_shots);
+    }
+
+    /**
+     * Set the volume of the Sound, see `AudioController::set_volume`.
+     */
+    pub fn set_volume(&self, volume: f32) {
+        self.with(|sound| sound.set_volume(volume));
+    }
+
+    /**
+     * Set the position of the Sound, see `AudioController::set_position`.
+     */
+    pub fn set_position(&self, position: [f32; 3]) {
+        self.with(|sound| sound.set_position(position));
+    }
+
+    /**
+     * Set the pitch of the Sound, see `AudioController::set_pitch`.
+     */
+    pub fn set_pitch(&self, pitch: f32) {
+        self.with(|sound| sound.set_pitch(pitch));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::play_sound;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    #[test]
+    #[ignore]
+    fn sound_handle_play_sound_OK() -> () {
+        let handle = play_sound("res/explosion.wav").unwrap();
+
+        assert!(handle.is_playing());
+        handle.stop();
+        assert!(!handle.is_playing());
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_handle_reclaimed_OK() -> () {
+        let handle = play_sound("res/explosion.wav").unwrap();
+
+        sleep(Duration::from_secs(3));
+        assert!(!handle.is_playing());
+        assert_eq!(handle.with(|_| ()), None);
+    }
+}
@@ -0,0 +1,285 @@
+//! Pools of voices playing the same SoundData, with a limited number of instances.
+
+use audio_controller::AudioController;
+use error::SoundError;
+use sound::Sound;
+use sound_data::SoundData;
+use states::State::{Paused, Playing};
+use std::sync::{Arc, Mutex};
+
+/// Which voice to take over when all the voices of a SoundPool are playing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoiceStealing {
+    /// Restart the voice which started playing first
+    Oldest,
+    /// Restart the voice with the lowest volume
+    Quietest,
+    /// Don't play the new instance
+    Never,
+}
+
+struct Voice {
+    sound: Sound,
+    /// When the voice started playing, in number of voices started before it
+    started: u64,
+}
+
+/// The state of a voice, to choose the voice to play.
+struct VoiceState {
+    /// Whether the voice is playing or paused, a free voice is neither
+    playing: bool,
+    started: u64,
+    volume: f32,
+}
+
+/// The voice to play a new instance on.
+#[derive(Debug, PartialEq)]
+enum Pick {
+    Reuse(usize),
+    Create,
+    Steal(usize),
+    Nothing,
+}
+
+/**
+ * Play many instances of the same SoundData, with a limited number of them
+ * playing at once.
+ *
+ * Creating a Sound for each gunshot of a machine gun quickly exhausts the
+ * OpenAL sources. A SoundPool instead creates at most `max_voices` Sounds,
+ * reuses the ones which stopped playing and, once they are all playing,
+ * takes over the oldest or the quietest one.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, SoundData, SoundPool, VoiceStealing};
+ * use std::sync::{Arc, Mutex};
+ *
+ * let data = Arc::new(Mutex::new(SoundData::new("path/to/shot.ogg").unwrap()));
+ * let mut shots = SoundPool::new(data, 8);
+ * shots.set_stealing(VoiceStealing::Oldest);
+ *
+ * for _ in 0..100 {
+ *     if let Some(shot) = shots.play().unwrap() {
+ *         shot.set_pitch(1.1);
+ *     }
+ * }
+ * ```
+ */
+pub struct SoundPool {
+    sound_data: Arc<Mutex<SoundData>>,
+    max_voices: usize,
+    stealing: VoiceStealing,
+    voices: Vec<Voice>,
+    /// The number of voices started so far
+    started: u64,
+}
+
+impl SoundPool {
+    /**
+     * Create a SoundPool, without any voice yet.
+     *
+     * # Arguments
+     * * `sound_data` - The SoundData played by the voices
+     * * `max_voices` - The maximum number of voices playing at once, at least 1
+     */
+    pub fn new(sound_data: Arc<Mutex<SoundData>>, max_voices: usize) -> SoundPool {
+        SoundPool {
+            sound_data,
+            max_voices: max_voices.max(1),
+            stealing: VoiceStealing::Oldest,
+            voices: Vec::new(),
+            started: 0,
+        }
+    }
+
+    /**
+     * Get the SoundData played by the voices.
+     */
+    pub fn get_datas(&self) -> Arc<Mutex<SoundData>> {
+        self.sound_data.clone()
+    }
+
+    /**
+     * Get the maximum number of voices playing at once.
+     */
+    pub fn max_voices(&self) -> usize {
+        self.max_voices
+    }
+
+    /**
+     * Set which voice to take over once all the voices are playing.
+     *
+     * # Argument
+     * * `stealing` - The stealing policy. Default is VoiceStealing::Oldest.
+     */
+    pub fn set_stealing(&mut self, stealing: VoiceStealing) {
+        self.stealing = stealing;
+    }
+
+    /**
+     * Get which voice is taken over once all the voices are playing.
+     */
+    pub fn get_stealing(&self) -> VoiceStealing {
+        self.stealing
+    }
+
+    /**
+     * Get the number of voices currently playing.
+     */
+    pub fn playing_voices(&self) -> usize {
+        self.voices
+            .iter()
+            .filter(|voice| voice.sound.is_playing())
+            .count()
+    }
+
+    /**
+     * Get a voice ready to play a new instance, stopped.
+     *
+     * The voice keeps the settings of its previous instance, like its
+     * volume or position, so they can be set before playing it.
+     *
+     * # Return
+     * The voice, or None if all the voices are playing and the stealing
+     * policy is VoiceStealing::Never. A SoundError if a new voice can't be
+     * created.
+     */
+    pub fn next_voice(&mut self) -> Result<Option<&mut Sound>, SoundError> {
+        let states: Vec<VoiceState> = self
+            .voices
+            .iter()
+            .map(|voice| VoiceState {
+                playing: matches!(voice.sound.get_state(), Playing | Paused),
+                started: voice.started,
+                volume: voice.sound.get_volume(),
+            })
+            .collect();
+        let index = match pick_voice(&states, self.max_voices, self.stealing) {
+            Pick::Reuse(index) => index,
+            Pick::Steal(index) => {
+                self.voices[index].sound.stop();
+                index
+            }
+            Pick::Create => {
+                let sound = Sound::new_with_data(self.sound_data.clone())?;
+                self.voices.push(Voice { sound, started: 0 });
+                self.voices.len() - 1
+            }
+            Pick::Nothing => return Ok(None),
+        };
+        let voice = &mut self.voices[index];
+        voice.started = self.started;
+        self.started += 1;
+        Ok(Some(&mut voice.sound))
+    }
+
+    /**
+     * Play a new instance of the SoundData.
+     *
+     * # Return
+     * The playing voice, or None if all the voices are playing and the
+     * stealing policy is VoiceStealing::Never. A SoundError if a new voice
+     * can't be created.
+     */
+    pub fn play(&mut self) -> Result<Option<&mut Sound>, SoundError> {
+        let mut voice = self.next_voice()?;
+        if let Some(ref mut sound) = voice {
+            sound.play();
+        }
+        Ok(voice)
+    }
+
+    /**
+     * Stop all the voices.
+     */
+    pub fn stop(&mut self) {
+        for voice in self.voices.iter_mut() {
+            voice.sound.stop();
+        }
+    }
+}
+
+/// Choose the voice to play a new instance on.
+fn pick_voice(voices: &[VoiceState], max_voices: usize, stealing: VoiceStealing) -> Pick {
+    if let Some(index) = voices.iter().position(|voice| !voice.playing) {
+        return Pick::Reuse(index);
+    }
+    if voices.len() < max_voices {
+        return Pick::Create;
+    }
+    let index = match stealing {
+        VoiceStealing::Oldest => (0..voices.len()).min_by_key(|i| voices[*i].started),
+        VoiceStealing::Quietest => (0..voices.len()).min_by(|a, b| {
+            voices[*a]
+                .volume
+                .partial_cmp(&voices[*b].volume)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(voices[*a].started.cmp(&voices[*b].started))
+        }),
+        VoiceStealing::Never => None,
+    };
+    match index {
+        Some(index) => Pick::Steal(index),
+        None => Pick::Nothing,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{pick_voice, Pick, SoundPool, VoiceState, VoiceStealing};
+    use sound_data::SoundData;
+    use std::sync::{Arc, Mutex};
+
+    fn voice(playing: bool, started: u64, volume: f32) -> VoiceState {
+        VoiceState {
+            playing,
+            started,
+            volume,
+        }
+    }
+
+    #[test]
+    fn sound_pool_pick_voice_OK() -> () {
+        let voices = [voice(true, 0, 1.0), voice(false, 1, 1.0)];
+
+        assert_eq!(pick_voice(&voices, 2, VoiceStealing::Never), Pick::Reuse(1));
+        assert_eq!(
+            pick_voice(&voices[..1], 2, VoiceStealing::Never),
+            Pick::Create
+        );
+    }
+
+    #[test]
+    fn sound_pool_pick_voice_steal_OK() -> () {
+        let voices = [
+            voice(true, 3, 1.0),
+            voice(true, 1, 0.8),
+            voice(true, 2, 0.2),
+        ];
+
+        assert_eq!(
+            pick_voice(&voices, 3, VoiceStealing::Oldest),
+            Pick::Steal(1)
+        );
+        assert_eq!(
+            pick_voice(&voices, 3, VoiceStealing::Quietest),
+            Pick::Steal(2)
+        );
+        assert_eq!(pick_voice(&voices, 3, VoiceStealing::Never), Pick::Nothing);
+    }
+
+    #[test]
+    #[ignore]
+    fn sound_pool_play_OK() -> () {
+        let data = SoundData::new("res/shots.ogg").expect("Cannot create SoundData");
+        let mut pool = SoundPool::new(Arc::new(Mutex::new(data)), 2);
+
+        for _ in 0..5 {
+            assert!(pool.play().expect("Cannot play the voice").is_some());
+        }
+        assert_eq!(pool.playing_voices(), 2);
+    }
+}
@@ -0,0 +1,119 @@
+//! Groups of Audio Sources started on the same sample.
+
+use audio_controller::AudioController;
+use internal::OpenAlData;
+use schedule;
+
+/**
+ * Audio Sources to start together.
+ *
+ * Calling `play` on each Sound or Music leaves them a few samples apart,
+ * which is heard when they are layers of the same piece. A SourceGroup
+ * starts them all with a single OpenAL call instead, so they begin
+ * sample-aligned.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{Music, SoundError, SourceGroup};
+ *
+ * fn main() -> Result<(), SoundError> {
+ *     let mut drums = Music::new("path/to/drums.ogg")?;
+ *     let mut bass = Music::new("path/to/bass.ogg")?;
+ *     let mut melody = Music::new("path/to/melody.ogg")?;
+ *
+ *     SourceGroup::new()
+ *         .add(&mut drums)
+ *         .add(&mut bass)
+ *         .add(&mut melody)
+ *         .play_all();
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Default)]
+pub struct SourceGroup<'a> {
+    sources: Vec<&'a mut dyn AudioController>,
+}
+
+impl<'a> SourceGroup<'a> {
+    /// Create an empty SourceGroup.
+    pub fn new() -> SourceGroup<'a> {
+        SourceGroup {
+            sources: Vec::new(),
+        }
+    }
+
+    /**
+     * Add an Audio Source to the group.
+     *
+     * # Argument
+     * * `source` - The Sound or Music to start with the others
+     */
+    pub fn add(&mut self, source: &'a mut dyn AudioController) -> &mut SourceGroup<'a> {
+        self.sources.push(source);
+        self
+    }
+
+    /// Get the number of Audio Sources in the group.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Check if the group has no Audio Source.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /**
+     * Play or resume all the Audio Sources of the group at once.
+     *
+     * Each Audio Source is prepared like with `play`, then they are all
+     * started by a single alSourcePlayv call.
+     */
+    pub fn play_all(&mut self) {
+        check_openal_context!();
+
+        let sources = &mut self.sources;
+        schedule::play_together(|| {
+            for source in sources.iter_mut() {
+                source.play();
+            }
+        });
+    }
+
+    /**
+     * Pause all the Audio Sources of the group at once.
+     *
+     * They are all paused by a single alSourcePausev call, so they stay
+     * aligned when resumed with `play_all`.
+     */
+    pub fn pause_all(&mut self) {
+        check_openal_context!();
+
+        let sources = &mut self.sources;
+        schedule::pause_together(|| {
+            for source in sources.iter_mut() {
+                source.pause();
+            }
+        });
+    }
+
+    /**
+     * Stop all the Audio Sources of the group at once.
+     *
+     * They are first paused together, then stopped by a single
+     * alSourceStopv call. A Music waits for its stream to end, so it is
+     * stopped on its own once silent.
+     */
+    pub fn stop_all(&mut self) {
+        check_openal_context!();
+
+        self.pause_all();
+        let sources = &mut self.sources;
+        schedule::stop_together(|| {
+            for source in sources.iter_mut() {
+                source.stop();
+            }
+        });
+    }
+}
@@ -0,0 +1,62 @@
+//! Playback offsets with the latency of the device, from the
+//! AL_SOFT_source_latency extension.
+
+use internal::OpenAlData;
+use libc::c_void;
+use openal::ffi;
+use std::ffi::CString;
+use std::mem;
+use std::time::Duration;
+
+/**
+ * Read the playback offset and the latency of a source at the same instant.
+ *
+ * # Argument
+ * * `source` - The source to read
+ *
+ * # Return
+ * The offset of the source in its queue and the latency of the device, both
+ * in seconds, or `None` if the AL_SOFT_source_latency extension is not
+ * present.
+ */
+pub(crate) fn offset_latency(source: u32) -> Option<(f64, f64)> {
+    if !OpenAlData::source_latency_capable() {
+        return None;
+    }
+
+    let name = CString::new("alGetSourcedvSOFT").unwrap();
+    let get_sourcedv = unsafe { ffi::alGetProcAddress(name.as_ptr()) };
+    if get_sourcedv.is_null() {
+        return None;
+    }
+    let get_sourcedv = unsafe { mem::transmute::<*mut c_void, ffi::GetSourcedvFn>(get_sourcedv) };
+
+    let mut values = [0f64; 2];
+    unsafe {
+        get_sourcedv(source, ffi::AL_SEC_OFFSET_LATENCY_SOFT, values.as_mut_ptr());
+    }
+    Some((values[0], values[1]))
+}
+
+/// Convert seconds read from OpenAL into a Duration, negative ones being 0.
+pub(crate) fn seconds_to_duration(seconds: f64) -> Duration {
+    Duration::from_secs_f64(seconds.max(0.0))
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::seconds_to_duration;
+    use std::time::Duration;
+
+    #[test]
+    fn source_latency_seconds_to_duration_OK() -> () {
+        assert_eq!(seconds_to_duration(1.5), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn source_latency_seconds_to_duration_negative_FAIL() -> () {
+        assert_eq!(seconds_to_duration(-0.5), Duration::from_secs(0));
+    }
+}
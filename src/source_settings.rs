@@ -0,0 +1,109 @@
+//! Settings which can be applied to a Sound or a Music all at once.
+
+use filter::FilterType;
+use std::f32;
+
+/**
+ * The properties of an Audio Source.
+ *
+ * Useful for data driven setups, or to reset a pooled source before reusing
+ * it. The default value of each field is the OpenAL default.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Sound, SoundError, SourceSettings};
+ *
+ * fn main() -> Result<(), SoundError> {
+ *     let settings = SourceSettings {
+ *         volume: 0.5,
+ *         position: [3.0, 0.0, -2.0],
+ *         looping: true,
+ *         ..Default::default()
+ *     };
+ *
+ *     let mut snd = Sound::new("path/to/my/sound.ogg")?;
+ *     snd.apply(&settings);
+ *     snd.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SourceSettings {
+    /// The volume, see `AudioController::set_volume`
+    pub volume: f32,
+    /// The minimal volume, see `AudioController::set_min_volume`
+    pub min_volume: f32,
+    /// The maximal volume, see `AudioController::set_max_volume`
+    pub max_volume: f32,
+    /// The pitch, see `AudioController::set_pitch`
+    pub pitch: f32,
+    /// The position, see `AudioController::set_position`
+    pub position: [f32; 3],
+    /// The direction, see `AudioController::set_direction`
+    pub direction: [f32; 3],
+    /// The velocity, see `AudioController::set_velocity`
+    pub velocity: [f32; 3],
+    /// Whether the position is relative to the listener
+    pub relative: bool,
+    /// Whether the source is looping
+    pub looping: bool,
+    /// The maximum distance, see `AudioController::set_max_distance`
+    pub max_distance: f32,
+    /// The reference distance, see `AudioController::set_reference_distance`
+    pub reference_distance: f32,
+    /// The attenuation, see `AudioController::set_attenuation`
+    pub attenuation: f32,
+    /// The inner angle of the cone in degrees, see `AudioController::set_cone`
+    pub cone_inner_angle: f32,
+    /// The outer angle of the cone in degrees, see `AudioController::set_cone`
+    pub cone_outer_angle: f32,
+    /// The volume outside of the cone, see `AudioController::set_cone`
+    pub cone_outer_volume: f32,
+    /// Whether OpenAL adjusts the auxiliary sends, see
+    /// `AudioController::set_auxiliary_send_auto`
+    pub auxiliary_send_auto: bool,
+    /// Whether the Doppler effect applies, see `AudioController::set_doppler_enabled`
+    pub doppler_enabled: bool,
+    /// Whether the velocity follows the positions, see
+    /// `AudioController::set_auto_velocity`
+    pub auto_velocity: bool,
+    /// The kind of direct filter, None for no filter, see
+    /// `AudioController::set_direct_filter`
+    pub direct_filter: Option<FilterType>,
+    /// The gain of the direct filter, see `Filter::set_gain`
+    pub direct_filter_gain: f32,
+    /// The low frequency gain of the direct filter, see `Filter::set_gain_lf`
+    pub direct_filter_gain_lf: f32,
+    /// The high frequency gain of the direct filter, see `Filter::set_gain_hf`
+    pub direct_filter_gain_hf: f32,
+}
+
+impl Default for SourceSettings {
+    fn default() -> SourceSettings {
+        SourceSettings {
+            volume: 1.0,
+            min_volume: 0.0,
+            max_volume: 1.0,
+            pitch: 1.0,
+            position: [0.0; 3],
+            direction: [0.0; 3],
+            velocity: [0.0; 3],
+            relative: false,
+            looping: false,
+            max_distance: f32::MAX,
+            reference_distance: 1.0,
+            attenuation: 1.0,
+            cone_inner_angle: 360.0,
+            cone_outer_angle: 360.0,
+            cone_outer_volume: 0.0,
+            auxiliary_send_auto: true,
+            doppler_enabled: true,
+            auto_velocity: false,
+            direct_filter: None,
+            direct_filter_gain: 1.0,
+            direct_filter_gain_lf: 1.0,
+            direct_filter_gain_hf: 1.0,
+        }
+    }
+}
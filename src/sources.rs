@@ -0,0 +1,155 @@
+//! Global control of all the Audio Sources.
+
+use internal::OpenAlData;
+use openal::{al, ffi};
+use std::collections::HashMap;
+use std::mem;
+use std::sync::Mutex;
+
+/// How to stop an Audio Source, as its own `stop` would
+pub(crate) type Stop = Box<dyn Fn() + Send>;
+
+struct Entry {
+    /// Whether the source has been paused by `pause_all`
+    paused: bool,
+    stop: Stop,
+}
+
+lazy_static! {
+    /// The sources of all the living Audio Sources
+    static ref SOURCES: Mutex<HashMap<u32, Entry>> = Mutex::new(HashMap::new());
+}
+
+/**
+ * Register a source, or replace how it is stopped.
+ *
+ * # Arguments
+ * * `source` - The source of the Audio Source
+ * * `stop` - How to stop the Audio Source
+ */
+pub(crate) fn register(source: u32, stop: Stop) {
+    let mut sources = SOURCES.lock().unwrap();
+    let paused = sources.get(&source).is_some_and(|entry| entry.paused);
+    sources.insert(source, Entry { paused, stop });
+}
+
+/// Get the sources of all the living Audio Sources.
+pub(crate) fn all() -> Vec<u32> {
+    SOURCES.lock().unwrap().keys().cloned().collect()
+}
+
+/// Forget a source being deleted.
+pub(crate) fn unregister(source: u32) {
+    SOURCES.lock().unwrap().remove(&source);
+}
+
+/// Forget all the sources, when the context is cleaned up.
+pub(crate) fn clear() {
+    let sources = mem::take(&mut *SOURCES.lock().unwrap());
+    drop(sources);
+}
+
+/**
+ * Pause all the playing Audio Sources, e.g. when the game is minimized or
+ * a pause menu opens.
+ *
+ * Only the Audio Sources paused here are resumed by `resume_all`.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Music, Sound};
+ *
+ * let mut music = Music::new("path/to/music.ogg").unwrap();
+ * let mut shot = Sound::new("path/to/shot.ogg").unwrap();
+ * music.play();
+ * shot.play();
+ *
+ * // The pause menu opens
+ * ears::pause_all();
+ * // The pause menu closes
+ * ears::resume_all();
+ * ```
+ */
+pub fn pause_all() {
+    check_openal_context!();
+
+    let mut sources = SOURCES.lock().unwrap();
+    for (source, entry) in sources.iter_mut() {
+        if al::alGetState(*source) == ffi::AL_PLAYING {
+            al::alSourcePause(*source);
+            entry.paused = true;
+        }
+    }
+}
+
+/**
+ * Resume the Audio Sources paused by `pause_all`.
+ *
+ * The Audio Sources paused on their own, or played or stopped since,
+ * are left as they are.
+ */
+pub fn resume_all() {
+    check_openal_context!();
+
+    let mut sources = SOURCES.lock().unwrap();
+    for (source, entry) in sources.iter_mut() {
+        if entry.paused && al::alGetState(*source) == ffi::AL_PAUSED {
+            al::alSourcePlay(*source);
+        }
+        entry.paused = false;
+    }
+}
+
+/**
+ * Stop all the Audio Sources, as if `stop` was called on each of them.
+ *
+ * The Musics are rewound to their beginning.
+ */
+pub fn stop_all() {
+    check_openal_context!();
+
+    let mut sources = SOURCES.lock().unwrap();
+    for entry in sources.values_mut() {
+        (entry.stop)();
+        entry.paused = false;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use audio_controller::AudioController;
+    use music::Music;
+    use sound::Sound;
+    use states::State::{Paused, Playing, Stopped};
+
+    #[test]
+    #[ignore]
+    fn sources_pause_all_resume_all_OK() -> () {
+        let mut snd = Sound::new("res/explosion.wav").expect("Cannot create sound");
+        let mut other = Sound::new("res/explosion.wav").expect("Cannot create sound");
+
+        snd.play();
+        other.play();
+        other.pause();
+        super::pause_all();
+        assert_eq!(snd.get_state(), Paused);
+        super::resume_all();
+        assert_eq!(snd.get_state(), Playing);
+        assert_eq!(other.get_state(), Paused);
+    }
+
+    #[test]
+    #[ignore]
+    fn sources_stop_all_OK() -> () {
+        let mut snd = Sound::new("res/explosion.wav").expect("Cannot create sound");
+        let mut music = Music::new("res/music.ogg").expect("Cannot create Music");
+
+        snd.play();
+        music.play();
+        super::stop_all();
+        assert_eq!(snd.get_state(), Stopped);
+        assert_eq!(music.get_state(), Stopped);
+    }
+}
@@ -0,0 +1,35 @@
+//! Spatialization of the Audio Sources, see `AudioController::set_spatialized`.
+
+use openal::ffi;
+
+/// Whether an Audio Source is positioned in 3D.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Spatialize {
+    /// Spatialize mono sources only, multi-channel sources play as is
+    #[default]
+    Auto,
+    /// Spatialize the source whatever its channels, downmixing them
+    Enabled,
+    /// Never spatialize the source, even if it is mono
+    Disabled,
+}
+
+impl Spatialize {
+    /// The value of the AL_SOURCE_SPATIALIZE_SOFT property.
+    pub(crate) fn value(self) -> i32 {
+        match self {
+            Spatialize::Auto => ffi::AL_AUTO_SOFT,
+            Spatialize::Enabled => ffi::AL_TRUE as i32,
+            Spatialize::Disabled => ffi::AL_FALSE as i32,
+        }
+    }
+
+    /// The mode matching a value of the AL_SOURCE_SPATIALIZE_SOFT property.
+    pub(crate) fn from_value(value: i32) -> Spatialize {
+        match value {
+            v if v == ffi::AL_TRUE as i32 => Spatialize::Enabled,
+            v if v == ffi::AL_FALSE as i32 => Spatialize::Disabled,
+            _ => Spatialize::Auto,
+        }
+    }
+}
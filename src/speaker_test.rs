@@ -0,0 +1,138 @@
+//! Test tones for the output speakers.
+
+use audio_controller::AudioController;
+use error::SoundError;
+use sound::Sound;
+use sound_data::SoundData;
+use std::f32::consts::PI;
+use std::sync::{Arc, Mutex};
+
+/// The sample rate of the test tones
+const SAMPLE_RATE: i32 = 44100;
+/// The number of channels of a 7.1 buffer
+const CHANNELS: usize = 8;
+/// The duration of the test tones, in seconds
+const DURATION: f32 = 1.0;
+/// The duration of the fade in and fade out of the test tones, in seconds
+const FADE: f32 = 0.02;
+
+/// An output speaker, in the channel order of a 7.1 layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Speaker {
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    Lfe,
+    RearLeft,
+    RearRight,
+    SideLeft,
+    SideRight,
+}
+
+impl Speaker {
+    /// All the speakers, in the channel order of a 7.1 layout.
+    pub fn all() -> [Speaker; 8] {
+        [
+            Speaker::FrontLeft,
+            Speaker::FrontRight,
+            Speaker::FrontCenter,
+            Speaker::Lfe,
+            Speaker::RearLeft,
+            Speaker::RearRight,
+            Speaker::SideLeft,
+            Speaker::SideRight,
+        ]
+    }
+
+    fn channel(self) -> usize {
+        self as usize
+    }
+
+    /// The frequency of the tone, low for the subwoofer.
+    fn frequency(self) -> f32 {
+        match self {
+            Speaker::Lfe => 60.0,
+            _ => 440.0,
+        }
+    }
+}
+
+/// Generate interleaved 7.1 samples with a tone in a single channel.
+fn tone(speaker: Speaker) -> Vec<i16> {
+    let frames = (SAMPLE_RATE as f32 * DURATION) as usize;
+    let fade = (SAMPLE_RATE as f32 * FADE) as usize;
+    let mut samples = vec![0i16; frames * CHANNELS];
+    for i in 0..frames {
+        let envelope = (i.min(frames - 1 - i) as f32 / fade as f32).min(1.0);
+        let t = i as f32 / SAMPLE_RATE as f32;
+        let value = (2.0 * PI * speaker.frequency() * t).sin() * envelope * 0.5;
+        samples[i * CHANNELS + speaker.channel()] = (value * 32767.0) as i16;
+    }
+    samples
+}
+
+/**
+ * Play a test tone on a single output speaker.
+ *
+ * The tone is a 7.1 buffer played with direct channels, so each channel
+ * goes straight to the matching speaker without any virtualization. A
+ * speaker missing from the current output configuration stays silent, or
+ * is mixed into the closest ones if direct channels are not supported.
+ *
+ * # Argument
+ * * `speaker` - The speaker to play the tone on
+ *
+ * # Return
+ * A `Result` containing Ok(Sound) with the playing tone on success,
+ * Err(SoundError) if there has been an error. The tone stops when the
+ * Sound is dropped.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Speaker};
+ *
+ * for speaker in Speaker::all().iter() {
+ *     println!("Playing on {:?}", speaker);
+ *     let tone = ears::speaker_test(*speaker).unwrap();
+ *     while tone.is_playing() {}
+ * }
+ * ```
+ */
+pub fn speaker_test(speaker: Speaker) -> Result<Sound, SoundError> {
+    let data = SoundData::from_samples(&tone(speaker), CHANNELS as i32, SAMPLE_RATE)?;
+    let mut sound = Sound::new_with_data(Arc::new(Mutex::new(data)))?;
+    sound.set_direct_channel(true);
+    sound.play();
+    Ok(sound)
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{speaker_test, tone, Speaker, CHANNELS};
+    use audio_controller::AudioController;
+
+    #[test]
+    fn speaker_test_tone_OK() -> () {
+        let samples = tone(Speaker::RearRight);
+
+        let channel = Speaker::RearRight.channel();
+        for (i, sample) in samples.iter().enumerate() {
+            if i % CHANNELS != channel {
+                assert_eq!(*sample, 0);
+            }
+        }
+        assert!(samples.iter().any(|s| *s > 10000));
+        assert_eq!(samples[channel], 0);
+        assert_eq!(samples[samples.len() - CHANNELS + channel], 0);
+    }
+
+    #[test]
+    #[ignore]
+    fn speaker_test_OK() -> () {
+        let tone = speaker_test(Speaker::FrontLeft).expect("Cannot play the tone");
+
+        assert!(tone.is_playing());
+    }
+}
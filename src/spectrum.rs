@@ -0,0 +1,227 @@
+//! Frequency and amplitude analysis of PCM samples.
+
+use sample::Sample;
+use std::f32::consts::PI;
+
+/// The lowest frequency of the analysed range, in Hz
+const MIN_FREQUENCY: f32 = 20.0;
+
+/// The number of frames summarized by each peak of an envelope
+const ENVELOPE_FRAMES: usize = 256;
+
+/**
+ * Compute the magnitude spectrum of mono samples, grouped in bands.
+ *
+ * The analysis uses the largest power of two number of the most recent
+ * samples, windowed with a Hann window. The bands are logarithmically
+ * spaced from 20Hz to half the sample rate, like the ones of a graphic
+ * equalizer.
+ *
+ * # Arguments
+ * * `samples` - The mono samples to analyse, the most recent last
+ * * `sample_rate` - The sample rate of the samples
+ * * `bands` - The number of bands to compute
+ *
+ * # Return
+ * The peak magnitude of each band, from low to high frequencies, where 1.0
+ * is a full scale sine wave.
+ */
+pub fn band_magnitudes(samples: &[i16], sample_rate: u32, bands: usize) -> Vec<f32> {
+    let mut magnitudes = vec![0.0; bands];
+    if samples.len() < 2 || bands == 0 {
+        return magnitudes;
+    }
+
+    let size = 1 << (usize::BITS - 1 - samples.len().leading_zeros());
+    let samples = &samples[samples.len() - size..];
+    let bins = bin_magnitudes(samples);
+
+    let nyquist = sample_rate as f32 / 2.0;
+    let ratio = (nyquist / MIN_FREQUENCY).ln();
+    let bin_width = sample_rate as f32 / size as f32;
+    for (bin, magnitude) in bins.iter().enumerate().skip(1) {
+        let frequency = bin as f32 * bin_width;
+        if frequency < MIN_FREQUENCY {
+            continue;
+        }
+        let band = ((frequency / MIN_FREQUENCY).ln() / ratio * bands as f32) as usize;
+        let band = band.min(bands - 1);
+        magnitudes[band] = magnitudes[band].max(*magnitude);
+    }
+    magnitudes
+}
+
+/**
+ * Compute the peak amplitude envelope of interleaved samples.
+ *
+ * # Arguments
+ * * `samples` - The interleaved samples
+ * * `channels` - The number of channels of the samples
+ *
+ * # Return
+ * The peak of each block of 256 frames, between 0.0 and 1.0
+ */
+pub(crate) fn envelope<S: Sample>(samples: &[S], channels: usize) -> Vec<f32> {
+    samples
+        .chunks(ENVELOPE_FRAMES * channels.max(1))
+        .map(|block| {
+            block
+                .iter()
+                .map(|sample| (sample.to_f32() / S::FULL_SCALE).abs())
+                .fold(0.0, f32::max)
+                .min(1.0)
+        })
+        .collect()
+}
+
+/**
+ * Reduce an envelope to a number of points, to draw a waveform.
+ *
+ * # Arguments
+ * * `envelope` - The envelope computed by `envelope`
+ * * `points` - The number of points to compute
+ *
+ * # Return
+ * The peak amplitude of each point, between 0.0 and 1.0, all zeros if the
+ * envelope is empty.
+ */
+pub(crate) fn waveform(envelope: &[f32], points: usize) -> Vec<f32> {
+    let len = envelope.len();
+    (0..points)
+        .map(|point| {
+            let start = point * len / points;
+            let end = ((point + 1) * len / points).max(start + 1).min(len);
+            envelope[start.min(end)..end]
+                .iter()
+                .cloned()
+                .fold(0.0, f32::max)
+        })
+        .collect()
+}
+
+/// Compute the normalized magnitude of the first half of the FFT bins.
+fn bin_magnitudes(samples: &[i16]) -> Vec<f32> {
+    let size = samples.len();
+    let mut re: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let window = 0.5 - 0.5 * (2.0 * PI * i as f32 / (size - 1) as f32).cos();
+            *s as f32 / 32768.0 * window
+        })
+        .collect();
+    let mut im = vec![0.0; size];
+    fft(&mut re, &mut im);
+
+    // A full scale sine gives a peak of size / 4 with a Hann window
+    let scale = 4.0 / size as f32;
+    (0..size / 2)
+        .map(|i| (re[i] * re[i] + im[i] * im[i]).sqrt() * scale)
+        .collect()
+}
+
+/// In place iterative radix-2 FFT, the length must be a power of two.
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let size = re.len();
+
+    let mut j = 0;
+    for i in 1..size {
+        let mut bit = size >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= size {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..size).step_by(len) {
+            for k in 0..len / 2 {
+                let (w_im, w_re) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{band_magnitudes, envelope, waveform};
+    use std::f32::consts::PI;
+
+    fn sine(frequency: f32, sample_rate: u32, len: usize) -> Vec<i16> {
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                ((2.0 * PI * frequency * t).sin() * 32767.0) as i16
+            })
+            .collect()
+    }
+
+    #[test]
+    fn band_magnitudes_sine_OK() -> () {
+        let magnitudes = band_magnitudes(&sine(1000.0, 44100, 2048), 44100, 10);
+
+        let loudest = (0..magnitudes.len())
+            .max_by(|a, b| magnitudes[*a].partial_cmp(&magnitudes[*b]).unwrap())
+            .unwrap();
+        // 1kHz is in the 6th logarithmic band between 20Hz and 22050Hz
+        assert_eq!(loudest, 5);
+        assert!(magnitudes[5] > 0.8 && magnitudes[5] < 1.1);
+        assert!(magnitudes[0] < 0.01);
+    }
+
+    #[test]
+    fn band_magnitudes_silence_OK() -> () {
+        let magnitudes = band_magnitudes(&[0; 1024], 44100, 8);
+
+        assert_eq!(magnitudes, vec![0.0; 8]);
+    }
+
+    #[test]
+    fn band_magnitudes_too_short_FAIL() -> () {
+        assert_eq!(band_magnitudes(&[1000], 44100, 4), vec![0.0; 4]);
+    }
+
+    #[test]
+    fn envelope_OK() -> () {
+        let mut samples = vec![0i16; 512 * 2];
+        samples[3] = -16384;
+        samples[700] = 8192;
+
+        assert_eq!(envelope(&samples, 2), vec![0.5, 0.25]);
+    }
+
+    #[test]
+    fn waveform_OK() -> () {
+        let peaks = waveform(&[0.1, 0.5, 0.2, 0.3], 2);
+
+        assert_eq!(peaks, vec![0.5, 0.3]);
+    }
+
+    #[test]
+    fn waveform_more_points_than_peaks_OK() -> () {
+        assert_eq!(waveform(&[0.1, 0.5], 4), vec![0.1, 0.1, 0.5, 0.5]);
+    }
+
+    #[test]
+    fn waveform_empty_FAIL() -> () {
+        assert_eq!(waveform(&[], 3), vec![0.0; 3]);
+    }
+}
@@ -0,0 +1,100 @@
+//! Settings of the buffers streaming a Music.
+
+use std::time::Duration;
+
+/// The fewest buffers a Music can stream with
+const MIN_BUFFERS: usize = 2;
+
+/// The fewest frames a buffer can hold
+const MIN_BUFFER_FRAMES: usize = 256;
+
+/**
+ * The buffers streaming a Music, given to `Music::with_stream_config`.
+ *
+ * More or larger buffers keep more of the file decoded ahead, so the music
+ * doesn't stop when reading it gets slow, like on a network filesystem.
+ * Fewer or smaller ones use less memory and react faster to `set_offset`.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{AudioController, Music, StreamConfig};
+ *
+ * fn main() -> Result<(), ears::SoundError> {
+ *     // 4 buffers of about a second each
+ *     let config = StreamConfig {
+ *         buffers: 4,
+ *         buffer_frames: 44100,
+ *     };
+ *     let mut music = Music::with_stream_config("path/to/music.ogg", config)?;
+ *     music.play();
+ *     Ok(())
+ * }
+ * ```
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamConfig {
+    /// The number of buffers queued on the source, at least 2. Default
+    /// is 2.
+    pub buffers: usize,
+    /// The number of frames of each buffer, at least 256. Default is 50000.
+    pub buffer_frames: usize,
+}
+
+impl Default for StreamConfig {
+    fn default() -> StreamConfig {
+        StreamConfig {
+            buffers: 2,
+            buffer_frames: 50000,
+        }
+    }
+}
+
+impl StreamConfig {
+    /// The config with its values raised to their minimum.
+    pub(crate) fn checked(self) -> StreamConfig {
+        StreamConfig {
+            buffers: self.buffers.max(MIN_BUFFERS),
+            buffer_frames: self.buffer_frames.max(MIN_BUFFER_FRAMES),
+        }
+    }
+
+    /// How long each buffer plays, at a sample rate.
+    pub(crate) fn buffer_duration(&self, sample_rate: i32) -> Duration {
+        Duration::from_secs_f64(self.buffer_frames as f64 / sample_rate.max(1) as f64)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::StreamConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn stream_config_checked_OK() -> () {
+        let config = StreamConfig {
+            buffers: 0,
+            buffer_frames: 10,
+        };
+
+        assert_eq!(
+            config.checked(),
+            StreamConfig {
+                buffers: 2,
+                buffer_frames: 256,
+            }
+        );
+        assert_eq!(StreamConfig::default().checked(), StreamConfig::default());
+    }
+
+    #[test]
+    fn stream_config_buffer_duration_OK() -> () {
+        let config = StreamConfig {
+            buffers: 4,
+            buffer_frames: 22050,
+        };
+
+        assert_eq!(config.buffer_duration(44100), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,207 @@
+//! Human readable information on the format of a file.
+
+use sndfile::FormatType::*;
+use sndfile::{FormatType, SndInfo};
+use std::fmt;
+
+/**
+ * The format of the file played by a Music.
+ *
+ * Displays as a short summary, like "FLAC 24-bit 96 kHz" or
+ * "OGG Vorbis 44.1 kHz".
+ *
+ * # Example
+ * ```no_run
+ * use ears::Music;
+ *
+ * let music = Music::new("res/music.flac").unwrap();
+ * let info = music.stream_info();
+ * println!("Playing {} ({} kbps)", info, info.bitrate.unwrap_or(0) / 1000);
+ * ```
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamInfo {
+    /// The name of the file format, like "WAV" or "OGG"
+    pub container: &'static str,
+    /// The name of the encoding of the samples, like "PCM" or "Vorbis"
+    pub codec: &'static str,
+    /// The number of bits of each sample, None for compressed encodings
+    pub sample_width: Option<u32>,
+    /// The sample rate, in Hz
+    pub sample_rate: i32,
+    /// The number of channels
+    pub channels: i32,
+    /// The average bitrate, in bits per second, estimated from the file size
+    pub bitrate: Option<u64>,
+    /// Whether the file can be seeked into
+    pub seekable: bool,
+}
+
+impl fmt::Display for StreamInfo {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.container)?;
+        if self.codec != self.container {
+            write!(fmt, " {}", self.codec)?;
+        }
+        if let Some(width) = self.sample_width {
+            write!(fmt, " {}-bit", width)?;
+        }
+        write!(fmt, " {} kHz", self.sample_rate as f64 / 1000.0)
+    }
+}
+
+fn container_name(format: i32) -> &'static str {
+    let containers = [
+        (FormatWav, "WAV"),
+        (FormatAiff, "AIFF"),
+        (FormatAu, "AU"),
+        (FormatRaw, "RAW"),
+        (FormatPaf, "PAF"),
+        (FormatSvx, "SVX"),
+        (FormatNist, "NIST"),
+        (FormatVoc, "VOC"),
+        (FormatIrcam, "IRCAM"),
+        (FormatW64, "W64"),
+        (FormatMat4, "MAT4"),
+        (FormatMat5, "MAT5"),
+        (FormatPvf, "PVF"),
+        (FormatXi, "XI"),
+        (FormatHtk, "HTK"),
+        (FormatSds, "SDS"),
+        (FormatAvr, "AVR"),
+        (FormatWavex, "WAV"),
+        (FormatSd2, "SD2"),
+        (FormatFlac, "FLAC"),
+        (FormatCaf, "CAF"),
+        (FormatWve, "WVE"),
+        (FormatOgg, "OGG"),
+        (FormatMpc2k, "MPC2K"),
+        (FormatRf64, "RF64"),
+        (FormatMpeg, "MPEG"),
+    ];
+    find(&containers, format & FormatTypeMask as i32).unwrap_or("Unknown")
+}
+
+fn codec(format: i32) -> (&'static str, Option<u32>) {
+    let codecs = [
+        (FormatPcmS8, ("PCM", Some(8))),
+        (FormatPcmU8, ("PCM", Some(8))),
+        (FormatPcm16, ("PCM", Some(16))),
+        (FormatPcm24, ("PCM", Some(24))),
+        (FormatPcm32, ("PCM", Some(32))),
+        (FormatFloat, ("Float", Some(32))),
+        (FormatDouble, ("Double", Some(64))),
+        (FormatUlaw, ("u-law", Some(8))),
+        (FormatAlaw, ("A-law", Some(8))),
+        (FormatImaAdpcm, ("IMA ADPCM", Some(4))),
+        (FormatApcm, ("MS ADPCM", Some(4))),
+        (FormatGsm610, ("GSM 6.10", None)),
+        (FormatVoxAdpcm, ("VOX ADPCM", Some(4))),
+        (FormatG72132, ("G721", Some(4))),
+        (FormatG72324, ("G723", Some(3))),
+        (FormatG72340, ("G723", Some(5))),
+        (FormatDww12, ("DWVW", Some(12))),
+        (FormatDww16, ("DWVW", Some(16))),
+        (FormatDww24, ("DWVW", Some(24))),
+        (FormatDwwN, ("DWVW", None)),
+        (FormatDpcm8, ("DPCM", Some(8))),
+        (FormatDpcm16, ("DPCM", Some(16))),
+        (FormatVorbis, ("Vorbis", None)),
+        (FormatOpus, ("Opus", None)),
+        (FormatMpegLayerI, ("MP1", None)),
+        (FormatMpegLayerII, ("MP2", None)),
+        (FormatMpegLayerIII, ("MP3", None)),
+    ];
+    find(&codecs, format & FormatSubMask as i32).unwrap_or(("Unknown", None))
+}
+
+fn find<T: Copy>(names: &[(FormatType, T)], format: i32) -> Option<T> {
+    names
+        .iter()
+        .find(|(f, _)| *f as i32 == format)
+        .map(|(_, name)| *name)
+}
+
+/**
+ * Describe the format of a file.
+ *
+ * # Arguments
+ * * `infos` - The information of the file
+ * * `file_size` - The size of the file in bytes, if known
+ */
+pub(crate) fn stream_info(infos: &SndInfo, file_size: Option<u64>) -> StreamInfo {
+    let container = container_name(infos.format);
+    let (mut codec, sample_width) = codec(infos.format);
+    // FLAC stores its sample width as a PCM subformat
+    if container == "FLAC" {
+        codec = container;
+    }
+    let bitrate = match (file_size, infos.frames, infos.samplerate) {
+        (Some(size), frames, rate) if frames > 0 && rate > 0 => {
+            Some(size * 8 * rate as u64 / frames as u64)
+        }
+        _ => None,
+    };
+
+    StreamInfo {
+        container,
+        codec,
+        sample_width,
+        sample_rate: infos.samplerate,
+        channels: infos.channels,
+        bitrate,
+        seekable: infos.seekable != 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::stream_info;
+    use sndfile::FormatType::*;
+    use sndfile::SndInfo;
+
+    fn infos(format: i32, frames: i64, samplerate: i32) -> SndInfo {
+        SndInfo {
+            frames,
+            samplerate,
+            channels: 2,
+            format,
+            sections: 1,
+            seekable: 1,
+        }
+    }
+
+    #[test]
+    fn stream_info_flac_OK() -> () {
+        let info = stream_info(
+            &infos(FormatFlac as i32 | FormatPcm24 as i32, 96000, 96000),
+            None,
+        );
+
+        assert_eq!(info.codec, "FLAC");
+        assert_eq!(info.sample_width, Some(24));
+        assert!(info.seekable);
+        assert_eq!(info.to_string(), "FLAC 24-bit 96 kHz");
+    }
+
+    #[test]
+    fn stream_info_vorbis_OK() -> () {
+        let info = stream_info(
+            &infos(FormatOgg as i32 | FormatVorbis as i32, 441000, 44100),
+            Some(200_000),
+        );
+
+        assert_eq!(info.bitrate, Some(160_000));
+        assert_eq!(info.to_string(), "OGG Vorbis 44.1 kHz");
+    }
+
+    #[test]
+    fn stream_info_unknown_FAIL() -> () {
+        let info = stream_info(&infos(0x7FF_FFFF, 0, 44100), Some(1000));
+
+        assert_eq!(info.container, "Unknown");
+        assert_eq!(info.bitrate, None);
+    }
+}
@@ -0,0 +1,243 @@
+//! The thread streaming all the playing Musics.
+
+use internal::OpenAlData;
+use libc::{c_char, c_void};
+use openal::ffi;
+use std::ffi::CString;
+use std::mem;
+use std::ptr;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// The longest wait between two updates of the streams
+const MAX_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The shortest wait between two updates of the streams
+const MIN_INTERVAL: Duration = Duration::from_millis(5);
+
+/// One update of a stream, returning false once the stream ended
+pub(crate) type Update = Box<dyn FnMut() -> bool + Send>;
+
+/// A stream, None once it ended
+type SharedUpdate = Arc<Mutex<Option<Update>>>;
+
+lazy_static! {
+    /// The running streams with their update intervals, and whether the service thread runs
+    static ref STREAMS: Mutex<(Vec<(SharedUpdate, Duration)>, bool)> =
+        Mutex::new((Vec::new(), false));
+    /// Set to update the streams before the end of the wait
+    static ref WAKE: (Mutex<bool>, Condvar) = (Mutex::new(false), Condvar::new());
+}
+
+/// Handle on a stream run by the service thread.
+pub(crate) struct StreamHandle {
+    update: SharedUpdate,
+}
+
+impl StreamHandle {
+    /**
+     * Wait for the stream to end.
+     *
+     * The remaining updates run on the calling thread, so a stream can be
+     * joined from the callback of another one. The stream must have been
+     * told to end, by stopping its source.
+     */
+    pub(crate) fn join(self) {
+        let mut update = self.update.lock().unwrap();
+        while update.as_mut().is_some_and(|update| update()) {}
+        *update = None;
+    }
+
+    /**
+     * End the stream without running its remaining updates, once its
+     * context is gone and its source can't stop.
+     */
+    pub(crate) fn cancel(self) {
+        *self.update.lock().unwrap() = None;
+    }
+}
+
+/**
+ * Run a stream on the service thread.
+ *
+ * A single thread updates all the streams, and only runs while there are
+ * some. With the AL_SOFT_events extension, it wakes up as soon as OpenAL
+ * processed a buffer. Otherwise, it updates the streams often enough for
+ * their shortest buffer, between every 5 and 50 milliseconds.
+ *
+ * # Arguments
+ * * `update` - The update of the stream
+ * * `buffer_duration` - How long each buffer of the stream plays
+ */
+pub(crate) fn spawn(update: Update, buffer_duration: Duration) -> StreamHandle {
+    let update = Arc::new(Mutex::new(Some(update)));
+    let mut streams = STREAMS.lock().unwrap();
+    streams
+        .0
+        .push((update.clone(), update_interval(buffer_duration)));
+    if !streams.1 {
+        streams.1 = true;
+        thread::Builder::new()
+            .name(String::from("ears-stream"))
+            .spawn(update_streams)
+            .unwrap();
+    }
+    StreamHandle { update }
+}
+
+/// Update the streams sooner than planned, e.g. once a buffer was processed.
+pub(crate) fn wake() {
+    let (ref woken, ref condvar) = *WAKE;
+    *woken.lock().unwrap() = true;
+    condvar.notify_one();
+}
+
+/// The wait between two updates of a stream, so a buffer is refilled well before the others run out.
+fn update_interval(buffer_duration: Duration) -> Duration {
+    (buffer_duration / 4).clamp(MIN_INTERVAL, MAX_INTERVAL)
+}
+
+fn update_streams() {
+    let events = enable_events();
+    loop {
+        let interval = STREAMS
+            .lock()
+            .unwrap()
+            .0
+            .iter()
+            .map(|(_, interval)| *interval)
+            .min()
+            .unwrap_or(MAX_INTERVAL);
+        // The events wake the thread, the timeout only catches what they missed
+        wait(if events { MAX_INTERVAL } else { interval });
+
+        // The streams are updated without the lock, so their callbacks can play Musics
+        let streams: Vec<SharedUpdate> = STREAMS
+            .lock()
+            .unwrap()
+            .0
+            .iter()
+            .map(|(stream, _)| stream.clone())
+            .collect();
+        let ended: Vec<SharedUpdate> = streams
+            .into_iter()
+            .filter(|stream| !update(stream))
+            .collect();
+
+        let mut streams = STREAMS.lock().unwrap();
+        streams
+            .0
+            .retain(|(stream, _)| !ended.iter().any(|ended| Arc::ptr_eq(stream, ended)));
+        if streams.0.is_empty() {
+            streams.1 = false;
+            return;
+        }
+    }
+}
+
+/// Wait until woken up, or the timeout elapsed.
+fn wait(timeout: Duration) {
+    let (ref woken, ref condvar) = *WAKE;
+    let guard = woken.lock().unwrap();
+    let (mut guard, _) = condvar
+        .wait_timeout_while(guard, timeout, |woken| !*woken)
+        .unwrap();
+    *guard = false;
+}
+
+/// Update a stream, returning false once it ended.
+fn update(stream: &SharedUpdate) -> bool {
+    let mut update = stream.lock().unwrap();
+    let running = update.as_mut().is_some_and(|update| update());
+    if !running {
+        *update = None;
+    }
+    running
+}
+
+/**
+ * Wake the service thread on the buffer and state events of the
+ * AL_SOFT_events extension.
+ *
+ * # Return
+ * true if the events are enabled on the current context, otherwise false.
+ */
+fn enable_events() -> bool {
+    if unsafe { ffi::alcGetCurrentContext() == 0 }
+        || !OpenAlData::extension_present("AL_SOFT_events")
+    {
+        return false;
+    }
+
+    let control = CString::new("alEventControlSOFT").unwrap();
+    let callback = CString::new("alEventCallbackSOFT").unwrap();
+    let control = unsafe { ffi::alGetProcAddress(control.as_ptr()) };
+    let callback = unsafe { ffi::alGetProcAddress(callback.as_ptr()) };
+    if control.is_null() || callback.is_null() {
+        return false;
+    }
+    let control: ffi::EventControlFn = unsafe { mem::transmute(control) };
+    let callback: ffi::EventCallbackFn = unsafe { mem::transmute(callback) };
+
+    let types = [
+        ffi::AL_EVENT_TYPE_BUFFER_COMPLETED_SOFT,
+        ffi::AL_EVENT_TYPE_SOURCE_STATE_CHANGED_SOFT,
+    ];
+    unsafe {
+        callback(Some(on_event), ptr::null_mut());
+        control(types.len() as i32, types.as_ptr(), ffi::AL_TRUE);
+    }
+    true
+}
+
+/// Called by OpenAL on its own thread, so it must not use OpenAL.
+extern "C" fn on_event(
+    _event_type: i32,
+    _object: u32,
+    _param: u32,
+    _length: i32,
+    _message: *const c_char,
+    _user_param: *mut c_void,
+) {
+    wake();
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::{spawn, update_interval};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn stream_service_join_OK() -> () {
+        let updates = Arc::new(AtomicUsize::new(0));
+        let counter = updates.clone();
+        let handle = spawn(
+            Box::new(move || counter.fetch_add(1, Ordering::SeqCst) < 4),
+            Duration::from_secs(1),
+        );
+
+        handle.join();
+        assert_eq!(updates.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn stream_service_update_interval_OK() -> () {
+        assert_eq!(
+            update_interval(Duration::from_millis(100)),
+            Duration::from_millis(25)
+        );
+        assert_eq!(
+            update_interval(Duration::from_secs(1)),
+            Duration::from_millis(50)
+        );
+        assert_eq!(
+            update_interval(Duration::from_millis(4)),
+            Duration::from_millis(5)
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Reading files with symphonia, for the mp3 feature and the pure Rust backend.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::sync::Mutex;
+
+use sndfile::{SndFileError, SndReader};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::{FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::MediaSource;
+
+/// A reader given to symphonia.
+pub(crate) struct ReaderSource<R: SndReader>(pub(crate) Mutex<R>);
+
+impl<R: SndReader> Read for ReaderSource<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        self.0.get_mut().unwrap().read(buffer)
+    }
+}
+
+impl<R: SndReader> Seek for ReaderSource<R> {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.0.get_mut().unwrap().seek(position)
+    }
+}
+
+impl<R: SndReader> MediaSource for ReaderSource<R> {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        let mut reader = self.0.lock().unwrap();
+        let position = reader.stream_position().ok()?;
+        let length = reader.seek(SeekFrom::End(0)).ok()?;
+        reader.seek(SeekFrom::Start(position)).ok()?;
+        Some(length)
+    }
+}
+
+/// Check if an error is the end of the stream.
+pub(crate) fn is_end_of_stream(err: &Error) -> bool {
+    match err {
+        Error::IoError(err) => err.kind() == io::ErrorKind::UnexpectedEof,
+        _ => false,
+    }
+}
+
+/// Count the frames of a stream without a header giving it, and rewind it.
+pub(crate) fn count_frames(
+    format: &mut dyn FormatReader,
+    track_id: u32,
+) -> Result<u64, SndFileError> {
+    let mut frames = 0;
+    loop {
+        match format.next_packet() {
+            Ok(packet) if packet.track_id() == track_id => {
+                frames = frames.max(packet.ts() + packet.dur());
+            }
+            Ok(_) => {}
+            Err(ref err) if is_end_of_stream(err) => break,
+            Err(err) => return Err(decode_error(err)),
+        }
+    }
+    format
+        .seek(SeekMode::Accurate, SeekTo::TimeStamp { ts: 0, track_id })
+        .map_err(decode_error)?;
+    Ok(frames)
+}
+
+/// Convert a symphonia error to a SndFileError.
+pub(crate) fn decode_error(err: Error) -> SndFileError {
+    match err {
+        Error::IoError(err) => SndFileError::SystemError(err.to_string()),
+        Error::Unsupported(message) => SndFileError::UnsupportedEncoding(message.to_string()),
+        err => SndFileError::MalformedFile(err.to_string()),
+    }
+}
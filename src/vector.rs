@@ -0,0 +1,94 @@
+//! The three dimensional vectors accepted for positions, directions and velocities.
+
+/**
+ * A three dimensional vector [x, y, z].
+ *
+ * It is implemented for `[f32; 3]`, for the `mint` types with the `mint`
+ * feature and for the `glam` types with the `glam` feature, so the vectors
+ * of a game engine can be given to __ears__ as they are.
+ *
+ * # Example
+ * ```no_run
+ * use ears::{listener, AudioController, Sound};
+ *
+ * fn main() -> Result<(), ears::SoundError> {
+ *     listener::set_position([0., 1.8, 0.]);
+ *
+ *     let mut snd = Sound::new("path/to/the/sound.ogg")?;
+ *     snd.set_position_from([10., 0., -5.]);
+ *     Ok(())
+ * }
+ * ```
+ */
+pub trait Vector3 {
+    /// Get the vector as [x, y, z].
+    fn to_array(self) -> [f32; 3];
+}
+
+impl Vector3 for [f32; 3] {
+    fn to_array(self) -> [f32; 3] {
+        self
+    }
+}
+
+#[cfg(feature = "mint")]
+impl Vector3 for mint::Vector3<f32> {
+    fn to_array(self) -> [f32; 3] {
+        self.into()
+    }
+}
+
+#[cfg(feature = "mint")]
+impl Vector3 for mint::Point3<f32> {
+    fn to_array(self) -> [f32; 3] {
+        self.into()
+    }
+}
+
+#[cfg(feature = "glam")]
+impl Vector3 for glam::Vec3 {
+    fn to_array(self) -> [f32; 3] {
+        self.into()
+    }
+}
+
+#[cfg(feature = "glam")]
+impl Vector3 for glam::Vec3A {
+    fn to_array(self) -> [f32; 3] {
+        self.into()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![allow(non_snake_case)]
+
+    use super::Vector3;
+
+    fn accept<V: Vector3>(vector: V) -> [f32; 3] {
+        vector.to_array()
+    }
+
+    #[test]
+    fn vector_array_OK() -> () {
+        assert_eq!(accept([1., 2., 3.]), [1f32, 2., 3.]);
+    }
+
+    #[cfg(feature = "mint")]
+    #[test]
+    fn vector_mint_OK() -> () {
+        let vector = mint::Vector3 {
+            x: 1f32,
+            y: 2.,
+            z: 3.,
+        };
+        assert_eq!(accept(vector), [1., 2., 3.]);
+    }
+
+    #[cfg(feature = "glam")]
+    #[test]
+    fn vector_glam_OK() -> () {
+        assert_eq!(accept(glam::Vec3::new(1., 2., 3.)), [1., 2., 3.]);
+        assert_eq!(accept(glam::Vec3A::new(1., 2., 3.)), [1., 2., 3.]);
+    }
+}